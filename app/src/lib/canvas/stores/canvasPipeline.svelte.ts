@@ -163,7 +163,7 @@ export const DEFAULT_VECTOR_DATA: VectorNode<EmptyData> = {
     right: 1,
   },
   strokeCap: "None",
-  strokeJoin: "Mitter",
+  strokeJoin: "Miter",
   strokeDashes: [],
   strokeMiterAngle: 28,
   strokeAlign: "Inside",
@@ -0,0 +1,480 @@
+//! Jetpack Compose composables for the layers of a document.
+//!
+//! Layers become boxes clipped to their corner radii and layers with a text style texts. Colors
+//! bound to variables refer to the color resource named like the variable in snake case. Image,
+//! emoji and video paints, inner shadows, background blurs and stroke dashes have no Compose
+//! equivalent and are left out, strokes are drawn inside the layers and shadows are cast by an
+//! elevation.
+
+use fig_file::document::Document;
+use fig_file::values::{self, number};
+use fig_types::node::vector::VectorNode;
+use fig_types::node::{Node, NodeType};
+use fig_types::properties::{
+    BlendMode, Color, EffectType, Paint, PaintType, TypeStyle, TypeStyleLineHeightUnit,
+    TypeStyleTextAlignHorizontal, TypeStyleTextCase, TypeStyleTextDecoration,
+    TypeStyleTextTruncation, VariableAlias,
+};
+
+use crate::{pascal_case, Backend};
+
+/// Indentation of the generated code.
+const INDENT: &str = "    ";
+
+/// The Jetpack Compose backend, generating a composable function for each canvas or layer.
+pub struct Compose;
+
+impl Backend for Compose {
+    fn name(&self) -> &'static str {
+        "compose"
+    }
+
+    fn generate(&self, document: &Document, node: &Node) -> String {
+        match &node.node {
+            NodeType::Document(_) => node
+                .children()
+                .iter()
+                .map(|canvas| composable(document, canvas))
+                .collect::<Vec<_>>()
+                .join("\n"),
+            _ => composable(document, node),
+        }
+    }
+}
+
+/// A composable function named after a canvas or a layer.
+fn composable(document: &Document, node: &Node) -> String {
+    let body = match &node.node {
+        NodeType::Canvas(_) => {
+            let mut lines = vec!["Box {".to_string()];
+            for layer in node.children() {
+                lines.extend(indent(self::layer(document, layer, true)));
+            }
+            lines.push("}".to_string());
+            lines
+        }
+        _ => layer(document, node, false),
+    };
+    let mut composable = format!("@Composable\nfun {}() {{\n", pascal_case(&node.name));
+    for line in indent(body) {
+        composable.push_str(&line);
+        composable.push('\n');
+    }
+    composable.push_str("}\n");
+    composable
+}
+
+/// The call of a composable drawing a layer, placed at its position in its parent if
+/// `positioned`.
+fn layer(document: &Document, node: &Node, positioned: bool) -> Vec<String> {
+    match &node.node {
+        NodeType::Document(_) | NodeType::Canvas(_) => Vec::new(),
+        NodeType::Vector(vector) => vector_composable(document, node, vector, None, positioned),
+        NodeType::Rectangle(vector) => {
+            let radii = values::corner_radii(&vector.additional_data);
+            vector_composable(document, node, vector, Some(radii), positioned)
+        }
+    }
+}
+
+fn vector_composable<Data>(
+    document: &Document,
+    node: &Node,
+    vector: &VectorNode<Data>,
+    radii: Option<[f32; 4]>,
+    positioned: bool,
+) -> Vec<String> {
+    let (width, height) = (vector.size.width, vector.size.height);
+    let shape = shape(radii);
+    let text = crate::text_style(document, vector);
+
+    let mut modifiers = Vec::new();
+    if positioned {
+        let (x, y) = crate::position(vector);
+        modifiers.push(format!(".offset(x = {}, y = {})", dp(x), dp(y)));
+    }
+    if node.rotation != 0.0 {
+        modifiers.push(format!(".rotate({})", float(node.rotation)));
+    }
+    // Layers apply to what the modifiers after them draw.
+    if vector.opacity < 1.0 {
+        modifiers.push(format!(".alpha({})", float(vector.opacity)));
+    }
+    if let Some(mode) = blend_mode(&vector.blend_mode) {
+        modifiers.push(format!(".graphicsLayer {{ blendMode = BlendMode.{mode} }}"));
+    }
+    modifiers.push(format!(
+        ".size(width = {}, height = {})",
+        dp(width),
+        dp(height)
+    ));
+    for effect in vector.effects.iter().filter(|effect| effect.visible) {
+        let color = color(
+            document,
+            effect.bound_variables.get("color"),
+            &effect.color,
+            1.0,
+        );
+        // Figma radii are twice the standard deviation of the gaussian blur.
+        let radius = dp(effect.radius / 2.0);
+        match effect.effect_type {
+            EffectType::DropShadow => modifiers.push(format!(
+                ".shadow(elevation = {radius}, shape = {shape}, ambientColor = {color}, \
+                 spotColor = {color})"
+            )),
+            EffectType::LayerBlur => modifiers.push(format!(".blur(radius = {radius})")),
+            EffectType::InnerShadow | EffectType::BackgroundBlur => {}
+        }
+    }
+    if text.is_none() {
+        // The first fill is the bottom most, and drawn first.
+        for paint in vector.fills.iter().filter(|paint| paint.visible) {
+            modifiers.extend(match self::paint(document, paint, width, height) {
+                Some(Brush::Color(color)) => {
+                    Some(format!(".background(color = {color}, shape = {shape})"))
+                }
+                Some(Brush::Gradient(brush)) => {
+                    Some(format!(".background(brush = {brush}, shape = {shape})"))
+                }
+                None => None,
+            });
+        }
+        let stroke = vector
+            .strokes
+            .iter()
+            .rev()
+            .find(|paint| paint.visible && paint.paint_type == PaintType::Solid)
+            .filter(|_| vector.stroke_weight > 0.0);
+        if let Some(stroke) = stroke {
+            modifiers.push(format!(
+                ".border(width = {}, color = {}, shape = {shape})",
+                dp(vector.stroke_weight),
+                paint_color(document, stroke)
+            ));
+        }
+    }
+    let mut modifier = vec!["modifier = Modifier".to_string()];
+    modifier.extend(indent(modifiers));
+
+    let mut arguments = Vec::new();
+    if let Some(style) = text {
+        arguments.push(vec![format!(
+            "text = {}",
+            string(&text_case(&node.name, style))
+        )]);
+    }
+    arguments.push(modifier);
+    if let Some(style) = text {
+        arguments.extend(
+            text_arguments(document, style)
+                .into_iter()
+                .map(|argument| vec![argument]),
+        );
+    }
+    let mut lines = vec![match text {
+        Some(_) => "Text(".to_string(),
+        None => "Box(".to_string(),
+    }];
+    for mut argument in arguments {
+        if let Some(last) = argument.last_mut() {
+            last.push(',');
+        }
+        lines.extend(indent(argument));
+    }
+    lines.push(")".to_string());
+    lines
+}
+
+/// The arguments of a text styling it.
+fn text_arguments(document: &Document, style: &TypeStyle) -> Vec<String> {
+    let mut arguments = Vec::new();
+    let fill = style
+        .fills
+        .iter()
+        .rev()
+        .find(|paint| paint.visible && paint.paint_type == PaintType::Solid);
+    if let Some(fill) = fill {
+        arguments.push(format!("color = {}", paint_color(document, fill)));
+    }
+    arguments.extend([
+        format!("fontSize = {}", sp(style.font_size)),
+        format!(
+            "fontFamily = FontFamily(Font(R.font.{}))",
+            snake_case(&style.font_family)
+        ),
+        format!("fontWeight = {}", font_weight(style.font_weight)),
+    ]);
+    if style.italic {
+        arguments.push("fontStyle = FontStyle.Italic".to_string());
+    }
+    if style.letter_spacing != 0.0 {
+        arguments.push(format!("letterSpacing = {}", sp(style.letter_spacing)));
+    }
+    let line_height = match style.line_height_unit {
+        TypeStyleLineHeightUnit::Pixels => Some(style.line_height_px),
+        TypeStyleLineHeightUnit::FontSize => {
+            Some(style.font_size * style.line_height_percent_font_size / 100.0)
+        }
+        TypeStyleLineHeightUnit::Intrinsic => None,
+    };
+    if let Some(line_height) = line_height {
+        arguments.push(format!("lineHeight = {}", sp(line_height)));
+    }
+    let align = match style.text_align_horizontal {
+        TypeStyleTextAlignHorizontal::Left => "Left",
+        TypeStyleTextAlignHorizontal::Right => "Right",
+        TypeStyleTextAlignHorizontal::Center => "Center",
+        TypeStyleTextAlignHorizontal::Justified => "Justify",
+    };
+    arguments.push(format!("textAlign = TextAlign.{align}"));
+    let decoration = match style.text_decoration {
+        TypeStyleTextDecoration::Underline => "Underline",
+        TypeStyleTextDecoration::StrikeThrought => "LineThrough",
+    };
+    arguments.push(format!("textDecoration = TextDecoration.{decoration}"));
+    match style.text_case {
+        TypeStyleTextCase::SmallCaps => {
+            arguments.push("fontFeatureSettings = \"smcp\"".to_string())
+        }
+        TypeStyleTextCase::SmallCapsForced => {
+            arguments.push("fontFeatureSettings = \"smcp, c2sc\"".to_string())
+        }
+        _ => {}
+    }
+    if style.text_truncation == TypeStyleTextTruncation::Ending {
+        arguments.push(format!("maxLines = {}", style.max_lines.max(1)));
+        arguments.push("overflow = TextOverflow.Ellipsis".to_string());
+    }
+    arguments
+}
+
+/// A text in the case of a text style, since Compose texts cannot transform it.
+fn text_case(text: &str, style: &TypeStyle) -> String {
+    match style.text_case {
+        TypeStyleTextCase::Upper => text.to_uppercase(),
+        TypeStyleTextCase::Lower => text.to_lowercase(),
+        TypeStyleTextCase::Title => text
+            .split(' ')
+            .map(|word| {
+                let mut characters = word.chars();
+                let first = characters.next().into_iter().flat_map(char::to_uppercase);
+                first.chain(characters).collect::<String>()
+            })
+            .collect::<Vec<_>>()
+            .join(" "),
+        TypeStyleTextCase::SmallCaps | TypeStyleTextCase::SmallCapsForced => text.to_string(),
+    }
+}
+
+/// The shape layers are clipped to.
+fn shape(radii: Option<[f32; 4]>) -> String {
+    let Some(radii) = radii.filter(|radii| radii.iter().any(|radius| *radius > 0.0)) else {
+        return "RectangleShape".to_string();
+    };
+    match radii {
+        [radius, ..] if radii.iter().all(|other| *other == radius) => {
+            format!("RoundedCornerShape({})", dp(radius))
+        }
+        [top_left, top_right, bottom_right, bottom_left] => format!(
+            "RoundedCornerShape(topStart = {}, topEnd = {}, bottomEnd = {}, bottomStart = {})",
+            dp(top_left),
+            dp(top_right),
+            dp(bottom_right),
+            dp(bottom_left)
+        ),
+    }
+}
+
+enum Brush {
+    Color(String),
+    Gradient(String),
+}
+
+/// The color or brush of a paint, `None` for image, emoji and video paints.
+fn paint(document: &Document, paint: &Paint, width: f32, height: f32) -> Option<Brush> {
+    // Handles are in normalized object space, where (1, 1) is the bottom right corner.
+    let handle = |index: usize| {
+        paint
+            .gradient_handle_positions
+            .get(index)
+            .map(|handle| (handle.x * width, handle.y * height))
+    };
+    let offset = |(x, y): (f32, f32)| format!("Offset({}, {})", float(x), float(y));
+    let stops = || {
+        let stops: Vec<String> = paint
+            .gradient_stops
+            .iter()
+            .map(|stop| {
+                let bound = stop.bound_variables.get("color");
+                format!(
+                    "{} to {}",
+                    float(stop.position),
+                    color(document, bound, &stop.color, paint.opacity)
+                )
+            })
+            .collect();
+        stops.join(", ")
+    };
+    let brush = match paint.paint_type {
+        PaintType::Solid => return Some(Brush::Color(paint_color(document, paint))),
+        PaintType::GradientLinear => format!(
+            "Brush.linearGradient({}, start = {}, end = {})",
+            stops(),
+            offset(handle(0)?),
+            offset(handle(1)?)
+        ),
+        // Compose has no diamond gradients, they are approximated with radial ones.
+        PaintType::GradientRadial | PaintType::GradientDiamond => {
+            let (center, end) = (handle(0)?, handle(1)?);
+            format!(
+                "Brush.radialGradient({}, center = {}, radius = {})",
+                stops(),
+                offset(center),
+                float((end.0 - center.0).hypot(end.1 - center.1))
+            )
+        }
+        PaintType::GradientAngular => format!(
+            "Brush.sweepGradient({}, center = {})",
+            stops(),
+            offset(handle(0)?)
+        ),
+        PaintType::Image | PaintType::Emoji | PaintType::Video => return None,
+    };
+    Some(Brush::Gradient(brush))
+}
+
+fn paint_color(document: &Document, paint: &Paint) -> String {
+    let bound = paint
+        .bound_variables
+        .as_ref()
+        .and_then(|variables| variables.get("color"));
+    color(document, bound, &paint.color, paint.opacity)
+}
+
+/// A color, or the color resource named like the variable it is bound to, with an opacity.
+fn color(
+    document: &Document,
+    bound: Option<&VariableAlias>,
+    color: &Color,
+    opacity: f32,
+) -> String {
+    match crate::variable(document, bound) {
+        Some(variable) => {
+            let resource = format!("colorResource(R.color.{})", snake_case(&variable.name));
+            match opacity < 1.0 {
+                true => format!("{resource}.copy(alpha = {})", float(opacity)),
+                false => resource,
+            }
+        }
+        None => {
+            let channel = |value: f32| (value.clamp(0.0, 1.0) * 255.0).round() as u8;
+            format!(
+                "Color(0x{:02X}{:02X}{:02X}{:02X})",
+                channel(color.a * opacity),
+                channel(color.r),
+                channel(color.g),
+                channel(color.b)
+            )
+        }
+    }
+}
+
+/// The font weight closest to a numeric font weight.
+fn font_weight(weight: i32) -> String {
+    let name = match weight {
+        100 => "Thin",
+        200 => "ExtraLight",
+        300 => "Light",
+        400 => "Normal",
+        500 => "Medium",
+        600 => "SemiBold",
+        700 => "Bold",
+        800 => "ExtraBold",
+        900 => "Black",
+        weight => return format!("FontWeight({})", weight.clamp(1, 1000)),
+    };
+    format!("FontWeight.{name}")
+}
+
+/// The name of the Compose blend mode closest to a blend mode, `None` for normal blending.
+fn blend_mode(mode: &BlendMode) -> Option<&'static str> {
+    Some(match mode {
+        BlendMode::PassThrough | BlendMode::Normal => return None,
+        BlendMode::Darken => "Darken",
+        BlendMode::Multiply => "Multiply",
+        // Compose has no linear burn, color burn is the closest.
+        BlendMode::LinearBurn | BlendMode::ColorBurn => "ColorBurn",
+        BlendMode::Lighten => "Lighten",
+        BlendMode::Screen => "Screen",
+        BlendMode::LinearDodge => "Plus",
+        BlendMode::ColorDodge => "ColorDodge",
+        BlendMode::Overlay => "Overlay",
+        BlendMode::SoftLight => "Softlight",
+        BlendMode::HardLight => "Hardlight",
+        BlendMode::Difference => "Difference",
+        BlendMode::Exclusion => "Exclusion",
+        BlendMode::Hue => "Hue",
+        BlendMode::Saturation => "Saturation",
+        BlendMode::Color => "Color",
+        BlendMode::Luminosity => "Luminosity",
+    })
+}
+
+/// Lower case words of a name joined with underscores, as resource names are.
+fn snake_case(name: &str) -> String {
+    crate::words(name)
+        .map(str::to_lowercase)
+        .collect::<Vec<_>>()
+        .join("_")
+}
+
+/// A Kotlin string literal.
+fn string(text: &str) -> String {
+    format!("{text:?}").replace('$', "\\$")
+}
+
+fn float(value: f32) -> String {
+    format!("{}f", number(value))
+}
+
+fn dp(value: f32) -> String {
+    unit(value, "dp")
+}
+
+fn sp(value: f32) -> String {
+    unit(value, "sp")
+}
+
+fn unit(value: f32, unit: &str) -> String {
+    match number(value) {
+        value if value.starts_with('-') => format!("({value}).{unit}"),
+        value => format!("{value}.{unit}"),
+    }
+}
+
+fn indent(lines: Vec<String>) -> Vec<String> {
+    lines
+        .into_iter()
+        .map(|line| format!("{INDENT}{line}"))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures;
+
+    #[test]
+    fn canvas_snapshot() {
+        let document = fixtures::document();
+        let canvas = &document.document.children()[0];
+        insta::assert_snapshot!(Compose.generate(&document, canvas));
+    }
+
+    #[test]
+    fn layer_snapshot() {
+        let document = fixtures::document();
+        let title = document.node("1:3").unwrap();
+        insta::assert_snapshot!(Compose.generate(&document, title));
+    }
+}
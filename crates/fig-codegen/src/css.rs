@@ -0,0 +1,957 @@
+//! CSS for the layers of a document.
+//!
+//! Fills become backgrounds, strokes become borders when they are inside the layer and outlines
+//! otherwise, shadows and blurs become `box-shadow`, `filter` and `backdrop-filter`, and the text
+//! style of a layer becomes font and text properties. Layers only carry the auto-layout properties
+//! of the children of auto-layout frames, which become flex item properties. Values bound to
+//! variables of the document refer to CSS custom properties, declared by [`Css::stylesheet`] for
+//! the default mode of their collection and overridden for the other modes under a
+//! `data-<collection>` attribute.
+
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::fmt;
+
+use fig_file::document::Document;
+use fig_file::values::{self, number};
+use fig_file::{render, tokens};
+use fig_types::node::vector::VectorNode;
+use fig_types::node::{Node, NodeType};
+use fig_types::properties::{
+    Color, Effect, EffectType, LayoutAlign, Paint, PaintScaleMode, PaintType, StrokeAlign,
+    TypeStyle, TypeStyleLineHeightUnit, TypeStyleTextAlignHorizontal, TypeStyleTextCase,
+    TypeStyleTextDecoration, TypeStyleTextTruncation, VariableAlias, VariableDataValue,
+};
+
+use crate::Backend;
+
+/// A CSS property and its value.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Declaration {
+    pub property: &'static str,
+    pub value: String,
+}
+
+impl Declaration {
+    fn new(property: &'static str, value: impl Into<String>) -> Self {
+        Self {
+            property,
+            value: value.into(),
+        }
+    }
+}
+
+impl fmt::Display for Declaration {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {};", self.property, self.value)
+    }
+}
+
+/// The CSS backend, generating a stylesheet with a rule for each layer.
+pub struct Stylesheet;
+
+impl Backend for Stylesheet {
+    fn name(&self) -> &'static str {
+        "css"
+    }
+
+    fn generate(&self, document: &Document, node: &Node) -> String {
+        Css::new(document).stylesheet(node)
+    }
+}
+
+/// Generates the CSS of the layers of a document, keeping track of the variables it refers to.
+pub struct Css<'a> {
+    document: &'a Document,
+    variables: BTreeSet<&'a str>,
+}
+
+impl<'a> Css<'a> {
+    pub fn new(document: &'a Document) -> Self {
+        Self {
+            document,
+            variables: BTreeSet::new(),
+        }
+    }
+
+    /// The declarations styling a layer, empty for documents and canvases.
+    pub fn declarations(&mut self, node: &Node) -> Vec<Declaration> {
+        match &node.node {
+            NodeType::Document(_) | NodeType::Canvas(_) => Vec::new(),
+            NodeType::Vector(vector) => self.layer(node, vector, None),
+            NodeType::Rectangle(vector) => {
+                let radii = values::corner_radii(&vector.additional_data);
+                self.layer(node, vector, Some(radii))
+            }
+        }
+    }
+
+    /// A rule styling a layer with a selector.
+    pub fn rule(&mut self, selector: &str, node: &Node) -> String {
+        let mut rule = format!("{selector} {{\n");
+        for declaration in self.declarations(node) {
+            rule.push_str(&format!("  {declaration}\n"));
+        }
+        rule.push_str("}\n");
+        rule
+    }
+
+    /// A stylesheet with a rule for the layer and for each of its descendants, selected by a class
+    /// named after the layer, preceded by the custom properties of the variables they refer to.
+    pub fn stylesheet(&mut self, root: &Node) -> String {
+        let mut classes = HashMap::new();
+        let mut rules = Vec::new();
+        self.rules(root, &mut classes, &mut rules);
+        let mut stylesheet = self.custom_properties();
+        for rule in rules {
+            if !stylesheet.is_empty() {
+                stylesheet.push('\n');
+            }
+            stylesheet.push_str(&rule);
+        }
+        stylesheet
+    }
+
+    /// Rules declaring the custom properties of the variables referred to so far, and of the
+    /// variables they are aliases of: one for the default modes, and one for each other mode in
+    /// which some of them have another value.
+    pub fn custom_properties(&self) -> String {
+        let mut ids: Vec<&str> = self.variables.iter().copied().collect();
+        let mut index = 0;
+        while index < ids.len() {
+            let aliases = self.document.variables[ids[index]]
+                .values_by_mode
+                .values()
+                .filter_map(|value| match value {
+                    VariableDataValue::VariableAlias(alias) => Some(alias.id.as_str()),
+                    _ => None,
+                })
+                .filter(|alias| self.document.variables.contains_key(*alias));
+            let aliases: Vec<&str> = aliases.filter(|alias| !ids.contains(alias)).collect();
+            ids.extend(aliases);
+            index += 1;
+        }
+        ids.sort_unstable();
+        ids.dedup();
+
+        let mut blocks: BTreeMap<String, Vec<String>> = BTreeMap::new();
+        for id in ids {
+            let variable = &self.document.variables[id];
+            let collection = self
+                .document
+                .variable_collections
+                .get(&variable.variable_collection_id);
+            let default_mode = collection.map(|collection| collection.default_mode_id.as_str());
+            let default = default_mode
+                .and_then(|mode| variable.values_by_mode.get(mode))
+                .or_else(|| variable.values_by_mode.values().next());
+            let Some(default) = default else {
+                continue;
+            };
+            let name = custom_property(&variable.name);
+            if let Some(value) = self.variable_value(default, tokens::is_dimension(variable)) {
+                blocks
+                    .entry(":root".to_string())
+                    .or_default()
+                    .push(format!("  {name}: {value};\n"));
+            }
+            let Some(collection) = collection else {
+                continue;
+            };
+            for mode in &collection.modes {
+                let Some(value) = variable.values_by_mode.get(&mode.mode_id) else {
+                    continue;
+                };
+                if mode.mode_id == collection.default_mode_id || value == default {
+                    continue;
+                }
+                if let Some(value) = self.variable_value(value, tokens::is_dimension(variable)) {
+                    let selector =
+                        format!("[data-{}=\"{}\"]", slug(&collection.name), slug(&mode.name));
+                    blocks
+                        .entry(selector)
+                        .or_default()
+                        .push(format!("  {name}: {value};\n"));
+                }
+            }
+        }
+        // The default values come first so the modes override them.
+        let root = blocks.remove_entry(":root");
+        root.into_iter()
+            .chain(blocks)
+            .map(|(selector, declarations)| format!("{selector} {{\n{}}}\n", declarations.concat()))
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    fn rules(
+        &mut self,
+        node: &Node,
+        classes: &mut HashMap<String, usize>,
+        rules: &mut Vec<String>,
+    ) {
+        if matches!(node.node, NodeType::Vector(_) | NodeType::Rectangle(_)) {
+            let name = match slug(&node.name) {
+                name if name.is_empty() => format!("node-{}", slug(&node.id)),
+                name => name,
+            };
+            let count = classes.entry(name.clone()).or_default();
+            *count += 1;
+            let class = match count {
+                1 => name,
+                count => format!("{name}-{count}"),
+            };
+            rules.push(self.rule(&format!(".{class}"), node));
+        }
+        for child in node.children() {
+            self.rules(child, classes, rules);
+        }
+    }
+
+    fn layer<Data>(
+        &mut self,
+        node: &Node,
+        vector: &VectorNode<Data>,
+        radii: Option<[f32; 4]>,
+    ) -> Vec<Declaration> {
+        let mut declarations = vec![
+            Declaration::new("width", px(vector.size.width)),
+            Declaration::new("height", px(vector.size.height)),
+        ];
+        let align = match vector.layout_align {
+            LayoutAlign::Inherit => None,
+            LayoutAlign::Stretch => Some("stretch"),
+            LayoutAlign::Min => Some("flex-start"),
+            LayoutAlign::Center => Some("center"),
+            LayoutAlign::Max => Some("flex-end"),
+        };
+        if let Some(align) = align {
+            declarations.push(Declaration::new("align-self", align));
+        }
+        if vector.layout_grow > 0 {
+            declarations.push(Declaration::new(
+                "flex-grow",
+                vector.layout_grow.to_string(),
+            ));
+        }
+        if node.rotation != 0.0 {
+            declarations.push(Declaration::new(
+                "transform",
+                format!("rotate({}deg)", number(node.rotation)),
+            ));
+        }
+        declarations.extend(self.background(&vector.fills, vector.size.width, vector.size.height));
+        declarations.extend(self.stroke(vector));
+        if let Some(radii) = radii.filter(|radii| radii.iter().any(|radius| *radius > 0.0)) {
+            let value = match radii {
+                [radius, ..] if radii.iter().all(|other| other == &radius) => px(radius),
+                radii => radii.map(px).join(" "),
+            };
+            declarations.push(Declaration::new("border-radius", value));
+        }
+        declarations.extend(self.effects(&vector.effects));
+        if vector.opacity < 1.0 {
+            declarations.push(Declaration::new("opacity", number(vector.opacity)));
+        }
+        if let Some(mode) = render::blend_mode(&vector.blend_mode) {
+            declarations.push(Declaration::new("mix-blend-mode", mode));
+        }
+        if let Some(text) = crate::text_style(self.document, vector) {
+            declarations.extend(self.type_style(text));
+        }
+        declarations
+    }
+
+    /// The backgrounds painting fills, the first fill being the bottom most.
+    fn background(&mut self, fills: &[Paint], width: f32, height: f32) -> Vec<Declaration> {
+        let fills: Vec<&Paint> = fills.iter().filter(|paint| paint.visible).collect();
+        if let [paint] = fills[..] {
+            if paint.paint_type == PaintType::Solid {
+                let color = self.paint_color(paint);
+                return vec![Declaration::new("background-color", color)];
+            }
+        }
+        let layers: Vec<String> = fills
+            .iter()
+            .rev()
+            .filter_map(|paint| self.image(paint, width, height))
+            .collect();
+        match layers.is_empty() {
+            true => Vec::new(),
+            false => vec![Declaration::new("background", layers.join(", "))],
+        }
+    }
+
+    /// A background layer painting a paint, `None` for emoji and video paints.
+    fn image(&mut self, paint: &Paint, width: f32, height: f32) -> Option<String> {
+        // Handles are in normalized object space, where (1, 1) is the bottom right corner.
+        let handle = |index: usize| {
+            paint
+                .gradient_handle_positions
+                .get(index)
+                .map(|handle| (handle.x * width, handle.y * height))
+        };
+        let function = match paint.paint_type {
+            PaintType::Solid => {
+                let color = self.paint_color(paint);
+                return Some(format!("linear-gradient({color}, {color})"));
+            }
+            PaintType::Image => {
+                let size = match paint.scale_mode {
+                    PaintScaleMode::Fill => "cover no-repeat",
+                    PaintScaleMode::Fit => "contain no-repeat",
+                    PaintScaleMode::Stretch => "100% 100% no-repeat",
+                    PaintScaleMode::Tile => "auto repeat",
+                };
+                return Some(format!("url(\"{}\") center / {size}", paint.image_ref));
+            }
+            PaintType::Emoji | PaintType::Video => return None,
+            PaintType::GradientLinear => {
+                let (start, end) = (handle(0)?, handle(1)?);
+                let angle = angle(start, end);
+                // CSS gradient lines go through the center, long enough for the corners to be at
+                // their ends: stops are projected on it.
+                let (sin, cos) = angle.to_radians().sin_cos();
+                let length = (width * sin).abs() + (height * cos).abs();
+                if length == 0.0 {
+                    return None;
+                }
+                let offset = |position: f32| {
+                    let x = start.0 + (end.0 - start.0) * position - width / 2.0;
+                    let y = start.1 + (end.1 - start.1) * position - height / 2.0;
+                    (x * sin - y * cos) / length + 0.5
+                };
+                let stops = self.stops(paint, offset);
+                format!("linear-gradient({}deg, {stops})", number(angle))
+            }
+            // CSS has no diamond gradients, they are approximated with radial ones.
+            PaintType::GradientRadial | PaintType::GradientDiamond => {
+                let (center, end, side) = (handle(0)?, handle(1)?, handle(2)?);
+                let stops = self.stops(paint, |position| position);
+                format!(
+                    "radial-gradient(ellipse {} {} at {} {}, {stops})",
+                    px((end.0 - center.0).hypot(end.1 - center.1)),
+                    px((side.0 - center.0).hypot(side.1 - center.1)),
+                    px(center.0),
+                    px(center.1),
+                )
+            }
+            PaintType::GradientAngular => {
+                let (center, end) = (handle(0)?, handle(1)?);
+                let stops = self.stops(paint, |position| position);
+                format!(
+                    "conic-gradient(from {}deg at {} {}, {stops})",
+                    number(angle(center, end)),
+                    px(center.0),
+                    px(center.1),
+                )
+            }
+        };
+        Some(function)
+    }
+
+    /// The color stops of a gradient, at the offsets of their positions.
+    fn stops(&mut self, paint: &Paint, offset: impl Fn(f32) -> f32) -> String {
+        paint
+            .gradient_stops
+            .iter()
+            .map(|stop| {
+                let bound = stop.bound_variables.get("color");
+                let color = self.color(bound, &stop.color, paint.opacity);
+                format!("{color} {}%", number(offset(stop.position) * 100.0))
+            })
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+
+    /// The border or outline drawing the top most visible solid stroke.
+    fn stroke<Data>(&mut self, vector: &VectorNode<Data>) -> Vec<Declaration> {
+        let paint = vector
+            .strokes
+            .iter()
+            .rev()
+            .find(|paint| paint.visible && paint.paint_type == PaintType::Solid);
+        let Some(paint) = paint.filter(|_| vector.stroke_weight > 0.0) else {
+            return Vec::new();
+        };
+        let color = self.paint_color(paint);
+        let style = match vector.stroke_dashes.is_empty() {
+            true => "solid",
+            false => "dashed",
+        };
+        let weight = vector.stroke_weight;
+        if vector.stroke_align != StrokeAlign::Inside {
+            let mut declarations = vec![Declaration::new(
+                "outline",
+                format!("{} {style} {color}", px(weight)),
+            )];
+            if vector.stroke_align == StrokeAlign::Center {
+                declarations.push(Declaration::new("outline-offset", px(-weight / 2.0)));
+            }
+            return declarations;
+        }
+        let sides = values::stroke_weights(vector);
+        let mut declarations = vec![Declaration::new("box-sizing", "border-box")];
+        match values::uniform(sides) && sides[0] == weight {
+            false => declarations.extend([
+                Declaration::new("border-width", sides.map(px).join(" ")),
+                Declaration::new("border-style", style),
+                Declaration::new("border-color", color),
+            ]),
+            true => declarations.push(Declaration::new(
+                "border",
+                format!("{} {style} {color}", px(weight)),
+            )),
+        }
+        declarations
+    }
+
+    fn effects(&mut self, effects: &[Effect]) -> Vec<Declaration> {
+        let mut shadows = Vec::new();
+        let mut filters = Vec::new();
+        let mut backdrop_filters = Vec::new();
+        // The first effect is the bottom most, the first CSS shadow the top most.
+        for effect in effects.iter().rev().filter(|effect| effect.visible) {
+            let bound = |field: &str| effect.bound_variables.get(field);
+            match effect.effect_type {
+                EffectType::DropShadow | EffectType::InnerShadow => {
+                    let inset = match effect.effect_type {
+                        EffectType::InnerShadow => "inset ",
+                        _ => "",
+                    };
+                    shadows.push(format!(
+                        "{inset}{} {} {} {} {}",
+                        self.length(bound("offsetX"), effect.offset.x),
+                        self.length(bound("offsetY"), effect.offset.y),
+                        self.length(bound("radius"), effect.radius),
+                        self.length(bound("spread"), effect.spread),
+                        self.color(bound("color"), &effect.color, 1.0),
+                    ));
+                }
+                EffectType::LayerBlur => filters.push(self.blur(effect)),
+                EffectType::BackgroundBlur => backdrop_filters.push(self.blur(effect)),
+            }
+        }
+        [
+            ("box-shadow", shadows.join(", ")),
+            ("filter", filters.join(" ")),
+            ("backdrop-filter", backdrop_filters.join(" ")),
+        ]
+        .into_iter()
+        .filter(|(_, value)| !value.is_empty())
+        .map(|(property, value)| Declaration::new(property, value))
+        .collect()
+    }
+
+    fn blur(&mut self, effect: &Effect) -> String {
+        // Figma radii are twice the standard deviation of the gaussian blur, CSS radii are equal to
+        // it.
+        match self.variable(effect.bound_variables.get("radius")) {
+            Some((name, true)) => format!("blur(calc(var({name}) / 2))"),
+            Some((name, false)) => format!("blur(calc(var({name}) * 0.5px))"),
+            None => format!("blur({})", px(effect.radius / 2.0)),
+        }
+    }
+
+    /// The font and text properties of a text style.
+    pub fn type_style(&mut self, style: &TypeStyle) -> Vec<Declaration> {
+        let mut declarations = vec![
+            Declaration::new("font-family", format!("\"{}\"", style.font_family)),
+            Declaration::new("font-size", px(style.font_size)),
+            Declaration::new("font-weight", style.font_weight.to_string()),
+        ];
+        if style.italic {
+            declarations.push(Declaration::new("font-style", "italic"));
+        }
+        let line_height = match style.line_height_unit {
+            TypeStyleLineHeightUnit::Pixels => px(style.line_height_px),
+            TypeStyleLineHeightUnit::FontSize => {
+                format!("{}%", number(style.line_height_percent_font_size))
+            }
+            TypeStyleLineHeightUnit::Intrinsic => "normal".to_string(),
+        };
+        declarations.push(Declaration::new("line-height", line_height));
+        if style.letter_spacing != 0.0 {
+            declarations.push(Declaration::new("letter-spacing", px(style.letter_spacing)));
+        }
+        let align = match style.text_align_horizontal {
+            TypeStyleTextAlignHorizontal::Left => "left",
+            TypeStyleTextAlignHorizontal::Right => "right",
+            TypeStyleTextAlignHorizontal::Center => "center",
+            TypeStyleTextAlignHorizontal::Justified => "justify",
+        };
+        declarations.push(Declaration::new("text-align", align));
+        declarations.push(match style.text_case {
+            TypeStyleTextCase::Upper => Declaration::new("text-transform", "uppercase"),
+            TypeStyleTextCase::Lower => Declaration::new("text-transform", "lowercase"),
+            TypeStyleTextCase::Title => Declaration::new("text-transform", "capitalize"),
+            TypeStyleTextCase::SmallCaps => Declaration::new("font-variant-caps", "small-caps"),
+            TypeStyleTextCase::SmallCapsForced => {
+                Declaration::new("font-variant-caps", "all-small-caps")
+            }
+        });
+        let decoration = match style.text_decoration {
+            TypeStyleTextDecoration::StrikeThrought => "line-through",
+            TypeStyleTextDecoration::Underline => "underline",
+        };
+        declarations.push(Declaration::new("text-decoration", decoration));
+        if style.paragraph_indent > 0.0 {
+            declarations.push(Declaration::new("text-indent", px(style.paragraph_indent)));
+        }
+        if !style.opentype_flags.is_empty() {
+            let mut flags: Vec<_> = style.opentype_flags.iter().collect();
+            flags.sort_unstable();
+            let flags: Vec<String> = flags
+                .into_iter()
+                .map(|(flag, value)| format!("\"{}\" {value}", flag.to_lowercase()))
+                .collect();
+            declarations.push(Declaration::new("font-feature-settings", flags.join(", ")));
+        }
+        if style.text_truncation == TypeStyleTextTruncation::Ending {
+            declarations.extend([
+                Declaration::new("overflow", "hidden"),
+                Declaration::new("text-overflow", "ellipsis"),
+            ]);
+            match style.max_lines {
+                lines if lines > 1 => declarations.extend([
+                    Declaration::new("display", "-webkit-box"),
+                    Declaration::new("-webkit-box-orient", "vertical"),
+                    Declaration::new("-webkit-line-clamp", lines.to_string()),
+                ]),
+                _ => declarations.push(Declaration::new("white-space", "nowrap")),
+            }
+        }
+        let fill = style
+            .fills
+            .iter()
+            .rev()
+            .find(|paint| paint.visible && paint.paint_type == PaintType::Solid);
+        if let Some(fill) = fill {
+            let color = self.paint_color(fill);
+            declarations.push(Declaration::new("color", color));
+        }
+        declarations
+    }
+
+    fn paint_color(&mut self, paint: &Paint) -> String {
+        let bound = paint
+            .bound_variables
+            .as_ref()
+            .and_then(|variables| variables.get("color"));
+        self.color(bound, &paint.color, paint.opacity)
+    }
+
+    /// A color, or a reference to the variable it is bound to, mixed with transparency by an
+    /// opacity.
+    fn color(&mut self, bound: Option<&VariableAlias>, color: &Color, opacity: f32) -> String {
+        match self.variable(bound) {
+            Some((name, _)) if opacity < 1.0 => format!(
+                "color-mix(in srgb, var({name}) {}%, transparent)",
+                number(opacity * 100.0)
+            ),
+            Some((name, _)) => format!("var({name})"),
+            None => css_color(color, opacity),
+        }
+    }
+
+    /// A length in pixels, or a reference to the variable it is bound to.
+    fn length(&mut self, bound: Option<&VariableAlias>, value: f32) -> String {
+        match self.variable(bound) {
+            Some((name, true)) => format!("var({name})"),
+            Some((name, false)) => format!("calc(var({name}) * 1px)"),
+            None => px(value),
+        }
+    }
+
+    /// The custom property of a bound variable of the document and whether it is a length,
+    /// recording it is referred to.
+    fn variable(&mut self, bound: Option<&VariableAlias>) -> Option<(String, bool)> {
+        let variable = crate::variable(self.document, bound)?;
+        self.variables.insert(&variable.id);
+        Some((
+            custom_property(&variable.name),
+            tokens::is_dimension(variable),
+        ))
+    }
+
+    /// The value of a custom property for a variable value, `None` for expressions and aliases
+    /// of unknown variables.
+    fn variable_value(&self, value: &VariableDataValue, dimension: bool) -> Option<String> {
+        Some(match value {
+            VariableDataValue::Boolean(value) => value.to_string(),
+            VariableDataValue::Float(value) if dimension => px(*value),
+            VariableDataValue::Float(value) => number(*value),
+            VariableDataValue::String(value) => format!("{value:?}"),
+            VariableDataValue::Color(color) => css_color(color, 1.0),
+            VariableDataValue::RGBColor(color) => css_color(
+                &Color {
+                    r: color.r,
+                    g: color.g,
+                    b: color.b,
+                    a: 1.0,
+                },
+                1.0,
+            ),
+            VariableDataValue::VariableAlias(alias) => {
+                let variable = self.document.variables.get(&alias.id)?;
+                format!("var({})", custom_property(&variable.name))
+            }
+            VariableDataValue::Expression(_) => return None,
+        })
+    }
+}
+
+/// The CSS angle of the direction from a point to another, clockwise from the top.
+fn angle(from: (f32, f32), to: (f32, f32)) -> f32 {
+    (to.0 - from.0)
+        .atan2(from.1 - to.1)
+        .to_degrees()
+        .rem_euclid(360.0)
+}
+
+/// A color as a hexadecimal color when it is opaque and as an `rgba()` color otherwise.
+fn css_color(color: &Color, opacity: f32) -> String {
+    let alpha = (color.a * opacity).clamp(0.0, 1.0);
+    match alpha < 1.0 {
+        true => {
+            let [r, g, b] = [color.r, color.g, color.b].map(values::channel);
+            format!("rgba({r}, {g}, {b}, {})", number(alpha))
+        }
+        false => values::hex(color, opacity),
+    }
+}
+
+/// The name of the custom property of a variable: its name in kebab case.
+fn custom_property(name: &str) -> String {
+    format!("--{}", slug(name))
+}
+
+/// Lower case words of a name joined with dashes.
+pub(crate) fn slug(name: &str) -> String {
+    crate::words(name)
+        .map(str::to_lowercase)
+        .collect::<Vec<_>>()
+        .join("-")
+}
+
+fn px(value: f32) -> String {
+    match number(value).as_str() {
+        "0" => "0".to_string(),
+        value => format!("{value}px"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use fig_types::properties::{
+        Style, StyleType, StyleValue, Variable, VariableCollection, VariableMode,
+    };
+    use serde_json::{json, Value};
+
+    use super::*;
+    use crate::fixtures::{self, effect, layer, paint, solid};
+
+    fn css(document: &Document, node: &Node) -> String {
+        let declarations = Css::new(document).declarations(node);
+        let declarations: Vec<String> = declarations.iter().map(ToString::to_string).collect();
+        declarations.join("\n")
+    }
+
+    #[test]
+    fn layers_are_styled() {
+        let mut stroke = solid(0.0, 0.0, 1.0);
+        stroke["opacity"] = json!(0.5);
+        let mut node = layer(
+            "rectangle",
+            "Card",
+            json!({
+                "fills": [solid(1.0, 1.0, 1.0)],
+                "strokes": [stroke],
+                "strokeWeight": 2.0,
+                "strokeDashes": [4.0, 2.0],
+                "effects": [
+                    effect("DropShadow", 4.0),
+                    effect("InnerShadow", 2.0),
+                    effect("LayerBlur", 8.0),
+                    effect("BackgroundBlur", 16.0),
+                ],
+                "opacity": 0.8,
+                "blendMode": "Multiply",
+                "layoutAlign": "Stretch",
+                "layoutGrow": 1,
+                "additionalData": {
+                    "cornerRadius": 0.0,
+                    "rectangleCornerRadii": [8.0, 8.0, 0.0, 0.0],
+                    "cornerSmoothing": 0.0,
+                },
+            }),
+        );
+        node.rotation = 45.0;
+
+        assert_eq!(
+            css(&Document::new("Css"), &node),
+            "width: 100px;
+height: 50px;
+align-self: stretch;
+flex-grow: 1;
+transform: rotate(45deg);
+background-color: #ffffff;
+box-sizing: border-box;
+border: 2px dashed rgba(0, 0, 255, 0.5);
+border-radius: 8px 8px 0 0;
+box-shadow: inset 0 2px 2px 0 rgba(0, 0, 0, 0.25), 0 2px 4px 0 rgba(0, 0, 0, 0.25);
+filter: blur(4px);
+backdrop-filter: blur(8px);
+opacity: 0.8;
+mix-blend-mode: multiply;"
+        );
+    }
+
+    #[test]
+    fn strokes_outside_and_centered_are_outlines() {
+        let document = Document::new("Css");
+        let mut node = layer(
+            "vector",
+            "Icon",
+            json!({ "strokes": [solid(0.0, 0.0, 0.0)], "strokeWeight": 2.0 }),
+        );
+        if let NodeType::Vector(vector) = &mut node.node {
+            vector.stroke_align = StrokeAlign::Center;
+        }
+        let declarations = Css::new(&document).declarations(&node);
+        assert_eq!(
+            declarations[2..],
+            [
+                Declaration::new("outline", "2px solid #000000"),
+                Declaration::new("outline-offset", "-1px"),
+            ]
+        );
+    }
+
+    #[test]
+    fn gradients_are_projected_on_css_gradient_lines() {
+        let stops = json!([
+            { "position": 0.0, "color": { "r": 1.0, "g": 0.0, "b": 0.0, "a": 1.0 }, "boundVariables": {} },
+            { "position": 1.0, "color": { "r": 0.0, "g": 0.0, "b": 1.0, "a": 1.0 }, "boundVariables": {} },
+        ]);
+        let gradient = |paint_type: &str, handles: Value| {
+            paint(
+                paint_type,
+                json!({ "r": 0.0, "g": 0.0, "b": 0.0, "a": 1.0 }),
+                stops.clone(),
+                handles,
+            )
+        };
+        let node = layer(
+            "vector",
+            "Gradients",
+            json!({ "fills": [
+                gradient("GradientLinear", json!([
+                    { "x": 0.0, "y": 0.5 }, { "x": 0.5, "y": 0.5 }, { "x": 0.0, "y": 1.0 },
+                ])),
+                gradient("GradientRadial", json!([
+                    { "x": 0.5, "y": 0.5 }, { "x": 1.0, "y": 0.5 }, { "x": 0.5, "y": 1.0 },
+                ])),
+                gradient("GradientAngular", json!([
+                    { "x": 0.5, "y": 0.5 }, { "x": 0.5, "y": 1.0 }, { "x": 0.0, "y": 0.5 },
+                ])),
+            ]}),
+        );
+
+        let declarations = Css::new(&Document::new("Css")).declarations(&node);
+        assert_eq!(
+            declarations[2].value,
+            "conic-gradient(from 180deg at 50px 25px, #ff0000 0%, #0000ff 100%), \
+             radial-gradient(ellipse 50px 25px at 50px 25px, #ff0000 0%, #0000ff 100%), \
+             linear-gradient(90deg, #ff0000 0%, #0000ff 50%)"
+        );
+    }
+
+    #[test]
+    fn text_styles_become_font_properties() {
+        let mut document = Document::new("Css");
+        let style: StyleValue = serde_json::from_value(json!({ "Text": {
+            "fontFamily": "Inter",
+            "fontPostScriptName": "Inter-Bold",
+            "paragraphSpacing": 0.0,
+            "paragraphIndent": 0.0,
+            "listSpacing": 0.0,
+            "italic": true,
+            "fontWeight": 700,
+            "fontSize": 32.0,
+            "textCase": "Upper",
+            "textDecoration": "Underline",
+            "textAutoResize": "Height",
+            "textTruncation": "Ending",
+            "maxLines": 2,
+            "textAlignHorizontal": "Justified",
+            "textAlignVertical": "Top",
+            "letterSpacing": -0.5,
+            "fills": [solid(0.0, 0.0, 0.0)],
+            "hyperlink": { "hyperLinkType": "Url", "url": "", "nodeId": "" },
+            "opentypeFlags": { "TNUM": 1 },
+            "lineHeightPx": 40.0,
+            "lineHeightPercent": 100.0,
+            "lineHeightPercentFontSize": 125.0,
+            "lineHeightUnit": "FontSize",
+            "isOverrideOverTextStyle": false,
+            "semanticWeight": "Bold",
+            "semanticItalic": "Italic",
+        }}))
+        .unwrap();
+        document.styles.insert(
+            "S:1".to_string(),
+            Style {
+                key: String::new(),
+                name: "Heading".to_string(),
+                description: String::new(),
+                remote: false,
+                style_type: StyleType::Text,
+                value: Some(style),
+            },
+        );
+        let node = layer("vector", "Title", json!({ "styles": { "Text": "S:1" } }));
+
+        assert_eq!(
+            css(&document, &node),
+            "width: 100px;
+height: 50px;
+font-family: \"Inter\";
+font-size: 32px;
+font-weight: 700;
+font-style: italic;
+line-height: 125%;
+letter-spacing: -0.5px;
+text-align: justify;
+text-transform: uppercase;
+text-decoration: underline;
+font-feature-settings: \"tnum\" 1;
+overflow: hidden;
+text-overflow: ellipsis;
+display: -webkit-box;
+-webkit-box-orient: vertical;
+-webkit-line-clamp: 2;
+color: #000000;"
+        );
+    }
+
+    #[test]
+    fn bound_values_refer_to_custom_properties() {
+        let mut document = Document::new("Css");
+        let variable = |id: &str, name: &str, scopes: &[&str], light, dark| Variable {
+            id: id.to_string(),
+            name: name.to_string(),
+            key: String::new(),
+            variable_collection_id: "C:1".to_string(),
+            resolved_type: fig_types::properties::VariableResolvedDataType::Color,
+            values_by_mode: HashMap::from([("M:1".to_string(), light), ("M:2".to_string(), dark)]),
+            remote: false,
+            description: String::new(),
+            hidden_from_publishing: false,
+            scopes: scopes.iter().map(|scope| scope.to_string()).collect(),
+            code_syntax: HashMap::new(),
+        };
+        let color = |r, g, b| VariableDataValue::Color(Color { r, g, b, a: 1.0 });
+        let alias = VariableDataValue::VariableAlias(VariableAlias {
+            variable_alias_type: "VARIABLE_ALIAS".to_string(),
+            id: "V:1".to_string(),
+        });
+        for variable in [
+            variable(
+                "V:1",
+                "Color/Primary",
+                &[],
+                color(1.0, 0.0, 0.0),
+                color(0.0, 0.0, 1.0),
+            ),
+            variable("V:2", "Color/Accent", &[], alias.clone(), alias),
+            variable(
+                "V:3",
+                "Shadow Blur",
+                &["EFFECT_FLOAT"],
+                VariableDataValue::Float(8.0),
+                VariableDataValue::Float(8.0),
+            ),
+            variable(
+                "V:4",
+                "Unused",
+                &[],
+                color(0.0, 0.0, 0.0),
+                color(1.0, 1.0, 1.0),
+            ),
+        ] {
+            document.variables.insert(variable.id.clone(), variable);
+        }
+        document.variable_collections.insert(
+            "C:1".to_string(),
+            VariableCollection {
+                id: "C:1".to_string(),
+                name: "Theme".to_string(),
+                key: String::new(),
+                modes: vec![
+                    VariableMode {
+                        mode_id: "M:1".to_string(),
+                        name: "Light".to_string(),
+                    },
+                    VariableMode {
+                        mode_id: "M:2".to_string(),
+                        name: "Dark".to_string(),
+                    },
+                ],
+                default_mode_id: "M:1".to_string(),
+                remote: false,
+                hidden_from_publishing: false,
+                variable_ids: vec!["V:1".to_string(), "V:2".to_string(), "V:3".to_string()],
+            },
+        );
+        let mut fill = solid(1.0, 0.0, 0.0);
+        fill["opacity"] = json!(0.5);
+        fill["boundVariables"] =
+            json!({ "color": { "variableAliasType": "VARIABLE_ALIAS", "id": "V:2" } });
+        let mut shadow = effect("DropShadow", 8.0);
+        shadow["boundVariables"] =
+            json!({ "radius": { "variableAliasType": "VARIABLE_ALIAS", "id": "V:3" } });
+        let node = layer(
+            "vector",
+            "Badge",
+            json!({ "fills": [fill], "effects": [shadow] }),
+        );
+        let canvas = &mut document.document.children_mut().unwrap()[0];
+        *canvas.children_mut().unwrap() = vec![node.clone(), node];
+
+        let root = document.document.clone();
+        assert_eq!(
+            Css::new(&document).stylesheet(&root),
+            ":root {
+  --color-primary: #ff0000;
+  --color-accent: var(--color-primary);
+  --shadow-blur: 8px;
+}
+
+[data-theme=\"dark\"] {
+  --color-primary: #0000ff;
+}
+
+.badge {
+  width: 100px;
+  height: 50px;
+  background-color: color-mix(in srgb, var(--color-accent) 50%, transparent);
+  box-shadow: 0 2px var(--shadow-blur) 0 rgba(0, 0, 0, 0.25);
+}
+
+.badge-2 {
+  width: 100px;
+  height: 50px;
+  background-color: color-mix(in srgb, var(--color-accent) 50%, transparent);
+  box-shadow: 0 2px var(--shadow-blur) 0 rgba(0, 0, 0, 0.25);
+}
+"
+        );
+    }
+
+    #[test]
+    fn canvas_snapshot() {
+        let document = fixtures::document();
+        let canvas = &document.document.children()[0];
+        insta::assert_snapshot!(Stylesheet.generate(&document, canvas));
+    }
+}
@@ -0,0 +1,196 @@
+//! The document shared by the tests of the backends, and layers of its size.
+
+use std::collections::HashMap;
+
+use fig_file::document::Document;
+use fig_file::fixtures;
+use fig_types::node::Node;
+use fig_types::properties::{
+    Color, Style, StyleType, Variable, VariableAlias, VariableCollection, VariableDataValue,
+    VariableMode, VariableResolvedDataType,
+};
+use serde_json::{json, Value};
+
+pub(crate) use fig_file::fixtures::{effect, paint, solid};
+
+/// A 100 by 50 layer `1:1` with the properties of the defaults replaced.
+pub(crate) fn layer(node_type: &str, name: &str, properties: Value) -> Node {
+    let mut data = fixtures::bounds(0.0, 0.0, 100.0, 50.0);
+    for (key, value) in properties.as_object().unwrap() {
+        data[key] = value.clone();
+    }
+    fixtures::layer(node_type, "1:1", name, data)
+}
+
+fn at(x: f32, y: f32) -> Value {
+    json!({ "matrix": [[1.0, 0.0, x], [0.0, 1.0, y]] })
+}
+
+fn stops() -> Value {
+    json!([
+        { "position": 0.0, "color": { "r": 1.0, "g": 0.0, "b": 0.0, "a": 1.0 }, "boundVariables": {} },
+        { "position": 1.0, "color": { "r": 0.0, "g": 0.0, "b": 1.0, "a": 1.0 }, "boundVariables": {} },
+    ])
+}
+
+pub(crate) fn type_style() -> Value {
+    json!({
+        "fontFamily": "Inter",
+        "fontPostScriptName": "Inter-Bold",
+        "paragraphSpacing": 0.0,
+        "paragraphIndent": 0.0,
+        "listSpacing": 0.0,
+        "italic": false,
+        "fontWeight": 700,
+        "fontSize": 32.0,
+        "textCase": "Title",
+        "textDecoration": "Underline",
+        "textAutoResize": "Height",
+        "textTruncation": "Ending",
+        "maxLines": 2,
+        "textAlignHorizontal": "Center",
+        "textAlignVertical": "Top",
+        "letterSpacing": -0.5,
+        "fills": [solid(0.1, 0.1, 0.1)],
+        "hyperlink": { "hyperLinkType": "Url", "url": "", "nodeId": "" },
+        "opentypeFlags": {},
+        "lineHeightPx": 40.0,
+        "lineHeightPercent": 100.0,
+        "lineHeightPercentFontSize": 125.0,
+        "lineHeightUnit": "Pixels",
+        "isOverrideOverTextStyle": false,
+        "semanticWeight": "Bold",
+        "semanticItalic": "Normal",
+    })
+}
+
+/// A document whose canvas has a card with a shadow, a hero with a gradient and uneven corners, a
+/// title with a text style and a rotated badge painted with a variable of a light and dark theme.
+pub(crate) fn document() -> Document {
+    let mut document = Document::new("Codegen");
+    let color = |r, g, b| VariableDataValue::Color(Color { r, g, b, a: 1.0 });
+    let primary = Variable {
+        id: "V:1".to_string(),
+        name: "Color/Primary".to_string(),
+        key: String::new(),
+        variable_collection_id: "C:1".to_string(),
+        resolved_type: VariableResolvedDataType::Color,
+        values_by_mode: HashMap::from([
+            ("M:1".to_string(), color(0.2, 0.4, 1.0)),
+            ("M:2".to_string(), color(0.6, 0.8, 1.0)),
+        ]),
+        remote: false,
+        description: String::new(),
+        hidden_from_publishing: false,
+        scopes: Vec::new(),
+        code_syntax: HashMap::new(),
+    };
+    document.variables.insert(primary.id.clone(), primary);
+    document.variable_collections.insert(
+        "C:1".to_string(),
+        VariableCollection {
+            id: "C:1".to_string(),
+            name: "Theme".to_string(),
+            key: String::new(),
+            modes: vec![
+                VariableMode {
+                    mode_id: "M:1".to_string(),
+                    name: "Light".to_string(),
+                },
+                VariableMode {
+                    mode_id: "M:2".to_string(),
+                    name: "Dark".to_string(),
+                },
+            ],
+            default_mode_id: "M:1".to_string(),
+            remote: false,
+            hidden_from_publishing: false,
+            variable_ids: vec!["V:1".to_string()],
+        },
+    );
+    document.styles.insert(
+        "S:1".to_string(),
+        Style {
+            key: String::new(),
+            name: "Heading".to_string(),
+            description: String::new(),
+            remote: false,
+            style_type: StyleType::Text,
+            value: Some(serde_json::from_value(json!({ "Text": type_style() })).unwrap()),
+        },
+    );
+
+    let mut border = solid(0.9, 0.9, 0.9);
+    border["opacity"] = json!(0.5);
+    let card = layer(
+        "rectangle",
+        "Card",
+        json!({
+            "relativeTransform": at(16.0, 24.0),
+            "fills": [solid(1.0, 1.0, 1.0)],
+            "strokes": [border],
+            "strokeWeight": 1.0,
+            "effects": [effect("DropShadow", 8.0)],
+            "additionalData": {
+                "cornerRadius": 12.0,
+                "rectangleCornerRadii": [0.0, 0.0, 0.0, 0.0],
+                "cornerSmoothing": 0.0,
+            },
+        }),
+    );
+    let black = json!({ "r": 0.0, "g": 0.0, "b": 0.0, "a": 1.0 });
+    let handles = json!([{ "x": 0.0, "y": 0.5 }, { "x": 1.0, "y": 0.5 }, { "x": 0.0, "y": 1.0 }]);
+    let hero = layer(
+        "rectangle",
+        "Hero",
+        json!({
+            "relativeTransform": at(16.0, 100.0),
+            "fills": [paint("GradientLinear", black, stops(), handles)],
+            "effects": [effect("BackgroundBlur", 20.0)],
+            "additionalData": {
+                "cornerRadius": 0.0,
+                "rectangleCornerRadii": [8.0, 8.0, 0.0, 0.0],
+                "cornerSmoothing": 0.0,
+            },
+        }),
+    );
+    let title = layer(
+        "vector",
+        "Title",
+        json!({ "relativeTransform": at(16.0, 160.0), "styles": { "Text": "S:1" } }),
+    );
+    let mut fill = solid(0.2, 0.4, 1.0);
+    fill["boundVariables"] = json!({ "color": VariableAlias {
+        variable_alias_type: "VARIABLE_ALIAS".to_string(),
+        id: "V:1".to_string(),
+    }});
+    let mut badge = layer(
+        "vector",
+        "Badge",
+        json!({
+            "relativeTransform": at(120.0, 24.0),
+            "fills": [fill],
+            "strokes": [solid(0.0, 0.0, 0.0)],
+            "strokeWeight": 2.0,
+            "strokeDashes": [4.0, 2.0],
+            "strokeAlign": "Center",
+            "effects": [effect("LayerBlur", 4.0)],
+            "opacity": 0.8,
+            "blendMode": "Multiply",
+        }),
+    );
+    badge.rotation = 45.0;
+
+    let canvas = &mut document.document.children_mut().unwrap()[0];
+    canvas.name = "Home".to_string();
+    let layers = [card, hero, title, badge];
+    *canvas.children_mut().unwrap() = layers
+        .into_iter()
+        .enumerate()
+        .map(|(index, mut layer)| {
+            layer.id = format!("1:{}", index + 1);
+            layer
+        })
+        .collect();
+    document
+}
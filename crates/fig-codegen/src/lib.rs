@@ -0,0 +1,110 @@
+//! Generation of code implementing the layers of a document, for the developers a design is
+//! handed off to.
+//!
+//! Each target is a [`Backend`]. Layers with a text style are generated as text showing the name
+//! of the layer, the other layers as boxes or shapes, and canvases as containers placing their
+//! layers at their position.
+
+use fig_file::document::Document;
+use fig_types::node::vector::VectorNode;
+use fig_types::node::Node;
+use fig_types::properties::{StyleType, StyleValue, TypeStyle, Variable, VariableAlias};
+
+pub mod compose;
+pub mod css;
+pub mod swiftui;
+pub mod tailwind;
+
+#[cfg(test)]
+mod fixtures;
+
+/// A language or framework code is generated for.
+pub trait Backend {
+    /// Name of the target, e.g. to select it from the command line.
+    fn name(&self) -> &'static str;
+
+    /// Code implementing a node: a view or component for each canvas of a document, one for the
+    /// layers of a canvas, or one for a single layer.
+    fn generate(&self, document: &Document, node: &Node) -> String;
+}
+
+/// Every backend, in the order they are offered to users.
+pub fn backends() -> Vec<Box<dyn Backend>> {
+    vec![
+        Box::new(css::Stylesheet),
+        Box::new(tailwind::Tailwind),
+        Box::new(swiftui::SwiftUi),
+        Box::new(compose::Compose),
+    ]
+}
+
+/// The backend with a name, `None` if there is none.
+pub fn backend(name: &str) -> Option<Box<dyn Backend>> {
+    backends()
+        .into_iter()
+        .find(|backend| backend.name().eq_ignore_ascii_case(name))
+}
+
+/// The value of the text style of a layer, `None` if it has none.
+pub(crate) fn text_style<'a, Data>(
+    document: &'a Document,
+    vector: &VectorNode<Data>,
+) -> Option<&'a TypeStyle> {
+    let id = vector.styles.as_ref()?.get(&StyleType::Text)?;
+    match &document.styles.get(id)?.value {
+        Some(StyleValue::Text(style)) => Some(style),
+        _ => None,
+    }
+}
+
+/// The position of a layer in its parent, from its relative transform.
+pub(crate) fn position<Data>(vector: &VectorNode<Data>) -> (f32, f32) {
+    match vector.relative_transform.matrix.as_slice() {
+        [first, second] if first.len() == 3 && second.len() == 3 => (first[2], second[2]),
+        _ => (0.0, 0.0),
+    }
+}
+
+/// The variable of the document a value is bound to, `None` for unbound values and variables of
+/// other documents.
+pub(crate) fn variable<'a>(
+    document: &'a Document,
+    bound: Option<&VariableAlias>,
+) -> Option<&'a Variable> {
+    document.variables.get(&bound?.id)
+}
+
+/// The alphanumeric words of a name.
+pub(crate) fn words(name: &str) -> impl Iterator<Item = &str> {
+    name.split(|character: char| !character.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+}
+
+/// A name in upper camel case, prefixed with an underscore when it starts with a digit.
+pub(crate) fn pascal_case(name: &str) -> String {
+    let name: String = words(name)
+        .map(|word| {
+            let mut characters = word.chars();
+            let first = characters.next().into_iter().flat_map(char::to_uppercase);
+            first.chain(characters).collect::<String>()
+        })
+        .collect();
+    match name.starts_with(|character: char| character.is_ascii_digit()) {
+        true => format!("_{name}"),
+        false => name,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn backends_are_found_by_name() {
+        let names: Vec<&str> = backends().iter().map(|backend| backend.name()).collect();
+        assert_eq!(names, ["css", "tailwind", "swiftui", "compose"]);
+        assert_eq!(backend("SwiftUI").unwrap().name(), "swiftui");
+        assert!(backend("flutter").is_none());
+        assert_eq!(pascal_case("2 column layout/Home"), "_2ColumnLayoutHome");
+    }
+}
@@ -0,0 +1,454 @@
+//! SwiftUI views for the layers of a document.
+//!
+//! Rectangles become rounded rectangles, other layers rectangles and layers with a text style
+//! texts. Colors bound to variables refer to the color of the asset catalog named like the
+//! variable. Image, emoji and video paints and inner shadows have no SwiftUI equivalent and are
+//! left out, background blurs become an ultra thin material.
+
+use fig_file::document::Document;
+use fig_file::values::{self, number};
+use fig_types::node::vector::VectorNode;
+use fig_types::node::{Node, NodeType};
+use fig_types::properties::{
+    BlendMode, Color, EffectType, Paint, PaintType, StrokeAlign, TypeStyle,
+    TypeStyleLineHeightUnit, TypeStyleTextAlignHorizontal, TypeStyleTextCase,
+    TypeStyleTextDecoration, TypeStyleTextTruncation, VariableAlias,
+};
+
+use crate::{pascal_case, Backend};
+
+/// Indentation of the generated code.
+const INDENT: &str = "    ";
+
+/// The SwiftUI backend, generating a view for each canvas or layer.
+pub struct SwiftUi;
+
+impl Backend for SwiftUi {
+    fn name(&self) -> &'static str {
+        "swiftui"
+    }
+
+    fn generate(&self, document: &Document, node: &Node) -> String {
+        match &node.node {
+            NodeType::Document(_) => node
+                .children()
+                .iter()
+                .map(|canvas| view(document, canvas))
+                .collect::<Vec<_>>()
+                .join("\n"),
+            _ => view(document, node),
+        }
+    }
+}
+
+/// A view struct named after a canvas or a layer.
+fn view(document: &Document, node: &Node) -> String {
+    let body = match &node.node {
+        NodeType::Canvas(_) => {
+            let mut lines = vec!["ZStack(alignment: .topLeading) {".to_string()];
+            for layer in node.children() {
+                lines.extend(indent(self::layer(document, layer, true)));
+            }
+            lines.push("}".to_string());
+            lines
+        }
+        _ => layer(document, node, false),
+    };
+    let mut view = format!(
+        "struct {}: View {{\n{INDENT}var body: some View {{\n",
+        pascal_case(&node.name)
+    );
+    for line in indent(indent(body)) {
+        view.push_str(&line);
+        view.push('\n');
+    }
+    view.push_str(&format!("{INDENT}}}\n}}\n"));
+    view
+}
+
+/// The view of a layer followed by its modifiers, placed at its position in its parent if
+/// `positioned`.
+fn layer(document: &Document, node: &Node, positioned: bool) -> Vec<String> {
+    match &node.node {
+        NodeType::Document(_) | NodeType::Canvas(_) => Vec::new(),
+        NodeType::Vector(vector) => vector_view(document, node, vector, None, positioned),
+        NodeType::Rectangle(vector) => {
+            let radii = values::corner_radii(&vector.additional_data);
+            vector_view(document, node, vector, Some(radii), positioned)
+        }
+    }
+}
+
+fn vector_view<Data>(
+    document: &Document,
+    node: &Node,
+    vector: &VectorNode<Data>,
+    radii: Option<[f32; 4]>,
+    positioned: bool,
+) -> Vec<String> {
+    let (width, height) = (vector.size.width, vector.size.height);
+    let shape = shape(radii);
+    let text = crate::text_style(document, vector);
+    let mut lines = match text {
+        Some(style) => {
+            let mut lines = text_view(document, &node.name, style);
+            lines.push(format!(
+                ".frame(width: {}, height: {}, alignment: .topLeading)",
+                number(width),
+                number(height)
+            ));
+            lines
+        }
+        None => {
+            let fills: Vec<String> = vector
+                .fills
+                .iter()
+                .filter(|paint| paint.visible)
+                .filter_map(|paint| self::paint(document, paint, width, height))
+                .collect();
+            let mut lines = vec![shape.clone()];
+            match fills.split_first() {
+                Some((first, rest)) => {
+                    lines.push(format!(".fill({first})"));
+                    for fill in rest {
+                        lines.push(format!(".overlay({shape}.fill({fill}))"));
+                    }
+                }
+                None => lines.push(".fill(Color.clear)".to_string()),
+            }
+            lines.extend(stroke(document, vector, &shape));
+            lines.push(format!(
+                ".frame(width: {}, height: {})",
+                number(width),
+                number(height)
+            ));
+            lines
+        }
+    };
+    // The first effect is the bottom most.
+    for effect in vector.effects.iter().filter(|effect| effect.visible) {
+        // Figma radii are twice the standard deviation of the gaussian blur, SwiftUI radii are
+        // equal to it.
+        let radius = number(effect.radius / 2.0);
+        match effect.effect_type {
+            EffectType::DropShadow => lines.push(format!(
+                ".shadow(color: {}, radius: {radius}, x: {}, y: {})",
+                color(
+                    document,
+                    effect.bound_variables.get("color"),
+                    &effect.color,
+                    1.0
+                ),
+                number(effect.offset.x),
+                number(effect.offset.y)
+            )),
+            EffectType::LayerBlur => lines.push(format!(".blur(radius: {radius})")),
+            EffectType::BackgroundBlur => lines.push(match text {
+                Some(_) => ".background(.ultraThinMaterial)".to_string(),
+                None => format!(".background(.ultraThinMaterial, in: {shape})"),
+            }),
+            EffectType::InnerShadow => {}
+        }
+    }
+    if vector.opacity < 1.0 {
+        lines.push(format!(".opacity({})", number(vector.opacity)));
+    }
+    if let Some(mode) = blend_mode(&vector.blend_mode) {
+        lines.push(format!(".blendMode(.{mode})"));
+    }
+    if node.rotation != 0.0 {
+        lines.push(format!(
+            ".rotationEffect(.degrees({}))",
+            number(node.rotation)
+        ));
+    }
+    if positioned {
+        let (x, y) = crate::position(vector);
+        lines.push(format!(".offset(x: {}, y: {})", number(x), number(y)));
+    }
+    let mut lines = lines.into_iter();
+    let view = lines.next().into_iter();
+    view.chain(lines.map(|modifier| format!("{INDENT}{modifier}")))
+        .collect()
+}
+
+/// A text showing a string with a text style, and its modifiers.
+fn text_view(document: &Document, text: &str, style: &TypeStyle) -> Vec<String> {
+    let mut font = format!(
+        ".custom({:?}, size: {})",
+        style.font_family,
+        number(style.font_size)
+    );
+    if matches!(
+        style.text_case,
+        TypeStyleTextCase::SmallCaps | TypeStyleTextCase::SmallCapsForced
+    ) {
+        font.push_str(".smallCaps()");
+    }
+    let mut lines = vec![
+        format!("Text({text:?})"),
+        format!(".font({font})"),
+        format!(".fontWeight(.{})", font_weight(style.font_weight)),
+    ];
+    if style.italic {
+        lines.push(".italic()".to_string());
+    }
+    if style.letter_spacing != 0.0 {
+        lines.push(format!(".kerning({})", number(style.letter_spacing)));
+    }
+    // SwiftUI only adds spacing to the natural height of the lines.
+    let line_height = match style.line_height_unit {
+        TypeStyleLineHeightUnit::Pixels => Some(style.line_height_px),
+        TypeStyleLineHeightUnit::FontSize => {
+            Some(style.font_size * style.line_height_percent_font_size / 100.0)
+        }
+        TypeStyleLineHeightUnit::Intrinsic => None,
+    };
+    if let Some(spacing) = line_height
+        .map(|height| height - style.font_size)
+        .filter(|spacing| *spacing > 0.0)
+    {
+        lines.push(format!(".lineSpacing({})", number(spacing)));
+    }
+    let alignment = match style.text_align_horizontal {
+        // SwiftUI cannot justify text.
+        TypeStyleTextAlignHorizontal::Left | TypeStyleTextAlignHorizontal::Justified => "leading",
+        TypeStyleTextAlignHorizontal::Center => "center",
+        TypeStyleTextAlignHorizontal::Right => "trailing",
+    };
+    lines.push(format!(".multilineTextAlignment(.{alignment})"));
+    match style.text_case {
+        TypeStyleTextCase::Upper => lines.push(".textCase(.uppercase)".to_string()),
+        TypeStyleTextCase::Lower => lines.push(".textCase(.lowercase)".to_string()),
+        _ => {}
+    }
+    lines.push(match style.text_decoration {
+        TypeStyleTextDecoration::Underline => ".underline()".to_string(),
+        TypeStyleTextDecoration::StrikeThrought => ".strikethrough()".to_string(),
+    });
+    if style.text_truncation == TypeStyleTextTruncation::Ending {
+        lines.push(format!(".lineLimit({})", style.max_lines.max(1)));
+        lines.push(".truncationMode(.tail)".to_string());
+    }
+    let fill = style.fills.iter().rev().find(|paint| paint.visible);
+    if let Some(fill) = fill.and_then(|fill| paint(document, fill, 0.0, 0.0)) {
+        lines.push(format!(".foregroundStyle({fill})"));
+    }
+    lines
+}
+
+/// The shape of a layer.
+fn shape(radii: Option<[f32; 4]>) -> String {
+    let Some(radii) = radii.filter(|radii| radii.iter().any(|radius| *radius > 0.0)) else {
+        return "Rectangle()".to_string();
+    };
+    match radii {
+        [radius, ..] if radii.iter().all(|other| *other == radius) => {
+            format!("RoundedRectangle(cornerRadius: {})", number(radius))
+        }
+        [top_left, top_right, bottom_right, bottom_left] => format!(
+            "UnevenRoundedRectangle(topLeadingRadius: {}, bottomLeadingRadius: {}, \
+             bottomTrailingRadius: {}, topTrailingRadius: {})",
+            number(top_left),
+            number(bottom_left),
+            number(bottom_right),
+            number(top_right)
+        ),
+    }
+}
+
+/// An overlay stroking the shape of a layer with its top most visible solid stroke.
+fn stroke<Data>(document: &Document, vector: &VectorNode<Data>, shape: &str) -> Option<String> {
+    let paint = vector
+        .strokes
+        .iter()
+        .rev()
+        .find(|paint| paint.visible && paint.paint_type == PaintType::Solid)
+        .filter(|_| vector.stroke_weight > 0.0)?;
+    let color = paint_color(document, paint);
+    let weight = vector.stroke_weight;
+    let style = match vector.stroke_dashes.is_empty() {
+        true => format!("lineWidth: {}", number(weight)),
+        false => {
+            let dashes: Vec<String> = vector.stroke_dashes.iter().copied().map(number).collect();
+            format!(
+                "style: StrokeStyle(lineWidth: {}, dash: [{}])",
+                number(weight),
+                dashes.join(", ")
+            )
+        }
+    };
+    let stroke = match vector.stroke_align {
+        StrokeAlign::Inside => format!("strokeBorder({color}, {style})"),
+        StrokeAlign::Center => format!("stroke({color}, {style})"),
+        StrokeAlign::Outside => {
+            format!(
+                "inset(by: {}).stroke({color}, {style})",
+                number(-weight / 2.0)
+            )
+        }
+    };
+    Some(format!(".overlay({shape}.{stroke})"))
+}
+
+/// The shape style of a paint, `None` for image, emoji and video paints.
+fn paint(document: &Document, paint: &Paint, width: f32, height: f32) -> Option<String> {
+    let handle = |index: usize| {
+        paint
+            .gradient_handle_positions
+            .get(index)
+            .map(|handle| (handle.x, handle.y))
+    };
+    let unit_point = |(x, y): (f32, f32)| format!("UnitPoint(x: {}, y: {})", number(x), number(y));
+    let stops = || {
+        let stops: Vec<String> = paint
+            .gradient_stops
+            .iter()
+            .map(|stop| {
+                let bound = stop.bound_variables.get("color");
+                format!(
+                    "Gradient.Stop(color: {}, location: {})",
+                    color(document, bound, &stop.color, paint.opacity),
+                    number(stop.position)
+                )
+            })
+            .collect();
+        stops.join(", ")
+    };
+    Some(match paint.paint_type {
+        PaintType::Solid => paint_color(document, paint),
+        PaintType::GradientLinear => format!(
+            "LinearGradient(stops: [{}], startPoint: {}, endPoint: {})",
+            stops(),
+            unit_point(handle(0)?),
+            unit_point(handle(1)?)
+        ),
+        // SwiftUI has no diamond gradients, they are approximated with radial ones.
+        PaintType::GradientRadial | PaintType::GradientDiamond => {
+            let (center, end) = (handle(0)?, handle(1)?);
+            let radius = ((end.0 - center.0) * width).hypot((end.1 - center.1) * height);
+            format!(
+                "RadialGradient(stops: [{}], center: {}, startRadius: 0, endRadius: {})",
+                stops(),
+                unit_point(center),
+                number(radius)
+            )
+        }
+        PaintType::GradientAngular => {
+            let (center, end) = (handle(0)?, handle(1)?);
+            let angle = ((end.1 - center.1) * height)
+                .atan2((end.0 - center.0) * width)
+                .to_degrees();
+            format!(
+                "AngularGradient(stops: [{}], center: {}, angle: .degrees({}))",
+                stops(),
+                unit_point(center),
+                number(angle)
+            )
+        }
+        PaintType::Image | PaintType::Emoji | PaintType::Video => return None,
+    })
+}
+
+fn paint_color(document: &Document, paint: &Paint) -> String {
+    let bound = paint
+        .bound_variables
+        .as_ref()
+        .and_then(|variables| variables.get("color"));
+    color(document, bound, &paint.color, paint.opacity)
+}
+
+/// A color, or the color of the asset catalog named like the variable it is bound to, with an
+/// opacity.
+fn color(
+    document: &Document,
+    bound: Option<&VariableAlias>,
+    color: &Color,
+    opacity: f32,
+) -> String {
+    match crate::variable(document, bound) {
+        Some(variable) if opacity < 1.0 => {
+            format!("Color({:?}).opacity({})", variable.name, number(opacity))
+        }
+        Some(variable) => format!("Color({:?})", variable.name),
+        None => {
+            let alpha = color.a * opacity;
+            let opacity = match alpha < 1.0 {
+                true => format!(", opacity: {}", number(alpha)),
+                false => String::new(),
+            };
+            format!(
+                "Color(red: {}, green: {}, blue: {}{opacity})",
+                number(color.r),
+                number(color.g),
+                number(color.b)
+            )
+        }
+    }
+}
+
+/// The name of the SwiftUI font weight closest to a numeric font weight.
+fn font_weight(weight: i32) -> &'static str {
+    match (weight + 50) / 100 {
+        ..=1 => "ultraLight",
+        2 => "thin",
+        3 => "light",
+        4 => "regular",
+        5 => "medium",
+        6 => "semibold",
+        7 => "bold",
+        8 => "heavy",
+        _ => "black",
+    }
+}
+
+/// The name of the SwiftUI blend mode closest to a blend mode, `None` for normal blending.
+fn blend_mode(mode: &BlendMode) -> Option<&'static str> {
+    Some(match mode {
+        BlendMode::PassThrough | BlendMode::Normal => return None,
+        BlendMode::Darken => "darken",
+        BlendMode::Multiply => "multiply",
+        BlendMode::LinearBurn => "plusDarker",
+        BlendMode::ColorBurn => "colorBurn",
+        BlendMode::Lighten => "lighten",
+        BlendMode::Screen => "screen",
+        BlendMode::LinearDodge => "plusLighter",
+        BlendMode::ColorDodge => "colorDodge",
+        BlendMode::Overlay => "overlay",
+        BlendMode::SoftLight => "softLight",
+        BlendMode::HardLight => "hardLight",
+        BlendMode::Difference => "difference",
+        BlendMode::Exclusion => "exclusion",
+        BlendMode::Hue => "hue",
+        BlendMode::Saturation => "saturation",
+        BlendMode::Color => "color",
+        BlendMode::Luminosity => "luminosity",
+    })
+}
+
+fn indent(lines: Vec<String>) -> Vec<String> {
+    lines
+        .into_iter()
+        .map(|line| format!("{INDENT}{line}"))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures;
+
+    #[test]
+    fn canvas_snapshot() {
+        let document = fixtures::document();
+        let canvas = &document.document.children()[0];
+        insta::assert_snapshot!(SwiftUi.generate(&document, canvas));
+    }
+
+    #[test]
+    fn layer_snapshot() {
+        let document = fixtures::document();
+        let title = document.node("1:3").unwrap();
+        insta::assert_snapshot!(SwiftUi.generate(&document, title));
+    }
+}
@@ -0,0 +1,249 @@
+//! Tailwind CSS class lists for the layers of a document, and HTML elements using them.
+//!
+//! Classes are derived from the [CSS](crate::css) of the layers. Values without a utility of the
+//! default theme are arbitrary values, and properties without a utility are arbitrary properties.
+//! The custom properties of the variables the classes refer to are declared in a `<style>`
+//! element preceding the elements.
+
+use fig_file::document::Document;
+use fig_file::values::px;
+use fig_types::node::{Node, NodeType};
+
+use crate::css::{Css, Declaration};
+use crate::Backend;
+
+/// The Tailwind backend, generating an element with a class list for each layer.
+pub struct Tailwind;
+
+impl Backend for Tailwind {
+    fn name(&self) -> &'static str {
+        "tailwind"
+    }
+
+    fn generate(&self, document: &Document, node: &Node) -> String {
+        let mut css = Css::new(document);
+        let mut html = String::new();
+        element(&mut css, document, node, 0, false, &mut html);
+        match css.custom_properties() {
+            custom_properties if custom_properties.is_empty() => html,
+            custom_properties => format!("<style>\n{custom_properties}</style>\n{html}"),
+        }
+    }
+}
+
+/// The classes styling a layer, empty for documents and canvases.
+pub fn classes(css: &mut Css, node: &Node) -> Vec<String> {
+    let declarations = css.declarations(node);
+    let clamped = declarations
+        .iter()
+        .any(|declaration| declaration.property == "-webkit-line-clamp");
+    let mut classes = Vec::new();
+    for declaration in &declarations {
+        // Line clamp utilities set the display and orientation of the box themselves.
+        if clamped && matches!(declaration.property, "display" | "-webkit-box-orient") {
+            continue;
+        }
+        utilities(declaration, &mut classes);
+    }
+    classes
+}
+
+fn element(
+    css: &mut Css,
+    document: &Document,
+    node: &Node,
+    depth: usize,
+    positioned: bool,
+    html: &mut String,
+) {
+    let indent = "  ".repeat(depth);
+    let (vector, text) = match &node.node {
+        NodeType::Document(_) => {
+            for canvas in node.children() {
+                element(css, document, canvas, depth, false, html);
+            }
+            return;
+        }
+        NodeType::Canvas(_) => {
+            html.push_str(&format!("{indent}<div class=\"relative\">\n"));
+            for layer in node.children() {
+                element(css, document, layer, depth + 1, true, html);
+            }
+            html.push_str(&format!("{indent}</div>\n"));
+            return;
+        }
+        NodeType::Vector(vector) => (
+            crate::position(vector),
+            crate::text_style(document, vector).is_some(),
+        ),
+        NodeType::Rectangle(vector) => (
+            crate::position(vector),
+            crate::text_style(document, vector).is_some(),
+        ),
+    };
+    let mut classes = Vec::new();
+    if positioned {
+        let (x, y) = vector;
+        classes.extend([
+            "absolute".to_string(),
+            format!("left-[{}]", px(x)),
+            format!("top-[{}]", px(y)),
+        ]);
+    }
+    classes.extend(self::classes(css, node));
+    let classes = classes.join(" ");
+    match text {
+        true => html.push_str(&format!(
+            "{indent}<p class=\"{classes}\">{}</p>\n",
+            escape(&node.name)
+        )),
+        false => html.push_str(&format!("{indent}<div class=\"{classes}\"></div>\n")),
+    }
+}
+
+/// Appends the utilities applying a declaration.
+fn utilities(declaration: &Declaration, classes: &mut Vec<String>) {
+    let value = declaration.value.as_str();
+    let mut push = |class: String| classes.push(class);
+    match declaration.property {
+        "width" => push(format!("w-[{}]", arbitrary(value))),
+        "height" => push(format!("h-[{}]", arbitrary(value))),
+        "align-self" => push(format!("self-{}", value.trim_start_matches("flex-"))),
+        "flex-grow" if value == "1" => push("grow".to_string()),
+        "flex-grow" => push(format!("grow-[{value}]")),
+        "transform" if value.starts_with("rotate(") => {
+            let angle = value.trim_start_matches("rotate(").trim_end_matches(')');
+            push(format!("rotate-[{angle}]"));
+        }
+        "background-color" => push(format!("bg-[color:{}]", arbitrary(value))),
+        "box-sizing" if value == "border-box" => push("box-border".to_string()),
+        "border" | "outline" => {
+            let property = declaration.property;
+            let mut parts = value.splitn(3, ' ');
+            let (width, style, color) = (parts.next(), parts.next(), parts.next());
+            if property == "outline" {
+                push("outline".to_string());
+            }
+            if let Some(width) = width {
+                push(format!("{property}-[{width}]"));
+            }
+            if let Some(style) = style.filter(|style| *style != "solid") {
+                push(format!("{property}-{style}"));
+            }
+            if let Some(color) = color {
+                push(format!("{property}-[color:{}]", arbitrary(color)));
+            }
+        }
+        "border-width" => {
+            for (side, width) in ["t", "r", "b", "l"].iter().zip(value.split(' ')) {
+                push(format!("border-{side}-[{width}]"));
+            }
+        }
+        "border-style" => push(format!("border-{value}")),
+        "border-color" => push(format!("border-[color:{}]", arbitrary(value))),
+        "outline-offset" => push(format!("outline-offset-[{value}]")),
+        "border-radius" => match value.split(' ').collect::<Vec<_>>()[..] {
+            [radius] => push(format!("rounded-[{radius}]")),
+            ref radii => {
+                for (corner, radius) in ["tl", "tr", "br", "bl"].iter().zip(radii) {
+                    if *radius != "0" {
+                        push(format!("rounded-{corner}-[{radius}]"));
+                    }
+                }
+            }
+        },
+        "box-shadow" => push(format!("shadow-[{}]", arbitrary(value))),
+        "filter" | "backdrop-filter" if is_single_blur(value) => {
+            let radius = &value["blur(".len()..value.len() - 1];
+            let prefix = match declaration.property {
+                "filter" => "",
+                _ => "backdrop-",
+            };
+            push(format!("{prefix}blur-[{}]", arbitrary(radius)));
+        }
+        "opacity" => {
+            let percent = value.parse::<f32>().unwrap_or(1.0) * 100.0;
+            let step = percent.round() as i32;
+            match (percent - step as f32).abs() < 1e-3 && step % 5 == 0 {
+                true => push(format!("opacity-{step}")),
+                false => push(format!("opacity-[{value}]")),
+            }
+        }
+        "mix-blend-mode" => push(format!("mix-blend-{value}")),
+        "font-family" => push(format!("font-[{}]", arbitrary(&value.replace('"', "'")))),
+        "font-size" => push(format!("text-[{value}]")),
+        "font-weight" => push(match font_weight(value) {
+            Some(weight) => format!("font-{weight}"),
+            None => format!("font-[{value}]"),
+        }),
+        "font-style" if value == "italic" => push("italic".to_string()),
+        "line-height" if value == "normal" => push("leading-normal".to_string()),
+        "line-height" => push(format!("leading-[{value}]")),
+        "letter-spacing" => push(format!("tracking-[{value}]")),
+        "text-align" => push(format!("text-{value}")),
+        "text-transform" => push(match value {
+            "uppercase" | "lowercase" | "capitalize" => value.to_string(),
+            _ => "normal-case".to_string(),
+        }),
+        "text-decoration" => push(value.to_string()),
+        "text-indent" => push(format!("indent-[{value}]")),
+        "overflow" => push(format!("overflow-{value}")),
+        "text-overflow" => push(format!("text-{value}")),
+        "white-space" => push(format!("whitespace-{value}")),
+        "-webkit-line-clamp" => push(format!("line-clamp-{value}")),
+        "color" => push(format!("text-[color:{}]", arbitrary(value))),
+        property => push(format!("[{property}:{}]", arbitrary(value))),
+    }
+}
+
+/// Whether a filter is a single blur.
+fn is_single_blur(filter: &str) -> bool {
+    filter.starts_with("blur(") && filter.ends_with(')') && filter.matches("blur(").count() == 1
+}
+
+/// The name of the utility of a font weight of the default theme.
+fn font_weight(weight: &str) -> Option<&'static str> {
+    Some(match weight {
+        "100" => "thin",
+        "200" => "extralight",
+        "300" => "light",
+        "400" => "normal",
+        "500" => "medium",
+        "600" => "semibold",
+        "700" => "bold",
+        "800" => "extrabold",
+        "900" => "black",
+        _ => return None,
+    })
+}
+
+/// A CSS value written as an arbitrary value, where spaces are underscores.
+fn arbitrary(value: &str) -> String {
+    value.replace(' ', "_")
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures;
+
+    #[test]
+    fn canvas_snapshot() {
+        let document = fixtures::document();
+        let canvas = &document.document.children()[0];
+        insta::assert_snapshot!(Tailwind.generate(&document, canvas));
+    }
+
+    #[test]
+    fn layer_snapshot() {
+        let document = fixtures::document();
+        let title = document.node("1:3").unwrap();
+        insta::assert_snapshot!(Tailwind.generate(&document, title));
+    }
+}
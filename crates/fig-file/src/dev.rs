@@ -0,0 +1,251 @@
+//! Dev status: designers mark layers ready for development or completed, and developers review
+//! what changed on the layers ready for development since they were marked.
+//!
+//! Setting the dev status of a layer keeps a snapshot of the layer in the document. The changes
+//! of a layer are the differences between its snapshot and its current properties.
+
+use std::fmt::Write;
+
+use fig_types::node::{Node, NodeType};
+use fig_types::patch::{self, PropertyChange};
+use fig_types::properties::{DevStatus, DevStatusType};
+use serde::Serialize;
+
+use crate::document::Document;
+use crate::values;
+
+#[derive(Debug, thiserror::Error)]
+pub enum DevStatusError {
+    #[error("no node with id `{0}`")]
+    NodeNotFound(String),
+    #[error("node `{0}` is not a layer, only layers have a dev status")]
+    NotALayer(String),
+}
+
+/// A layer ready for development and its changes since it was marked ready.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PendingChanges<'a> {
+    pub node_id: &'a str,
+    pub node_name: &'a str,
+    /// The description given with the dev status.
+    pub description: &'a str,
+    pub changes: Vec<PropertyChange>,
+}
+
+impl Document {
+    /// Sets the dev status of a layer and snapshots the layer, or clears it and drops the
+    /// snapshot when `status` is `None`.
+    pub fn set_dev_status(
+        &mut self,
+        node: &str,
+        status: Option<DevStatus>,
+    ) -> Result<(), DevStatusError> {
+        let target = self
+            .document
+            .find_mut(node)
+            .ok_or_else(|| DevStatusError::NodeNotFound(node.to_string()))?;
+        let dev_status = match &mut target.node {
+            NodeType::Vector(vector) => &mut vector.dev_status,
+            NodeType::Rectangle(vector) => &mut vector.dev_status,
+            NodeType::Document(_) | NodeType::Canvas(_) => {
+                return Err(DevStatusError::NotALayer(node.to_string()))
+            }
+        };
+        *dev_status = status;
+        match dev_status.is_some() {
+            true => {
+                let snapshot = target.clone();
+                self.dev_snapshots.insert(node.to_string(), snapshot)
+            }
+            false => self.dev_snapshots.remove(node),
+        };
+        Ok(())
+    }
+
+    /// The changes of a layer since its dev status was set, `None` for layers without a dev
+    /// status or a snapshot.
+    pub fn dev_changes(&self, node: &str) -> Option<Vec<PropertyChange>> {
+        let current = self.node(node)?;
+        dev_status(current)?;
+        let snapshot = self.dev_snapshots.get(node)?;
+        Some(patch::changes(snapshot, current))
+    }
+
+    /// The layers ready for development which changed since they were marked ready, in tree
+    /// order.
+    pub fn pending_changes(&self) -> Vec<PendingChanges<'_>> {
+        let mut pending = Vec::new();
+        self.collect_pending(&self.document, &mut pending);
+        pending
+    }
+
+    fn collect_pending<'a>(&'a self, node: &'a Node, pending: &mut Vec<PendingChanges<'a>>) {
+        if let Some(status) = dev_status(node) {
+            let changes = self.dev_changes(&node.id).unwrap_or_default();
+            if status.dev_status_type == DevStatusType::ReadyForDev && !changes.is_empty() {
+                pending.push(PendingChanges {
+                    node_id: &node.id,
+                    node_name: &node.name,
+                    description: &status.description,
+                    changes,
+                });
+            }
+        }
+        for child in node.children() {
+            self.collect_pending(child, pending);
+        }
+    }
+}
+
+/// The dev status of a node, `None` for nodes without one.
+pub fn dev_status(node: &Node) -> Option<&DevStatus> {
+    match &node.node {
+        NodeType::Vector(vector) => vector.dev_status.as_ref(),
+        NodeType::Rectangle(vector) => vector.dev_status.as_ref(),
+        NodeType::Document(_) | NodeType::Canvas(_) => None,
+    }
+}
+
+/// A change report listing the pending changes of each layer, one property per line.
+pub fn report(pending: &[PendingChanges]) -> String {
+    let mut report = String::new();
+    for layer in pending {
+        let _ = write!(report, "{} {:?}", layer.node_id, layer.node_name);
+        if !layer.description.is_empty() {
+            let _ = write!(report, ": {}", layer.description);
+        }
+        report.push('\n');
+        for change in &layer.changes {
+            let _ = writeln!(
+                report,
+                "  {}: {} -> {}",
+                change.path,
+                values::property(change.before.as_ref()),
+                values::property(change.after.as_ref())
+            );
+        }
+    }
+    report
+}
+
+#[cfg(test)]
+mod tests {
+    use fig_types::patch::PathSegment;
+    use serde_json::json;
+
+    use super::*;
+    use crate::fixtures;
+
+    /// A dashed vector layer.
+    fn layer(id: &str, name: &str) -> Node {
+        fixtures::layer("vector", id, name, json!({ "strokeDashes": [1.0, 2.0] }))
+    }
+
+    fn document() -> Document {
+        let mut document = Document::new("Handoff");
+        let canvas = &mut document.document.children_mut().unwrap()[0];
+        *canvas.children_mut().unwrap() = vec![layer("1:1", "Button"), layer("1:2", "Card")];
+        document
+    }
+
+    fn status(dev_status_type: DevStatusType, description: &str) -> Option<DevStatus> {
+        Some(DevStatus {
+            dev_status_type,
+            description: description.to_string(),
+        })
+    }
+
+    fn edit(document: &mut Document, id: &str, edit: impl FnOnce(&mut Node)) {
+        edit(document.document.find_mut(id).unwrap());
+    }
+
+    fn paths(changes: &[PropertyChange]) -> Vec<String> {
+        changes
+            .iter()
+            .map(|change| change.path.to_string())
+            .collect()
+    }
+
+    #[test]
+    fn changes_since_the_dev_status_was_set_are_reported() {
+        let mut document = document();
+        let ready = status(DevStatusType::ReadyForDev, "Hover state");
+        document.set_dev_status("1:1", ready).unwrap();
+        assert!(document.pending_changes().is_empty());
+        assert_eq!(document.dev_changes("1:1"), Some(Vec::new()));
+
+        edit(&mut document, "1:1", |node| {
+            node.name = "Primary button".to_string();
+            let NodeType::Vector(vector) = &mut node.node else {
+                unreachable!();
+            };
+            vector.opacity = 0.5;
+            vector.stroke_dashes = vec![1.0];
+            vector.transition_node_id = Some("1:2".to_string());
+        });
+        let pending = document.pending_changes();
+        assert_eq!(pending.len(), 1);
+        assert_eq!(
+            (pending[0].node_id, pending[0].description),
+            ("1:1", "Hover state")
+        );
+        let changes = &pending[0].changes;
+        assert_eq!(
+            paths(changes),
+            ["name", "opacity", "strokeDashes[1]", "transitionNodeId"]
+        );
+        assert_eq!(changes[1].before, Some(json!(1.0)));
+        assert_eq!(changes[1].after, Some(json!(0.5)));
+        assert_eq!(
+            (&changes[2].before, &changes[2].after),
+            (&Some(json!(2.0)), &None)
+        );
+        assert_eq!(
+            (&changes[3].before, &changes[3].after),
+            (&None, &Some(json!("1:2")))
+        );
+        assert_eq!(
+            changes[2].path.segments(),
+            [
+                PathSegment::Key("strokeDashes".into()),
+                PathSegment::Index(1)
+            ]
+        );
+        assert_eq!(
+            report(&pending),
+            "1:1 \"Primary button\": Hover state\n  name: \"Button\" -> \"Primary button\"\n  \
+             opacity: 1.0 -> 0.5\n  strokeDashes[1]: 2.0 -> (unset)\n  \
+             transitionNodeId: (unset) -> \"1:2\"\n"
+        );
+    }
+
+    #[test]
+    fn setting_the_dev_status_again_takes_a_new_snapshot() {
+        let mut document = document();
+        document
+            .set_dev_status("1:1", status(DevStatusType::ReadyForDev, ""))
+            .unwrap();
+        document
+            .set_dev_status("1:2", status(DevStatusType::Completed, ""))
+            .unwrap();
+        edit(&mut document, "1:1", |node| node.visible = false);
+        edit(&mut document, "1:2", |node| node.visible = false);
+        // Completed layers are not pending, even when they changed.
+        assert_eq!(paths(&document.dev_changes("1:2").unwrap()), ["visible"]);
+        assert_eq!(document.pending_changes().len(), 1);
+
+        document
+            .set_dev_status("1:1", status(DevStatusType::ReadyForDev, "Hidden"))
+            .unwrap();
+        assert!(document.pending_changes().is_empty());
+
+        document.set_dev_status("1:1", None).unwrap();
+        assert!(!document.dev_snapshots.contains_key("1:1"));
+        assert_eq!(document.dev_changes("1:1"), None);
+        assert!(matches!(
+            document.set_dev_status("0:1", status(DevStatusType::Completed, "")),
+            Err(DevStatusError::NotALayer(_))
+        ));
+    }
+}
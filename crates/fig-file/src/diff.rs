@@ -0,0 +1,458 @@
+//! Structural diff between two versions of a document.
+//!
+//! Nodes are matched by id. A node only in the new version was added and a node only in the old
+//! one was removed. A node in both versions was renamed when its name changed, moved when its
+//! parent changed or its order among the siblings it kept did, and modified when any other of its
+//! properties changed. Children are compared as nodes of their own rather than as properties of
+//! their parent.
+
+use std::collections::HashMap;
+use std::fmt::{self, Write};
+
+use fig_types::node::Node;
+use fig_types::patch::{self, PathSegment, PropertyChange};
+use serde::Serialize;
+
+use crate::document::Document;
+use crate::render::{self, RenderError};
+use crate::values;
+
+/// Where a node is in the tree.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Position<'a> {
+    pub parent: &'a str,
+    /// Index of the node among the children of its parent.
+    pub index: usize,
+}
+
+impl fmt::Display for Position<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}[{}]", self.parent, self.index)
+    }
+}
+
+/// A change to a node between two versions of a document.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "change", rename_all = "camelCase")]
+pub enum NodeChange<'a> {
+    #[serde(rename_all = "camelCase")]
+    Added {
+        id: &'a str,
+        name: &'a str,
+        /// The position of the node, `None` for the root.
+        position: Option<Position<'a>>,
+    },
+    #[serde(rename_all = "camelCase")]
+    Removed {
+        id: &'a str,
+        name: &'a str,
+        /// The position the node was removed from, `None` for the root.
+        position: Option<Position<'a>>,
+    },
+    #[serde(rename_all = "camelCase")]
+    Modified {
+        id: &'a str,
+        name: &'a str,
+        /// The previous name of a renamed node.
+        renamed_from: Option<&'a str>,
+        /// The previous position of a moved node.
+        moved_from: Option<Position<'a>>,
+        /// The position of the node, `None` for the root.
+        position: Option<Position<'a>>,
+        /// The changed properties, other than the name.
+        properties: Vec<PropertyChange>,
+    },
+}
+
+impl NodeChange<'_> {
+    pub fn id(&self) -> &str {
+        match self {
+            NodeChange::Added { id, .. }
+            | NodeChange::Removed { id, .. }
+            | NodeChange::Modified { id, .. } => id,
+        }
+    }
+}
+
+/// The changes between two versions of a document: the added and modified nodes in the tree
+/// order of the new version, followed by the removed nodes in the tree order of the old one.
+pub fn diff<'a>(before: &'a Document, after: &'a Document) -> Vec<NodeChange<'a>> {
+    let old = Tree::new(&before.document);
+    let new = Tree::new(&after.document);
+    let mut changes = Vec::new();
+    for (node, position) in &new.order {
+        let Some((previous, previous_position)) = old.nodes.get(node.id.as_str()) else {
+            changes.push(NodeChange::Added {
+                id: &node.id,
+                name: &node.name,
+                position: *position,
+            });
+            continue;
+        };
+        let renamed_from = (previous.name != node.name).then_some(previous.name.as_str());
+        let moved = match (previous_position, position) {
+            (Some(previous), Some(position)) => {
+                previous.parent != position.parent
+                    || old.kept_index(&node.id, &new) != new.kept_index(&node.id, &old)
+            }
+            (previous, position) => previous != position,
+        };
+        let properties: Vec<PropertyChange> = patch::changes(previous, node)
+            .into_iter()
+            .filter(|change| change.path.segments() != [PathSegment::Key("name".to_string())])
+            .collect();
+        if renamed_from.is_some() || moved || !properties.is_empty() {
+            changes.push(NodeChange::Modified {
+                id: &node.id,
+                name: &node.name,
+                renamed_from,
+                moved_from: moved.then_some(*previous_position).flatten(),
+                position: *position,
+                properties,
+            });
+        }
+    }
+    for (node, position) in &old.order {
+        if !new.nodes.contains_key(node.id.as_str()) {
+            changes.push(NodeChange::Removed {
+                id: &node.id,
+                name: &node.name,
+                position: *position,
+            });
+        }
+    }
+    changes
+}
+
+/// The nodes of a version of a document and their position, by id and in tree order.
+pub(crate) struct Tree<'a> {
+    pub(crate) nodes: HashMap<&'a str, (&'a Node, Option<Position<'a>>)>,
+    pub(crate) order: Vec<(&'a Node, Option<Position<'a>>)>,
+}
+
+impl<'a> Tree<'a> {
+    pub(crate) fn new(root: &'a Node) -> Self {
+        let mut tree = Tree {
+            nodes: HashMap::new(),
+            order: Vec::new(),
+        };
+        tree.visit(root, None);
+        tree
+    }
+
+    fn visit(&mut self, node: &'a Node, position: Option<Position<'a>>) {
+        // Documents with duplicate ids are invalid, the first node with an id is kept.
+        self.nodes.entry(&node.id).or_insert((node, position));
+        self.order.push((node, position));
+        for (index, child) in node.children().iter().enumerate() {
+            let position = Position {
+                parent: &node.id,
+                index,
+            };
+            self.visit(child, Some(position));
+        }
+    }
+
+    /// The index of a node among the siblings which are in the tree of the other version under
+    /// the same parent, so nodes do not count as moved when their siblings are added or removed.
+    fn kept_index(&self, id: &str, other: &Tree) -> Option<usize> {
+        let (_, position) = self.nodes.get(id)?;
+        let parent = self.nodes.get(position.as_ref()?.parent)?.0;
+        let kept = |sibling: &&Node| {
+            other
+                .nodes
+                .get(sibling.id.as_str())
+                .and_then(|(_, position)| position.as_ref())
+                .is_some_and(|position| position.parent == parent.id)
+        };
+        parent
+            .children()
+            .iter()
+            .filter(kept)
+            .position(|sibling| sibling.id == id)
+    }
+}
+
+/// The changes as text, a line per node followed by an indented line per change of a modified
+/// node.
+pub fn text(changes: &[NodeChange]) -> String {
+    let mut text = String::new();
+    for change in changes {
+        match change {
+            NodeChange::Added { id, name, position } => match position {
+                Some(position) => {
+                    let _ = writeln!(text, "+ {id} {name:?} added at {position}");
+                }
+                None => {
+                    let _ = writeln!(text, "+ {id} {name:?} added as the root");
+                }
+            },
+            NodeChange::Removed { id, name, position } => match position {
+                Some(position) => {
+                    let _ = writeln!(text, "- {id} {name:?} removed from {position}");
+                }
+                None => {
+                    let _ = writeln!(text, "- {id} {name:?} removed as the root");
+                }
+            },
+            NodeChange::Modified {
+                id,
+                name,
+                renamed_from,
+                moved_from,
+                position,
+                properties,
+            } => {
+                let _ = writeln!(text, "~ {id} {name:?}");
+                if let Some(renamed_from) = renamed_from {
+                    let _ = writeln!(text, "    renamed from {renamed_from:?}");
+                }
+                if let (Some(moved_from), Some(position)) = (moved_from, position) {
+                    let _ = writeln!(text, "    moved from {moved_from} to {position}");
+                }
+                for property in properties {
+                    let _ = writeln!(
+                        text,
+                        "    {}: {} -> {}",
+                        property.path,
+                        values::property(property.before.as_ref()),
+                        values::property(property.after.as_ref())
+                    );
+                }
+            }
+        }
+    }
+    text
+}
+
+/// A side by side render of a changed node, previous version on the left.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Comparison {
+    /// Id of the changed node.
+    pub node: String,
+    /// Name of the image file, made of the id of the node.
+    pub file_name: String,
+    /// The PNG image.
+    pub png: Vec<u8>,
+}
+
+/// Renders the two versions of every changed canvas and layer side by side, scaled by `scale`.
+/// Changes of nodes which cannot be rendered or have nothing visible in both versions are
+/// skipped, other render errors are returned with the id of their node.
+pub fn comparisons(
+    before: &Document,
+    after: &Document,
+    changes: &[NodeChange],
+    scale: f64,
+) -> Vec<Result<Comparison, RenderError>> {
+    changes
+        .iter()
+        .filter_map(|change| {
+            let id = change.id();
+            let render = render::side_by_side(before.node(id), after.node(id), scale);
+            match render {
+                Err(RenderError::Empty(_) | RenderError::NotRenderable(_)) => None,
+                render => Some(render.map(|png| Comparison {
+                    node: id.to_string(),
+                    file_name: format!("{}.png", crate::export::sanitize(id)),
+                    png,
+                })),
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+    use crate::fixtures;
+
+    /// A 40 by 20 vector layer with an opacity.
+    fn layer(id: &str, name: &str, opacity: f32) -> Node {
+        let mut properties = fixtures::bounds(0.0, 0.0, 40.0, 20.0);
+        properties["opacity"] = json!(opacity);
+        fixtures::layer("vector", id, name, properties)
+    }
+
+    /// A document with two canvases holding the layers of each list.
+    fn document(first: Vec<Node>, second: Vec<Node>) -> Document {
+        let mut document = Document::new("Diff");
+        let canvases = document.document.children_mut().unwrap();
+        let mut other = canvases[0].clone();
+        other.id = "0:2".to_string();
+        other.name = "Page 2".to_string();
+        *canvases[0].children_mut().unwrap() = first;
+        *other.children_mut().unwrap() = second;
+        canvases.push(other);
+        document
+    }
+
+    fn versions() -> (Document, Document) {
+        let before = document(
+            vec![
+                layer("1:1", "Card", 1.0),
+                layer("1:2", "Hero", 1.0),
+                layer("1:3", "Badge", 1.0),
+                layer("1:4", "Icon", 1.0),
+            ],
+            vec![],
+        );
+        let after = document(
+            vec![
+                layer("1:5", "Footer", 1.0),
+                layer("1:1", "Card/Default", 1.0),
+                layer("1:3", "Badge", 0.5),
+            ],
+            vec![layer("1:4", "Icon", 1.0)],
+        );
+        (before, after)
+    }
+
+    #[test]
+    fn nodes_are_matched_by_id() {
+        let (before, after) = versions();
+        let changes = diff(&before, &after);
+        let at = |parent, index| Position { parent, index };
+        assert_eq!(
+            changes[0],
+            NodeChange::Added {
+                id: "1:5",
+                name: "Footer",
+                position: Some(at("0:1", 0))
+            }
+        );
+        // Adding a sibling before the card and removing the hero does not move the card.
+        assert_eq!(
+            changes[1],
+            NodeChange::Modified {
+                id: "1:1",
+                name: "Card/Default",
+                renamed_from: Some("Card"),
+                moved_from: None,
+                position: Some(at("0:1", 1)),
+                properties: Vec::new(),
+            }
+        );
+        let NodeChange::Modified { properties, .. } = &changes[2] else {
+            panic!("the badge is modified");
+        };
+        assert_eq!(properties[0].path.to_string(), "opacity");
+        assert!(matches!(
+            changes[3],
+            NodeChange::Modified {
+                id: "1:4",
+                moved_from: Some(Position {
+                    parent: "0:1",
+                    index: 3
+                }),
+                position: Some(Position {
+                    parent: "0:2",
+                    index: 0
+                }),
+                ..
+            }
+        ));
+        assert_eq!(
+            changes[4],
+            NodeChange::Removed {
+                id: "1:2",
+                name: "Hero",
+                position: Some(at("0:1", 1))
+            }
+        );
+        assert_eq!(changes.len(), 5);
+        assert!(diff(&before, &before).is_empty());
+    }
+
+    #[test]
+    fn reordered_siblings_are_moved() {
+        let before = document(vec![layer("1:1", "A", 1.0), layer("1:2", "B", 1.0)], vec![]);
+        let after = document(vec![layer("1:2", "B", 1.0), layer("1:1", "A", 1.0)], vec![]);
+        let changes = diff(&before, &after);
+        let moved: Vec<&str> = changes.iter().map(NodeChange::id).collect();
+        assert_eq!(moved, ["1:2", "1:1"]);
+    }
+
+    #[test]
+    fn changes_are_written_as_text_and_json() {
+        let (before, after) = versions();
+        let changes = diff(&before, &after);
+        assert_eq!(
+            text(&changes),
+            "+ 1:5 \"Footer\" added at 0:1[0]\n\
+             ~ 1:1 \"Card/Default\"\n    renamed from \"Card\"\n\
+             ~ 1:3 \"Badge\"\n    opacity: 1.0 -> 0.5\n\
+             ~ 1:4 \"Icon\"\n    moved from 0:1[3] to 0:2[0]\n\
+             - 1:2 \"Hero\" removed from 0:1[1]\n"
+        );
+        let json = serde_json::to_value(&changes).unwrap();
+        assert_eq!(
+            json[0],
+            json!({ "change": "added", "id": "1:5", "name": "Footer",
+                    "position": { "parent": "0:1", "index": 0 } })
+        );
+        assert_eq!(json[2]["properties"][0]["path"], "opacity");
+        assert_eq!(json[3]["movedFrom"], json!({ "parent": "0:1", "index": 3 }));
+    }
+
+    #[test]
+    fn changed_nodes_are_rendered_side_by_side() {
+        let (before, after) = versions();
+        let changes = diff(&before, &after);
+        let comparisons: Vec<Comparison> = comparisons(&before, &after, &changes, 1.0)
+            .into_iter()
+            .collect::<Result<_, _>>()
+            .unwrap();
+        let names: Vec<&str> = comparisons
+            .iter()
+            .map(|comparison| comparison.file_name.as_str())
+            .collect();
+        assert_eq!(
+            names,
+            ["1_5.png", "1_1.png", "1_3.png", "1_4.png", "1_2.png"]
+        );
+        // Two 40px wide columns separated by the gap, as large as the layer is high.
+        let png = &comparisons[0].png;
+        assert_eq!(&png[1..4], b"PNG");
+        assert_eq!(png[16..24], [0, 0, 0, 96, 0, 0, 0, 20]);
+    }
+
+    #[test]
+    fn replaced_roots_are_added_and_removed() {
+        let (before, _) = versions();
+        let mut after = before.clone();
+        after.document.id = "1:0".to_string();
+        let changes = diff(&before, &after);
+        assert_eq!(
+            changes[0],
+            NodeChange::Added {
+                id: "1:0",
+                name: &after.document.name,
+                position: None
+            }
+        );
+        assert!(matches!(
+            changes[1],
+            NodeChange::Modified {
+                id: "0:1",
+                moved_from: Some(Position {
+                    parent: "0:0",
+                    index: 0
+                }),
+                ..
+            }
+        ));
+        assert_eq!(
+            changes.last(),
+            Some(&NodeChange::Removed {
+                id: "0:0",
+                name: &before.document.name,
+                position: None
+            })
+        );
+        assert!(text(&changes).ends_with("removed as the root\n"));
+    }
+}
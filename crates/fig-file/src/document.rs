@@ -0,0 +1,281 @@
+//! Documents and the formats they are stored in.
+//!
+//! The native container is a short header (magic bytes and container version) followed by the
+//! gzip compressed JSON serialization of the [`Document`]. Plain JSON files and files exported
+//! from the Figma REST API are accepted as well and detected by the absence of the header.
+//!
+//! Every format is written in the canonical form of [`text`](crate::text), so saving a document
+//! twice gives the same bytes.
+
+use std::collections::BTreeMap;
+use std::fs::File;
+use std::io::{BufReader, BufWriter, Read, Write};
+use std::path::Path;
+
+use fig_types::node::{CanvasNode, DocumentNode, Node, NodeType};
+use fig_types::properties::{Color, Component, ComponentSet, Style, Variable, VariableCollection};
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::versions::Version;
+use crate::{figma, text};
+
+/// Bytes every native container starts with.
+const MAGIC: &[u8; 7] = b"FIGFILE";
+/// Version of the native container layout.
+const CONTAINER_VERSION: u8 = 1;
+/// Version of the document schema written by this version of fig-file.
+pub const SCHEMA_VERSION: u32 = 1;
+
+#[derive(Debug, thiserror::Error)]
+pub enum FileError {
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+    #[error("invalid document: {0}")]
+    Json(#[from] serde_json::Error),
+    #[error("unsupported container version {0}")]
+    UnsupportedContainer(u8),
+    #[error(
+        "unsupported schema version {0}, this version of fig-file reads up to {SCHEMA_VERSION}"
+    )]
+    UnsupportedSchema(u32),
+    #[error("the root node of a document must be a document node")]
+    NotADocument,
+    #[error("the journal does not start with a snapshot of the document")]
+    MissingSnapshot,
+    #[error("`{0}` is not the file of a canvas in the `canvases` directory")]
+    InvalidCanvasFile(String),
+}
+
+/// Formats a document can be stored in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum Format {
+    /// Compressed native container
+    Native,
+    /// Plain JSON in canonical form
+    Json,
+    /// JSON in canonical form laid out like the files returned by the Figma REST API
+    Figma,
+}
+
+impl Format {
+    /// Guesses the format from a file extension, `.json` files are stored as JSON and everything
+    /// else in the native container.
+    pub fn from_path(path: &Path) -> Self {
+        match path.extension().and_then(|extension| extension.to_str()) {
+            Some(extension) if extension.eq_ignore_ascii_case("json") => Format::Json,
+            _ => Format::Native,
+        }
+    }
+
+    /// Detects the format a document was stored in from its bytes.
+    pub fn detect(bytes: &[u8]) -> Self {
+        if bytes.starts_with(MAGIC) {
+            return Format::Native;
+        }
+        match serde_json::from_slice(bytes) {
+            Ok(value) if figma::is_rest(&value) => Format::Figma,
+            _ => Format::Json,
+        }
+    }
+}
+
+/// A design document: a tree of canvases and their layers, and the definitions they share.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Document {
+    /// Version of the schema the document was written with.
+    pub schema_version: u32,
+    /// The root node, a document node whose children are the canvases.
+    pub document: Node,
+    /// Components used in the document, by id of their main component node.
+    #[serde(default)]
+    pub components: BTreeMap<String, Component>,
+    /// Component sets used in the document, by id of their node.
+    #[serde(default)]
+    pub component_sets: BTreeMap<String, ComponentSet>,
+    /// Styles used in the document, by style id.
+    #[serde(default)]
+    pub styles: BTreeMap<String, Style>,
+    /// Variables defined in or used by the document, by id.
+    #[serde(default)]
+    pub variables: BTreeMap<String, Variable>,
+    /// Collections of the variables, by id.
+    #[serde(default)]
+    pub variable_collections: BTreeMap<String, VariableCollection>,
+    /// The nodes with a dev status as they were when it was set, by id.
+    #[serde(default)]
+    pub dev_snapshots: BTreeMap<String, Node>,
+    /// The checkpoints of the document, oldest first.
+    #[serde(default)]
+    pub versions: Vec<Version>,
+}
+
+impl Document {
+    /// A new document with a single empty canvas.
+    pub fn new(name: &str) -> Self {
+        let page = Node {
+            id: "0:1".to_string(),
+            name: "Page 1".to_string(),
+            visible: true,
+            node: NodeType::Canvas(CanvasNode {
+                children: Vec::new(),
+                background_color: Color {
+                    r: 0.96,
+                    g: 0.96,
+                    b: 0.96,
+                    a: 1.0,
+                },
+                flow_starting_points: Vec::new(),
+                prototype_device: None,
+                measurements: Vec::new(),
+            }),
+            rotation: 0.0,
+        };
+        Self {
+            schema_version: SCHEMA_VERSION,
+            document: Node {
+                id: "0:0".to_string(),
+                name: name.to_string(),
+                visible: true,
+                node: NodeType::Document(DocumentNode {
+                    children: vec![page],
+                }),
+                rotation: 0.0,
+            },
+            components: BTreeMap::new(),
+            component_sets: BTreeMap::new(),
+            styles: BTreeMap::new(),
+            variables: BTreeMap::new(),
+            variable_collections: BTreeMap::new(),
+            dev_snapshots: BTreeMap::new(),
+            versions: Vec::new(),
+        }
+    }
+
+    /// The name of the document.
+    pub fn name(&self) -> &str {
+        &self.document.name
+    }
+
+    /// The canvases (pages) of the document.
+    pub fn canvases(&self) -> impl Iterator<Item = &Node> {
+        self.document
+            .children()
+            .iter()
+            .filter(|node| matches!(node.node, NodeType::Canvas(_)))
+    }
+
+    /// Looks up any node of the document by id.
+    pub fn node(&self, id: &str) -> Option<&Node> {
+        self.document.find(id)
+    }
+
+    /// The canvas containing a node, or the node itself if it is a canvas.
+    pub fn canvas_of(&self, id: &str) -> Option<&Node> {
+        self.canvases().find(|canvas| canvas.find(id).is_some())
+    }
+
+    /// Reads a document in any format.
+    pub fn read(mut reader: impl Read) -> Result<Self, FileError> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes)?;
+        let value = match bytes.strip_prefix(MAGIC) {
+            Some([CONTAINER_VERSION, payload @ ..]) => {
+                serde_json::from_reader(GzDecoder::new(payload))?
+            }
+            Some([version, ..]) => return Err(FileError::UnsupportedContainer(*version)),
+            Some([]) => return Err(FileError::UnsupportedContainer(0)),
+            None => serde_json::from_slice(&bytes)?,
+        };
+        Self::from_value(value)
+    }
+
+    /// Reads a document from its JSON serialization in any format.
+    pub(crate) fn from_value(value: Value) -> Result<Self, FileError> {
+        if figma::is_rest(&value) {
+            return figma::from_rest(value);
+        }
+        // The version is checked before parsing, newer schemas may not match the current types.
+        let version = value.get("schemaVersion").and_then(Value::as_u64);
+        if let Some(version) = version.filter(|version| *version > u64::from(SCHEMA_VERSION)) {
+            return Err(FileError::UnsupportedSchema(
+                u32::try_from(version).unwrap_or(u32::MAX),
+            ));
+        }
+        let document: Document = serde_json::from_value(value)?;
+        if !matches!(document.document.node, NodeType::Document(_)) {
+            return Err(FileError::NotADocument);
+        }
+        Ok(document)
+    }
+
+    /// Writes the document in the given format.
+    pub fn write(&self, mut writer: impl Write, format: Format) -> Result<(), FileError> {
+        match format {
+            Format::Native => {
+                writer.write_all(MAGIC)?;
+                writer.write_all(&[CONTAINER_VERSION])?;
+                let mut encoder = GzEncoder::new(writer, Compression::default());
+                let text = text::to_compact_string(&serde_json::to_value(self)?);
+                encoder.write_all(text.as_bytes())?;
+                encoder.finish()?.flush()?;
+            }
+            Format::Json => {
+                writer.write_all(text::to_string(&serde_json::to_value(self)?).as_bytes())?;
+                writer.flush()?;
+            }
+            Format::Figma => {
+                writer.write_all(text::to_string(&figma::to_rest(self)?).as_bytes())?;
+                writer.flush()?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Opens a document file, or a document split into a directory.
+    pub fn open(path: impl AsRef<Path>) -> Result<Self, FileError> {
+        let path = path.as_ref();
+        if path.is_dir() {
+            return Self::open_split(path);
+        }
+        Self::read(BufReader::new(File::open(path)?))
+    }
+
+    /// Saves the document, in the format matching the extension of `path`, or split into `path`
+    /// if it is a directory. The document is written to a temporary file first so a failed save
+    /// never corrupts an existing file.
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<(), FileError> {
+        let path = path.as_ref();
+        if path.is_dir() {
+            return self.save_split(path);
+        }
+        self.save_as(path, Format::from_path(path))
+    }
+
+    /// Saves the document in the given format.
+    pub fn save_as(&self, path: impl AsRef<Path>, format: Format) -> Result<(), FileError> {
+        replace(path.as_ref(), |writer| self.write(writer, format))
+    }
+}
+
+/// Replaces a file with what `write` writes, through a temporary file.
+pub(crate) fn replace(
+    path: &Path,
+    write: impl FnOnce(BufWriter<File>) -> Result<(), FileError>,
+) -> Result<(), FileError> {
+    let mut temporary = path.as_os_str().to_owned();
+    temporary.push(".tmp");
+    let result = File::create(&temporary)
+        .map_err(FileError::from)
+        .and_then(|file| write(BufWriter::new(file)))
+        .and_then(|()| Ok(std::fs::rename(&temporary, path)?));
+    if result.is_err() {
+        let _ = std::fs::remove_file(&temporary);
+    }
+    result
+}
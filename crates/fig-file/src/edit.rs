@@ -0,0 +1,658 @@
+//! Invertible edit operations on a document tree, grouped into named transactions and recorded in
+//! an undo/redo history.
+//!
+//! Applying an operation returns the operation undoing it, so a transaction only has to keep the
+//! inverse of what it did. Undoing a transaction applies those inverses and yields the operations
+//! redoing it, and the other way around.
+
+use std::collections::HashSet;
+
+use fig_types::node::Node;
+use fig_types::patch::{Patch, PatchError, PatchOperation, PropertyPath};
+use serde::{Deserialize, Serialize};
+
+/// A single edit of a document tree.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", tag = "op")]
+pub enum Operation {
+    /// Sets, inserts or removes properties of a node.
+    Patch(Patch),
+    /// Inserts a node with its subtree in the children of `parent` at `index`.
+    Insert {
+        parent: String,
+        index: usize,
+        node: Box<Node>,
+    },
+    /// Deletes a node and its subtree.
+    Delete { id: String },
+    /// Moves a node to `index` in the children of `parent`, which can be its current parent.
+    Move {
+        id: String,
+        parent: String,
+        index: usize,
+    },
+    /// Reorders the children of `parent`. `order` lists the ids of all its children in their new
+    /// order.
+    Reorder { parent: String, order: Vec<String> },
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum EditError {
+    #[error("no node with id `{0}`")]
+    NodeNotFound(String),
+    #[error("a node with id `{0}` already exists")]
+    DuplicateId(String),
+    #[error("node `{0}` cannot hold children")]
+    NotAContainer(String),
+    #[error("index {index} is out of bounds for the children of `{parent}`")]
+    IndexOutOfBounds { parent: String, index: usize },
+    #[error("the root node cannot be deleted or moved")]
+    Root,
+    #[error("cannot move `{0}` inside its own subtree")]
+    Cycle(String),
+    #[error("the new order is not a permutation of the children of `{0}`")]
+    InvalidOrder(String),
+    #[error("invalid patch for node `{id}`: {source}")]
+    Patch { id: String, source: PatchError },
+}
+
+impl Operation {
+    /// Applies the operation to the tree rooted at `root` and returns the operation undoing it.
+    /// The tree is left untouched when an error is returned.
+    pub fn apply(&self, root: &mut Node) -> Result<Operation, EditError> {
+        match self {
+            Operation::Patch(patch) => {
+                let node = root
+                    .find_mut(&patch.id)
+                    .ok_or_else(|| not_found(&patch.id))?;
+                let inverse = patch.apply(node).map_err(|source| EditError::Patch {
+                    id: patch.id.clone(),
+                    source,
+                })?;
+                Ok(Operation::Patch(inverse))
+            }
+            Operation::Insert {
+                parent,
+                index,
+                node,
+            } => {
+                if let Some(id) = ids(node).find(|id| root.find(id).is_some()) {
+                    return Err(EditError::DuplicateId(id.to_string()));
+                }
+                insert(root, parent, *index, (**node).clone())?;
+                Ok(Operation::Delete {
+                    id: node.id.clone(),
+                })
+            }
+            Operation::Delete { id } => {
+                let (parent, index) = locate(root, id)?;
+                let node = children_of(root, &parent)?.remove(index);
+                Ok(Operation::Insert {
+                    parent,
+                    index,
+                    node: Box::new(node),
+                })
+            }
+            Operation::Move { id, parent, index } => {
+                let (from, from_index) = locate(root, id)?;
+                let moved = root.find(id).ok_or_else(|| not_found(id))?;
+                if moved.find(parent).is_some() {
+                    return Err(EditError::Cycle(id.clone()));
+                }
+                // Check the destination before detaching the node so errors leave the tree as is.
+                let destination = children_of(root, parent)?;
+                let len = destination.len() - usize::from(from == *parent);
+                if *index > len {
+                    return Err(EditError::IndexOutOfBounds {
+                        parent: parent.clone(),
+                        index: *index,
+                    });
+                }
+                let node = children_of(root, &from)?.remove(from_index);
+                children_of(root, parent)?.insert(*index, node);
+                Ok(Operation::Move {
+                    id: id.clone(),
+                    parent: from,
+                    index: from_index,
+                })
+            }
+            Operation::Reorder { parent, order } => {
+                let children = children_of(root, parent)?;
+                let previous: Vec<String> = children.iter().map(|child| child.id.clone()).collect();
+                let mut sorted = order.clone();
+                sorted.sort_unstable();
+                let mut expected = previous.clone();
+                expected.sort_unstable();
+                if sorted != expected {
+                    return Err(EditError::InvalidOrder(parent.clone()));
+                }
+                children.sort_by_key(|child| order.iter().position(|id| *id == child.id));
+                Ok(Operation::Reorder {
+                    parent: parent.clone(),
+                    order: previous,
+                })
+            }
+        }
+    }
+
+    /// Ids of the nodes the operation is applied to.
+    pub fn targets(&self) -> Vec<&str> {
+        match self {
+            Operation::Patch(patch) => vec![&patch.id],
+            Operation::Insert { parent, node, .. } => vec![parent, &node.id],
+            Operation::Delete { id } => vec![id],
+            Operation::Move { id, parent, .. } => vec![id, parent],
+            Operation::Reorder { parent, .. } => vec![parent],
+        }
+    }
+
+    /// Whether the operation changes the structure of the tree rather than node properties.
+    pub fn is_structural(&self) -> bool {
+        !matches!(self, Operation::Patch(_))
+    }
+}
+
+fn not_found(id: &str) -> EditError {
+    EditError::NodeNotFound(id.to_string())
+}
+
+/// Ids of a node and all its descendants.
+fn ids(node: &Node) -> Box<dyn Iterator<Item = &str> + '_> {
+    Box::new(std::iter::once(node.id.as_str()).chain(node.children().iter().flat_map(ids)))
+}
+
+/// Id of the parent of a node and the index of the node in its children.
+fn locate(root: &Node, id: &str) -> Result<(String, usize), EditError> {
+    fn search(node: &Node, id: &str) -> Option<(String, usize)> {
+        match node.children().iter().position(|child| child.id == id) {
+            Some(index) => Some((node.id.clone(), index)),
+            None => node.children().iter().find_map(|child| search(child, id)),
+        }
+    }
+    if root.id == id {
+        return Err(EditError::Root);
+    }
+    search(root, id).ok_or_else(|| not_found(id))
+}
+
+fn children_of<'a>(root: &'a mut Node, id: &str) -> Result<&'a mut Vec<Node>, EditError> {
+    root.find_mut(id)
+        .ok_or_else(|| not_found(id))?
+        .children_mut()
+        .ok_or_else(|| EditError::NotAContainer(id.to_string()))
+}
+
+fn insert(root: &mut Node, parent: &str, index: usize, node: Node) -> Result<(), EditError> {
+    let children = children_of(root, parent)?;
+    if index > children.len() {
+        return Err(EditError::IndexOutOfBounds {
+            parent: parent.to_string(),
+            index,
+        });
+    }
+    children.insert(index, node);
+    Ok(())
+}
+
+/// Applies operations in order, returning their inverses in the same order. Either all
+/// operations are applied or, on error, the tree is restored to its previous state.
+fn apply_all<'a>(
+    root: &mut Node,
+    operations: impl IntoIterator<Item = &'a Operation>,
+) -> Result<Vec<Operation>, EditError> {
+    let mut inverses = Vec::new();
+    for operation in operations {
+        match operation.apply(root) {
+            Ok(inverse) => inverses.push(inverse),
+            Err(error) => {
+                for inverse in inverses.iter().rev() {
+                    inverse
+                        .apply(root)
+                        .expect("inverse operations always apply");
+                }
+                return Err(error);
+            }
+        }
+    }
+    Ok(inverses)
+}
+
+/// The node ids and paths set by operations, `None` if they do anything else than setting
+/// properties.
+fn set_paths(operations: &[Operation]) -> Option<HashSet<(String, PropertyPath)>> {
+    let mut paths = HashSet::new();
+    for operation in operations {
+        let Operation::Patch(patch) = operation else {
+            return None;
+        };
+        for operation in &patch.operations {
+            let PatchOperation::Set { path, .. } = operation else {
+                return None;
+            };
+            paths.insert((patch.id.clone(), path.clone()));
+        }
+    }
+    Some(paths)
+}
+
+/// A named group of operations undone and redone as a single step.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Transaction {
+    pub name: String,
+    /// Operations to apply, in reverse order, to undo the transaction when it is on the undo
+    /// stack or to redo it when it is on the redo stack.
+    operations: Vec<Operation>,
+}
+
+impl Transaction {
+    pub fn operations(&self) -> &[Operation] {
+        &self.operations
+    }
+
+    fn replay(&self, root: &mut Node) -> Result<Transaction, EditError> {
+        Ok(Transaction {
+            name: self.name.clone(),
+            operations: apply_all(root, self.operations.iter().rev())?,
+        })
+    }
+}
+
+/// Names of the transactions that can be undone and redone, most recent first.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HistorySummary {
+    pub undo: Vec<String>,
+    pub redo: Vec<String>,
+}
+
+/// Unlimited undo/redo history of the transactions applied to a document.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct History {
+    undo: Vec<Transaction>,
+    redo: Vec<Transaction>,
+    /// Key of the transaction on top of the undo stack while it still accepts coalesced edits.
+    coalescing: Option<String>,
+}
+
+impl History {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Applies operations atomically as a new transaction.
+    pub fn apply(
+        &mut self,
+        root: &mut Node,
+        name: &str,
+        operations: &[Operation],
+    ) -> Result<(), EditError> {
+        let inverses = apply_all(root, operations)?;
+        self.redo.clear();
+        self.coalescing = None;
+        self.undo.push(Transaction {
+            name: name.to_string(),
+            operations: inverses,
+        });
+        Ok(())
+    }
+
+    /// Applies operations atomically, merging them into the previous transaction if it was
+    /// applied with the same key. Used for drag-style edits which should be undone in one step.
+    pub fn apply_coalesced(
+        &mut self,
+        root: &mut Node,
+        name: &str,
+        key: &str,
+        operations: &[Operation],
+    ) -> Result<(), EditError> {
+        if self.coalescing.as_deref() != Some(key) {
+            self.apply(root, name, operations)?;
+            self.coalescing = Some(key.to_string());
+            return Ok(());
+        }
+        let inverses = apply_all(root, operations)?;
+        let transaction = self
+            .undo
+            .last_mut()
+            .expect("a coalescing transaction is on the undo stack");
+        transaction.name = name.to_string();
+        // Drags produce a patch per step. Undo applies inverses from last to first, so when the
+        // transaction only sets properties, restoring one that an earlier inverse restores as
+        // well is redundant.
+        let inverses: Vec<Operation> =
+            match (set_paths(&transaction.operations), set_paths(&inverses)) {
+                (Some(restored), Some(_)) => inverses
+                    .into_iter()
+                    .filter(|inverse| {
+                        !set_paths(std::slice::from_ref(inverse))
+                            .is_some_and(|paths| paths.is_subset(&restored))
+                    })
+                    .collect(),
+                _ => inverses,
+            };
+        transaction.operations.extend(inverses);
+        Ok(())
+    }
+
+    /// Stops merging edits into the last transaction, e.g. once a drag ends.
+    pub fn end_coalescing(&mut self) {
+        self.coalescing = None;
+    }
+
+    /// Undoes the last transaction, returning the transaction redoing it, or `None` if there is
+    /// nothing to undo.
+    pub fn undo(&mut self, root: &mut Node) -> Result<Option<&Transaction>, EditError> {
+        self.coalescing = None;
+        let Some(transaction) = self.undo.pop() else {
+            return Ok(None);
+        };
+        match transaction.replay(root) {
+            Ok(redo) => {
+                self.redo.push(redo);
+                Ok(self.redo.last())
+            }
+            Err(error) => {
+                self.undo.push(transaction);
+                Err(error)
+            }
+        }
+    }
+
+    /// Redoes the last undone transaction, returning the transaction undoing it, or `None` if
+    /// there is nothing to redo.
+    pub fn redo(&mut self, root: &mut Node) -> Result<Option<&Transaction>, EditError> {
+        self.coalescing = None;
+        let Some(transaction) = self.redo.pop() else {
+            return Ok(None);
+        };
+        match transaction.replay(root) {
+            Ok(undo) => {
+                self.undo.push(undo);
+                Ok(self.undo.last())
+            }
+            Err(error) => {
+                self.redo.push(transaction);
+                Err(error)
+            }
+        }
+    }
+
+    pub fn can_undo(&self) -> bool {
+        !self.undo.is_empty()
+    }
+
+    pub fn can_redo(&self) -> bool {
+        !self.redo.is_empty()
+    }
+
+    pub fn clear(&mut self) {
+        self.undo.clear();
+        self.redo.clear();
+        self.coalescing = None;
+    }
+
+    pub fn summary(&self) -> HistorySummary {
+        let names = |stack: &[Transaction]| {
+            stack
+                .iter()
+                .rev()
+                .map(|transaction| transaction.name.clone())
+                .collect()
+        };
+        HistorySummary {
+            undo: names(&self.undo),
+            redo: names(&self.redo),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+    use crate::document::Document;
+    use crate::fixtures;
+
+    /// A document with layers `1:1`, `1:2` and `1:3` on its page `0:1`.
+    fn root() -> Node {
+        let mut document = Document::new("Edit");
+        let page = &mut document.document.children_mut().unwrap()[0];
+        *page.children_mut().unwrap() = ["1:1", "1:2", "1:3"]
+            .map(|id| fixtures::vector(id, id))
+            .to_vec();
+        document.document
+    }
+
+    fn set_opacity(id: &str, opacity: f32) -> Operation {
+        Operation::Patch(Patch {
+            id: id.to_string(),
+            operations: vec![PatchOperation::Set {
+                path: "opacity".parse().unwrap(),
+                value: json!(opacity),
+            }],
+        })
+    }
+
+    fn opacity(root: &Node, id: &str) -> serde_json::Value {
+        fig_types::patch::properties(root.find(id).unwrap())["opacity"].clone()
+    }
+
+    fn children(root: &Node, id: &str) -> Vec<String> {
+        let node = root.find(id).unwrap();
+        node.children()
+            .iter()
+            .map(|child| child.id.clone())
+            .collect()
+    }
+
+    /// Applies an operation, checks its inverse and that the inverse restores the tree.
+    fn apply(root: &mut Node, operation: Operation, expected: Operation) {
+        let before = root.clone();
+        let inverse = operation.apply(root).unwrap();
+        assert_eq!(inverse, expected);
+        assert_ne!(*root, before);
+        assert_eq!(inverse.apply(root).unwrap(), operation);
+        assert_eq!(*root, before);
+    }
+
+    #[test]
+    fn operations_return_their_inverse() {
+        let mut root = root();
+        apply(&mut root, set_opacity("1:1", 0.5), set_opacity("1:1", 1.0));
+        let node = Box::new(fixtures::vector("1:4", "Added"));
+        let insert = Operation::Insert {
+            parent: "0:1".to_string(),
+            index: 1,
+            node: node.clone(),
+        };
+        let delete = Operation::Delete {
+            id: "1:4".to_string(),
+        };
+        apply(&mut root, insert.clone(), delete.clone());
+        insert.apply(&mut root).unwrap();
+        apply(&mut root, delete, insert);
+        let moved = Operation::Move {
+            id: "1:1".to_string(),
+            parent: "0:1".to_string(),
+            index: 3,
+        };
+        let back = Operation::Move {
+            id: "1:1".to_string(),
+            parent: "0:1".to_string(),
+            index: 0,
+        };
+        apply(&mut root, moved, back);
+        let reorder = Operation::Reorder {
+            parent: "0:1".to_string(),
+            order: ["1:3", "1:1", "1:4", "1:2"].map(String::from).to_vec(),
+        };
+        let previous = Operation::Reorder {
+            parent: "0:1".to_string(),
+            order: children(&root, "0:1"),
+        };
+        apply(&mut root, reorder, previous);
+    }
+
+    #[test]
+    fn invalid_operations_leave_the_tree_untouched() {
+        let mut root = root();
+        let before = root.clone();
+        let errors = [
+            Operation::Delete {
+                id: "0:0".to_string(),
+            },
+            Operation::Delete {
+                id: "9:9".to_string(),
+            },
+            Operation::Insert {
+                parent: "0:1".to_string(),
+                index: 0,
+                node: Box::new(fixtures::vector("1:2", "Duplicate")),
+            },
+            Operation::Insert {
+                parent: "0:1".to_string(),
+                index: 4,
+                node: Box::new(fixtures::vector("1:4", "Added")),
+            },
+            Operation::Move {
+                id: "0:1".to_string(),
+                parent: "0:1".to_string(),
+                index: 0,
+            },
+            Operation::Move {
+                id: "1:1".to_string(),
+                parent: "1:2".to_string(),
+                index: 0,
+            },
+            Operation::Move {
+                id: "1:1".to_string(),
+                parent: "0:1".to_string(),
+                index: 3,
+            },
+            Operation::Reorder {
+                parent: "0:1".to_string(),
+                order: vec!["1:1".to_string()],
+            },
+        ]
+        .map(|operation| operation.apply(&mut root).unwrap_err().to_string());
+        assert_eq!(
+            errors,
+            [
+                "the root node cannot be deleted or moved",
+                "no node with id `9:9`",
+                "a node with id `1:2` already exists",
+                "index 4 is out of bounds for the children of `0:1`",
+                "cannot move `0:1` inside its own subtree",
+                "node `1:2` cannot hold children",
+                "index 3 is out of bounds for the children of `0:1`",
+                "the new order is not a permutation of the children of `0:1`",
+            ]
+        );
+        assert_eq!(root, before);
+    }
+
+    #[test]
+    fn transactions_are_undone_and_redone() {
+        let mut root = root();
+        let original = root.clone();
+        let mut history = History::new();
+        history
+            .apply(&mut root, "Fade", &[set_opacity("1:1", 0.5)])
+            .unwrap();
+        let delete = Operation::Delete {
+            id: "1:2".to_string(),
+        };
+        history
+            .apply(&mut root, "Delete", &[set_opacity("1:3", 0.25), delete])
+            .unwrap();
+        let edited = root.clone();
+        assert_eq!(children(&root, "0:1"), ["1:1", "1:3"]);
+
+        let redo = history.undo(&mut root).unwrap().unwrap();
+        assert_eq!(redo.name, "Delete");
+        assert_eq!(children(&root, "0:1"), ["1:1", "1:2", "1:3"]);
+        assert_eq!(opacity(&root, "1:3"), json!(1.0));
+        history.undo(&mut root).unwrap();
+        assert_eq!(root, original);
+        assert!(history.undo(&mut root).unwrap().is_none());
+        assert_eq!(
+            history.summary(),
+            HistorySummary {
+                undo: vec![],
+                redo: vec!["Fade".to_string(), "Delete".to_string()],
+            }
+        );
+
+        history.redo(&mut root).unwrap();
+        history.redo(&mut root).unwrap();
+        assert_eq!(root, edited);
+        assert!(!history.can_redo());
+        assert!(history.redo(&mut root).unwrap().is_none());
+    }
+
+    #[test]
+    fn failed_transactions_are_not_recorded() {
+        let mut root = root();
+        let before = root.clone();
+        let mut history = History::new();
+        let missing = Operation::Delete {
+            id: "9:9".to_string(),
+        };
+        let error = history.apply(&mut root, "Fade", &[set_opacity("1:1", 0.5), missing]);
+        assert!(matches!(error, Err(EditError::NodeNotFound(_))));
+        assert_eq!(root, before);
+        assert!(!history.can_undo());
+    }
+
+    #[test]
+    fn new_transactions_clear_the_redo_stack() {
+        let mut root = root();
+        let mut history = History::new();
+        history
+            .apply(&mut root, "Fade", &[set_opacity("1:1", 0.5)])
+            .unwrap();
+        history.undo(&mut root).unwrap();
+        assert!(history.can_redo());
+        history
+            .apply(&mut root, "Dim", &[set_opacity("1:2", 0.5)])
+            .unwrap();
+        assert!(!history.can_redo());
+        assert_eq!(history.summary().undo, ["Dim"]);
+    }
+
+    #[test]
+    fn coalesced_edits_are_undone_in_one_step() {
+        let mut root = root();
+        let mut history = History::new();
+        for opacity in [0.75, 0.5, 0.25] {
+            history
+                .apply_coalesced(
+                    &mut root,
+                    "Drag",
+                    "drag:1:1",
+                    &[set_opacity("1:1", opacity)],
+                )
+                .unwrap();
+        }
+        assert_eq!(history.summary().undo, ["Drag"]);
+        // Only the first inverse restores the opacity, the later ones are redundant.
+        assert_eq!(history.undo[0].operations(), [set_opacity("1:1", 1.0)]);
+        history.undo(&mut root).unwrap();
+        assert_eq!(opacity(&root, "1:1"), json!(1.0));
+        history.redo(&mut root).unwrap();
+        assert_eq!(opacity(&root, "1:1"), json!(0.25));
+
+        // Another key, or the end of the drag, starts a new transaction.
+        history
+            .apply_coalesced(&mut root, "Drag", "drag:1:2", &[set_opacity("1:2", 0.5)])
+            .unwrap();
+        history.end_coalescing();
+        history
+            .apply_coalesced(&mut root, "Drag", "drag:1:2", &[set_opacity("1:2", 0.25)])
+            .unwrap();
+        assert_eq!(history.summary().undo, ["Drag", "Drag", "Drag"]);
+        history.undo(&mut root).unwrap();
+        assert_eq!(opacity(&root, "1:2"), json!(0.5));
+    }
+}
@@ -0,0 +1,231 @@
+//! Exports of nodes as configured by their export settings.
+
+use std::collections::HashSet;
+
+use fig_types::node::{Node, NodeType};
+use fig_types::properties::{ConstraintType, ExportSetting, FormatType};
+
+use crate::render::{self, ImageFormat, RenderError};
+
+/// An image to render for an export setting of a node.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Export {
+    /// Id of the exported node.
+    pub node: String,
+    /// Name of the exported file, made of the node name, the suffix of the setting and the
+    /// extension of the format.
+    pub file_name: String,
+    pub format: ImageFormat,
+    pub scale: f64,
+}
+
+impl Export {
+    pub fn render(&self, root: &Node) -> Result<Vec<u8>, RenderError> {
+        let node = root
+            .find(&self.node)
+            .ok_or_else(|| RenderError::NotRenderable(self.node.clone()))?;
+        render::render(node, self.format, self.scale)
+    }
+}
+
+/// The export settings of a node.
+pub fn settings(node: &Node) -> &[ExportSetting] {
+    match &node.node {
+        NodeType::Vector(vector) => &vector.export_settings,
+        NodeType::Rectangle(vector) => &vector.export_settings,
+        NodeType::Document(_) | NodeType::Canvas(_) => &[],
+    }
+}
+
+/// The exports configured on the nodes of a tree, with unique file names.
+pub fn exports(root: &Node) -> Vec<Export> {
+    let mut exports = Vec::new();
+    let mut file_names = HashSet::new();
+    collect(root, &mut exports, &mut file_names);
+    exports
+}
+
+fn collect(node: &Node, exports: &mut Vec<Export>, file_names: &mut HashSet<String>) {
+    for setting in settings(node) {
+        let (format, extension) = match setting.format {
+            FormatType::SVG => (ImageFormat::Svg, "svg"),
+            FormatType::PNG => (ImageFormat::Png, "png"),
+            FormatType::JPG => (ImageFormat::Jpg, "jpg"),
+        };
+        let name = match node.name.trim() {
+            "" => &node.id,
+            name => name,
+        };
+        let stem = format!("{}{}", sanitize(name), sanitize(&setting.suffix));
+        let mut file_name = format!("{stem}.{extension}");
+        let mut copy = 1;
+        while !file_names.insert(file_name.clone()) {
+            copy += 1;
+            file_name = format!("{stem} {copy}.{extension}");
+        }
+        exports.push(Export {
+            node: node.id.clone(),
+            file_name,
+            format,
+            scale: scale(node, setting),
+        });
+    }
+    for child in node.children() {
+        collect(child, exports, file_names);
+    }
+}
+
+/// The scale factor of an export: given directly, or deduced from the target width or height.
+fn scale(node: &Node, setting: &ExportSetting) -> f64 {
+    let value = f64::from(setting.constraint.value);
+    let bounds = render::bounds(node);
+    let scale = match setting.constraint.constraint_type {
+        ConstraintType::Scale => value,
+        ConstraintType::Width => bounds.map_or(1.0, |bounds| value / bounds.width),
+        ConstraintType::Height => bounds.map_or(1.0, |bounds| value / bounds.height),
+    };
+    match scale.is_finite() && scale > 0.0 {
+        true => scale,
+        false => 1.0,
+    }
+}
+
+/// A node name usable as a file name on every platform.
+pub(crate) fn sanitize(name: &str) -> String {
+    name.chars()
+        .map(|character| match character {
+            '/' | '\\' | ':' | '*' | '?' | '"' | '<' | '>' | '|' => '_',
+            character if character.is_control() => '_',
+            character => character,
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use fig_types::properties::Constraint;
+    use resvg::tiny_skia::Pixmap;
+    use serde_json::json;
+
+    use super::*;
+    use crate::fixtures;
+
+    fn setting(
+        suffix: &str,
+        format: FormatType,
+        constraint_type: ConstraintType,
+        value: f32,
+    ) -> ExportSetting {
+        ExportSetting {
+            suffix: suffix.to_string(),
+            format,
+            constraint: Constraint {
+                constraint_type,
+                value,
+            },
+        }
+    }
+
+    /// A 100 by 40 rectangle exported with the settings.
+    fn layer(id: &str, name: &str, settings: Vec<ExportSetting>) -> Node {
+        let mut properties = fixtures::bounds(0.0, 0.0, 100.0, 40.0);
+        properties["fills"] = json!([fixtures::solid(0.0, 0.0, 1.0)]);
+        properties["exportSettings"] = serde_json::to_value(settings).unwrap();
+        fixtures::layer("rectangle", id, name, properties)
+    }
+
+    #[test]
+    fn scales_follow_the_constraints() {
+        let node = layer("1:1", "Icon", Vec::new());
+        let scale = |constraint_type, value| {
+            scale(&node, &setting("", FormatType::PNG, constraint_type, value))
+        };
+        assert_eq!(scale(ConstraintType::Scale, 2.0), 2.0);
+        assert_eq!(scale(ConstraintType::Width, 300.0), 3.0);
+        assert_eq!(scale(ConstraintType::Height, 20.0), 0.5);
+        // Constraints which would not give an image fall back to the actual size.
+        assert_eq!(scale(ConstraintType::Width, 0.0), 1.0);
+        assert_eq!(scale(ConstraintType::Scale, -1.0), 1.0);
+
+        let mut hidden = node.clone();
+        hidden.visible = false;
+        let setting = setting("", FormatType::PNG, ConstraintType::Width, 300.0);
+        assert_eq!(super::scale(&hidden, &setting), 1.0);
+    }
+
+    #[test]
+    fn exports_have_unique_file_names() {
+        let settings = vec![
+            setting("@2x", FormatType::PNG, ConstraintType::Scale, 2.0),
+            setting("", FormatType::SVG, ConstraintType::Scale, 1.0),
+        ];
+        let mut document = crate::document::Document::new("Export");
+        let page = &mut document.document.children_mut().unwrap()[0];
+        *page.children_mut().unwrap() = vec![
+            layer("1:1", "icons/close", settings.clone()),
+            layer("1:2", "icons/close", settings),
+            layer(
+                "1:3",
+                " ",
+                vec![setting("", FormatType::JPG, ConstraintType::Height, 80.0)],
+            ),
+        ];
+        let exports: Vec<_> = exports(&document.document)
+            .into_iter()
+            .map(|export| (export.node, export.file_name, export.format, export.scale))
+            .collect();
+        assert_eq!(
+            exports,
+            [
+                (
+                    "1:1".to_string(),
+                    "icons_close@2x.png".to_string(),
+                    ImageFormat::Png,
+                    2.0
+                ),
+                (
+                    "1:1".to_string(),
+                    "icons_close.svg".to_string(),
+                    ImageFormat::Svg,
+                    1.0
+                ),
+                (
+                    "1:2".to_string(),
+                    "icons_close@2x 2.png".to_string(),
+                    ImageFormat::Png,
+                    2.0
+                ),
+                (
+                    "1:2".to_string(),
+                    "icons_close 2.svg".to_string(),
+                    ImageFormat::Svg,
+                    1.0
+                ),
+                (
+                    "1:3".to_string(),
+                    "1_3.jpg".to_string(),
+                    ImageFormat::Jpg,
+                    2.0
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn exports_are_rendered_at_their_scale() {
+        let settings = vec![setting("", FormatType::PNG, ConstraintType::Width, 250.0)];
+        let node = layer("1:1", "Banner", settings);
+        let export = &exports(&node)[0];
+        let pixmap = Pixmap::decode_png(&export.render(&node).unwrap()).unwrap();
+        assert_eq!((pixmap.width(), pixmap.height()), (250, 100));
+
+        let missing = Export {
+            node: "9:9".to_string(),
+            ..export.clone()
+        };
+        assert!(matches!(
+            missing.render(&node),
+            Err(RenderError::NotRenderable(id)) if id == "9:9"
+        ));
+    }
+}
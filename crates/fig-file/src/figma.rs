@@ -0,0 +1,136 @@
+//! Conversion from and to the JSON files returned by the Figma REST API (`GET /v1/files/:key`).
+//!
+//! Nodes and the definitions they share are written with the Figma profile of the types, see
+//! [`fig_types::profile`]. The file adds its name and the version of its schema at the top level.
+
+use fig_types::node::NodeType;
+use fig_types::profile::{self, Profile};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use serde_json::{Map, Value};
+
+use crate::document::{Document, FileError, SCHEMA_VERSION};
+
+/// Whether a JSON value looks like a Figma file rather than a native document.
+pub fn is_rest(value: &Value) -> bool {
+    value
+        .get("document")
+        .and_then(|document| document.get("type"))
+        .is_some_and(Value::is_string)
+}
+
+/// The Figma file equivalent to a document.
+pub fn to_rest(document: &Document) -> Result<Value, FileError> {
+    fn insert<T: Serialize>(
+        file: &mut Map<String, Value>,
+        key: &str,
+        value: &T,
+    ) -> Result<(), FileError> {
+        file.insert(key.to_string(), profile::to_value(value, Profile::Figma)?);
+        Ok(())
+    }
+    let mut file = Map::new();
+    file.insert("name".to_string(), Value::from(document.name()));
+    file.insert("schemaVersion".to_string(), Value::from(0));
+    insert(&mut file, "document", &document.document)?;
+    insert(&mut file, "components", &document.components)?;
+    insert(&mut file, "componentSets", &document.component_sets)?;
+    insert(&mut file, "styles", &document.styles)?;
+    insert(&mut file, "variables", &document.variables)?;
+    insert(
+        &mut file,
+        "variableCollections",
+        &document.variable_collections,
+    )?;
+    insert(&mut file, "devSnapshots", &document.dev_snapshots)?;
+    insert(&mut file, "versions", &document.versions)?;
+    Ok(Value::Object(file))
+}
+
+/// The document equivalent to a Figma file.
+pub fn from_rest(file: Value) -> Result<Document, FileError> {
+    fn take<T: DeserializeOwned + Default>(
+        file: &mut Map<String, Value>,
+        key: &str,
+    ) -> Result<T, FileError> {
+        match file.remove(key) {
+            Some(value) => Ok(profile::from_value(value, Profile::Figma)?),
+            None => Ok(T::default()),
+        }
+    }
+    let Value::Object(mut file) = file else {
+        return Err(FileError::NotADocument);
+    };
+    let root = file.remove("document").ok_or(FileError::NotADocument)?;
+    let mut document = Document {
+        schema_version: SCHEMA_VERSION,
+        document: profile::from_value(root, Profile::Figma)?,
+        components: take(&mut file, "components")?,
+        component_sets: take(&mut file, "componentSets")?,
+        styles: take(&mut file, "styles")?,
+        variables: take(&mut file, "variables")?,
+        variable_collections: take(&mut file, "variableCollections")?,
+        dev_snapshots: take(&mut file, "devSnapshots")?,
+        versions: take(&mut file, "versions")?,
+    };
+    if !matches!(document.document.node, NodeType::Document(_)) {
+        return Err(FileError::NotADocument);
+    }
+    if let Some(Value::String(name)) = file.remove("name") {
+        document.document.name = name;
+    }
+    Ok(document)
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+    use crate::fixtures;
+
+    fn document() -> Document {
+        let mut document = Document::new("Design system");
+        let mut properties = fixtures::bounds(10.0, 20.0, 100.0, 40.0);
+        properties["fills"] = json!([fixtures::solid(0.2, 0.4, 0.6)]);
+        properties["styles"] = json!({ "Fill": "S:1" });
+        let page = &mut document.document.children_mut().unwrap()[0];
+        *page.children_mut().unwrap() = vec![
+            fixtures::layer("rectangle", "1:1", "Card", properties),
+            fixtures::vector("1:2", "Icon"),
+        ];
+        document.styles.insert(
+            "S:1".to_string(),
+            serde_json::from_value(json!({
+                "key": "",
+                "name": "Brand",
+                "description": "",
+                "remote": false,
+                "styleType": "Fill",
+                "value": { "Fill": [fixtures::solid(0.2, 0.4, 0.6)] },
+            }))
+            .unwrap(),
+        );
+        document
+    }
+
+    #[test]
+    fn documents_round_trip_through_figma_files() {
+        let document = document();
+        let file = to_rest(&document).unwrap();
+        assert!(is_rest(&file));
+        assert_eq!(file["name"], "Design system");
+        assert_eq!(file["document"]["type"], "DOCUMENT");
+        assert_eq!(from_rest(file).unwrap(), document);
+    }
+
+    #[test]
+    fn native_documents_are_not_figma_files() {
+        let native = serde_json::to_value(document()).unwrap();
+        assert!(!is_rest(&native));
+        assert!(matches!(from_rest(json!([])), Err(FileError::NotADocument)));
+        let mut file = to_rest(&document()).unwrap();
+        file["document"] = file["document"]["children"][0].take();
+        assert!(matches!(from_rest(file), Err(FileError::NotADocument)));
+    }
+}
@@ -0,0 +1,132 @@
+//! Layers and paints shared by the tests of the modules, and of the crates depending on fig-file
+//! with the `test-fixtures` feature.
+
+use fig_types::node::Node;
+use serde_json::{json, Value};
+
+/// A 100 by 40 layer at the origin with the properties of the defaults replaced.
+pub fn layer(node_type: &str, id: &str, name: &str, properties: Value) -> Node {
+    let mut data = json!({
+        "locked": false,
+        "exportSettings": [],
+        "blendMode": "Normal",
+        "preserveRatio": false,
+        "layoutAlign": "Inherit",
+        "layoutGrow": 0,
+        "constraint": { "vertical": "Top", "horizontal": "Left" },
+        "transitionEasing": "Linear",
+        "opacity": 1.0,
+        "absoluteBoundingBox": { "x": 0.0, "y": 0.0, "width": 100.0, "height": 40.0 },
+        "effects": [],
+        "size": { "width": 100.0, "height": 40.0 },
+        "relativeTransform": { "matrix": [[1.0, 0.0, 0.0], [0.0, 1.0, 0.0]] },
+        "isMask": false,
+        "fills": [],
+        "fillGeometry": [],
+        "strokes": [],
+        "strokeWeight": 0.0,
+        "individualStrokeWeights": { "top": 0.0, "right": 0.0, "bottom": 0.0, "left": 0.0 },
+        "strokeCap": "None",
+        "strokeJoin": "Miter",
+        "strokeDashes": [],
+        "strokeMiterAngle": 28.96,
+        "strokeGeometry": [],
+        "strokeAlign": "Inside",
+        "annotations": [],
+        "additionalData": {},
+    });
+    if node_type == "rectangle" {
+        data["additionalData"] = json!({
+            "cornerRadius": 0.0,
+            "rectangleCornerRadii": [0.0, 0.0, 0.0, 0.0],
+            "cornerSmoothing": 0.0,
+        });
+    }
+    for (key, value) in properties.as_object().unwrap() {
+        data[key] = value.clone();
+    }
+    serde_json::from_value(json!({
+        "id": id,
+        "name": name,
+        "visible": true,
+        "rotation": 0.0,
+        "node": { "type": node_type, "data": data },
+    }))
+    .unwrap()
+}
+
+/// A vector layer with the default properties.
+pub fn vector(id: &str, name: &str) -> Node {
+    layer("vector", id, name, json!({}))
+}
+
+/// The properties placing a layer at a position, with its size.
+pub fn bounds(x: f32, y: f32, width: f32, height: f32) -> Value {
+    json!({
+        "absoluteBoundingBox": { "x": x, "y": y, "width": width, "height": height },
+        "size": { "width": width, "height": height },
+        "relativeTransform": { "matrix": [[1.0, 0.0, x], [0.0, 1.0, y]] },
+    })
+}
+
+/// A paint of a type with its color, gradient stops and gradient handles.
+pub fn paint(paint_type: &str, color: Value, stops: Value, handles: Value) -> Value {
+    json!({
+        "paintType": paint_type,
+        "visible": true,
+        "opacity": 1.0,
+        "color": color,
+        "blendMode": "Normal",
+        "gradientHandlePositions": handles,
+        "gradientStops": stops,
+        "scaleMode": "Fill",
+        "rotation": 0.0,
+        "imageRef": "",
+        "filters": {
+            "exposure": 0.0,
+            "contrast": 0.0,
+            "saturation": 0.0,
+            "temperature": 0.0,
+            "tint": 0.0,
+            "highlights": 0.0,
+            "shadows": 0.0,
+        },
+        "gifRef": "",
+    })
+}
+
+/// An opaque solid paint.
+pub fn solid(r: f32, g: f32, b: f32) -> Value {
+    let color = json!({ "r": r, "g": g, "b": b, "a": 1.0 });
+    paint("Solid", color, json!([]), json!([]))
+}
+
+/// A canvas `0:1` holding layers.
+pub fn canvas(children: Vec<Node>) -> Node {
+    serde_json::from_value(json!({
+        "id": "0:1",
+        "name": "Page 1",
+        "visible": true,
+        "rotation": 0.0,
+        "node": { "type": "canvas", "data": {
+            "children": children,
+            "backgroundColor": { "r": 1.0, "g": 1.0, "b": 1.0, "a": 1.0 },
+        }},
+    }))
+    .unwrap()
+}
+
+/// An effect of a type with a translucent black color, offset 2 pixels down.
+pub fn effect(effect_type: &str, radius: f32) -> Value {
+    json!({
+        "effectType": effect_type,
+        "visible": true,
+        "radius": radius,
+        "color": { "r": 0.0, "g": 0.0, "b": 0.0, "a": 0.25 },
+        "blendMode": "Normal",
+        "offset": { "x": 0.0, "y": 2.0 },
+        "spread": 0.0,
+        "showShadowBehindNode": false,
+        "boundVariables": {},
+    })
+}
@@ -0,0 +1,395 @@
+//! The flow graphs of the prototypes of a document.
+//!
+//! The screens of a canvas are its top-level nodes, and their interactions navigate between
+//! them. Each flow starts from a screen and reaches the screens its navigations lead to. Screens
+//! no flow reaches, and reached screens which cannot be left, are usually mistakes.
+
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::fmt::Write;
+
+use fig_types::node::{Node, NodeType};
+use fig_types::profile::screaming_snake_case;
+use fig_types::properties::{Action, NavigationType, TriggerType};
+
+use crate::document::Document;
+
+/// A navigation from a screen to another.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Edge<'a> {
+    /// The screen navigated from.
+    pub from: &'a str,
+    /// The screen navigated to.
+    pub to: &'a str,
+    /// The node of the screen holding the interaction.
+    pub node: &'a str,
+    /// The trigger of the interaction, `None` for interactions without one.
+    pub trigger: Option<TriggerType>,
+    pub navigation: NavigationType,
+}
+
+/// A prototype flow and the screens it reaches.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Flow<'a> {
+    pub name: &'a str,
+    /// The node the flow starts from.
+    pub start: &'a str,
+    /// The screens reached by the flow, from its starting screen, closest first.
+    pub screens: Vec<&'a str>,
+}
+
+/// The screens of a canvas, the navigations between them and its flows.
+#[derive(Debug, Clone)]
+pub struct FlowGraph<'a> {
+    pub canvas: &'a Node,
+    pub screens: Vec<&'a Node>,
+    pub edges: Vec<Edge<'a>>,
+    pub flows: Vec<Flow<'a>>,
+    /// Screens which can be left by going back or closing them.
+    exits: HashSet<&'a str>,
+}
+
+impl<'a> FlowGraph<'a> {
+    /// The flow graph of a canvas, `None` for other nodes.
+    pub fn new(canvas: &'a Node) -> Option<Self> {
+        let NodeType::Canvas(data) = &canvas.node else {
+            return None;
+        };
+        let mut screen_of = HashMap::new();
+        for screen in canvas.children() {
+            index(screen, &screen.id, &mut screen_of);
+        }
+        let mut graph = FlowGraph {
+            canvas,
+            screens: canvas.children().iter().collect(),
+            edges: Vec::new(),
+            flows: Vec::new(),
+            exits: HashSet::new(),
+        };
+        for screen in canvas.children() {
+            graph.collect(screen, &screen.id, &screen_of);
+        }
+        graph.flows = data
+            .flow_starting_points
+            .iter()
+            .map(|flow| Flow {
+                name: &flow.name,
+                start: &flow.node_id,
+                screens: screen_of
+                    .get(flow.node_id.as_str())
+                    .map(|start| graph.reach(start))
+                    .unwrap_or_default(),
+            })
+            .collect();
+        Some(graph)
+    }
+
+    /// The screens no flow reaches.
+    pub fn unreachable(&self) -> Vec<&'a Node> {
+        let reached = self.reached();
+        self.screens
+            .iter()
+            .filter(|screen| !reached.contains(screen.id.as_str()))
+            .copied()
+            .collect()
+    }
+
+    /// The screens reached by a flow which navigate nowhere and cannot be left by going back or
+    /// closing them.
+    pub fn dead_ends(&self) -> Vec<&'a Node> {
+        let reached = self.reached();
+        let left: HashSet<&str> = self.edges.iter().map(|edge| edge.from).collect();
+        self.screens
+            .iter()
+            .filter(|screen| {
+                let id = screen.id.as_str();
+                reached.contains(id) && !left.contains(id) && !self.exits.contains(id)
+            })
+            .copied()
+            .collect()
+    }
+
+    fn reached(&self) -> HashSet<&'a str> {
+        self.flows
+            .iter()
+            .flat_map(|flow| flow.screens.iter().copied())
+            .collect()
+    }
+
+    /// The screens reached from a screen, breadth first.
+    fn reach(&self, start: &'a str) -> Vec<&'a str> {
+        let mut reached = vec![start];
+        let mut seen = HashSet::from([start]);
+        let mut queue = VecDeque::from([start]);
+        while let Some(screen) = queue.pop_front() {
+            for edge in self.edges.iter().filter(|edge| edge.from == screen) {
+                if seen.insert(edge.to) {
+                    reached.push(edge.to);
+                    queue.push_back(edge.to);
+                }
+            }
+        }
+        reached
+    }
+
+    fn collect(&mut self, node: &'a Node, screen: &'a str, screen_of: &HashMap<&str, &'a str>) {
+        for interaction in node.interactions() {
+            let trigger = interaction
+                .trigger
+                .as_ref()
+                .map(|trigger| trigger.trigger_type.clone());
+            self.actions(&interaction.actions, node, screen, &trigger, screen_of);
+        }
+        for child in node.children() {
+            self.collect(child, screen, screen_of);
+        }
+    }
+
+    fn actions(
+        &mut self,
+        actions: &'a [Action],
+        node: &'a Node,
+        screen: &'a str,
+        trigger: &Option<TriggerType>,
+        screen_of: &HashMap<&str, &'a str>,
+    ) {
+        for action in actions {
+            match action {
+                Action::BackAction | Action::CloseAction => {
+                    self.exits.insert(screen);
+                }
+                Action::ConditionalAction(conditional) => {
+                    for block in &conditional.conditional_blocks {
+                        self.actions(&block.actions, node, screen, trigger, screen_of);
+                    }
+                }
+                Action::NodeAction(action) => {
+                    let navigation = &action.navigation.navigation_type;
+                    // Scrolling and changing variants stay on the screen.
+                    if matches!(
+                        navigation,
+                        NavigationType::ScrollTo | NavigationType::ChangeTo
+                    ) {
+                        continue;
+                    }
+                    let destination = action
+                        .destination_id
+                        .as_deref()
+                        .and_then(|id| screen_of.get(id));
+                    if let Some(destination) = destination {
+                        self.edges.push(Edge {
+                            from: screen,
+                            to: destination,
+                            node: &node.id,
+                            trigger: trigger.clone(),
+                            navigation: navigation.clone(),
+                        });
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+}
+
+/// Maps the ids of a screen and its descendants to the id of the screen.
+fn index<'a>(node: &'a Node, screen: &'a str, screen_of: &mut HashMap<&'a str, &'a str>) {
+    screen_of.insert(&node.id, screen);
+    for child in node.children() {
+        index(child, screen, screen_of);
+    }
+}
+
+/// The flow graphs of the canvases of a document.
+pub fn flow_graphs(document: &Document) -> Vec<FlowGraph<'_>> {
+    document
+        .document
+        .children()
+        .iter()
+        .filter_map(FlowGraph::new)
+        .collect()
+}
+
+/// Writes flow graphs as a Graphviz DOT digraph, with a cluster per canvas. Flows point to their
+/// starting screens, unreachable screens are dashed and dead ends are red.
+pub fn dot(graphs: &[FlowGraph]) -> String {
+    let mut dot = String::from("digraph flows {\n  rankdir=LR;\n  node [shape=box];\n");
+    for graph in graphs {
+        let unreachable: HashSet<&str> = graph
+            .unreachable()
+            .iter()
+            .map(|screen| screen.id.as_str())
+            .collect();
+        let dead_ends: HashSet<&str> = graph
+            .dead_ends()
+            .iter()
+            .map(|screen| screen.id.as_str())
+            .collect();
+        let canvas = &graph.canvas.id;
+        writeln!(dot, "  subgraph {} {{", quote(&format!("cluster_{canvas}"))).unwrap();
+        writeln!(dot, "    label={};", quote(&graph.canvas.name)).unwrap();
+        for screen in &graph.screens {
+            let id = screen.id.as_str();
+            let mut attributes = format!("label={}", quote(&screen.name));
+            if unreachable.contains(id) {
+                attributes.push_str(", style=dashed");
+            }
+            if dead_ends.contains(id) {
+                attributes.push_str(", color=red");
+            }
+            writeln!(dot, "    {} [{attributes}];", quote(id)).unwrap();
+        }
+        for (index, flow) in graph.flows.iter().enumerate() {
+            let node = quote(&format!("{canvas}/flow/{index}"));
+            let name = quote(flow.name);
+            writeln!(dot, "    {node} [label={name}, shape=plaintext];").unwrap();
+            if let Some(start) = flow.screens.first() {
+                writeln!(dot, "    {node} -> {};", quote(start)).unwrap();
+            }
+        }
+        let mut written = HashSet::new();
+        for edge in &graph.edges {
+            let navigation = screaming_snake_case(&format!("{:?}", edge.navigation));
+            let label = match &edge.trigger {
+                Some(trigger) => {
+                    let trigger = screaming_snake_case(&format!("{trigger:?}"));
+                    format!("{trigger} / {navigation}")
+                }
+                None => navigation,
+            };
+            // Several nodes of a screen often lead to the same place the same way.
+            if written.insert((edge.from, edge.to, label.clone())) {
+                writeln!(
+                    dot,
+                    "    {} -> {} [label={}];",
+                    quote(edge.from),
+                    quote(edge.to),
+                    quote(&label)
+                )
+                .unwrap();
+            }
+        }
+        dot.push_str("  }\n");
+    }
+    dot.push_str("}\n");
+    dot
+}
+
+fn quote(text: &str) -> String {
+    format!("\"{}\"", text.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::{json, Value};
+
+    use super::*;
+    use crate::fixtures;
+
+    fn go(navigation: &str, destination: &str) -> Value {
+        json!({
+            "trigger": {
+                "triggerType": "OnClick",
+                "timeout": 0,
+                "delay": 0,
+                "deprecatedVersion": false,
+                "device": "keyboard",
+                "keyCodes": [],
+                "mediaHitTime": 0,
+            },
+            "actions": [{
+                "type": "Node",
+                "destinationId": destination,
+                "navigation": { "navigationType": navigation },
+                "transition": null,
+                "preserveScrollPosition": false,
+                "overlayRelativePosition": { "x": 0.0, "y": 0.0 },
+                "resetVideoPosition": false,
+                "resetScrollPosition": false,
+                "resetInteractiveComponents": false,
+            }],
+        })
+    }
+
+    fn back() -> Value {
+        json!({ "actions": [{ "type": "Back" }] })
+    }
+
+    /// A phone sized screen with interactions.
+    fn screen(id: &str, name: &str, interactions: Vec<Value>) -> Node {
+        let mut properties = fixtures::bounds(0.0, 0.0, 375.0, 812.0);
+        properties["interactions"] = Value::from(interactions);
+        fixtures::layer("vector", id, name, properties)
+    }
+
+    /// A canvas with an onboarding flow from home to details and a menu overlay, and an orphan
+    /// screen. Details leads nowhere.
+    fn document() -> Document {
+        let mut document = Document::new("Prototype");
+        let canvas = &mut document.document.children_mut().unwrap()[0];
+        *canvas.children_mut().unwrap() = vec![
+            screen(
+                "1:1",
+                "Home",
+                vec![go("Navigate", "1:2"), go("Overlay", "1:3")],
+            ),
+            screen("1:2", "Details", vec![go("ScrollTo", "1:2")]),
+            screen("1:3", "Menu", vec![back()]),
+            screen("1:4", "Orphan", vec![go("Navigate", "1:1")]),
+        ];
+        let NodeType::Canvas(canvas) = &mut canvas.node else {
+            unreachable!();
+        };
+        canvas.flow_starting_points = serde_json::from_value(json!([
+            { "nodeId": "1:1", "name": "Onboarding" },
+            { "nodeId": "1:3", "name": "Menu \"only\"" },
+        ]))
+        .unwrap();
+        document
+    }
+
+    fn ids<'a>(nodes: &[&'a Node]) -> Vec<&'a str> {
+        nodes.iter().map(|node| node.id.as_str()).collect()
+    }
+
+    #[test]
+    fn flows_reach_screens_through_navigations() {
+        let document = document();
+        let graphs = flow_graphs(&document);
+        let graph = &graphs[0];
+
+        assert_eq!(graph.edges.len(), 3);
+        assert_eq!(graph.flows[0].name, "Onboarding");
+        assert_eq!(graph.flows[0].screens, ["1:1", "1:2", "1:3"]);
+        assert_eq!(graph.flows[1].screens, ["1:3"]);
+        assert_eq!(ids(&graph.unreachable()), ["1:4"]);
+        assert_eq!(ids(&graph.dead_ends()), ["1:2"]);
+    }
+
+    #[test]
+    fn flow_graphs_are_written_as_dot() {
+        let document = document();
+
+        assert_eq!(
+            dot(&flow_graphs(&document)),
+            r#"digraph flows {
+  rankdir=LR;
+  node [shape=box];
+  subgraph "cluster_0:1" {
+    label="Page 1";
+    "1:1" [label="Home"];
+    "1:2" [label="Details", color=red];
+    "1:3" [label="Menu"];
+    "1:4" [label="Orphan", style=dashed];
+    "0:1/flow/0" [label="Onboarding", shape=plaintext];
+    "0:1/flow/0" -> "1:1";
+    "0:1/flow/1" [label="Menu \"only\"", shape=plaintext];
+    "0:1/flow/1" -> "1:3";
+    "1:1" -> "1:2" [label="ON_CLICK / NAVIGATE"];
+    "1:1" -> "1:3" [label="ON_CLICK / OVERLAY"];
+    "1:4" -> "1:1" [label="ON_CLICK / NAVIGATE"];
+  }
+}
+"#
+        );
+    }
+}
@@ -0,0 +1,618 @@
+//! Handoff specs: the measurements pinned on the canvases of a document and the annotations of
+//! its layers, with their current values.
+//!
+//! Measurements are resolved to the distance between the sides of the layers they are pinned to,
+//! and the properties pinned by annotations to their value on the annotated layer. Properties of
+//! auto-layout frames and instances have no value, documents have none of them. Specs are written
+//! as JSON or as a Markdown document.
+
+use std::fmt::Write;
+
+use fig_types::node::vector::VectorNode;
+use fig_types::node::{Node, NodeType};
+use fig_types::properties::{
+    Annotation, AnnotationPropertyType, Effect, EffectType, Measurement, MeasurementStartEnd,
+    MeasurementStartEndSide, Paint, PaintType, StyleType, StyleValue, TypeStyle,
+    TypeStyleLineHeightUnit,
+};
+use serde::Serialize;
+
+use crate::document::Document;
+use crate::values::{corner_radii, hex, number, px, stroke_weights, uniform};
+
+/// The handoff spec of a document.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Spec<'a> {
+    pub name: &'a str,
+    pub canvases: Vec<CanvasSpec<'a>>,
+}
+
+/// The measurements and annotations of a canvas.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CanvasSpec<'a> {
+    pub id: &'a str,
+    pub name: &'a str,
+    pub measurements: Vec<MeasurementSpec<'a>>,
+    /// The annotations of the layers of the canvas, in the order of the layers.
+    pub annotations: Vec<AnnotationSpec<'a>>,
+}
+
+/// A measurement and its value.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MeasurementSpec<'a> {
+    pub id: &'a str,
+    pub start: &'a MeasurementStartEnd,
+    pub end: &'a MeasurementStartEnd,
+    /// The distance between the measured sides, see [`distance`].
+    pub distance: Option<f32>,
+    /// The displayed value: the free text of the measurement, or its distance.
+    pub value: Option<String>,
+}
+
+/// An annotation of a layer and the values of its pinned properties.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AnnotationSpec<'a> {
+    pub node_id: &'a str,
+    pub node_name: &'a str,
+    pub label: &'a str,
+    pub properties: Vec<PropertySpec<'a>>,
+}
+
+/// A pinned property and its value on the annotated layer, `None` when the layer has none.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PropertySpec<'a> {
+    pub property: &'a AnnotationPropertyType,
+    pub value: Option<String>,
+}
+
+/// The handoff spec of a document.
+pub fn spec(document: &Document) -> Spec<'_> {
+    let canvases = document
+        .canvases()
+        .filter_map(|canvas| {
+            let NodeType::Canvas(data) = &canvas.node else {
+                return None;
+            };
+            let measurements = data
+                .measurements
+                .iter()
+                .map(|measurement| {
+                    let distance = distance(document, measurement);
+                    let value = match measurement.free_text.is_empty() {
+                        true => distance.map(px),
+                        false => Some(measurement.free_text.clone()),
+                    };
+                    MeasurementSpec {
+                        id: &measurement.id,
+                        start: &measurement.start,
+                        end: &measurement.end,
+                        distance,
+                        value,
+                    }
+                })
+                .collect();
+            let mut annotations = Vec::new();
+            for layer in canvas.children() {
+                collect(document, layer, &mut annotations);
+            }
+            Some(CanvasSpec {
+                id: &canvas.id,
+                name: &canvas.name,
+                measurements,
+                annotations,
+            })
+        })
+        .collect();
+    Spec {
+        name: document.name(),
+        canvases,
+    }
+}
+
+fn collect<'a>(document: &'a Document, node: &'a Node, annotations: &mut Vec<AnnotationSpec<'a>>) {
+    let pinned: &[Annotation] = match &node.node {
+        NodeType::Vector(vector) => &vector.annotations,
+        NodeType::Rectangle(vector) => &vector.annotations,
+        NodeType::Document(_) | NodeType::Canvas(_) => &[],
+    };
+    for annotation in pinned {
+        annotations.push(AnnotationSpec {
+            node_id: &node.id,
+            node_name: &node.name,
+            label: &annotation.label,
+            properties: annotation
+                .properties
+                .iter()
+                .map(|property| PropertySpec {
+                    property: &property.annotation_property_type,
+                    value: property_value(document, node, &property.annotation_property_type),
+                })
+                .collect(),
+        });
+    }
+    for child in node.children() {
+        collect(document, child, annotations);
+    }
+}
+
+/// The distance in pixels between the sides of the layers a measurement is pinned to, `None` when
+/// a layer is missing or the sides are not parallel.
+pub fn distance(document: &Document, measurement: &Measurement) -> Option<f32> {
+    let (start_axis, start) = side(document, &measurement.start)?;
+    let (end_axis, end) = side(document, &measurement.end)?;
+    (start_axis == end_axis).then(|| (end - start).abs())
+}
+
+/// Whether a measured side is vertical, and its absolute coordinate.
+fn side(document: &Document, pinned: &MeasurementStartEnd) -> Option<(bool, f32)> {
+    let bounds = match &document.node(&pinned.node_id)?.node {
+        NodeType::Vector(vector) => &vector.absolute_bounding_box,
+        NodeType::Rectangle(vector) => &vector.absolute_bounding_box,
+        NodeType::Document(_) | NodeType::Canvas(_) => return None,
+    };
+    Some(match pinned.side {
+        MeasurementStartEndSide::Top => (false, bounds.y),
+        MeasurementStartEndSide::Bottom => (false, bounds.y + bounds.height),
+        MeasurementStartEndSide::Left => (true, bounds.x),
+        MeasurementStartEndSide::Right => (true, bounds.x + bounds.width),
+    })
+}
+
+/// The current value of a property of a layer, `None` when the layer has no such property.
+pub fn property_value(
+    document: &Document,
+    node: &Node,
+    property: &AnnotationPropertyType,
+) -> Option<String> {
+    match &node.node {
+        NodeType::Vector(vector) => vector_value(document, vector, None, property),
+        NodeType::Rectangle(vector) => {
+            let radii = corner_radii(&vector.additional_data);
+            let radii = match uniform(radii) {
+                true => px(radii[0]),
+                false => radii.map(px).join(" "),
+            };
+            vector_value(document, vector, Some(radii), property)
+        }
+        NodeType::Document(_) | NodeType::Canvas(_) => None,
+    }
+}
+
+fn vector_value<Data>(
+    document: &Document,
+    vector: &VectorNode<Data>,
+    corner_radius: Option<String>,
+    property: &AnnotationPropertyType,
+) -> Option<String> {
+    use AnnotationPropertyType::*;
+
+    let bounds = &vector.absolute_bounding_box;
+    let style = |style_type: StyleType| {
+        let id = vector.styles.as_ref()?.get(&style_type)?;
+        document.styles.get(id)
+    };
+    let text = || match &style(StyleType::Text)?.value {
+        Some(StyleValue::Text(text)) => Some(text.as_ref()),
+        _ => None,
+    };
+    match property {
+        Width => Some(px(bounds.width)),
+        Height => Some(px(bounds.height)),
+        Fills => match style(StyleType::Fill) {
+            Some(style) => Some(style.name.clone()),
+            None => paints(document, &vector.fills),
+        },
+        Strokes => paints(document, &vector.strokes),
+        Effects => match style(StyleType::Effect) {
+            Some(style) => Some(style.name.clone()),
+            None => effects(&vector.effects),
+        },
+        Strokeweight => {
+            let weights = stroke_weights(vector);
+            match uniform(weights) {
+                true => Some(px(weights[0])),
+                false => Some(weights.map(px).join(" ")),
+            }
+        }
+        Cornerradius => corner_radius,
+        Textstyleid => Some(style(StyleType::Text)?.name.clone()),
+        Textalignhorizontal => Some(format!("{:?}", text()?.text_align_horizontal)),
+        Fontfamily => Some(text()?.font_family.clone()),
+        Fontstyle => Some(font_style(text()?)),
+        Fontsize => Some(px(text()?.font_size)),
+        Fontweight => Some(text()?.font_weight.to_string()),
+        Lineheight => Some(line_height(text()?)),
+        Letterspacing => Some(px(text()?.letter_spacing)),
+        Opacity => Some(format!("{}%", number(vector.opacity * 100.0))),
+        // Nodes are neither auto-layout frames, nor instances, nor sized by constraints.
+        Maxwidth | Minwidth | Maxheight | Minheight | Itemspacing | Padding | Layoutmode
+        | Alignitems | Maincomponent => None,
+    }
+}
+
+/// The visible paints of a layer, separated by commas, `None` when there are none.
+fn paints(document: &Document, paints: &[Paint]) -> Option<String> {
+    let paints: Vec<String> = paints
+        .iter()
+        .filter(|paint| paint.visible)
+        .map(|paint| {
+            let variable = paint
+                .bound_variables
+                .as_ref()
+                .and_then(|bound| document.variables.get(&bound.get("color")?.id));
+            match (&paint.paint_type, variable) {
+                (PaintType::Solid, Some(variable)) => variable.name.clone(),
+                (PaintType::Solid, None) => hex(&paint.color, paint.opacity),
+                (PaintType::GradientLinear, _) => "Linear gradient".to_string(),
+                (PaintType::GradientRadial, _) => "Radial gradient".to_string(),
+                (PaintType::GradientAngular, _) => "Angular gradient".to_string(),
+                (PaintType::GradientDiamond, _) => "Diamond gradient".to_string(),
+                (PaintType::Image, _) => "Image".to_string(),
+                (PaintType::Emoji, _) => "Emoji".to_string(),
+                (PaintType::Video, _) => "Video".to_string(),
+            }
+        })
+        .collect();
+    (!paints.is_empty()).then(|| paints.join(", "))
+}
+
+/// The visible effects of a layer, separated by commas, `None` when there are none.
+fn effects(effects: &[Effect]) -> Option<String> {
+    let effects: Vec<String> = effects
+        .iter()
+        .filter(|effect| effect.visible)
+        .map(|effect| {
+            let shadow = |name: &str| {
+                format!(
+                    "{name} {} {} {} {} {}",
+                    px(effect.offset.x),
+                    px(effect.offset.y),
+                    px(effect.radius),
+                    px(effect.spread),
+                    hex(&effect.color, 1.0)
+                )
+            };
+            match effect.effect_type {
+                EffectType::DropShadow => shadow("Drop shadow"),
+                EffectType::InnerShadow => shadow("Inner shadow"),
+                EffectType::LayerBlur => format!("Layer blur {}", px(effect.radius)),
+                EffectType::BackgroundBlur => format!("Background blur {}", px(effect.radius)),
+            }
+        })
+        .collect();
+    (!effects.is_empty()).then(|| effects.join(", "))
+}
+
+/// The name of the style of a font, e.g. `Bold Italic`.
+fn font_style(text: &TypeStyle) -> String {
+    let weight = match text.font_weight {
+        ..=149 => "Thin",
+        150..=249 => "ExtraLight",
+        250..=349 => "Light",
+        350..=449 => "Regular",
+        450..=549 => "Medium",
+        550..=649 => "SemiBold",
+        650..=749 => "Bold",
+        750..=849 => "ExtraBold",
+        850.. => "Black",
+    };
+    match (text.italic, weight) {
+        (true, "Regular") => "Italic".to_string(),
+        (true, weight) => format!("{weight} Italic"),
+        (false, weight) => weight.to_string(),
+    }
+}
+
+fn line_height(text: &TypeStyle) -> String {
+    match text.line_height_unit {
+        TypeStyleLineHeightUnit::Pixels => px(text.line_height_px),
+        TypeStyleLineHeightUnit::FontSize => {
+            format!("{}%", number(text.line_height_percent_font_size))
+        }
+        TypeStyleLineHeightUnit::Intrinsic => "Auto".to_string(),
+    }
+}
+
+/// The name of a property in specs.
+pub fn property_name(property: &AnnotationPropertyType) -> &'static str {
+    use AnnotationPropertyType::*;
+
+    match property {
+        Width => "Width",
+        Height => "Height",
+        Maxwidth => "Max width",
+        Minwidth => "Min width",
+        Maxheight => "Max height",
+        Minheight => "Min height",
+        Fills => "Fills",
+        Strokes => "Strokes",
+        Effects => "Effects",
+        Strokeweight => "Stroke weight",
+        Cornerradius => "Corner radius",
+        Textstyleid => "Text style",
+        Textalignhorizontal => "Text align",
+        Fontfamily => "Font family",
+        Fontstyle => "Font style",
+        Fontsize => "Font size",
+        Fontweight => "Font weight",
+        Lineheight => "Line height",
+        Letterspacing => "Letter spacing",
+        Itemspacing => "Item spacing",
+        Padding => "Padding",
+        Layoutmode => "Layout mode",
+        Alignitems => "Align items",
+        Opacity => "Opacity",
+        Maincomponent => "Main component",
+    }
+}
+
+/// A spec as a Markdown document, with a section for each canvas having measurements or
+/// annotations.
+pub fn markdown(spec: &Spec) -> String {
+    let mut markdown = format!("# {}\n", spec.name);
+    for canvas in &spec.canvases {
+        if canvas.measurements.is_empty() && canvas.annotations.is_empty() {
+            continue;
+        }
+        let _ = write!(markdown, "\n## {}\n", canvas.name);
+        if !canvas.measurements.is_empty() {
+            markdown.push_str("\n### Measurements\n\n");
+            markdown.push_str("| Measurement | From | To | Value |\n| --- | --- | --- | --- |\n");
+            for measurement in &canvas.measurements {
+                let _ = writeln!(
+                    markdown,
+                    "| {} | {} | {} | {} |",
+                    cell(measurement.id),
+                    pinned(measurement.start),
+                    pinned(measurement.end),
+                    cell(measurement.value.as_deref().unwrap_or("–"))
+                );
+            }
+        }
+        if !canvas.annotations.is_empty() {
+            markdown.push_str("\n### Annotations\n");
+        }
+        for annotation in &canvas.annotations {
+            let _ = write!(
+                markdown,
+                "\n#### {} ({})\n",
+                annotation.node_name, annotation.node_id
+            );
+            if !annotation.label.is_empty() {
+                let _ = write!(markdown, "\n{}\n", annotation.label);
+            }
+            if !annotation.properties.is_empty() {
+                markdown.push_str("\n| Property | Value |\n| --- | --- |\n");
+            }
+            for property in &annotation.properties {
+                let _ = writeln!(
+                    markdown,
+                    "| {} | {} |",
+                    property_name(property.property),
+                    cell(property.value.as_deref().unwrap_or("–"))
+                );
+            }
+        }
+    }
+    markdown
+}
+
+fn pinned(pinned: &MeasurementStartEnd) -> String {
+    format!("{} {:?}", cell(&pinned.node_id), pinned.side)
+}
+
+/// Text escaped to stay in a cell of a Markdown table.
+fn cell(text: &str) -> String {
+    text.replace('|', "\\|").replace('\n', "<br>")
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::{json, Value};
+
+    use super::*;
+    use crate::fixtures;
+
+    /// A rectangle with rounded corners at a position.
+    fn layer(id: &str, name: &str, x: f32, y: f32, properties: Value) -> Node {
+        let mut data = fixtures::bounds(x, y, 100.0, 40.0);
+        data["additionalData"] = json!({
+            "cornerRadius": 8.0,
+            "rectangleCornerRadii": [0.0, 0.0, 0.0, 0.0],
+            "cornerSmoothing": 0.0,
+        });
+        for (key, value) in properties.as_object().unwrap() {
+            data[key] = value.clone();
+        }
+        fixtures::layer("rectangle", id, name, data)
+    }
+
+    fn measurement(id: &str, start: (&str, &str), end: (&str, &str), free_text: &str) -> Value {
+        json!({
+            "id": id,
+            "start": { "nodeId": start.0, "side": start.1 },
+            "end": { "nodeId": end.0, "side": end.1 },
+            "offset": { "type": "Inner", "relative": 0.0 },
+            "freeText": free_text,
+        })
+    }
+
+    /// A button 24px right of a card, with an annotated title using a text style.
+    fn document() -> Document {
+        let mut document = Document::new("Checkout");
+        document.styles = serde_json::from_value(json!({
+            "S:1": {
+                "key": "S:1",
+                "name": "Heading/Large",
+                "description": "",
+                "remote": false,
+                "styleType": "Text",
+                "value": { "Text": {
+                    "fontFamily": "Inter",
+                    "fontPostScriptName": "Inter-Bold",
+                    "paragraphSpacing": 0.0,
+                    "paragraphIndent": 0.0,
+                    "listSpacing": 0.0,
+                    "italic": true,
+                    "fontWeight": 700,
+                    "fontSize": 24.0,
+                    "textCase": "Upper",
+                    "textDecoration": "Underline",
+                    "textAutoResize": "Height",
+                    "textTruncation": "Disabled",
+                    "maxLines": 0,
+                    "textAlignHorizontal": "Left",
+                    "textAlignVertical": "Top",
+                    "letterSpacing": -0.5,
+                    "fills": [],
+                    "hyperlink": { "hyperLinkType": "Url", "url": "", "nodeId": "" },
+                    "opentypeFlags": {},
+                    "lineHeightPx": 32.0,
+                    "lineHeightPercent": 100.0,
+                    "lineHeightPercentFontSize": 133.0,
+                    "lineHeightUnit": "Pixels",
+                    "isOverrideOverTextStyle": false,
+                    "semanticWeight": "Bold",
+                    "semanticItalic": "Italic",
+                }},
+            },
+        }))
+        .unwrap();
+        let canvas = &mut document.document.children_mut().unwrap()[0];
+        let pins =
+            |types: &[&str]| Value::from_iter(types.iter().map(|pinned| json!({ "type": pinned })));
+        *canvas.children_mut().unwrap() = vec![
+            layer("1:1", "Card", 0.0, 0.0, json!({})),
+            layer(
+                "1:2",
+                "Button",
+                124.0,
+                10.0,
+                json!({
+                    "opacity": 0.5,
+                    "fills": [{
+                        "paintType": "Solid",
+                        "visible": true,
+                        "opacity": 1.0,
+                        "color": { "r": 1.0, "g": 0.0, "b": 0.0, "a": 1.0 },
+                        "blendMode": "Normal",
+                        "gradientHandlePositions": [],
+                        "gradientStops": [],
+                        "scaleMode": "Fill",
+                        "imageTransform": { "matrix": [[1.0, 0.0, 0.0], [0.0, 1.0, 0.0]] },
+                        "scalingFactor": 1.0,
+                        "rotation": 0.0,
+                        "imageRef": "",
+                        "filters": {
+                            "exposure": 0.0, "contrast": 0.0, "saturation": 0.0,
+                            "temperature": 0.0, "tint": 0.0, "highlights": 0.0, "shadows": 0.0,
+                        },
+                        "gifRef": "",
+                    }],
+                    "annotations": [{
+                        "label": "Primary | call to action",
+                        "properties": pins(&["Width", "Fills", "Cornerradius", "Opacity", "Itemspacing"]),
+                    }],
+                }),
+            ),
+            layer(
+                "1:3",
+                "Title",
+                0.0,
+                60.0,
+                json!({
+                    "styles": { "Text": "S:1" },
+                    "annotations": [{
+                        "label": "",
+                        "properties": pins(&["Textstyleid", "Fontstyle", "Fontsize", "Lineheight", "Letterspacing"]),
+                    }],
+                }),
+            ),
+        ];
+        let NodeType::Canvas(canvas) = &mut canvas.node else {
+            unreachable!();
+        };
+        canvas.measurements = serde_json::from_value(json!([
+            measurement("M:1", ("1:1", "Right"), ("1:2", "Left"), ""),
+            measurement("M:2", ("1:1", "Bottom"), ("1:3", "Top"), "Gap/Medium"),
+            measurement("M:3", ("1:1", "Right"), ("1:3", "Top"), ""),
+        ]))
+        .unwrap();
+        document
+    }
+
+    #[test]
+    fn measurements_resolve_to_the_distance_between_sides() {
+        let document = document();
+        let spec = spec(&document);
+        let measurements = &spec.canvases[0].measurements;
+        let values: Vec<_> = measurements
+            .iter()
+            .map(|measurement| (measurement.distance, measurement.value.as_deref()))
+            .collect();
+        assert_eq!(
+            values,
+            [
+                (Some(24.0), Some("24px")),
+                (Some(20.0), Some("Gap/Medium")),
+                (None, None)
+            ]
+        );
+    }
+
+    #[test]
+    fn pinned_properties_resolve_to_their_current_value() {
+        let document = document();
+        let spec = spec(&document);
+        let values: Vec<Vec<Option<&str>>> = spec.canvases[0]
+            .annotations
+            .iter()
+            .map(|annotation| {
+                annotation
+                    .properties
+                    .iter()
+                    .map(|property| property.value.as_deref())
+                    .collect()
+            })
+            .collect();
+        assert_eq!(
+            values,
+            [
+                vec![
+                    Some("100px"),
+                    Some("#ff0000"),
+                    Some("8px"),
+                    Some("50%"),
+                    None
+                ],
+                vec![
+                    Some("Heading/Large"),
+                    Some("Bold Italic"),
+                    Some("24px"),
+                    Some("32px"),
+                    Some("-0.5px")
+                ],
+            ]
+        );
+    }
+
+    #[test]
+    fn markdown_lists_measurements_and_annotations() {
+        let document = document();
+        let markdown = markdown(&spec(&document));
+        assert!(markdown.starts_with("# Checkout\n\n## Page 1\n\n### Measurements\n"));
+        assert!(markdown.contains("| M:1 | 1:1 Right | 1:2 Left | 24px |\n"));
+        assert!(markdown.contains("| M:3 | 1:1 Right | 1:3 Top | – |\n"));
+        assert!(markdown.contains(
+            "#### Button (1:2)\n\nPrimary | call to action\n\n| Property | Value |\n| --- | --- |\n\
+             | Width | 100px |\n"
+        ));
+        assert!(markdown.contains("| Item spacing | – |\n"));
+        assert!(markdown.contains("#### Title (1:3)\n\n| Property | Value |\n"));
+    }
+}
@@ -0,0 +1,451 @@
+//! Crash recovery journal.
+//!
+//! While a document has unsaved changes, every edit is appended to a journal stored next to it.
+//! The journal starts with a snapshot of the document and its undo history, followed by one JSON
+//! entry per line. Autosave periodically replaces the journal with a fresh snapshot so it stays
+//! short. The journal is removed once the changes are saved or discarded, so finding one means the
+//! app did not shut down cleanly and the changes it holds can be recovered.
+//!
+//! Entries are flushed as soon as they are recorded. A crash while appending leaves at most one
+//! incomplete line at the end of the journal, which is ignored on recovery.
+
+use std::borrow::Cow;
+use std::fs::{File, OpenOptions};
+use std::io::{BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+use serde::{Deserialize, Serialize};
+
+use crate::document::{Document, FileError};
+use crate::edit::{EditError, History, Operation};
+
+/// Extension appended to the path of a document to get the path of its journal.
+pub const EXTENSION: &str = "journal";
+/// Interval between two autosave snapshots unless configured otherwise.
+pub const DEFAULT_AUTOSAVE_INTERVAL: Duration = Duration::from_secs(60);
+
+/// First line of a journal: the state of the document when the journal was created or last
+/// autosaved.
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Snapshot<'a> {
+    /// Where the document is saved, `None` if it was never saved.
+    source: Option<Cow<'a, Path>>,
+    document: Cow<'a, Document>,
+    history: Cow<'a, History>,
+}
+
+/// A change to the document or its history, replayed on top of the snapshot during recovery.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", tag = "entry")]
+pub enum Entry {
+    /// Operations applied as a transaction, merged into the previous one if `coalesce` matches
+    /// its key.
+    Apply {
+        name: String,
+        operations: Vec<Operation>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        coalesce: Option<String>,
+    },
+    EndCoalescing,
+    Undo,
+    Redo,
+}
+
+impl Entry {
+    fn replay(&self, document: &mut Document, history: &mut History) -> Result<(), EditError> {
+        let root = &mut document.document;
+        match self {
+            Entry::Apply {
+                name,
+                operations,
+                coalesce: Some(key),
+            } => history.apply_coalesced(root, name, key, operations),
+            Entry::Apply {
+                name, operations, ..
+            } => history.apply(root, name, operations),
+            Entry::EndCoalescing => {
+                history.end_coalescing();
+                Ok(())
+            }
+            Entry::Undo => history.undo(root).map(|_| ()),
+            Entry::Redo => history.redo(root).map(|_| ()),
+        }
+    }
+}
+
+/// The append-only journal of a document with unsaved changes.
+#[derive(Debug)]
+pub struct Journal {
+    path: PathBuf,
+    file: BufWriter<File>,
+    source: Option<PathBuf>,
+    autosave_interval: Duration,
+    last_snapshot: Instant,
+    /// Number of entries recorded since the last snapshot.
+    pending: usize,
+}
+
+impl Journal {
+    /// The path of the journal of a document stored at `document`.
+    pub fn path_for(document: &Path) -> PathBuf {
+        let mut path = document.as_os_str().to_owned();
+        path.push(".");
+        path.push(EXTENSION);
+        PathBuf::from(path)
+    }
+
+    /// Starts a journal at `path` from a snapshot of the document and its history, replacing any
+    /// existing journal. `source` is where the document is saved, if it ever was.
+    pub fn create(
+        path: impl Into<PathBuf>,
+        document: &Document,
+        history: &History,
+        source: Option<PathBuf>,
+    ) -> Result<Self, FileError> {
+        let path = path.into();
+        let file = write_snapshot(&path, source.as_deref(), document, history)?;
+        Ok(Self {
+            path,
+            file,
+            source,
+            autosave_interval: DEFAULT_AUTOSAVE_INTERVAL,
+            last_snapshot: Instant::now(),
+            pending: 0,
+        })
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    pub fn set_autosave_interval(&mut self, interval: Duration) {
+        self.autosave_interval = interval;
+    }
+
+    /// Appends an entry and flushes it to the file.
+    pub fn record(&mut self, entry: &Entry) -> Result<(), FileError> {
+        serde_json::to_writer(&mut self.file, entry)?;
+        self.file.write_all(b"\n")?;
+        self.file.flush()?;
+        self.pending += 1;
+        Ok(())
+    }
+
+    /// Replaces the journal with a snapshot of the current state of the document.
+    pub fn snapshot(&mut self, document: &Document, history: &History) -> Result<(), FileError> {
+        self.file = write_snapshot(&self.path, self.source.as_deref(), document, history)?;
+        self.last_snapshot = Instant::now();
+        self.pending = 0;
+        Ok(())
+    }
+
+    /// Takes a snapshot if entries were recorded and the autosave interval elapsed since the last
+    /// one. Returns whether a snapshot was taken.
+    pub fn autosave(&mut self, document: &Document, history: &History) -> Result<bool, FileError> {
+        if self.pending == 0 || self.last_snapshot.elapsed() < self.autosave_interval {
+            return Ok(false);
+        }
+        self.snapshot(document, history)?;
+        Ok(true)
+    }
+
+    /// Removes the journal once its changes are saved or discarded.
+    pub fn close(self) -> Result<(), FileError> {
+        let Self { path, file, .. } = self;
+        drop(file);
+        std::fs::remove_file(path)?;
+        Ok(())
+    }
+}
+
+/// Writes a journal holding only a snapshot and opens it for appending. The snapshot is written to
+/// a temporary file first so a crash never leaves a journal without a complete snapshot.
+fn write_snapshot(
+    path: &Path,
+    source: Option<&Path>,
+    document: &Document,
+    history: &History,
+) -> Result<BufWriter<File>, FileError> {
+    let snapshot = Snapshot {
+        source: source.map(Cow::Borrowed),
+        document: Cow::Borrowed(document),
+        history: Cow::Borrowed(history),
+    };
+    let mut temporary = path.as_os_str().to_owned();
+    temporary.push(".tmp");
+    let result = File::create(&temporary)
+        .map_err(FileError::from)
+        .and_then(|file| {
+            let mut writer = BufWriter::new(file);
+            serde_json::to_writer(&mut writer, &snapshot)?;
+            writer.write_all(b"\n")?;
+            let file = writer.into_inner().map_err(|error| error.into_error())?;
+            file.sync_data()?;
+            Ok(())
+        })
+        .and_then(|()| Ok(std::fs::rename(&temporary, path)?));
+    if result.is_err() {
+        let _ = std::fs::remove_file(&temporary);
+    }
+    result?;
+    let file = OpenOptions::new().append(true).open(path)?;
+    Ok(BufWriter::new(file))
+}
+
+/// The state of a document rebuilt from its journal.
+#[derive(Debug)]
+pub struct Recovery {
+    pub document: Document,
+    pub history: History,
+    /// Where the document is saved, `None` if it was never saved.
+    pub source: Option<PathBuf>,
+    /// Number of entries replayed on top of the snapshot.
+    pub replayed: usize,
+    /// Whether the journal ended with an incomplete or unreadable entry. Everything recorded
+    /// before it is recovered, the entry itself and anything after it is lost.
+    pub truncated: bool,
+}
+
+/// Rebuilds the document and history from a journal.
+pub fn recover(path: impl AsRef<Path>) -> Result<Recovery, FileError> {
+    let bytes = std::fs::read(path)?;
+    // Only lines ending with a newline are complete, whatever follows the last one is an entry
+    // interrupted while being written.
+    let (complete, incomplete) = match bytes.iter().rposition(|byte| *byte == b'\n') {
+        Some(end) => bytes.split_at(end + 1),
+        None => return Err(FileError::MissingSnapshot),
+    };
+    let mut lines = complete[..complete.len() - 1].split(|byte| *byte == b'\n');
+    let snapshot = lines.next().filter(|line| !line.is_empty());
+    let snapshot: Snapshot = serde_json::from_slice(snapshot.ok_or(FileError::MissingSnapshot)?)?;
+    let mut recovery = Recovery {
+        document: snapshot.document.into_owned(),
+        history: snapshot.history.into_owned(),
+        source: snapshot.source.map(Cow::into_owned),
+        replayed: 0,
+        truncated: !incomplete.is_empty(),
+    };
+    for line in lines {
+        let replayed = serde_json::from_slice::<Entry>(line)
+            .ok()
+            .is_some_and(|entry| {
+                entry
+                    .replay(&mut recovery.document, &mut recovery.history)
+                    .is_ok()
+            });
+        if !replayed {
+            recovery.truncated = true;
+            break;
+        }
+        recovery.replayed += 1;
+    }
+    Ok(recovery)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A fresh directory for the files of a test.
+    fn directory(test: &str) -> PathBuf {
+        let path = std::env::temp_dir().join(format!("fig-file-{}-{test}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&path);
+        std::fs::create_dir_all(&path).unwrap();
+        path
+    }
+
+    fn rename(name: &str) -> Vec<Operation> {
+        let operation = serde_json::json!({
+            "op": "patch",
+            "id": "0:1",
+            "operations": [{ "op": "set", "path": "name", "value": name }],
+        });
+        vec![serde_json::from_value(operation).unwrap()]
+    }
+
+    fn add_page(id: &str) -> Vec<Operation> {
+        let mut page = Document::new("").document.children()[0].clone();
+        page.id = id.to_string();
+        vec![Operation::Insert {
+            parent: "0:0".to_string(),
+            index: 1,
+            node: Box::new(page),
+        }]
+    }
+
+    fn apply(name: &str, operations: Vec<Operation>) -> Entry {
+        Entry::Apply {
+            name: name.to_string(),
+            operations,
+            coalesce: None,
+        }
+    }
+
+    fn entries() -> Vec<Entry> {
+        vec![
+            apply("Rename", rename("Cover")),
+            apply("Add page", add_page("0:2")),
+            Entry::Apply {
+                name: "Rename".to_string(),
+                operations: rename("Draft"),
+                coalesce: Some("typing".to_string()),
+            },
+            Entry::Apply {
+                name: "Rename".to_string(),
+                operations: rename("Drafts"),
+                coalesce: Some("typing".to_string()),
+            },
+            Entry::EndCoalescing,
+            Entry::Undo,
+            Entry::Undo,
+            Entry::Redo,
+            apply("Add page", add_page("0:3")),
+        ]
+    }
+
+    /// Records entries while applying them, returning the state after each entry.
+    fn record(journal: &mut Journal, entries: &[Entry]) -> Vec<(Document, History)> {
+        let mut document = Document::new("Test");
+        let mut history = History::new();
+        let mut states = vec![(document.clone(), history.clone())];
+        for entry in entries {
+            entry.replay(&mut document, &mut history).unwrap();
+            journal.record(entry).unwrap();
+            states.push((document.clone(), history.clone()));
+        }
+        states
+    }
+
+    #[test]
+    fn recovers_recorded_edits() {
+        let directory = directory("recovers");
+        let source = directory.join("test.fig");
+        let path = Journal::path_for(&source);
+        let mut journal = Journal::create(
+            &path,
+            &Document::new("Test"),
+            &History::new(),
+            Some(source.clone()),
+        )
+        .unwrap();
+        let states = record(&mut journal, &entries());
+
+        let recovery = recover(&path).unwrap();
+        let (document, history) = states.last().unwrap();
+        assert_eq!(&recovery.document, document);
+        assert_eq!(&recovery.history, history);
+        assert_eq!(recovery.source, Some(source));
+        assert_eq!(recovery.replayed, entries().len());
+        assert!(!recovery.truncated);
+        assert_eq!(
+            recovery.history.summary().undo,
+            ["Add page", "Add page", "Rename"]
+        );
+    }
+
+    #[test]
+    fn recovers_truncated_journals_up_to_the_last_complete_entry() {
+        let directory = directory("truncated");
+        let path = directory.join("untitled.journal");
+        let mut journal =
+            Journal::create(&path, &Document::new("Test"), &History::new(), None).unwrap();
+        let states = record(&mut journal, &entries());
+        let bytes = std::fs::read(&path).unwrap();
+        let snapshot = bytes.iter().position(|byte| *byte == b'\n').unwrap() + 1;
+
+        let truncated = directory.join("truncated.journal");
+        for len in snapshot..=bytes.len() {
+            std::fs::write(&truncated, &bytes[..len]).unwrap();
+            let recovery = recover(&truncated).unwrap();
+            let complete = bytes[snapshot..len]
+                .iter()
+                .filter(|byte| **byte == b'\n')
+                .count();
+            let (document, history) = &states[complete];
+            assert_eq!(recovery.replayed, complete, "truncated at {len}");
+            assert_eq!(&recovery.document, document, "truncated at {len}");
+            assert_eq!(&recovery.history, history, "truncated at {len}");
+            assert_eq!(
+                recovery.truncated,
+                bytes[len - 1] != b'\n',
+                "truncated at {len}"
+            );
+        }
+    }
+
+    #[test]
+    fn stops_at_unreadable_entries() {
+        let directory = directory("unreadable");
+        let path = directory.join("untitled.journal");
+        let mut journal =
+            Journal::create(&path, &Document::new("Test"), &History::new(), None).unwrap();
+        let entries = entries();
+        let states = record(&mut journal, &entries[..2]);
+        // An entry that no longer applies, e.g. after the file was tampered with.
+        journal.record(&apply("Add page", add_page("0:2"))).unwrap();
+        journal.record(&entries[2]).unwrap();
+
+        let recovery = recover(&path).unwrap();
+        assert!(recovery.truncated);
+        assert_eq!(recovery.replayed, 2);
+        assert_eq!(recovery.document, states[2].0);
+    }
+
+    #[test]
+    fn rejects_journals_without_a_complete_snapshot() {
+        let directory = directory("snapshot");
+        let path = directory.join("untitled.journal");
+        Journal::create(&path, &Document::new("Test"), &History::new(), None).unwrap();
+        let bytes = std::fs::read(&path).unwrap();
+
+        for len in [0, 1, bytes.len() / 2, bytes.len() - 1] {
+            std::fs::write(&path, &bytes[..len]).unwrap();
+            assert!(recover(&path).is_err(), "truncated at {len}");
+        }
+    }
+
+    #[test]
+    fn autosave_compacts_the_journal() {
+        let directory = directory("autosave");
+        let path = directory.join("untitled.journal");
+        let mut journal =
+            Journal::create(&path, &Document::new("Test"), &History::new(), None).unwrap();
+        assert!(!journal
+            .autosave(&Document::new("Test"), &History::new())
+            .unwrap());
+        let entries = entries();
+        // Stops in the middle of a coalesced transaction, which continues after the snapshot.
+        let states = record(&mut journal, &entries[..3]);
+        let (document, history) = states.last().unwrap();
+        assert!(!journal.autosave(document, history).unwrap());
+
+        journal.set_autosave_interval(Duration::ZERO);
+        assert!(journal.autosave(document, history).unwrap());
+        let lines = std::fs::read(&path).unwrap();
+        assert_eq!(lines.iter().filter(|byte| **byte == b'\n').count(), 1);
+
+        let (mut document, mut history) = (document.clone(), history.clone());
+        for entry in &entries[3..] {
+            entry.replay(&mut document, &mut history).unwrap();
+            journal.record(entry).unwrap();
+        }
+        let recovery = recover(&path).unwrap();
+        assert_eq!(recovery.replayed, entries.len() - 3);
+        assert_eq!(recovery.document, document);
+        assert_eq!(recovery.history, history);
+    }
+
+    #[test]
+    fn close_removes_the_journal() {
+        let directory = directory("close");
+        let path = Journal::path_for(&directory.join("test.fig"));
+        assert_eq!(path, directory.join("test.fig.journal"));
+        let journal =
+            Journal::create(&path, &Document::new("Test"), &History::new(), None).unwrap();
+        assert!(path.exists());
+        journal.close().unwrap();
+        assert!(!path.exists());
+    }
+}
@@ -0,0 +1,20 @@
+pub mod dev;
+pub mod diff;
+pub mod document;
+pub mod edit;
+pub mod export;
+pub mod figma;
+#[cfg(any(test, feature = "test-fixtures"))]
+pub mod fixtures;
+pub mod flow;
+pub mod handoff;
+pub mod journal;
+pub mod merge;
+pub mod render;
+pub mod spatial;
+pub mod styles;
+pub mod text;
+pub mod tokens;
+pub mod validate;
+pub mod values;
+pub mod versions;
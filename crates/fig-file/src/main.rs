@@ -1,3 +1,624 @@
-fn main() {
-    println!("Hello, world!");
+//! Command-line tool to inspect, validate, convert and export documents without the app.
+
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+use std::time::Duration;
+
+use clap::{Parser, Subcommand, ValueEnum};
+use fig_file::document::{Document, Format};
+use fig_file::versions::PrunePolicy;
+use fig_file::{dev, diff, export, flow, handoff, merge, tokens, validate};
+use fig_types::node::{Node, NodeType};
+
+#[derive(Parser)]
+#[command(name = "fig-file", version, about)]
+struct Cli {
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Prints the tree of nodes of a document with their ids, types and names
+    Inspect {
+        file: PathBuf,
+        /// Prints only the subtree of this node
+        #[arg(long)]
+        node: Option<String>,
+        /// Maximum depth of the printed tree
+        #[arg(long)]
+        depth: Option<usize>,
+    },
+    /// Checks that a document matches the schema, that its references are valid and that its
+    /// property values are within their bounds
+    Validate {
+        file: PathBuf,
+        /// Prints the problems as JSON
+        #[arg(long)]
+        json: bool,
+    },
+    /// Converts a document to another format
+    Convert {
+        input: PathBuf,
+        output: PathBuf,
+        /// Format of the output, guessed from its extension by default
+        #[arg(long, value_enum)]
+        to: Option<OutputFormat>,
+        /// Clamps out of range values and fixes arrays of the wrong length before writing
+        #[arg(long)]
+        repair: bool,
+        /// Writes the document to the `output` directory, with a file per canvas
+        #[arg(long, conflicts_with = "to")]
+        split: bool,
+    },
+    /// Renders the export settings of the nodes of a document to files
+    Export {
+        file: PathBuf,
+        /// Directory the files are written to
+        #[arg(long, default_value = ".")]
+        out: PathBuf,
+        /// Exports only these nodes and their descendants
+        #[arg(long)]
+        node: Vec<String>,
+    },
+    /// Prints the prototype flow graph of a document as Graphviz DOT, and warns about the
+    /// screens no flow reaches and the screens flows cannot leave
+    Flows {
+        file: PathBuf,
+        /// Writes the graph to this file instead of printing it
+        #[arg(long)]
+        out: Option<PathBuf>,
+    },
+    /// Prints the variables, styles and component properties of a document as design tokens, or
+    /// updates its variables from a tokens file
+    Tokens {
+        file: PathBuf,
+        /// Writes the tokens to this file instead of printing them
+        #[arg(long, conflicts_with = "import")]
+        out: Option<PathBuf>,
+        /// Creates or updates the variables of the document from this tokens file and saves it
+        #[arg(long)]
+        import: Option<PathBuf>,
+    },
+    /// Prints the measurements and annotations of a document with their current values as a
+    /// Markdown handoff spec
+    Spec {
+        file: PathBuf,
+        /// Prints the spec as JSON
+        #[arg(long)]
+        json: bool,
+        /// Writes the spec to this file instead of printing it
+        #[arg(long)]
+        out: Option<PathBuf>,
+    },
+    /// Compares two versions of a document node by node: added, removed, moved and renamed
+    /// nodes, and the changed properties of the others
+    Diff {
+        before: PathBuf,
+        after: PathBuf,
+        /// Prints the changes as JSON
+        #[arg(long)]
+        json: bool,
+        /// Renders both versions of each changed node side by side to PNG files in this directory
+        #[arg(long)]
+        images: Option<PathBuf>,
+        /// Scale of the rendered images
+        #[arg(long, default_value_t = 1.0)]
+        scale: f64,
+    },
+    /// Lists the layers ready for development which changed since they were marked ready, with
+    /// the previous and current value of each changed property
+    Changes {
+        file: PathBuf,
+        /// Prints the changes as JSON
+        #[arg(long)]
+        json: bool,
+    },
+    /// Merges the changes two versions made to a base version of a document, and lists the
+    /// conflicting changes. Exits with status 1 when there are conflicts, which makes it usable
+    /// as a git merge driver: `driver = fig-file merge %O %A %B`
+    Merge {
+        base: PathBuf,
+        ours: PathBuf,
+        theirs: PathBuf,
+        /// Writes the merged document to this file instead of overwriting `ours`
+        #[arg(long)]
+        out: Option<PathBuf>,
+        /// Prints the conflicts as JSON
+        #[arg(long)]
+        json: bool,
+    },
+    /// Checkpoints the current content of a document as a new version
+    Checkpoint {
+        file: PathBuf,
+        /// Names the version, versions without a name are automatic checkpoints
+        #[arg(long)]
+        name: Option<String>,
+        #[arg(long)]
+        author: String,
+        #[arg(long, default_value = "")]
+        description: String,
+    },
+    /// Lists the versions of a document
+    Versions {
+        file: PathBuf,
+        /// Prints the versions as JSON
+        #[arg(long)]
+        json: bool,
+    },
+    /// Restores a document, or a single node, to a version. The current content is checkpointed
+    /// first, so the restore can be undone by restoring that checkpoint.
+    Restore {
+        file: PathBuf,
+        version: u32,
+        /// Restores only this node and its subtree
+        #[arg(long)]
+        node: Option<String>,
+        #[arg(long)]
+        author: String,
+    },
+    /// Removes old versions of a document
+    Prune {
+        file: PathBuf,
+        /// Number of most recent automatic checkpoints kept
+        #[arg(long, default_value_t = PrunePolicy::default().keep_automatic)]
+        keep_automatic: usize,
+        /// Removes versions older than this number of days
+        #[arg(long)]
+        max_age_days: Option<u64>,
+        /// Removes named versions too when they are older than `--max-age-days`
+        #[arg(long)]
+        prune_named: bool,
+    },
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum OutputFormat {
+    /// Compressed native container
+    Native,
+    /// Plain JSON
+    Json,
+    /// JSON laid out like the files of the Figma REST API
+    Figma,
+}
+
+impl From<OutputFormat> for Format {
+    fn from(format: OutputFormat) -> Self {
+        match format {
+            OutputFormat::Native => Format::Native,
+            OutputFormat::Json => Format::Json,
+            OutputFormat::Figma => Format::Figma,
+        }
+    }
+}
+
+fn main() -> ExitCode {
+    let result = match Cli::parse().command {
+        Command::Inspect { file, node, depth } => inspect(&file, node.as_deref(), depth),
+        Command::Validate { file, json } => validate(&file, json),
+        Command::Convert {
+            input,
+            output,
+            to,
+            repair,
+            split,
+        } => convert(&input, &output, to, repair, split),
+        Command::Export { file, out, node } => export(&file, &out, &node),
+        Command::Flows { file, out } => flows(&file, out.as_deref()),
+        Command::Tokens { file, out, import } => match import {
+            Some(import) => import_tokens(&file, &import),
+            None => export_tokens(&file, out.as_deref()),
+        },
+        Command::Spec { file, json, out } => spec(&file, json, out.as_deref()),
+        Command::Diff {
+            before,
+            after,
+            json,
+            images,
+            scale,
+        } => diff(&before, &after, json, images.as_deref(), scale),
+        Command::Changes { file, json } => changes(&file, json),
+        Command::Merge {
+            base,
+            ours,
+            theirs,
+            out,
+            json,
+        } => merge(&base, &ours, &theirs, out.as_deref(), json),
+        Command::Checkpoint {
+            file,
+            name,
+            author,
+            description,
+        } => checkpoint(&file, name.as_deref(), &author, &description),
+        Command::Versions { file, json } => versions(&file, json),
+        Command::Restore {
+            file,
+            version,
+            node,
+            author,
+        } => restore(&file, version, node.as_deref(), &author),
+        Command::Prune {
+            file,
+            keep_automatic,
+            max_age_days,
+            prune_named,
+        } => prune(
+            &file,
+            &PrunePolicy {
+                keep_automatic,
+                max_age: max_age_days.map(|days| Duration::from_secs(days * 24 * 60 * 60)),
+                prune_named,
+            },
+        ),
+    };
+    match result {
+        Ok(code) => code,
+        Err(error) => {
+            eprintln!("error: {error}");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+type Result<T> = std::result::Result<T, Box<dyn std::error::Error>>;
+
+fn open(path: &Path) -> Result<Document> {
+    Document::open(path).map_err(|error| format!("{}: {error}", path.display()).into())
+}
+
+fn find<'a>(document: &'a Document, id: &str) -> Result<&'a Node> {
+    document
+        .node(id)
+        .ok_or_else(|| format!("no node with id `{id}`").into())
+}
+
+fn inspect(file: &Path, node: Option<&str>, depth: Option<usize>) -> Result<ExitCode> {
+    let document = open(file)?;
+    let root = match node {
+        Some(id) => find(&document, id)?,
+        None => &document.document,
+    };
+    print_tree(root, 0, depth.unwrap_or(usize::MAX));
+    Ok(ExitCode::SUCCESS)
+}
+
+fn print_tree(node: &Node, level: usize, depth: usize) {
+    let node_type = match node.node {
+        NodeType::Document(_) => "DOCUMENT",
+        NodeType::Canvas(_) => "CANVAS",
+        NodeType::Vector(_) => "VECTOR",
+        NodeType::Rectangle(_) => "RECTANGLE",
+    };
+    let hidden = if node.visible { "" } else { " (hidden)" };
+    println!(
+        "{}{node_type} {} {:?}{hidden}",
+        "  ".repeat(level),
+        node.id,
+        node.name
+    );
+    if level < depth {
+        for child in node.children() {
+            print_tree(child, level + 1, depth);
+        }
+    }
+}
+
+fn validate(file: &Path, json: bool) -> Result<ExitCode> {
+    // Schema errors are reported by the parser when the document is opened.
+    let document = open(file)?;
+    let diagnostics = validate::validate(&document);
+    let violations = document.invariants();
+    if json {
+        let problems = serde_json::json!({
+            "diagnostics": diagnostics,
+            "violations": violations,
+        });
+        println!("{}", serde_json::to_string_pretty(&problems)?);
+    } else {
+        for diagnostic in &diagnostics {
+            println!("{diagnostic}");
+        }
+        for violation in &violations {
+            println!("{violation}");
+        }
+    }
+    match diagnostics.len() + violations.len() {
+        0 => Ok(ExitCode::SUCCESS),
+        problems => {
+            eprintln!("{}: {problems} problem(s) found", file.display());
+            Ok(ExitCode::FAILURE)
+        }
+    }
+}
+
+fn convert(
+    input: &Path,
+    output: &Path,
+    to: Option<OutputFormat>,
+    repair: bool,
+    split: bool,
+) -> Result<ExitCode> {
+    let mut document = open(input)?;
+    if repair {
+        for violation in document.repair() {
+            eprintln!("repaired {violation}");
+        }
+    }
+    let format = to.map_or_else(|| Format::from_path(output), Format::from);
+    match split {
+        true => document.save_split(output),
+        false => document.save_as(output, format),
+    }
+    .map_err(|error| format!("{}: {error}", output.display()))?;
+    Ok(ExitCode::SUCCESS)
+}
+
+fn export(file: &Path, out: &Path, nodes: &[String]) -> Result<ExitCode> {
+    let document = open(file)?;
+    let roots = match nodes {
+        [] => vec![&document.document],
+        ids => ids
+            .iter()
+            .map(|id| find(&document, id))
+            .collect::<Result<_>>()?,
+    };
+    std::fs::create_dir_all(out)?;
+    let mut failed = false;
+    for root in roots {
+        for export in export::exports(root) {
+            let path = out.join(&export.file_name);
+            let result: Result<()> = export
+                .render(root)
+                .map_err(Into::into)
+                .and_then(|bytes| Ok(std::fs::write(&path, bytes)?));
+            match result {
+                Ok(()) => println!("{}", path.display()),
+                Err(error) => {
+                    eprintln!("error: {}: {error}", path.display());
+                    failed = true;
+                }
+            }
+        }
+    }
+    Ok(if failed {
+        ExitCode::FAILURE
+    } else {
+        ExitCode::SUCCESS
+    })
+}
+
+fn flows(file: &Path, out: Option<&Path>) -> Result<ExitCode> {
+    let document = open(file)?;
+    let graphs = flow::flow_graphs(&document);
+    for graph in &graphs {
+        for screen in graph.unreachable() {
+            eprintln!("warning: no flow reaches {} {:?}", screen.id, screen.name);
+        }
+        for screen in graph.dead_ends() {
+            eprintln!("warning: {} {:?} is a dead end", screen.id, screen.name);
+        }
+    }
+    let dot = flow::dot(&graphs);
+    match out {
+        Some(out) => {
+            std::fs::write(out, dot).map_err(|error| format!("{}: {error}", out.display()))?
+        }
+        None => print!("{dot}"),
+    }
+    Ok(ExitCode::SUCCESS)
+}
+
+fn export_tokens(file: &Path, out: Option<&Path>) -> Result<ExitCode> {
+    let document = open(file)?;
+    let json = serde_json::to_string_pretty(&tokens::export(&document))?;
+    match out {
+        Some(out) => {
+            std::fs::write(out, json).map_err(|error| format!("{}: {error}", out.display()))?
+        }
+        None => println!("{json}"),
+    }
+    Ok(ExitCode::SUCCESS)
+}
+
+fn spec(file: &Path, json: bool, out: Option<&Path>) -> Result<ExitCode> {
+    let document = open(file)?;
+    let spec = handoff::spec(&document);
+    let text = match json {
+        true => serde_json::to_string_pretty(&spec)? + "\n",
+        false => handoff::markdown(&spec),
+    };
+    match out {
+        Some(out) => {
+            std::fs::write(out, text).map_err(|error| format!("{}: {error}", out.display()))?
+        }
+        None => print!("{text}"),
+    }
+    Ok(ExitCode::SUCCESS)
+}
+
+fn diff(
+    before: &Path,
+    after: &Path,
+    json: bool,
+    images: Option<&Path>,
+    scale: f64,
+) -> Result<ExitCode> {
+    let (before, after) = (open(before)?, open(after)?);
+    let changes = diff::diff(&before, &after);
+    match json {
+        true => println!("{}", serde_json::to_string_pretty(&changes)?),
+        false => print!("{}", diff::text(&changes)),
+    }
+    let Some(images) = images else {
+        return Ok(ExitCode::SUCCESS);
+    };
+    std::fs::create_dir_all(images)?;
+    let mut failed = false;
+    for comparison in diff::comparisons(&before, &after, &changes, scale) {
+        let result: Result<()> = comparison.map_err(Into::into).and_then(|comparison| {
+            let path = images.join(&comparison.file_name);
+            std::fs::write(&path, comparison.png)
+                .map_err(|error| format!("{}: {error}", path.display()).into())
+        });
+        if let Err(error) = result {
+            eprintln!("error: {error}");
+            failed = true;
+        }
+    }
+    Ok(if failed {
+        ExitCode::FAILURE
+    } else {
+        ExitCode::SUCCESS
+    })
+}
+
+fn changes(file: &Path, json: bool) -> Result<ExitCode> {
+    let document = open(file)?;
+    let pending = document.pending_changes();
+    match json {
+        true => println!("{}", serde_json::to_string_pretty(&pending)?),
+        false => print!("{}", dev::report(&pending)),
+    }
+    Ok(ExitCode::SUCCESS)
+}
+
+fn merge(
+    base: &Path,
+    ours: &Path,
+    theirs: &Path,
+    out: Option<&Path>,
+    json: bool,
+) -> Result<ExitCode> {
+    // Git hands the versions over in temporary files without extension, so the merged document
+    // keeps the format ours was stored in.
+    let bytes = std::fs::read(ours).map_err(|error| format!("{}: {error}", ours.display()))?;
+    let format = Format::detect(&bytes);
+    // Git hands over an empty base when both sides added the document.
+    let added = std::fs::metadata(base)
+        .map_err(|error| format!("{}: {error}", base.display()))?
+        .len()
+        == 0;
+    let base = match added {
+        true => merge::added_base(),
+        false => open(base)?,
+    };
+    let merge = merge::merge(&base, &open(ours)?, &open(theirs)?);
+    let out = out.unwrap_or(ours);
+    merge
+        .document
+        .save_as(out, format)
+        .map_err(|error| format!("{}: {error}", out.display()))?;
+    match json {
+        true => println!("{}", serde_json::to_string_pretty(&merge.conflicts)?),
+        false => {
+            for conflict in &merge.conflicts {
+                eprintln!("conflict: {conflict}");
+            }
+        }
+    }
+    Ok(match merge.conflicts.is_empty() {
+        true => ExitCode::SUCCESS,
+        false => ExitCode::FAILURE,
+    })
+}
+
+fn save(document: &Document, file: &Path) -> Result<()> {
+    document
+        .save(file)
+        .map_err(|error| format!("{}: {error}", file.display()).into())
+}
+
+fn checkpoint(
+    file: &Path,
+    name: Option<&str>,
+    author: &str,
+    description: &str,
+) -> Result<ExitCode> {
+    let mut document = open(file)?;
+    match document.checkpoint(name, author, description)? {
+        Some(id) => {
+            save(&document, file)?;
+            println!("version {id}");
+        }
+        None => println!("no changes since the latest version"),
+    }
+    Ok(ExitCode::SUCCESS)
+}
+
+fn versions(file: &Path, json: bool) -> Result<ExitCode> {
+    let document = open(file)?;
+    if json {
+        let versions: Vec<_> = document
+            .versions
+            .iter()
+            .map(|version| {
+                serde_json::json!({
+                    "id": version.id,
+                    "name": version.name,
+                    "author": version.author,
+                    "timestamp": version.timestamp,
+                    "description": version.description,
+                })
+            })
+            .collect();
+        println!("{}", serde_json::to_string_pretty(&versions)?);
+        return Ok(ExitCode::SUCCESS);
+    }
+    for version in &document.versions {
+        let name = version.name.as_deref().unwrap_or("(automatic)");
+        print!(
+            "{} {name} by {} at {}",
+            version.id, version.author, version.timestamp
+        );
+        match version.description.is_empty() {
+            true => println!(),
+            false => println!(": {}", version.description),
+        }
+    }
+    Ok(ExitCode::SUCCESS)
+}
+
+fn restore(file: &Path, version: u32, node: Option<&str>, author: &str) -> Result<ExitCode> {
+    let mut document = open(file)?;
+    let description = match node {
+        Some(node) => format!("Before restoring {node} from version {version}"),
+        None => format!("Before restoring version {version}"),
+    };
+    document.checkpoint(None, author, &description)?;
+    match node {
+        Some(node) => document.restore_node(version, node)?,
+        None => document.restore_version(version)?,
+    }
+    save(&document, file)?;
+    Ok(ExitCode::SUCCESS)
+}
+
+fn prune(file: &Path, policy: &PrunePolicy) -> Result<ExitCode> {
+    let mut document = open(file)?;
+    let pruned = document.prune_versions(policy)?;
+    save(&document, file)?;
+    println!("{} version(s) removed", pruned.len());
+    Ok(ExitCode::SUCCESS)
+}
+
+fn import_tokens(file: &Path, import: &Path) -> Result<ExitCode> {
+    let mut document = open(file)?;
+    let json = std::fs::read_to_string(import)
+        .map_err(|error| format!("{}: {error}", import.display()))?;
+    let tokens =
+        serde_json::from_str(&json).map_err(|error| format!("{}: {error}", import.display()))?;
+    let import = tokens::import(&mut document, &tokens)?;
+    for token in &import.skipped {
+        eprintln!("warning: skipped {token}, which has no variable type");
+    }
+    document
+        .save(file)
+        .map_err(|error| format!("{}: {error}", file.display()))?;
+    println!(
+        "{} variable(s) created, {} updated",
+        import.created.len(),
+        import.updated.len()
+    );
+    Ok(ExitCode::SUCCESS)
 }
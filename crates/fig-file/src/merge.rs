@@ -0,0 +1,924 @@
+//! Three-way merge of documents: the versions of a document on two branches and the version they
+//! both started from.
+//!
+//! Nodes are matched by id. Each side takes the changes the other side made to the nodes,
+//! properties and definitions it left as they were in the base. Changes made by both sides agree
+//! when they lead to the same value and conflict otherwise: the version of ours is kept and a
+//! [`Conflict`] is recorded. Nodes one side deleted and the other changed, or added children to,
+//! are kept.
+//!
+//! Properties are merged field by field, so both sides changing different paints of the fills of
+//! a node merge, unless a side added or removed paints, which changes the fills as a whole. The
+//! parent of a node and the order of the children of a node are merged
+//! separately from the properties of the node.
+//!
+//! The versions theirs checkpointed since the base are added after the versions of ours.
+//!
+//! The `merge` command of the CLI is a git merge driver, declared in the git configuration
+//!
+//! ```text
+//! [merge "fig"]
+//!     name = fig-file documents
+//!     driver = fig-file merge %O %A %B
+//! ```
+//!
+//! and enabled for documents in `.gitattributes` with `*.fig merge=fig`.
+
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+use std::fmt;
+
+use fig_types::node::Node;
+use fig_types::patch::{self, Patch, PatchOperation, PathSegment, PropertyPath};
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::diff::{Position, Tree};
+use crate::document::{Document, SCHEMA_VERSION};
+use crate::versions;
+
+/// A side of a merge.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum Side {
+    Ours,
+    Theirs,
+}
+
+impl fmt::Display for Side {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Side::Ours => write!(f, "ours"),
+            Side::Theirs => write!(f, "theirs"),
+        }
+    }
+}
+
+/// Changes of both sides which could not be merged, and how they were resolved.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "conflict", rename_all = "camelCase")]
+pub enum Conflict {
+    /// Both sides changed a property of a node to different values, ours is kept.
+    #[serde(rename_all = "camelCase")]
+    Property {
+        node: String,
+        property: String,
+        base: Option<Value>,
+        ours: Option<Value>,
+        theirs: Option<Value>,
+    },
+    /// Both sides moved a node to different parents, or their moves together made a node its
+    /// own ancestor, ours is kept.
+    #[serde(rename_all = "camelCase")]
+    Parent {
+        node: String,
+        base: Option<String>,
+        ours: Option<String>,
+        theirs: Option<String>,
+    },
+    /// Both sides reordered the children of a node differently, the order of ours is kept.
+    #[serde(rename_all = "camelCase")]
+    Order { parent: String },
+    /// A side deleted a node the other side changed or added children to, the node is kept.
+    #[serde(rename_all = "camelCase")]
+    Deleted { node: String, deleted_by: Side },
+    /// Both sides added a node with the same id and different properties, ours is kept.
+    #[serde(rename_all = "camelCase")]
+    Added { node: String },
+    /// The properties of theirs do not fit the node of ours, e.g. when both sides changed its
+    /// type, ours is kept.
+    #[serde(rename_all = "camelCase")]
+    Incompatible { node: String },
+    /// Both sides changed a definition shared by the nodes differently, ours is kept unless it
+    /// deleted the definition.
+    #[serde(rename_all = "camelCase")]
+    Definition {
+        /// The field of the document holding the definition, e.g. `styles`.
+        definitions: &'static str,
+        id: String,
+    },
+    /// The version history of a side is corrupted, the versions of ours are kept.
+    Versions,
+}
+
+impl fmt::Display for Conflict {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let parent = |parent: &Option<String>| parent.as_deref().unwrap_or("(root)").to_string();
+        match self {
+            Conflict::Property { node, property, .. } => {
+                write!(f, "{node}: both sides changed `{property}`, kept ours")
+            }
+            Conflict::Parent {
+                node, ours, theirs, ..
+            } => write!(
+                f,
+                "{node}: moved to {} by ours and to {} by theirs, kept ours",
+                parent(ours),
+                parent(theirs)
+            ),
+            Conflict::Order { parent } => {
+                write!(f, "{parent}: both sides reordered its children, kept ours")
+            }
+            Conflict::Deleted { node, deleted_by } => {
+                write!(
+                    f,
+                    "{node}: deleted by {deleted_by} and changed by the other side, kept"
+                )
+            }
+            Conflict::Added { node } => {
+                write!(f, "{node}: added by both sides differently, kept ours")
+            }
+            Conflict::Incompatible { node } => {
+                write!(f, "{node}: changed by both sides incompatibly, kept ours")
+            }
+            Conflict::Definition { definitions, id } => {
+                write!(f, "{definitions}.{id}: changed by both sides differently")
+            }
+            Conflict::Versions => write!(f, "corrupted version history, kept the versions of ours"),
+        }
+    }
+}
+
+/// A merged document and the conflicts found while merging it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Merge {
+    pub document: Document,
+    pub conflicts: Vec<Conflict>,
+}
+
+/// Merges the changes two sides made to a base document.
+pub fn merge(base: &Document, ours: &Document, theirs: &Document) -> Merge {
+    let mut merger = Merger {
+        base: Tree::new(&base.document),
+        ours: Tree::new(&ours.document),
+        theirs: Tree::new(&theirs.document),
+        nodes: HashMap::new(),
+        conflicts: Vec::new(),
+    };
+    let mut ids = Vec::new();
+    let mut seen = HashSet::new();
+    for tree in [&merger.ours, &merger.theirs, &merger.base] {
+        for (node, _) in &tree.order {
+            if seen.insert(node.id.as_str()) {
+                ids.push(node.id.as_str());
+            }
+        }
+    }
+    for id in ids {
+        merger.merge_node(id);
+    }
+    merger.restore_parents();
+    merger.connect(&ours.document.id);
+
+    let mut children: HashMap<&str, HashSet<&str>> = HashMap::new();
+    for (id, (_, parent)) in &merger.nodes {
+        if let Some(parent) = parent {
+            children.entry(parent).or_default().insert(id);
+        }
+    }
+    let root = merger
+        .build(&ours.document.id, &children)
+        .unwrap_or_else(|| ours.document.clone());
+
+    let conflicts = &mut merger.conflicts;
+    let document = Document {
+        schema_version: SCHEMA_VERSION,
+        document: root,
+        components: merge_map(
+            "components",
+            &base.components,
+            &ours.components,
+            &theirs.components,
+            conflicts,
+        ),
+        component_sets: merge_map(
+            "componentSets",
+            &base.component_sets,
+            &ours.component_sets,
+            &theirs.component_sets,
+            conflicts,
+        ),
+        styles: merge_map(
+            "styles",
+            &base.styles,
+            &ours.styles,
+            &theirs.styles,
+            conflicts,
+        ),
+        variables: merge_map(
+            "variables",
+            &base.variables,
+            &ours.variables,
+            &theirs.variables,
+            conflicts,
+        ),
+        variable_collections: merge_map(
+            "variableCollections",
+            &base.variable_collections,
+            &ours.variable_collections,
+            &theirs.variable_collections,
+            conflicts,
+        ),
+        dev_snapshots: merge_map(
+            "devSnapshots",
+            &base.dev_snapshots,
+            &ours.dev_snapshots,
+            &theirs.dev_snapshots,
+            conflicts,
+        ),
+        versions: versions::merge_versions(base, ours, theirs).unwrap_or_else(|_| {
+            conflicts.push(Conflict::Versions);
+            ours.versions.clone()
+        }),
+    };
+    Merge {
+        document,
+        conflicts: merger.conflicts,
+    }
+}
+
+/// The base of a document both sides added, e.g. for the empty base git hands over then: a
+/// document without nodes, so every node of the sides is added by both.
+pub fn added_base() -> Document {
+    let mut base = Document::new("");
+    // No node of the sides has an empty id.
+    base.document.id = String::new();
+    base.document
+        .children_mut()
+        .expect("documents hold canvases")
+        .clear();
+    base
+}
+
+/// The value both sides agree on, `None` when they changed it differently.
+fn merge3<T: PartialEq>(base: T, ours: T, theirs: T) -> Option<T> {
+    match (ours == theirs || theirs == base, ours == base) {
+        (true, _) => Some(ours),
+        (false, true) => Some(theirs),
+        (false, false) => None,
+    }
+}
+
+type Entry<'a> = (&'a Node, Option<Position<'a>>);
+
+struct Merger<'a> {
+    base: Tree<'a>,
+    ours: Tree<'a>,
+    theirs: Tree<'a>,
+    /// The merged nodes without their children, and the id of their parent.
+    nodes: HashMap<&'a str, (Node, Option<&'a str>)>,
+    conflicts: Vec<Conflict>,
+}
+
+impl<'a> Merger<'a> {
+    fn merge_node(&mut self, id: &'a str) {
+        let base = self.base.nodes.get(id).copied();
+        let ours = self.ours.nodes.get(id).copied();
+        let theirs = self.theirs.nodes.get(id).copied();
+        let merged = match (base, ours, theirs) {
+            (Some(base), Some(ours), Some(theirs)) => {
+                let node = self.merge_properties(base.0, ours.0, theirs.0);
+                let parents = (parent(base), parent(ours), parent(theirs));
+                let parent = merge3(parents.0, parents.1, parents.2).unwrap_or_else(|| {
+                    self.conflicts.push(Conflict::Parent {
+                        node: id.to_string(),
+                        base: parents.0.map(str::to_string),
+                        ours: parents.1.map(str::to_string),
+                        theirs: parents.2.map(str::to_string),
+                    });
+                    parents.1
+                });
+                Some((node, parent))
+            }
+            (Some(base), Some(kept), None) | (Some(base), None, Some(kept)) => {
+                match unchanged(base, kept) {
+                    true => None,
+                    false => {
+                        self.conflicts.push(Conflict::Deleted {
+                            node: id.to_string(),
+                            deleted_by: match ours {
+                                Some(_) => Side::Theirs,
+                                None => Side::Ours,
+                            },
+                        });
+                        Some((kept.0.clone(), parent(kept)))
+                    }
+                }
+            }
+            (None, Some(ours), Some(theirs)) => {
+                if !unchanged(ours, theirs) {
+                    self.conflicts.push(Conflict::Added {
+                        node: id.to_string(),
+                    });
+                }
+                Some((ours.0.clone(), parent(ours)))
+            }
+            (None, Some(added), None) | (None, None, Some(added)) => {
+                Some((added.0.clone(), parent(added)))
+            }
+            (Some(_), None, None) | (None, None, None) => None,
+        };
+        if let Some((mut node, parent)) = merged {
+            if let Some(children) = node.children_mut() {
+                children.clear();
+            }
+            self.nodes.insert(id, (node, parent));
+        }
+    }
+
+    /// The node of ours with the changes only theirs made. Changes are matched by path, so
+    /// changes of different fields of a property merge, while the changes adding or removing
+    /// elements of an array change the whole array.
+    fn merge_properties(&mut self, base: &Node, ours: &Node, theirs: &Node) -> Node {
+        let properties = [base, ours, theirs].map(|node| Value::Object(patch::properties(node)));
+        let [ours_paths, theirs_paths] = [ours, theirs].map(|side| changed(base, side));
+        // The paths both sides changed, or inside a property the other side changed, are merged
+        // at the outermost of them.
+        let mut paths: Vec<PropertyPath> = Vec::new();
+        for path in theirs_paths {
+            let path = match ours_paths.iter().find(|changed| path.starts_with(changed)) {
+                Some(changed) => changed.clone(),
+                None => path,
+            };
+            if !paths.contains(&path) {
+                paths.push(path);
+            }
+        }
+        let id = &ours.id;
+        let mut operations = Vec::new();
+        for path in paths {
+            let [base, ours, theirs] = properties
+                .each_ref()
+                .map(|properties| value(properties, &path));
+            match merge3(&base, &ours, &theirs) {
+                Some(merged) if *merged == ours => {}
+                Some(_) => operations.push(match (ours, theirs) {
+                    (_, None) => PatchOperation::Remove { path },
+                    (None, Some(value)) => PatchOperation::Insert { path, value },
+                    (Some(_), Some(value)) => PatchOperation::Set { path, value },
+                }),
+                None => self.conflicts.push(Conflict::Property {
+                    node: id.clone(),
+                    property: path.to_string(),
+                    base,
+                    ours,
+                    theirs,
+                }),
+            }
+        }
+        let mut node = ours.clone();
+        let patch = Patch {
+            id: node.id.clone(),
+            operations,
+        };
+        if !patch.operations.is_empty() && patch.apply(&mut node).is_err() {
+            self.conflicts.push(Conflict::Incompatible {
+                node: ours.id.clone(),
+            });
+            return ours.clone();
+        }
+        node
+    }
+
+    /// Keeps the parents of merged nodes which a side deleted while the other side added or
+    /// moved nodes into them.
+    fn restore_parents(&mut self) {
+        loop {
+            let missing: BTreeSet<&'a str> = self
+                .nodes
+                .values()
+                .filter_map(|(_, parent)| *parent)
+                .filter(|parent| !self.nodes.contains_key(parent))
+                .collect();
+            let mut restored = false;
+            for id in missing {
+                let (entry, deleted_by) = match (self.ours.nodes.get(id), self.theirs.nodes.get(id))
+                {
+                    (Some(entry), _) => (*entry, Side::Theirs),
+                    (None, Some(entry)) => (*entry, Side::Ours),
+                    (None, None) => continue,
+                };
+                self.conflicts.push(Conflict::Deleted {
+                    node: id.to_string(),
+                    deleted_by,
+                });
+                let mut node = entry.0.clone();
+                if let Some(children) = node.children_mut() {
+                    children.clear();
+                }
+                self.nodes.insert(id, (node, parent(entry)));
+                restored = true;
+            }
+            if !restored {
+                break;
+            }
+        }
+    }
+
+    /// Moves back to their parent in ours the merged nodes which the merged parents do not
+    /// connect to the root, e.g. when each side moved a node into the other.
+    fn connect(&mut self, root: &'a str) {
+        loop {
+            let mut moved = false;
+            for id in self.detached(root) {
+                let Some(&entry) = self.ours.nodes.get(id) else {
+                    continue;
+                };
+                let (_, merged) = self.nodes.get_mut(id).expect("detached nodes are merged");
+                if *merged == parent(entry) {
+                    continue;
+                }
+                *merged = parent(entry);
+                let side = |tree: &Tree<'a>| tree.nodes.get(id).and_then(|entry| parent(*entry));
+                self.conflicts.push(Conflict::Parent {
+                    node: id.to_string(),
+                    base: side(&self.base).map(str::to_string),
+                    ours: side(&self.ours).map(str::to_string),
+                    theirs: side(&self.theirs).map(str::to_string),
+                });
+                moved = true;
+            }
+            if !moved {
+                break;
+            }
+        }
+    }
+
+    /// The merged nodes whose chain of merged parents does not lead to the root.
+    fn detached(&self, root: &'a str) -> BTreeSet<&'a str> {
+        let mut connected = HashSet::from([root]);
+        let mut detached = BTreeSet::new();
+        for &id in self.nodes.keys() {
+            let mut chain = Vec::new();
+            let mut current = Some(id);
+            let placed = loop {
+                let Some(node) = current.filter(|node| !chain.contains(node)) else {
+                    break false;
+                };
+                if connected.contains(node) {
+                    break true;
+                }
+                chain.push(node);
+                current = self.nodes.get(node).and_then(|(_, parent)| *parent);
+            };
+            match placed {
+                true => connected.extend(chain),
+                false => detached.extend(chain),
+            }
+        }
+        detached
+    }
+
+    /// Builds the merged subtree of a node. Nodes are taken out of the merged nodes as they are
+    /// placed, so each node is placed at most once.
+    fn build(&mut self, id: &str, children: &HashMap<&'a str, HashSet<&'a str>>) -> Option<Node> {
+        let (id, (mut node, _)) = self.nodes.remove_entry(id)?;
+        let order = match children.get(id) {
+            Some(children) => self.order(id, children),
+            None => Vec::new(),
+        };
+        let built = order
+            .into_iter()
+            .filter_map(|child| self.build(child, children))
+            .collect();
+        if let Some(children) = node.children_mut() {
+            *children = built;
+        }
+        Some(node)
+    }
+
+    /// The merged order of the children of a node. The order of the children kept by both sides
+    /// is merged like a property. The children only one side has are placed after their closest
+    /// preceding sibling on that side.
+    fn order(&mut self, parent: &'a str, children: &HashSet<&'a str>) -> Vec<&'a str> {
+        let listed = |tree: &Tree<'a>| -> Vec<&'a str> {
+            tree.nodes
+                .get(parent)
+                .map(|(node, _)| node.children().iter().map(|child| child.id.as_str()))
+                .into_iter()
+                .flatten()
+                .collect()
+        };
+        let [base, ours, theirs] = [&self.base, &self.ours, &self.theirs].map(listed);
+        let kept = |listed: &[&'a str]| -> Vec<&'a str> {
+            listed
+                .iter()
+                .filter(|id| base.contains(id) && ours.contains(id) && theirs.contains(id))
+                .copied()
+                .collect()
+        };
+        let (kept_base, kept_ours, kept_theirs) = (kept(&base), kept(&ours), kept(&theirs));
+        let sources = match merge3(&kept_base, &kept_ours, &kept_theirs) {
+            Some(kept) if *kept == kept_ours => [&ours, &theirs, &base],
+            Some(_) => [&theirs, &ours, &base],
+            None => {
+                self.conflicts.push(Conflict::Order {
+                    parent: parent.to_string(),
+                });
+                [&ours, &theirs, &base]
+            }
+        };
+        let mut order: Vec<&'a str> = Vec::new();
+        for source in sources {
+            for (index, id) in source.iter().enumerate() {
+                if !children.contains(id) || order.contains(id) {
+                    continue;
+                }
+                let after = source[..index]
+                    .iter()
+                    .rev()
+                    .find_map(|sibling| order.iter().position(|placed| placed == sibling));
+                order.insert(after.map_or(0, |placed| placed + 1), id);
+            }
+        }
+        order
+    }
+}
+
+/// The paths of the properties a side changed, where arrays with added or removed elements changed
+/// as a whole.
+fn changed(base: &Node, side: &Node) -> Vec<PropertyPath> {
+    let mut paths: Vec<PropertyPath> = Vec::new();
+    for change in patch::changes(base, side) {
+        let resized = matches!(change.path.segments().last(), Some(PathSegment::Index(_)))
+            && (change.before.is_none() || change.after.is_none());
+        let path = match resized.then(|| change.path.parent()).flatten() {
+            Some(array) => array,
+            None => change.path,
+        };
+        if !paths.contains(&path) {
+            paths.push(path);
+        }
+    }
+    let within = |path: &PropertyPath| {
+        paths
+            .iter()
+            .any(|other| other != path && path.starts_with(other))
+    };
+    paths.iter().filter(|path| !within(path)).cloned().collect()
+}
+
+/// The value of a property, `None` when it is unset.
+fn value(properties: &Value, path: &PropertyPath) -> Option<Value> {
+    let mut value = properties;
+    for segment in path.segments() {
+        value = match segment {
+            PathSegment::Key(key) => value.get(key)?,
+            PathSegment::Index(index) => value.get(index)?,
+        };
+    }
+    // Unset optional properties serialize to null.
+    Some(value).filter(|value| !value.is_null()).cloned()
+}
+
+fn parent<'a>((_, position): Entry<'a>) -> Option<&'a str> {
+    position.map(|position| position.parent)
+}
+
+/// Whether a node has the same properties and parent in both versions.
+fn unchanged(before: Entry, after: Entry) -> bool {
+    parent(before) == parent(after) && patch::changes(before.0, after.0).is_empty()
+}
+
+/// Merges a map of definitions by id.
+fn merge_map<T: Clone + PartialEq>(
+    definitions: &'static str,
+    base: &BTreeMap<String, T>,
+    ours: &BTreeMap<String, T>,
+    theirs: &BTreeMap<String, T>,
+    conflicts: &mut Vec<Conflict>,
+) -> BTreeMap<String, T> {
+    let ids: BTreeSet<&String> = ours
+        .keys()
+        .chain(theirs.keys())
+        .chain(base.keys())
+        .collect();
+    let mut merged = BTreeMap::new();
+    for id in ids {
+        let (ours, theirs) = (ours.get(id), theirs.get(id));
+        let definition = merge3(base.get(id), ours, theirs).unwrap_or_else(|| {
+            conflicts.push(Conflict::Definition {
+                definitions,
+                id: id.clone(),
+            });
+            ours.or(theirs)
+        });
+        if let Some(definition) = definition {
+            merged.insert(id.clone(), definition.clone());
+        }
+    }
+    merged
+}
+
+#[cfg(test)]
+mod tests {
+    use fig_types::node::NodeType;
+    use serde_json::json;
+
+    use super::*;
+    use crate::fixtures;
+
+    /// A document with layers `1:1`, `1:2` and `1:3` on its page.
+    fn base() -> Document {
+        let mut document = Document::new("Merge");
+        *children(&mut document, "0:1") = ["1:1", "1:2", "1:3"]
+            .map(|id| fixtures::vector(id, &format!("Layer {id}")))
+            .to_vec();
+        document
+    }
+
+    fn children<'a>(document: &'a mut Document, id: &str) -> &'a mut Vec<Node> {
+        document
+            .document
+            .find_mut(id)
+            .unwrap()
+            .children_mut()
+            .unwrap()
+    }
+
+    fn set_opacity(document: &mut Document, id: &str, opacity: f32) {
+        let NodeType::Vector(vector) = &mut document.document.find_mut(id).unwrap().node else {
+            unreachable!();
+        };
+        vector.opacity = opacity;
+    }
+
+    fn set(document: &mut Document, id: &str, path: &str, value: Value) {
+        let path = path.parse().unwrap();
+        let patch = Patch {
+            id: id.to_string(),
+            operations: vec![PatchOperation::Set { path, value }],
+        };
+        patch
+            .apply(document.document.find_mut(id).unwrap())
+            .unwrap();
+    }
+
+    fn ids(document: &Document, id: &str) -> Vec<String> {
+        let node = document.node(id).unwrap();
+        node.children()
+            .iter()
+            .map(|child| child.id.clone())
+            .collect()
+    }
+
+    #[test]
+    fn changes_of_both_sides_to_different_nodes_and_properties_are_merged() {
+        let base = base();
+        let mut ours = base.clone();
+        ours.document.find_mut("1:1").unwrap().name = "Title".to_string();
+        children(&mut ours, "0:1").remove(2);
+        children(&mut ours, "0:1").insert(1, fixtures::vector("1:4", "Added by ours"));
+        let mut theirs = base.clone();
+        theirs.document.find_mut("1:1").unwrap().visible = false;
+        set_opacity(&mut theirs, "1:2", 0.5);
+        children(&mut theirs, "0:1").push(fixtures::vector("1:5", "Added by theirs"));
+        // Both sides making the same change agree.
+        set_opacity(&mut ours, "1:1", 0.25);
+        set_opacity(&mut theirs, "1:1", 0.25);
+
+        let merge = merge(&base, &ours, &theirs);
+        assert_eq!(merge.conflicts, []);
+        let document = merge.document;
+        assert_eq!(ids(&document, "0:1"), ["1:1", "1:4", "1:2", "1:5"]);
+        let title = document.node("1:1").unwrap();
+        assert_eq!((title.name.as_str(), title.visible), ("Title", false));
+        let opacity = |id| patch::properties(document.node(id).unwrap())["opacity"].clone();
+        assert_eq!(opacity("1:1"), json!(0.25));
+        assert_eq!(opacity("1:2"), json!(0.5));
+    }
+
+    #[test]
+    fn changes_of_both_sides_to_different_fields_of_a_property_are_merged() {
+        let mut base = base();
+        let fills = json!([
+            fixtures::solid(1.0, 0.0, 0.0),
+            fixtures::solid(0.0, 1.0, 0.0)
+        ]);
+        set(&mut base, "1:1", "fills", fills.clone());
+        set(&mut base, "1:2", "fills", fills);
+        let mut ours = base.clone();
+        let mut theirs = base.clone();
+        set(&mut ours, "1:1", "fills[0].color.b", json!(1.0));
+        set(&mut theirs, "1:1", "fills[1].opacity", json!(0.5));
+        set(&mut ours, "1:1", "constraint.vertical", json!("Bottom"));
+        set(&mut theirs, "1:1", "constraint.horizontal", json!("Right"));
+        // Adding a paint changes the fills as a whole.
+        let mut fills = patch::properties(ours.node("1:2").unwrap())["fills"].clone();
+        fills
+            .as_array_mut()
+            .unwrap()
+            .push(fixtures::solid(0.0, 0.0, 1.0));
+        set(&mut ours, "1:2", "fills", fills);
+        set(&mut theirs, "1:2", "fills[0].opacity", json!(0.5));
+
+        let merge = merge(&base, &ours, &theirs);
+        let properties = patch::properties(merge.document.node("1:1").unwrap());
+        assert_eq!(properties["fills"][0]["color"]["b"], json!(1.0));
+        assert_eq!(properties["fills"][1]["opacity"], json!(0.5));
+        assert_eq!(
+            properties["constraint"],
+            json!({ "vertical": "Bottom", "horizontal": "Right" })
+        );
+        let [Conflict::Property {
+            node,
+            property,
+            ours,
+            ..
+        }] = merge.conflicts.as_slice()
+        else {
+            panic!("the fills of 1:2 conflict: {:?}", merge.conflicts);
+        };
+        assert_eq!((node.as_str(), property.as_str()), ("1:2", "fills"));
+        let fills = patch::properties(merge.document.node("1:2").unwrap())["fills"].clone();
+        assert_eq!(ours, &Some(fills));
+    }
+
+    #[test]
+    fn versions_checkpointed_by_theirs_follow_the_versions_of_ours() {
+        let mut base = base();
+        base.checkpoint(Some("Base"), "Ada", "").unwrap();
+        let mut ours = base.clone();
+        set_opacity(&mut ours, "1:1", 0.25);
+        ours.checkpoint(Some("Ours"), "Ada", "").unwrap();
+        let mut theirs = base.clone();
+        set_opacity(&mut theirs, "1:2", 0.5);
+        theirs.checkpoint(Some("Theirs"), "Grace", "").unwrap();
+        theirs.document.find_mut("1:3").unwrap().name = "Later".to_string();
+        theirs.checkpoint(None, "Grace", "").unwrap();
+
+        let merge = merge(&base, &ours, &theirs);
+        assert_eq!(merge.conflicts, []);
+        let document = merge.document;
+        let versions: Vec<(u32, Option<&str>)> = document
+            .versions
+            .iter()
+            .map(|version| (version.id, version.name.as_deref()))
+            .collect();
+        assert_eq!(
+            versions,
+            [
+                (1, Some("Base")),
+                (2, Some("Ours")),
+                (3, Some("Theirs")),
+                (4, None)
+            ]
+        );
+        assert_eq!(document.version(2).unwrap().document, ours.document);
+        assert_eq!(
+            document.version(3).unwrap().document,
+            theirs.version(2).unwrap().document
+        );
+        assert_eq!(document.version(4).unwrap().document, theirs.document);
+    }
+
+    #[test]
+    fn changes_of_both_sides_to_the_same_property_conflict() {
+        let base = base();
+        let mut ours = base.clone();
+        let mut theirs = base.clone();
+        set_opacity(&mut ours, "1:1", 0.25);
+        set_opacity(&mut theirs, "1:1", 0.75);
+        // Ours deletes a layer theirs renamed.
+        children(&mut ours, "0:1").remove(1);
+        theirs.document.find_mut("1:2").unwrap().name = "Renamed".to_string();
+        // Both sides add a node with the same id.
+        children(&mut ours, "0:1").push(fixtures::vector("1:9", "Ours"));
+        children(&mut theirs, "0:1").push(fixtures::vector("1:9", "Theirs"));
+        ours.dev_snapshots
+            .insert("1:1".to_string(), fixtures::vector("1:1", "Ours"));
+        theirs
+            .dev_snapshots
+            .insert("1:1".to_string(), fixtures::vector("1:1", "Theirs"));
+
+        let merge = merge(&base, &ours, &theirs);
+        assert_eq!(
+            merge.conflicts,
+            [
+                Conflict::Property {
+                    node: "1:1".to_string(),
+                    property: "opacity".to_string(),
+                    base: Some(json!(1.0)),
+                    ours: Some(json!(0.25)),
+                    theirs: Some(json!(0.75)),
+                },
+                Conflict::Added {
+                    node: "1:9".to_string()
+                },
+                Conflict::Deleted {
+                    node: "1:2".to_string(),
+                    deleted_by: Side::Ours
+                },
+                Conflict::Definition {
+                    definitions: "devSnapshots",
+                    id: "1:1".to_string()
+                },
+            ]
+        );
+        let document = merge.document;
+        assert_eq!(ids(&document, "0:1"), ["1:1", "1:2", "1:3", "1:9"]);
+        assert_eq!(document.node("1:2").unwrap().name, "Renamed");
+        assert_eq!(document.node("1:9").unwrap().name, "Ours");
+        assert_eq!(document.dev_snapshots["1:1"].name, "Ours");
+        assert_eq!(
+            merge.conflicts[2].to_string(),
+            "1:2: deleted by ours and changed by the other side, kept"
+        );
+    }
+
+    #[test]
+    fn reordered_children_and_moves_are_merged() {
+        let base = base();
+        let mut ours = base.clone();
+        let mut theirs = base.clone();
+        // Theirs reverses the layers, ours adds one after the first.
+        children(&mut theirs, "0:1").reverse();
+        children(&mut ours, "0:1").insert(1, fixtures::vector("1:4", "Added"));
+        let merge = merge(&base, &ours, &theirs);
+        assert_eq!(merge.conflicts, []);
+        assert_eq!(ids(&merge.document, "0:1"), ["1:3", "1:2", "1:1", "1:4"]);
+
+        // Both sides reordering differently keeps the order of ours.
+        let mut ours = base.clone();
+        children(&mut ours, "0:1").swap(0, 1);
+        let merge = super::merge(&base, &ours, &theirs);
+        assert_eq!(
+            merge.conflicts,
+            [Conflict::Order {
+                parent: "0:1".to_string()
+            }]
+        );
+        assert_eq!(ids(&merge.document, "0:1"), ["1:2", "1:1", "1:3"]);
+    }
+
+    #[test]
+    fn crossed_moves_keep_every_node() {
+        let mut base = base();
+        let mut page = base.document.find("0:1").unwrap().clone();
+        page.id = "0:2".to_string();
+        *page.children_mut().unwrap() = vec![fixtures::vector("2:1", "Layer 2:1")];
+        base.document.children_mut().unwrap().push(page);
+        // Ours moves the first page into the second, theirs the second into the first.
+        let mut ours = base.clone();
+        let page = children(&mut ours, "0:0").remove(0);
+        children(&mut ours, "0:2").push(page);
+        let mut theirs = base.clone();
+        let page = children(&mut theirs, "0:0").remove(1);
+        children(&mut theirs, "0:1").push(page);
+
+        let merge = merge(&base, &ours, &theirs);
+        assert_eq!(
+            merge.conflicts,
+            [Conflict::Parent {
+                node: "0:2".to_string(),
+                base: Some("0:0".to_string()),
+                ours: Some("0:0".to_string()),
+                theirs: Some("0:1".to_string()),
+            }]
+        );
+        assert_eq!(merge.document, ours);
+    }
+
+    #[test]
+    fn documents_added_by_both_sides_are_merged_node_by_node() {
+        let ours = base();
+        let mut theirs = base();
+        theirs.document.find_mut("1:2").unwrap().name = "Renamed".to_string();
+        let merge = merge(&added_base(), &ours, &theirs);
+        assert_eq!(
+            merge.conflicts,
+            [Conflict::Added {
+                node: "1:2".to_string()
+            }]
+        );
+        assert_eq!(merge.document, ours);
+    }
+
+    #[test]
+    fn pages_deleted_by_a_side_are_kept_when_the_other_side_adds_layers_to_them() {
+        let mut base = base();
+        let mut page = base.document.find("0:1").unwrap().clone();
+        page.id = "0:2".to_string();
+        page.name = "Page 2".to_string();
+        page.children_mut().unwrap().clear();
+        base.document.children_mut().unwrap().push(page);
+        let mut ours = base.clone();
+        children(&mut ours, "0:0").remove(1);
+        let mut theirs = base.clone();
+        children(&mut theirs, "0:2").push(fixtures::vector("2:1", "Added"));
+        // Theirs moves a layer to the page ours deletes.
+        let moved = children(&mut theirs, "0:1").remove(0);
+        children(&mut theirs, "0:2").push(moved);
+
+        let merge = merge(&base, &ours, &theirs);
+        assert_eq!(
+            merge.conflicts,
+            [Conflict::Deleted {
+                node: "0:2".to_string(),
+                deleted_by: Side::Ours
+            }]
+        );
+        assert_eq!(ids(&merge.document, "0:0"), ["0:1", "0:2"]);
+        assert_eq!(ids(&merge.document, "0:1"), ["1:2", "1:3"]);
+        assert_eq!(ids(&merge.document, "0:2"), ["2:1", "1:1"]);
+    }
+}
@@ -0,0 +1,681 @@
+//! Rendering of nodes to SVG, PNG and JPG images.
+//!
+//! Nodes are first rendered to SVG, which is then rasterized for bitmap formats. Fills and strokes
+//! with solid and gradient paints, opacity, blend modes, drop shadows, layer blurs and masks are
+//! rendered. Image, emoji and video paints, inner shadows and background blurs are not, they need
+//! data or context a single document does not have.
+
+use std::fmt::Write;
+
+use fig_types::node::vector::VectorNode;
+use fig_types::node::{Node, NodeType};
+use fig_types::properties::{
+    BlendMode, Color, Effect, EffectType, Paint, PaintType, Rectangle, StrokeCap, StrokeJoin,
+};
+
+use crate::spatial::Bounds;
+
+#[derive(Debug, thiserror::Error)]
+pub enum RenderError {
+    #[error("node `{0}` cannot be rendered, only canvases and layers can")]
+    NotRenderable(String),
+    #[error("node `{0}` has nothing visible to render")]
+    Empty(String),
+    #[error("the image of node `{id}` would be {width}x{height} pixels, which is too large")]
+    TooLarge { id: String, width: u32, height: u32 },
+    #[error("failed to rasterize node `{id}`: {message}")]
+    Raster { id: String, message: String },
+}
+
+/// Image formats nodes can be rendered to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ImageFormat {
+    Svg,
+    Png,
+    Jpg,
+}
+
+/// Largest width or height of a rendered bitmap.
+const MAX_DIMENSION: u32 = 16384;
+/// Quality of rendered JPG images, from 1 to 100.
+const JPG_QUALITY: u8 = 90;
+/// Space between the two versions of a node rendered side by side, in pixels.
+const SIDE_BY_SIDE_GAP: u32 = 16;
+
+/// The area covered by a node once rendered, in absolute coordinates.
+pub fn bounds(node: &Node) -> Option<Bounds> {
+    if !node.visible {
+        return None;
+    }
+    match &node.node {
+        NodeType::Document(_) => None,
+        NodeType::Canvas(canvas) => canvas
+            .children
+            .iter()
+            .filter(|child| !is_mask(child))
+            .filter_map(bounds)
+            .reduce(|union, bounds| union.union(&bounds)),
+        NodeType::Vector(vector) => Some(vector_bounds(vector)),
+        NodeType::Rectangle(vector) => Some(vector_bounds(vector)),
+    }
+}
+
+fn vector_bounds<Data>(vector: &VectorNode<Data>) -> Bounds {
+    Bounds::from(
+        vector
+            .absolute_render_bounds
+            .as_ref()
+            .unwrap_or(&vector.absolute_bounding_box),
+    )
+}
+
+/// Renders a node to an image, scaled by `scale`.
+pub fn render(node: &Node, format: ImageFormat, scale: f64) -> Result<Vec<u8>, RenderError> {
+    match format {
+        ImageFormat::Svg => Ok(svg(node, scale)?.into_bytes()),
+        ImageFormat::Png => {
+            let pixmap = rasterize(node, scale)?;
+            pixmap.encode_png().map_err(|error| RenderError::Raster {
+                id: node.id.clone(),
+                message: error.to_string(),
+            })
+        }
+        ImageFormat::Jpg => jpg(node, &rasterize(node, scale)?),
+    }
+}
+
+/// Renders two versions of a node next to each other as a PNG image on a white background, the
+/// previous version on the left. A missing version, or one with nothing visible, leaves its side
+/// blank.
+pub fn side_by_side(
+    before: Option<&Node>,
+    after: Option<&Node>,
+    scale: f64,
+) -> Result<Vec<u8>, RenderError> {
+    let id = after
+        .or(before)
+        .map(|node| node.id.clone())
+        .unwrap_or_default();
+    let side = |node: Option<&Node>| match node.map(|node| rasterize(node, scale)) {
+        None | Some(Err(RenderError::Empty(_))) => Ok(None),
+        Some(result) => result.map(Some),
+    };
+    let sides = [side(before)?, side(after)?];
+    // Both sides are as wide as the widest, so the versions line up.
+    let column = sides.iter().flatten().map(|side| side.width()).max();
+    let height = sides.iter().flatten().map(|side| side.height()).max();
+    let (Some(column), Some(height)) = (column, height) else {
+        return Err(RenderError::Empty(id));
+    };
+    let width = column * 2 + SIDE_BY_SIDE_GAP;
+    if width > MAX_DIMENSION {
+        return Err(RenderError::TooLarge { id, width, height });
+    }
+    let mut pixmap = resvg::tiny_skia::Pixmap::new(width, height)
+        .ok_or_else(|| RenderError::Empty(id.clone()))?;
+    pixmap.fill(resvg::tiny_skia::Color::WHITE);
+    for (index, side) in sides.iter().enumerate() {
+        if let Some(side) = side {
+            pixmap.draw_pixmap(
+                (index as u32 * (column + SIDE_BY_SIDE_GAP)) as i32,
+                0,
+                side.as_ref(),
+                &resvg::tiny_skia::PixmapPaint::default(),
+                resvg::tiny_skia::Transform::identity(),
+                None,
+            );
+        }
+    }
+    pixmap.encode_png().map_err(|error| RenderError::Raster {
+        id,
+        message: error.to_string(),
+    })
+}
+
+/// Renders a node to an SVG document, scaled by `scale`.
+pub fn svg(node: &Node, scale: f64) -> Result<String, RenderError> {
+    if matches!(node.node, NodeType::Document(_)) {
+        return Err(RenderError::NotRenderable(node.id.clone()));
+    }
+    let bounds = bounds(node)
+        .filter(|bounds| bounds.width > 0.0 && bounds.height > 0.0)
+        .ok_or_else(|| RenderError::Empty(node.id.clone()))?;
+    let mut svg = Svg::default();
+    match &node.node {
+        NodeType::Canvas(canvas) => {
+            let color = &canvas.background_color;
+            write!(
+                svg.body,
+                r#"<rect x="{}" y="{}" width="{}" height="{}" fill="{}" fill-opacity="{}"/>"#,
+                bounds.x,
+                bounds.y,
+                bounds.width,
+                bounds.height,
+                rgb(color),
+                color.a
+            )
+            .unwrap();
+            svg.siblings(&canvas.children);
+        }
+        _ => svg.node(node),
+    }
+    Ok(format!(
+        r#"<svg xmlns="http://www.w3.org/2000/svg" width="{}" height="{}" viewBox="{} {} {} {}"><defs>{}</defs>{}</svg>"#,
+        bounds.width * scale,
+        bounds.height * scale,
+        bounds.x,
+        bounds.y,
+        bounds.width,
+        bounds.height,
+        svg.defs,
+        svg.body
+    ))
+}
+
+fn rasterize(node: &Node, scale: f64) -> Result<resvg::tiny_skia::Pixmap, RenderError> {
+    let svg = svg(node, scale)?;
+    let raster = |message: String| RenderError::Raster {
+        id: node.id.clone(),
+        message,
+    };
+    let tree = resvg::usvg::Tree::from_str(&svg, &resvg::usvg::Options::default())
+        .map_err(|error| raster(error.to_string()))?;
+    let size = tree.size().to_int_size();
+    if size.width() > MAX_DIMENSION || size.height() > MAX_DIMENSION {
+        return Err(RenderError::TooLarge {
+            id: node.id.clone(),
+            width: size.width(),
+            height: size.height(),
+        });
+    }
+    let mut pixmap = resvg::tiny_skia::Pixmap::new(size.width(), size.height())
+        .ok_or_else(|| RenderError::Empty(node.id.clone()))?;
+    resvg::render(
+        &tree,
+        resvg::tiny_skia::Transform::identity(),
+        &mut pixmap.as_mut(),
+    );
+    Ok(pixmap)
+}
+
+/// Encodes a bitmap as JPG, on a white background since JPG has no transparency.
+fn jpg(node: &Node, pixmap: &resvg::tiny_skia::Pixmap) -> Result<Vec<u8>, RenderError> {
+    // Pixels are premultiplied, so compositing over white adds the missing coverage to each
+    // channel.
+    let rgb: Vec<u8> = pixmap
+        .pixels()
+        .iter()
+        .flat_map(|pixel| {
+            let uncovered = 255 - pixel.alpha();
+            [pixel.red(), pixel.green(), pixel.blue()].map(|channel| channel + uncovered)
+        })
+        .collect();
+    let mut bytes = Vec::new();
+    // Sizes are bounded by MAX_DIMENSION, which fits in a u16.
+    jpeg_encoder::Encoder::new(&mut bytes, JPG_QUALITY)
+        .encode(
+            &rgb,
+            pixmap.width() as u16,
+            pixmap.height() as u16,
+            jpeg_encoder::ColorType::Rgb,
+        )
+        .map_err(|error| RenderError::Raster {
+            id: node.id.clone(),
+            message: error.to_string(),
+        })?;
+    Ok(bytes)
+}
+
+fn is_mask(node: &Node) -> bool {
+    match &node.node {
+        NodeType::Vector(vector) => vector.is_mask,
+        NodeType::Rectangle(vector) => vector.is_mask,
+        NodeType::Document(_) | NodeType::Canvas(_) => false,
+    }
+}
+
+/// The outline of a layer, in its own coordinate space.
+enum Shape<'a> {
+    Paths(Vec<(&'a str, Option<&'a str>)>),
+    Rectangle {
+        width: f64,
+        height: f64,
+        /// Top left, top right, bottom right and bottom left corner radii
+        radii: [f64; 4],
+    },
+}
+
+impl Shape<'_> {
+    /// SVG elements drawing the shape with the given presentation attributes.
+    fn elements(&self, attributes: &str) -> String {
+        match self {
+            Shape::Paths(paths) => paths
+                .iter()
+                .map(|(path, winding_rule)| {
+                    let rule = match winding_rule.map(str::to_ascii_lowercase).as_deref() {
+                        Some("evenodd") => "evenodd",
+                        _ => "nonzero",
+                    };
+                    format!(
+                        r#"<path d="{}" fill-rule="{rule}" {attributes}/>"#,
+                        escape(path)
+                    )
+                })
+                .collect(),
+            Shape::Rectangle {
+                width,
+                height,
+                radii,
+            } if radii.iter().all(|radius| *radius == radii[0]) => format!(
+                r#"<rect width="{width}" height="{height}" rx="{}" {attributes}/>"#,
+                radii[0]
+            ),
+            Shape::Rectangle {
+                width,
+                height,
+                radii: [top_left, top_right, bottom_right, bottom_left],
+            } => format!(
+                r#"<path d="M{top_left} 0H{}A{top_right} {top_right} 0 0 1 {width} {top_right}V{}A{bottom_right} {bottom_right} 0 0 1 {} {height}H{bottom_left}A{bottom_left} {bottom_left} 0 0 1 0 {}V{top_left}A{top_left} {top_left} 0 0 1 {top_left} 0Z" {attributes}/>"#,
+                width - top_right,
+                height - bottom_right,
+                width - bottom_right,
+                height - bottom_left,
+            ),
+        }
+    }
+}
+
+/// An SVG document being built.
+#[derive(Default)]
+struct Svg {
+    defs: String,
+    body: String,
+    /// Number of definitions, used to give them unique ids.
+    ids: usize,
+}
+
+impl Svg {
+    fn id(&mut self, prefix: &str) -> String {
+        self.ids += 1;
+        format!("{prefix}{}", self.ids)
+    }
+
+    /// Renders sibling layers from back to front. A mask clips the siblings in front of it.
+    fn siblings(&mut self, siblings: &[Node]) {
+        let mut masked = false;
+        for node in siblings.iter().filter(|node| node.visible) {
+            if is_mask(node) {
+                let id = self.id("mask");
+                let content = std::mem::take(&mut self.body);
+                self.node(node);
+                let mask = std::mem::replace(&mut self.body, content);
+                write!(
+                    self.defs,
+                    r#"<mask id="{id}" maskUnits="userSpaceOnUse" style="mask-type:alpha">{mask}</mask>"#
+                )
+                .unwrap();
+                if masked {
+                    self.body.push_str("</g>");
+                }
+                write!(self.body, r#"<g mask="url(#{id})">"#).unwrap();
+                masked = true;
+            } else {
+                self.node(node);
+            }
+        }
+        if masked {
+            self.body.push_str("</g>");
+        }
+    }
+
+    fn node(&mut self, node: &Node) {
+        match &node.node {
+            NodeType::Document(_) | NodeType::Canvas(_) => self.siblings(node.children()),
+            NodeType::Vector(vector) => {
+                let shape = Shape::Paths(paths(&vector.fill_geometry));
+                self.layer(vector, &shape);
+            }
+            NodeType::Rectangle(vector) => {
+                let shape = match vector.fill_geometry.is_empty() {
+                    true => rectangle(vector),
+                    false => Shape::Paths(paths(&vector.fill_geometry)),
+                };
+                self.layer(vector, &shape);
+            }
+        }
+    }
+
+    fn layer<Data>(&mut self, vector: &VectorNode<Data>, shape: &Shape) {
+        let mut group = format!(r#"<g transform="{}""#, transform(vector));
+        if vector.opacity < 1.0 {
+            write!(group, r#" opacity="{}""#, vector.opacity).unwrap();
+        }
+        if let Some(mode) = blend_mode(&vector.blend_mode) {
+            write!(group, r#" style="mix-blend-mode:{mode}""#).unwrap();
+        }
+        if let Some(filter) = self.filter(&vector.effects) {
+            write!(group, r#" filter="url(#{filter})""#).unwrap();
+        }
+        group.push('>');
+        self.body.push_str(&group);
+
+        let (width, height) = size(vector);
+        for paint in vector.fills.iter().filter(|paint| paint.visible) {
+            if let Some((fill, opacity)) = self.paint(paint, width, height) {
+                let attributes = format!(r#"fill="{fill}" fill-opacity="{opacity}""#);
+                self.body.push_str(&shape.elements(&attributes));
+            }
+        }
+        let weight = f64::from(vector.stroke_weight);
+        if weight > 0.0 {
+            let outline = Shape::Paths(paths(&vector.stroke_geometry));
+            for paint in vector.strokes.iter().filter(|paint| paint.visible) {
+                let Some((stroke, opacity)) = self.paint(paint, width, height) else {
+                    continue;
+                };
+                // Stroke geometry already is the outline of the stroke, as aligned in the tool.
+                if !vector.stroke_geometry.is_empty() {
+                    let attributes = format!(r#"fill="{stroke}" fill-opacity="{opacity}""#);
+                    self.body.push_str(&outline.elements(&attributes));
+                    continue;
+                }
+                let mut attributes = format!(
+                    r#"fill="none" stroke="{stroke}" stroke-opacity="{opacity}" stroke-width="{weight}" stroke-linecap="{}" stroke-linejoin="{}""#,
+                    line_cap(&vector.stroke_cap),
+                    line_join(&vector.stroke_join)
+                );
+                if !vector.stroke_dashes.is_empty() {
+                    let dashes: Vec<String> = vector
+                        .stroke_dashes
+                        .iter()
+                        .map(|dash| f64::from(*dash).to_string())
+                        .collect();
+                    write!(attributes, r#" stroke-dasharray="{}""#, dashes.join(" ")).unwrap();
+                }
+                self.body.push_str(&shape.elements(&attributes));
+            }
+        }
+        self.body.push_str("</g>");
+    }
+
+    /// The paint server and opacity of a paint: a color or a reference to a gradient definition.
+    /// Returns `None` for paints which cannot be rendered.
+    fn paint(&mut self, paint: &Paint, width: f64, height: f64) -> Option<(String, f64)> {
+        let opacity = f64::from(paint.opacity);
+        let gradient = match paint.paint_type {
+            PaintType::Solid => {
+                return Some((rgb(&paint.color), f64::from(paint.color.a) * opacity));
+            }
+            PaintType::GradientLinear => "linearGradient",
+            // SVG has no angular or diamond gradients, they are approximated with radial ones.
+            PaintType::GradientRadial | PaintType::GradientAngular | PaintType::GradientDiamond => {
+                "radialGradient"
+            }
+            PaintType::Image | PaintType::Emoji | PaintType::Video => return None,
+        };
+        // Handles are in normalized object space, where (1, 1) is the bottom right corner.
+        let handle = |index: usize| {
+            paint
+                .gradient_handle_positions
+                .get(index)
+                .map(|handle| (f64::from(handle.x) * width, f64::from(handle.y) * height))
+        };
+        let (start, end) = (handle(0)?, handle(1)?);
+        let geometry = match gradient {
+            "linearGradient" => format!(
+                r#"x1="{}" y1="{}" x2="{}" y2="{}""#,
+                start.0, start.1, end.0, end.1
+            ),
+            _ => format!(
+                r#"cx="{}" cy="{}" r="{}""#,
+                start.0,
+                start.1,
+                (end.0 - start.0).hypot(end.1 - start.1)
+            ),
+        };
+        let id = self.id("paint");
+        write!(
+            self.defs,
+            r#"<{gradient} id="{id}" gradientUnits="userSpaceOnUse" {geometry}>"#
+        )
+        .unwrap();
+        for stop in &paint.gradient_stops {
+            write!(
+                self.defs,
+                r#"<stop offset="{}" stop-color="{}" stop-opacity="{}"/>"#,
+                f64::from(stop.position),
+                rgb(&stop.color),
+                f64::from(stop.color.a) * opacity
+            )
+            .unwrap();
+        }
+        write!(self.defs, "</{gradient}>").unwrap();
+        Some((format!("url(#{id})"), 1.0))
+    }
+
+    /// A filter definition rendering drop shadows and layer blurs, `None` if there are none.
+    fn filter(&mut self, effects: &[Effect]) -> Option<String> {
+        let effects: Vec<&Effect> = effects
+            .iter()
+            .filter(|effect| effect.visible)
+            .filter(|effect| {
+                matches!(
+                    effect.effect_type,
+                    EffectType::DropShadow | EffectType::LayerBlur
+                )
+            })
+            .collect();
+        if effects.is_empty() {
+            return None;
+        }
+        let id = self.id("effects");
+        let mut filter = format!(
+            r#"<filter id="{id}" x="-50%" y="-50%" width="200%" height="200%" color-interpolation-filters="sRGB">"#
+        );
+        let mut layers = Vec::new();
+        let mut blur = None;
+        for (index, effect) in effects.iter().enumerate() {
+            // Figma radii are twice the standard deviation of the gaussian blur.
+            let deviation = f64::from(effect.radius) / 2.0;
+            match effect.effect_type {
+                EffectType::DropShadow => {
+                    let spread = f64::from(effect.spread);
+                    let operator = if spread < 0.0 { "erode" } else { "dilate" };
+                    write!(
+                        filter,
+                        r#"<feMorphology in="SourceAlpha" operator="{operator}" radius="{}"/><feGaussianBlur stdDeviation="{deviation}"/><feOffset dx="{}" dy="{}" result="offset{index}"/><feFlood flood-color="{}" flood-opacity="{}"/><feComposite operator="in" in2="offset{index}" result="shadow{index}"/>"#,
+                        spread.abs(),
+                        f64::from(effect.offset.x),
+                        f64::from(effect.offset.y),
+                        rgb(&effect.color),
+                        effect.color.a,
+                    )
+                    .unwrap();
+                    layers.push(format!("shadow{index}"));
+                }
+                _ => blur = Some(deviation),
+            }
+        }
+        filter.push_str("<feMerge>");
+        for layer in &layers {
+            write!(filter, r#"<feMergeNode in="{layer}"/>"#).unwrap();
+        }
+        filter.push_str(r#"<feMergeNode in="SourceGraphic"/></feMerge>"#);
+        if let Some(deviation) = blur {
+            write!(filter, r#"<feGaussianBlur stdDeviation="{deviation}"/>"#).unwrap();
+        }
+        filter.push_str("</filter>");
+        self.defs.push_str(&filter);
+        Some(id)
+    }
+}
+
+fn paths(paths: &[fig_types::properties::Path]) -> Vec<(&str, Option<&str>)> {
+    paths
+        .iter()
+        .map(|path| (path.path.as_str(), path.winding_rule.as_deref()))
+        .collect()
+}
+
+fn rectangle(vector: &VectorNode<fig_types::node::vector::RectangleData>) -> Shape<'static> {
+    let (width, height) = size(vector);
+    let data = &vector.additional_data;
+    let radii = match data
+        .rectangle_corner_radii
+        .iter()
+        .any(|radius| *radius > 0.0)
+    {
+        true => data.rectangle_corner_radii.map(f64::from),
+        false => [f64::from(data.corner_radius); 4],
+    };
+    let limit = width.min(height) / 2.0;
+    Shape::Rectangle {
+        width,
+        height,
+        radii: radii.map(|radius| radius.clamp(0.0, limit)),
+    }
+}
+
+/// Width and height of a layer before its transform.
+fn size<Data>(vector: &VectorNode<Data>) -> (f64, f64) {
+    let size = (f64::from(vector.size.width), f64::from(vector.size.height));
+    match size {
+        (width, height) if width > 0.0 && height > 0.0 => (width, height),
+        _ => {
+            let Rectangle { width, height, .. } = &vector.absolute_bounding_box;
+            (f64::from(*width), f64::from(*height))
+        }
+    }
+}
+
+/// The SVG transform placing a layer, from its relative transform or, when it is missing, from
+/// its bounding box.
+fn transform<Data>(vector: &VectorNode<Data>) -> String {
+    match vector.relative_transform.matrix.as_slice() {
+        [first, second] if first.len() == 3 && second.len() == 3 => format!(
+            "matrix({} {} {} {} {} {})",
+            f64::from(first[0]),
+            f64::from(second[0]),
+            f64::from(first[1]),
+            f64::from(second[1]),
+            f64::from(first[2]),
+            f64::from(second[2]),
+        ),
+        _ => {
+            let bounds = &vector.absolute_bounding_box;
+            format!("translate({} {})", f64::from(bounds.x), f64::from(bounds.y))
+        }
+    }
+}
+
+fn rgb(color: &Color) -> String {
+    let channel = |value: f32| (value.clamp(0.0, 1.0) * 255.0).round() as u8;
+    format!(
+        "#{:02x}{:02x}{:02x}",
+        channel(color.r),
+        channel(color.g),
+        channel(color.b)
+    )
+}
+
+/// The CSS blend mode equivalent to a blend mode, `None` for normal blending.
+pub fn blend_mode(mode: &BlendMode) -> Option<&'static str> {
+    Some(match mode {
+        BlendMode::PassThrough | BlendMode::Normal => return None,
+        BlendMode::Darken => "darken",
+        BlendMode::Multiply => "multiply",
+        // CSS has no linear burn or dodge, color burn and dodge are the closest.
+        BlendMode::LinearBurn | BlendMode::ColorBurn => "color-burn",
+        BlendMode::Lighten => "lighten",
+        BlendMode::Screen => "screen",
+        BlendMode::LinearDodge | BlendMode::ColorDodge => "color-dodge",
+        BlendMode::Overlay => "overlay",
+        BlendMode::SoftLight => "soft-light",
+        BlendMode::HardLight => "hard-light",
+        BlendMode::Difference => "difference",
+        BlendMode::Exclusion => "exclusion",
+        BlendMode::Hue => "hue",
+        BlendMode::Saturation => "saturation",
+        BlendMode::Color => "color",
+        BlendMode::Luminosity => "luminosity",
+    })
+}
+
+fn line_cap(cap: &StrokeCap) -> &'static str {
+    match cap {
+        StrokeCap::Round => "round",
+        StrokeCap::Square => "square",
+        // Arrows and other decorations are drawn on top of a butt cap.
+        _ => "butt",
+    }
+}
+
+fn line_join(join: &StrokeJoin) -> &'static str {
+    match join {
+        StrokeJoin::Miter => "miter",
+        StrokeJoin::Bevel => "bevel",
+        StrokeJoin::Round => "round",
+    }
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('"', "&quot;")
+        .replace('<', "&lt;")
+}
+
+#[cfg(test)]
+mod tests {
+    use resvg::tiny_skia::Pixmap;
+    use serde_json::json;
+
+    use super::*;
+    use crate::fixtures;
+
+    /// A red 100 by 40 rectangle at 10, 20.
+    fn layer() -> Node {
+        let mut properties = fixtures::bounds(10.0, 20.0, 100.0, 40.0);
+        properties["fills"] = json!([fixtures::solid(1.0, 0.0, 0.0)]);
+        fixtures::layer("rectangle", "1:1", "Badge", properties)
+    }
+
+    #[test]
+    fn bitmaps_are_scaled() {
+        let png = render(&layer(), ImageFormat::Png, 2.0).unwrap();
+        let pixmap = Pixmap::decode_png(&png).unwrap();
+        assert_eq!((pixmap.width(), pixmap.height()), (200, 80));
+        let pixel = pixmap.pixel(100, 40).unwrap();
+        assert_eq!(
+            (pixel.red(), pixel.green(), pixel.blue(), pixel.alpha()),
+            (255, 0, 0, 255)
+        );
+
+        let jpg = render(&layer(), ImageFormat::Jpg, 0.5).unwrap();
+        assert!(jpg.starts_with(&[0xff, 0xd8]));
+        let svg = String::from_utf8(render(&layer(), ImageFormat::Svg, 1.5).unwrap()).unwrap();
+        assert!(svg.contains(r#"width="150" height="60""#), "{svg}");
+    }
+
+    #[test]
+    fn documents_hidden_and_huge_nodes_are_not_rendered() {
+        let document = crate::document::Document::new("Render").document;
+        assert!(matches!(
+            render(&document, ImageFormat::Png, 1.0),
+            Err(RenderError::NotRenderable(id)) if id == "0:0"
+        ));
+        let mut hidden = layer();
+        hidden.visible = false;
+        assert!(matches!(
+            render(&hidden, ImageFormat::Png, 1.0),
+            Err(RenderError::Empty(_))
+        ));
+        assert!(matches!(
+            render(&layer(), ImageFormat::Png, 1000.0),
+            Err(RenderError::TooLarge {
+                width: 100000,
+                height: 40000,
+                ..
+            })
+        ));
+    }
+}
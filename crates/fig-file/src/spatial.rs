@@ -0,0 +1,635 @@
+//! Spatial index over the layers of a canvas.
+//!
+//! The index keeps every layer of a canvas in an R-tree keyed by its absolute bounds, so hit
+//! tests, marquee selections and viewport culling only look at the handful of layers that are
+//! actually near the queried area instead of walking the whole tree.
+
+use std::collections::HashMap;
+
+use fig_types::node::vector::VectorNode;
+use fig_types::node::{Node, NodeType};
+use fig_types::properties::Rectangle;
+use rstar::primitives::{GeomWithData, Rectangle as Envelope};
+use rstar::{RTree, AABB};
+use serde::{Deserialize, Serialize};
+
+/// A point in absolute canvas coordinates.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Point {
+    pub x: f64,
+    pub y: f64,
+}
+
+/// An axis aligned rectangle in absolute canvas coordinates.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Bounds {
+    pub x: f64,
+    pub y: f64,
+    pub width: f64,
+    pub height: f64,
+}
+
+impl Bounds {
+    fn from_corners(min: [f64; 2], max: [f64; 2]) -> Self {
+        Self {
+            x: min[0],
+            y: min[1],
+            width: max[0] - min[0],
+            height: max[1] - min[1],
+        }
+    }
+
+    fn min(&self) -> [f64; 2] {
+        [
+            self.x.min(self.x + self.width),
+            self.y.min(self.y + self.height),
+        ]
+    }
+
+    fn max(&self) -> [f64; 2] {
+        [
+            self.x.max(self.x + self.width),
+            self.y.max(self.y + self.height),
+        ]
+    }
+
+    pub(crate) fn union(&self, other: &Bounds) -> Bounds {
+        let (a_min, a_max, b_min, b_max) = (self.min(), self.max(), other.min(), other.max());
+        Bounds::from_corners(
+            [a_min[0].min(b_min[0]), a_min[1].min(b_min[1])],
+            [a_max[0].max(b_max[0]), a_max[1].max(b_max[1])],
+        )
+    }
+
+    fn intersects(&self, other: &Bounds) -> bool {
+        let (a_min, a_max, b_min, b_max) = (self.min(), self.max(), other.min(), other.max());
+        a_min[0] <= b_max[0] && b_min[0] <= a_max[0] && a_min[1] <= b_max[1] && b_min[1] <= a_max[1]
+    }
+
+    fn envelope(&self) -> AABB<[f64; 2]> {
+        AABB::from_corners(self.min(), self.max())
+    }
+}
+
+impl From<&Rectangle> for Bounds {
+    fn from(rectangle: &Rectangle) -> Self {
+        Self {
+            x: f64::from(rectangle.x),
+            y: f64::from(rectangle.y),
+            width: f64::from(rectangle.width),
+            height: f64::from(rectangle.height),
+        }
+    }
+}
+
+/// The exact area covered by a layer: a `width` x `height` rectangle in the layer's own space,
+/// placed on the canvas by an affine transform.
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Area {
+    /// Maps local coordinates to absolute coordinates.
+    transform: [[f64; 3]; 2],
+    /// Maps absolute coordinates back to local coordinates.
+    inverse: [[f64; 3]; 2],
+    width: f64,
+    height: f64,
+}
+
+impl Area {
+    fn new(transform: [[f64; 3]; 2], width: f64, height: f64) -> Option<Self> {
+        let [[a, c, e], [b, d, f]] = transform;
+        let determinant = a * d - b * c;
+        if determinant == 0.0 || !determinant.is_finite() {
+            return None;
+        }
+        let inverse = [
+            [
+                d / determinant,
+                -c / determinant,
+                (c * f - d * e) / determinant,
+            ],
+            [
+                -b / determinant,
+                a / determinant,
+                (b * e - a * f) / determinant,
+            ],
+        ];
+        Some(Self {
+            transform,
+            inverse,
+            width,
+            height,
+        })
+    }
+
+    /// Area of a layer that only has a bounding box and a rotation in degrees around its center.
+    fn rotated(bounds: &Bounds, width: f64, height: f64, degrees: f64) -> Self {
+        let (sin, cos) = degrees.to_radians().sin_cos();
+        let center = [
+            bounds.x + bounds.width / 2.0,
+            bounds.y + bounds.height / 2.0,
+        ];
+        let origin = [
+            center[0] - (cos * width - sin * height) / 2.0,
+            center[1] - (sin * width + cos * height) / 2.0,
+        ];
+        Self::new(
+            [[cos, -sin, origin[0]], [sin, cos, origin[1]]],
+            width,
+            height,
+        )
+        .expect("rotation matrices are always invertible")
+    }
+
+    fn apply(matrix: &[[f64; 3]; 2], point: [f64; 2]) -> [f64; 2] {
+        [
+            matrix[0][0] * point[0] + matrix[0][1] * point[1] + matrix[0][2],
+            matrix[1][0] * point[0] + matrix[1][1] * point[1] + matrix[1][2],
+        ]
+    }
+
+    fn corners(&self) -> [[f64; 2]; 4] {
+        [
+            [0.0, 0.0],
+            [self.width, 0.0],
+            [self.width, self.height],
+            [0.0, self.height],
+        ]
+        .map(|corner| Self::apply(&self.transform, corner))
+    }
+
+    fn contains(&self, point: Point) -> bool {
+        let [x, y] = Self::apply(&self.inverse, [point.x, point.y]);
+        (0.0..=self.width).contains(&x) && (0.0..=self.height).contains(&y)
+    }
+
+    /// Separating axis test between this oriented rectangle and an axis aligned one.
+    fn intersects(&self, bounds: &Bounds) -> bool {
+        let corners = self.corners();
+        let rectangle = [
+            bounds.min(),
+            [bounds.max()[0], bounds.min()[1]],
+            bounds.max(),
+            [bounds.min()[0], bounds.max()[1]],
+        ];
+        let axes = [
+            [1.0, 0.0],
+            [0.0, 1.0],
+            [corners[1][0] - corners[0][0], corners[1][1] - corners[0][1]],
+            [corners[3][0] - corners[0][0], corners[3][1] - corners[0][1]],
+        ];
+        axes.iter().all(|axis| {
+            let project = |points: &[[f64; 2]; 4]| {
+                points
+                    .iter()
+                    .fold((f64::INFINITY, f64::NEG_INFINITY), |(min, max), p| {
+                        let value = p[0] * axis[0] + p[1] * axis[1];
+                        (min.min(value), max.max(value))
+                    })
+            };
+            let (a_min, a_max) = project(&corners);
+            let (b_min, b_max) = project(&rectangle);
+            a_min <= b_max && b_min <= a_max
+        })
+    }
+}
+
+/// Everything the index needs to know about a single layer.
+#[derive(Debug, Clone)]
+struct Layer {
+    /// Paint order, layers with a higher order are drawn on top.
+    order: u64,
+    /// Bounding box of the layer, used for marquee selection.
+    bounds: Bounds,
+    /// Bounds including shadows, strokes, etc, used for viewport culling.
+    render_bounds: Bounds,
+    /// Exact area used for hit testing and marquee selection.
+    area: Area,
+    visible: bool,
+    locked: bool,
+    is_mask: bool,
+    /// The mask clipping this layer, if any.
+    mask: Option<String>,
+}
+
+impl Layer {
+    fn envelope(&self) -> Bounds {
+        self.bounds.union(&self.render_bounds)
+    }
+
+    fn selectable(&self) -> bool {
+        self.visible && !self.locked
+    }
+}
+
+type Entry = GeomWithData<Envelope<[f64; 2]>, String>;
+
+/// R-tree backed index of the layers of a canvas, answering hit tests, marquee selections and
+/// viewport queries.
+#[derive(Debug, Default)]
+pub struct SpatialIndex {
+    tree: RTree<Entry>,
+    layers: HashMap<String, Layer>,
+    /// Order given to the next layer inserted on top of the canvas.
+    next_order: u64,
+    /// Id of the top most layer.
+    top: Option<String>,
+}
+
+impl SpatialIndex {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Builds an index over all the layers of a canvas node.
+    pub fn from_canvas(canvas: &Node) -> Self {
+        let mut index = Self::new();
+        index.rebuild(canvas);
+        index
+    }
+
+    /// Replaces the content of the index with the layers of a canvas node. Needed after structural
+    /// changes (reordering, reparenting or toggling masks) since those change paint order and mask
+    /// scopes.
+    pub fn rebuild(&mut self, canvas: &Node) {
+        self.layers.clear();
+        self.next_order = 0;
+        self.top = None;
+        self.collect(canvas.children(), None);
+        self.tree = RTree::bulk_load(
+            self.layers
+                .iter()
+                .map(|(id, layer)| Self::entry(id, layer))
+                .collect(),
+        );
+    }
+
+    fn collect(&mut self, siblings: &[Node], mut mask: Option<String>) {
+        for node in siblings {
+            if let Some(mut layer) = Self::layer(node) {
+                layer.order = self.next_order;
+                layer.mask = mask.clone();
+                self.next_order += 1;
+                self.top = Some(node.id.clone());
+                // A mask clips every sibling in front of it.
+                if layer.is_mask {
+                    mask = Some(node.id.clone());
+                }
+                self.layers.insert(node.id.clone(), layer);
+            }
+            self.collect(node.children(), mask.clone());
+        }
+    }
+
+    /// Inserts a new layer or updates the geometry and flags of an existing one. Existing layers
+    /// keep their paint order and mask, new layers are placed on top of the canvas, clipped by
+    /// the same mask as the previous top most layer.
+    pub fn upsert(&mut self, node: &Node) {
+        let Some(mut layer) = Self::layer(node) else {
+            self.remove(&node.id);
+            return;
+        };
+        match self.layers.get(&node.id) {
+            Some(previous) => {
+                layer.order = previous.order;
+                layer.mask = previous.mask.clone();
+                self.tree.remove(&Self::entry(&node.id, previous));
+            }
+            None => {
+                layer.order = self.next_order;
+                layer.mask = self.top.as_ref().and_then(|top| {
+                    let top_layer = &self.layers[top];
+                    if top_layer.is_mask {
+                        Some(top.clone())
+                    } else {
+                        top_layer.mask.clone()
+                    }
+                });
+                self.next_order += 1;
+                self.top = Some(node.id.clone());
+            }
+        }
+        self.tree.insert(Self::entry(&node.id, &layer));
+        self.layers.insert(node.id.clone(), layer);
+    }
+
+    /// Removes a layer from the index. Layers it was masking are clipped by the masks clipping it
+    /// instead.
+    pub fn remove(&mut self, id: &str) {
+        if let Some(layer) = self.layers.remove(id) {
+            self.tree.remove(&Self::entry(id, &layer));
+            if layer.is_mask {
+                for other in self.layers.values_mut() {
+                    if other.mask.as_deref() == Some(id) {
+                        other.mask = layer.mask.clone();
+                    }
+                }
+            }
+            if self.top.as_deref() == Some(id) {
+                self.top = self
+                    .layers
+                    .iter()
+                    .max_by_key(|(_, layer)| layer.order)
+                    .map(|(id, _)| id.clone());
+            }
+        }
+    }
+
+    /// Number of layers in the index.
+    pub fn len(&self) -> usize {
+        self.layers.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.layers.is_empty()
+    }
+
+    /// The top most visible and unlocked layer under a point, taking rotation and masks into
+    /// account.
+    pub fn hit_test(&self, point: Point) -> Option<&str> {
+        self.tree
+            .locate_all_at_point(&[point.x, point.y])
+            .filter_map(|entry| {
+                let layer = &self.layers[&entry.data];
+                let hit =
+                    layer.selectable() && layer.area.contains(point) && self.unmasked(layer, point);
+                hit.then_some((layer.order, entry.data.as_str()))
+            })
+            .max_by_key(|(order, _)| *order)
+            .map(|(_, id)| id)
+    }
+
+    /// Whether a point is inside every visible mask clipping a layer.
+    fn unmasked(&self, layer: &Layer, point: Point) -> bool {
+        let mut mask = layer.mask.as_ref();
+        while let Some(layer) = mask.and_then(|id| self.layers.get(id)) {
+            if layer.visible && !layer.area.contains(point) {
+                return false;
+            }
+            mask = layer.mask.as_ref();
+        }
+        true
+    }
+
+    /// Visible and unlocked layers touched by a marquee selection, from back to front.
+    pub fn marquee(&self, area: Bounds) -> Vec<&str> {
+        self.query(area, |layer| {
+            layer.selectable() && layer.area.intersects(&area)
+        })
+    }
+
+    /// Layers that have to be drawn to render a viewport, from back to front.
+    pub fn visible_in(&self, viewport: Bounds) -> Vec<&str> {
+        self.query(viewport, |layer| {
+            layer.visible && layer.render_bounds.intersects(&viewport)
+        })
+    }
+
+    fn query(&self, area: Bounds, filter: impl Fn(&Layer) -> bool) -> Vec<&str> {
+        let mut layers: Vec<_> = self
+            .tree
+            .locate_in_envelope_intersecting(&area.envelope())
+            .filter_map(|entry| {
+                let layer = &self.layers[&entry.data];
+                filter(layer).then_some((layer.order, entry.data.as_str()))
+            })
+            .collect();
+        layers.sort_unstable_by_key(|(order, _)| *order);
+        layers.into_iter().map(|(_, id)| id).collect()
+    }
+
+    fn entry(id: &str, layer: &Layer) -> Entry {
+        let bounds = layer.envelope();
+        GeomWithData::new(
+            Envelope::from_corners(bounds.min(), bounds.max()),
+            id.to_string(),
+        )
+    }
+
+    fn layer(node: &Node) -> Option<Layer> {
+        match &node.node {
+            NodeType::Vector(vector) => Some(Self::vector_layer(node, vector)),
+            NodeType::Rectangle(rectangle) => Some(Self::vector_layer(node, rectangle)),
+            NodeType::Document(_) | NodeType::Canvas(_) => None,
+        }
+    }
+
+    fn vector_layer<Data>(node: &Node, vector: &VectorNode<Data>) -> Layer {
+        let bounds = Bounds::from(&vector.absolute_bounding_box);
+        let render_bounds = vector
+            .absolute_render_bounds
+            .as_ref()
+            .map_or(bounds, Bounds::from);
+        // The size is only known when geometry was requested, fall back to the bounding box.
+        let (width, height) = match (vector.size.width, vector.size.height) {
+            (0.0, 0.0) => (bounds.width, bounds.height),
+            (width, height) => (f64::from(width), f64::from(height)),
+        };
+        let area = Self::matrix(&vector.relative_transform.matrix)
+            .and_then(|matrix| Area::new(matrix, width, height))
+            .unwrap_or_else(|| Area::rotated(&bounds, width, height, f64::from(node.rotation)));
+        Layer {
+            order: 0,
+            bounds,
+            render_bounds,
+            area,
+            visible: node.visible,
+            locked: vector.locked,
+            is_mask: vector.is_mask,
+            mask: None,
+        }
+    }
+
+    fn matrix(rows: &[Vec<f32>]) -> Option<[[f64; 3]; 2]> {
+        match rows {
+            [first, second] if first.len() == 3 && second.len() == 3 => Some([
+                [first[0], first[1], first[2]].map(f64::from),
+                [second[0], second[1], second[2]].map(f64::from),
+            ]),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::{json, Value};
+
+    use super::*;
+    use crate::fixtures;
+
+    fn layer(id: &str, x: f32, y: f32, width: f32, height: f32, properties: Value) -> Node {
+        let mut data = fixtures::bounds(x, y, width, height);
+        for (key, value) in properties.as_object().unwrap() {
+            data[key] = value.clone();
+        }
+        fixtures::layer("vector", id, id, data)
+    }
+
+    fn square(id: &str, x: f32, y: f32, size: f32) -> Node {
+        layer(id, x, y, size, size, json!({}))
+    }
+
+    fn mask(id: &str, x: f32, y: f32, size: f32) -> Node {
+        layer(id, x, y, size, size, json!({ "isMask": true }))
+    }
+
+    /// A 100px square rotated by 45 degrees around its center, at (150, 150).
+    fn diamond(id: &str) -> Node {
+        let (sin, cos) = std::f32::consts::FRAC_PI_4.sin_cos();
+        let half = 50.0 * std::f32::consts::SQRT_2;
+        let mut node = layer(
+            id,
+            150.0 - half,
+            150.0 - half,
+            2.0 * half,
+            2.0 * half,
+            json!({ "relativeTransform": {
+                "matrix": [[cos, -sin, 150.0], [sin, cos, 150.0 - half]],
+            }}),
+        );
+        let NodeType::Vector(vector) = &mut node.node else {
+            unreachable!();
+        };
+        vector.size.width = 100.0;
+        vector.size.height = 100.0;
+        node
+    }
+
+    fn at(x: f64, y: f64) -> Point {
+        Point { x, y }
+    }
+
+    fn area(x: f64, y: f64, width: f64, height: f64) -> Bounds {
+        Bounds {
+            x,
+            y,
+            width,
+            height,
+        }
+    }
+
+    #[test]
+    fn hit_tests_follow_the_rotation_of_layers() {
+        let index = SpatialIndex::from_canvas(&fixtures::canvas(vec![diamond("1:1")]));
+        assert_eq!(index.hit_test(at(150.0, 150.0)), Some("1:1"));
+        assert_eq!(index.hit_test(at(150.0, 85.0)), Some("1:1"));
+        // Inside the bounding box, outside the rotated square.
+        assert_eq!(index.hit_test(at(85.0, 85.0)), None);
+        assert_eq!(
+            index.marquee(area(80.0, 80.0, 20.0, 20.0)),
+            Vec::<&str>::new()
+        );
+        assert_eq!(index.marquee(area(80.0, 140.0, 20.0, 20.0)), ["1:1"]);
+    }
+
+    #[test]
+    fn hidden_and_locked_layers_are_not_selectable() {
+        let mut hidden = square("1:2", 0.0, 0.0, 100.0);
+        hidden.visible = false;
+        let locked = layer("1:3", 0.0, 0.0, 100.0, 100.0, json!({ "locked": true }));
+        let index = SpatialIndex::from_canvas(&fixtures::canvas(vec![
+            square("1:1", 0.0, 0.0, 100.0),
+            hidden,
+            locked,
+        ]));
+        assert_eq!(index.hit_test(at(50.0, 50.0)), Some("1:1"));
+        assert_eq!(index.marquee(area(0.0, 0.0, 10.0, 10.0)), ["1:1"]);
+        // Locked layers are still drawn.
+        assert_eq!(index.visible_in(area(0.0, 0.0, 10.0, 10.0)), ["1:1", "1:3"]);
+    }
+
+    #[test]
+    fn masks_clip_the_layers_in_front_of_them_through_chains() {
+        let index = SpatialIndex::from_canvas(&fixtures::canvas(vec![
+            square("1:1", 0.0, 0.0, 300.0),
+            mask("1:2", 0.0, 0.0, 100.0),
+            mask("1:3", 50.0, 0.0, 100.0),
+            square("1:4", 0.0, 0.0, 300.0),
+        ]));
+        // Inside both masks.
+        assert_eq!(index.hit_test(at(75.0, 50.0)), Some("1:4"));
+        // Outside the first mask, which clips the second one.
+        assert_eq!(index.hit_test(at(125.0, 50.0)), Some("1:1"));
+        // Outside the second mask.
+        assert_eq!(index.hit_test(at(25.0, 50.0)), Some("1:2"));
+        assert_eq!(index.hit_test(at(250.0, 250.0)), Some("1:1"));
+    }
+
+    #[test]
+    fn marquee_and_viewport_queries_list_layers_from_back_to_front() {
+        let shadowed = layer(
+            "1:3",
+            200.0,
+            200.0,
+            100.0,
+            100.0,
+            json!({ "absoluteRenderBounds": { "x": 180.0, "y": 180.0, "width": 140.0, "height": 140.0 } }),
+        );
+        let index = SpatialIndex::from_canvas(&fixtures::canvas(vec![
+            square("1:1", 0.0, 0.0, 100.0),
+            square("1:2", 50.0, 50.0, 100.0),
+            shadowed,
+        ]));
+        assert_eq!(index.len(), 3);
+        assert_eq!(index.marquee(area(60.0, 60.0, 10.0, 10.0)), ["1:1", "1:2"]);
+        assert_eq!(
+            index.marquee(area(140.0, 140.0, -40.0, -40.0)),
+            ["1:1", "1:2"]
+        );
+        // The shadow is drawn, but does not select the layer.
+        assert_eq!(index.visible_in(area(185.0, 185.0, 10.0, 10.0)), ["1:3"]);
+        assert_eq!(
+            index.marquee(area(185.0, 185.0, 10.0, 10.0)),
+            Vec::<&str>::new()
+        );
+        assert_eq!(
+            index.visible_in(area(0.0, 0.0, 400.0, 400.0)),
+            ["1:1", "1:2", "1:3"]
+        );
+    }
+
+    #[test]
+    fn upserted_layers_keep_their_order_and_new_layers_go_on_top() {
+        let mut index = SpatialIndex::from_canvas(&fixtures::canvas(vec![
+            square("1:1", 0.0, 0.0, 100.0),
+            square("1:2", 200.0, 0.0, 100.0),
+        ]));
+        // Moving the top layer over the other one keeps it in front.
+        index.upsert(&square("1:2", 50.0, 0.0, 100.0));
+        assert_eq!(index.hit_test(at(75.0, 50.0)), Some("1:2"));
+        // Moving the bottom layer does not bring it to the front.
+        index.upsert(&square("1:1", 60.0, 0.0, 100.0));
+        assert_eq!(index.hit_test(at(75.0, 50.0)), Some("1:2"));
+        index.upsert(&square("1:3", 0.0, 0.0, 100.0));
+        assert_eq!(index.hit_test(at(75.0, 50.0)), Some("1:3"));
+        index.remove("1:3");
+        assert_eq!(index.hit_test(at(75.0, 50.0)), Some("1:2"));
+        index.remove("1:2");
+        assert_eq!(index.marquee(area(0.0, 0.0, 300.0, 300.0)), ["1:1"]);
+        assert_eq!(index.hit_test(at(25.0, 50.0)), None);
+        assert_eq!(index.len(), 1);
+    }
+
+    #[test]
+    fn layers_added_above_a_mask_are_clipped_by_it() {
+        let mut index =
+            SpatialIndex::from_canvas(&fixtures::canvas(vec![mask("1:1", 0.0, 0.0, 100.0)]));
+        index.upsert(&square("1:2", 0.0, 0.0, 300.0));
+        assert_eq!(index.hit_test(at(50.0, 50.0)), Some("1:2"));
+        assert_eq!(index.hit_test(at(150.0, 150.0)), None);
+    }
+
+    #[test]
+    fn removing_a_mask_keeps_the_outer_masks() {
+        let mut index = SpatialIndex::from_canvas(&fixtures::canvas(vec![
+            mask("1:1", 0.0, 0.0, 100.0),
+            mask("1:2", 50.0, 0.0, 100.0),
+            square("1:3", 0.0, 0.0, 300.0),
+        ]));
+        assert_eq!(index.hit_test(at(25.0, 50.0)), Some("1:1"));
+        index.remove("1:2");
+        // Only clipped by the first mask now.
+        assert_eq!(index.hit_test(at(25.0, 50.0)), Some("1:3"));
+        assert_eq!(index.hit_test(at(125.0, 50.0)), None);
+    }
+}
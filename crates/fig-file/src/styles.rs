@@ -0,0 +1,388 @@
+//! Shared styles: defining them, applying them to nodes, detaching nodes from them and propagating
+//! their edits.
+//!
+//! Nodes refer to the styles applied to them by id in their `styles` map, and keep a copy of the
+//! properties of these styles so they can be rendered without looking styles up. Editing a style
+//! copies its new properties to every node using it, detaching a node only drops the reference.
+
+use std::collections::{BTreeMap, HashMap};
+
+use fig_types::node::vector::VectorNode;
+use fig_types::node::{Node, NodeType};
+use fig_types::properties::{Style, StyleType, StyleValue};
+
+use crate::document::Document;
+
+#[derive(Debug, thiserror::Error)]
+pub enum StyleError {
+    #[error("no node with id `{0}`")]
+    NodeNotFound(String),
+    #[error("no style with id `{0}`")]
+    StyleNotFound(String),
+    #[error("a style with id `{0}` already exists")]
+    DuplicateStyle(String),
+    #[error("style `{0}` has no value, remote styles are edited in the file defining them")]
+    NoValue(String),
+    #[error("style `{style}` is a {expected:?} style, not a {found:?} style")]
+    TypeMismatch {
+        style: String,
+        expected: StyleType,
+        found: StyleType,
+    },
+    #[error("{style_type:?} styles cannot be applied to node `{node}`")]
+    NotApplicable { style_type: StyleType, node: String },
+}
+
+impl Document {
+    /// Adds a style, whose value has to match its type.
+    pub fn add_style(&mut self, id: &str, style: Style) -> Result<(), StyleError> {
+        if self.styles.contains_key(id) {
+            return Err(StyleError::DuplicateStyle(id.to_string()));
+        }
+        if let Some(value) = &style.value {
+            check_type(id, &style, value)?;
+        }
+        self.styles.insert(id.to_string(), style);
+        Ok(())
+    }
+
+    /// Applies a style to a node, replacing the style of the same type applied to it if any: the
+    /// node takes the properties of the style and follows its edits.
+    pub fn apply_style(&mut self, node: &str, style: &str) -> Result<(), StyleError> {
+        let value = self.style_value(style)?.clone();
+        let target = self
+            .document
+            .find_mut(node)
+            .ok_or_else(|| StyleError::NodeNotFound(node.to_string()))?;
+        if !set_value(target, &value) {
+            return Err(StyleError::NotApplicable {
+                style_type: value.style_type(),
+                node: node.to_string(),
+            });
+        }
+        let styles = styles_mut(target).expect("styles apply to vector nodes only");
+        styles
+            .get_or_insert_with(Default::default)
+            .insert(value.style_type(), style.to_string());
+        Ok(())
+    }
+
+    /// Detaches a node from its style of `style_type`. The node keeps the properties of the style
+    /// but no longer follows its edits. Returns the id of the detached style, `None` when no style
+    /// of this type was applied to the node.
+    pub fn detach_style(
+        &mut self,
+        node: &str,
+        style_type: &StyleType,
+    ) -> Result<Option<String>, StyleError> {
+        let target = self
+            .document
+            .find_mut(node)
+            .ok_or_else(|| StyleError::NodeNotFound(node.to_string()))?;
+        Ok(styles_mut(target).and_then(|styles| detach(styles, style_type)))
+    }
+
+    /// Replaces the properties of a style and copies them to every node using it. Returns the ids
+    /// of the updated nodes.
+    pub fn update_style(
+        &mut self,
+        style: &str,
+        value: StyleValue,
+    ) -> Result<Vec<String>, StyleError> {
+        let definition = self
+            .styles
+            .get(style)
+            .ok_or_else(|| StyleError::StyleNotFound(style.to_string()))?;
+        check_type(style, definition, &value)?;
+        // Check every node before changing any, so errors leave the document as is.
+        let consumers: Vec<String> = self
+            .style_consumers(style)
+            .iter()
+            .map(|node| node.id.clone())
+            .collect();
+        if let Some(node) = consumers.iter().find(|id| {
+            self.document
+                .find(id)
+                .is_some_and(|node| !accepts(node, &value))
+        }) {
+            return Err(StyleError::NotApplicable {
+                style_type: value.style_type(),
+                node: node.clone(),
+            });
+        }
+        for id in &consumers {
+            let node = self.document.find_mut(id).expect("consumers exist");
+            set_value(node, &value);
+        }
+        self.styles.get_mut(style).expect("the style exists").value = Some(value);
+        Ok(consumers)
+    }
+
+    /// Removes a style, detaching the nodes using it which keep its properties.
+    pub fn remove_style(&mut self, style: &str) -> Result<Style, StyleError> {
+        let removed = self
+            .styles
+            .remove(style)
+            .ok_or_else(|| StyleError::StyleNotFound(style.to_string()))?;
+        visit_mut(&mut self.document, &mut |node| {
+            if let Some(styles) = styles_mut(node) {
+                if style_id(styles, &removed.style_type) == Some(style) {
+                    detach(styles, &removed.style_type);
+                }
+            }
+        });
+        Ok(removed)
+    }
+
+    /// The nodes using a style, in tree order.
+    pub fn style_consumers(&self, style: &str) -> Vec<&Node> {
+        let mut consumers = Vec::new();
+        if let Some(definition) = self.styles.get(style) {
+            collect(&self.document, &mut |node| {
+                if let Some(styles) = styles(node) {
+                    if style_id(styles, &definition.style_type) == Some(style) {
+                        consumers.push(node);
+                    }
+                }
+            });
+        }
+        consumers
+    }
+
+    /// Whether any node uses a style.
+    pub fn is_style_in_use(&self, style: &str) -> bool {
+        !self.style_consumers(style).is_empty()
+    }
+
+    /// How many nodes use each style of the document, including the unused ones.
+    pub fn style_usage(&self) -> BTreeMap<&str, usize> {
+        let mut usage: BTreeMap<&str, usize> =
+            self.styles.keys().map(|id| (id.as_str(), 0)).collect();
+        collect(&self.document, &mut |node| {
+            for id in styles(node)
+                .into_iter()
+                .flatten()
+                .flat_map(|styles| styles.values())
+            {
+                if let Some(count) = usage.get_mut(id.as_str()) {
+                    *count += 1;
+                }
+            }
+        });
+        usage
+    }
+
+    fn style_value(&self, style: &str) -> Result<&StyleValue, StyleError> {
+        self.styles
+            .get(style)
+            .ok_or_else(|| StyleError::StyleNotFound(style.to_string()))?
+            .value
+            .as_ref()
+            .ok_or_else(|| StyleError::NoValue(style.to_string()))
+    }
+}
+
+/// The `styles` map of a vector node, from the type of each style applied to it to its id.
+type Styles = Option<HashMap<StyleType, String>>;
+
+fn check_type(id: &str, style: &Style, value: &StyleValue) -> Result<(), StyleError> {
+    match style.accepts(value) {
+        true => Ok(()),
+        false => Err(StyleError::TypeMismatch {
+            style: id.to_string(),
+            expected: style.style_type.clone(),
+            found: value.style_type(),
+        }),
+    }
+}
+
+fn style_id<'a>(styles: &'a Styles, style_type: &StyleType) -> Option<&'a str> {
+    styles.as_ref()?.get(style_type).map(String::as_str)
+}
+
+fn detach(styles: &mut Styles, style_type: &StyleType) -> Option<String> {
+    let map = styles.as_mut()?;
+    let detached = map.remove(style_type);
+    // Nodes without styles have no map, like in files from Figma.
+    if map.is_empty() {
+        *styles = None;
+    }
+    detached
+}
+
+fn styles(node: &Node) -> Option<&Styles> {
+    match &node.node {
+        NodeType::Vector(vector) => Some(&vector.styles),
+        NodeType::Rectangle(vector) => Some(&vector.styles),
+        NodeType::Document(_) | NodeType::Canvas(_) => None,
+    }
+}
+
+fn styles_mut(node: &mut Node) -> Option<&mut Styles> {
+    match &mut node.node {
+        NodeType::Vector(vector) => Some(&mut vector.styles),
+        NodeType::Rectangle(vector) => Some(&mut vector.styles),
+        NodeType::Document(_) | NodeType::Canvas(_) => None,
+    }
+}
+
+/// Whether the properties of a style can be set on a node. Only vector nodes have fills and
+/// effects, no node has text or layout grids yet.
+fn accepts(node: &Node, value: &StyleValue) -> bool {
+    styles(node).is_some() && matches!(value, StyleValue::Fill(_) | StyleValue::Effect(_))
+}
+
+/// Sets the properties of a style on a node, `false` when the node does not have them.
+fn set_value(node: &mut Node, value: &StyleValue) -> bool {
+    fn vector<Data>(vector: &mut VectorNode<Data>, value: &StyleValue) -> bool {
+        match value {
+            StyleValue::Fill(paints) => vector.fills = paints.clone(),
+            StyleValue::Effect(effects) => vector.effects = effects.clone(),
+            StyleValue::Text(_) | StyleValue::Grid(_) => return false,
+        }
+        true
+    }
+
+    match &mut node.node {
+        NodeType::Vector(data) => vector(data, value),
+        NodeType::Rectangle(data) => vector(data, value),
+        NodeType::Document(_) | NodeType::Canvas(_) => false,
+    }
+}
+
+fn collect<'a>(node: &'a Node, visit: &mut impl FnMut(&'a Node)) {
+    visit(node);
+    for child in node.children() {
+        collect(child, visit);
+    }
+}
+
+fn visit_mut(node: &mut Node, visit: &mut impl FnMut(&mut Node)) {
+    visit(node);
+    for child in node.children_mut().into_iter().flatten() {
+        visit_mut(child, visit);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+    use crate::fixtures::{self, solid};
+
+    const RED: &str = "S:red";
+    const BLUE: &str = "S:blue";
+
+    fn fill(r: f32, g: f32, b: f32) -> StyleValue {
+        serde_json::from_value(json!({ "Fill": [solid(r, g, b)] })).unwrap()
+    }
+
+    fn style(name: &str, style_type: StyleType, value: Option<StyleValue>) -> Style {
+        Style {
+            key: String::new(),
+            name: name.to_string(),
+            description: String::new(),
+            remote: false,
+            style_type,
+            value,
+        }
+    }
+
+    /// A document with three vectors and a red and a blue fill style.
+    fn document() -> Document {
+        let mut document = Document::new("Styles");
+        let canvas = &mut document.document.children_mut().unwrap()[0];
+        *canvas.children_mut().unwrap() = ["1:1", "1:2", "1:3"]
+            .map(|id| fixtures::vector(id, id))
+            .to_vec();
+        let red = style("Red", StyleType::Fill, Some(fill(1.0, 0.0, 0.0)));
+        document.add_style(RED, red).unwrap();
+        let blue = style("Blue", StyleType::Fill, Some(fill(0.0, 0.0, 1.0)));
+        document.add_style(BLUE, blue).unwrap();
+        document
+    }
+
+    fn fills(document: &Document, id: &str) -> StyleValue {
+        match &document.node(id).unwrap().node {
+            NodeType::Vector(vector) => StyleValue::Fill(vector.fills.clone()),
+            node => panic!("not a vector: {node:?}"),
+        }
+    }
+
+    #[test]
+    fn edits_of_styles_propagate_to_attached_nodes() {
+        let mut document = document();
+        document.apply_style("1:1", RED).unwrap();
+        document.apply_style("1:2", RED).unwrap();
+        assert_eq!(fills(&document, "1:1"), fill(1.0, 0.0, 0.0));
+
+        let detached = document.detach_style("1:2", &StyleType::Fill).unwrap();
+        assert_eq!(detached.as_deref(), Some(RED));
+        let updated = document.update_style(RED, fill(0.5, 0.0, 0.0)).unwrap();
+
+        assert_eq!(updated, ["1:1"]);
+        assert_eq!(fills(&document, "1:1"), fill(0.5, 0.0, 0.0));
+        // Detached nodes keep the properties the style had.
+        assert_eq!(fills(&document, "1:2"), fill(1.0, 0.0, 0.0));
+        assert!(styles(document.node("1:2").unwrap()).unwrap().is_none());
+    }
+
+    #[test]
+    fn styles_in_use_are_counted() {
+        let mut document = document();
+        document.apply_style("1:1", BLUE).unwrap();
+        document.apply_style("1:2", RED).unwrap();
+        // Applying a style replaces the style of the same type.
+        document.apply_style("1:1", RED).unwrap();
+
+        assert_eq!(
+            document.style_usage(),
+            BTreeMap::from([(BLUE, 0), (RED, 2)])
+        );
+        assert!(!document.is_style_in_use(BLUE));
+        let consumers = document.style_consumers(RED);
+        assert_eq!(
+            consumers.iter().map(|node| &node.id).collect::<Vec<_>>(),
+            ["1:1", "1:2"]
+        );
+
+        document.remove_style(RED).unwrap();
+        assert!(!document.is_style_in_use(RED));
+        assert_eq!(fills(&document, "1:1"), fill(1.0, 0.0, 0.0));
+        assert!(document.validate().is_empty());
+    }
+
+    #[test]
+    fn styles_are_checked_before_changing_anything() {
+        let mut document = document();
+        document.apply_style("1:1", RED).unwrap();
+
+        let effects = StyleValue::Effect(Vec::new());
+        assert!(matches!(
+            document.update_style(RED, effects),
+            Err(StyleError::TypeMismatch { .. })
+        ));
+        let grids = StyleValue::Grid(Vec::new());
+        document
+            .add_style("S:grid", style("Grid", StyleType::Grid, Some(grids)))
+            .unwrap();
+        assert!(matches!(
+            document.apply_style("1:2", "S:grid"),
+            Err(StyleError::NotApplicable { .. })
+        ));
+        document
+            .add_style("S:remote", style("Remote", StyleType::Fill, None))
+            .unwrap();
+        assert!(matches!(
+            document.apply_style("1:2", "S:remote"),
+            Err(StyleError::NoValue(_))
+        ));
+        assert!(matches!(
+            document.add_style(RED, style("Red", StyleType::Fill, None)),
+            Err(StyleError::DuplicateStyle(_))
+        ));
+        assert_eq!(fills(&document, "1:1"), fill(1.0, 0.0, 0.0));
+        assert!(styles(document.node("1:2").unwrap()).unwrap().is_none());
+    }
+}
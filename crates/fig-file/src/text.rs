@@ -0,0 +1,335 @@
+//! Canonical text serialization: the same document always serializes to the same JSON, whatever
+//! order its maps were filled in, so the versions of a document stored in git diff cleanly.
+//!
+//! Object keys are sorted, except that the id, name and type of a node come first and the data
+//! and children of a node last, which keeps each node a block of its own properties followed by
+//! the blocks of its children. Floats are written in the shortest form reading back to the same
+//! 32-bit float, the precision of the document properties.
+//!
+//! Documents can also be split into a directory, with a file per canvas in `canvases/` and the
+//! rest of the document in `document.json`, where the canvases are replaced by the names of their
+//! files.
+
+use std::collections::HashSet;
+use std::io::Write;
+use std::path::Path;
+
+use serde_json::{Number, Value};
+
+use crate::document::{replace, Document, FileError};
+use crate::export;
+
+/// Keys written before the others, in this order.
+const FIRST: [&str; 3] = ["id", "name", "type"];
+/// Keys written after the others, in this order.
+const LAST: [&str; 3] = ["node", "data", "children"];
+
+/// The file holding the document in a split directory.
+const DOCUMENT: &str = "document.json";
+/// The directory holding the canvases in a split directory.
+const CANVASES: &str = "canvases";
+/// Pointer to the canvases in the serialization of a document.
+const CHILDREN: &str = "/document/node/data/children";
+
+/// The canonical form of a value, indented by two spaces and ending with a newline.
+pub fn to_string(value: &Value) -> String {
+    let mut text = String::new();
+    write(&mut text, value, Some(0));
+    text.push('\n');
+    text
+}
+
+/// The canonical form of a value without any whitespace.
+pub fn to_compact_string(value: &Value) -> String {
+    let mut text = String::new();
+    write(&mut text, value, None);
+    text
+}
+
+fn write(text: &mut String, value: &Value, indent: Option<usize>) {
+    match value {
+        Value::Number(number) => text.push_str(&self::number(number)),
+        Value::Array(values) => block(
+            text,
+            ['[', ']'],
+            values.iter().map(|value| (None, value)),
+            indent,
+        ),
+        Value::Object(map) => {
+            let mut entries: Vec<_> = map.iter().collect();
+            entries.sort_by_key(|&(key, _)| rank(key));
+            let entries = entries.into_iter().map(|(key, value)| (Some(key), value));
+            block(text, ['{', '}'], entries, indent);
+        }
+        Value::Null | Value::Bool(_) | Value::String(_) => text.push_str(&value.to_string()),
+    }
+}
+
+fn rank(key: &str) -> (usize, usize, &str) {
+    match (
+        FIRST.iter().position(|first| *first == key),
+        LAST.iter().position(|last| *last == key),
+    ) {
+        (Some(index), _) => (0, index, ""),
+        (None, Some(index)) => (2, index, ""),
+        (None, None) => (1, 0, key),
+    }
+}
+
+fn block<'a>(
+    text: &mut String,
+    [open, close]: [char; 2],
+    entries: impl ExactSizeIterator<Item = (Option<&'a String>, &'a Value)>,
+    indent: Option<usize>,
+) {
+    text.push(open);
+    if entries.len() == 0 {
+        text.push(close);
+        return;
+    }
+    let inner = indent.map(|indent| indent + 1);
+    for (index, (key, value)) in entries.enumerate() {
+        if index > 0 {
+            text.push(',');
+        }
+        newline(text, inner);
+        if let Some(key) = key {
+            text.push_str(&Value::from(key.as_str()).to_string());
+            text.push_str(if indent.is_some() { ": " } else { ":" });
+        }
+        write(text, value, inner);
+    }
+    newline(text, indent);
+    text.push(close);
+}
+
+fn newline(text: &mut String, indent: Option<usize>) {
+    if let Some(indent) = indent {
+        text.push('\n');
+        text.extend(std::iter::repeat_n("  ", indent));
+    }
+}
+
+fn number(number: &Number) -> String {
+    match number.as_f64().filter(|_| number.is_f64()) {
+        // Floats are stored as 32-bit floats and widened when serialized, which adds digits.
+        Some(float) if f64::from(float as f32) == float => {
+            let text = (float as f32).to_string();
+            match text.contains('.') {
+                true => text,
+                false => text + ".0",
+            }
+        }
+        _ => number.to_string(),
+    }
+}
+
+/// The path of a canvas file named in `document.json`, which must be a plain file name in
+/// `canvases/` so a document cannot read files outside its directory.
+fn canvas_file(file: &str) -> Result<&str, FileError> {
+    let plain = |name: &str| {
+        !name.is_empty() && name != "." && name != ".." && !name.contains(['/', '\\', ':'])
+    };
+    match file.split_once('/') {
+        Some((CANVASES, name)) if plain(name) => Ok(file),
+        _ => Err(FileError::InvalidCanvasFile(file.to_string())),
+    }
+}
+
+impl Document {
+    /// Opens a document split into a directory.
+    pub fn open_split(directory: impl AsRef<Path>) -> Result<Self, FileError> {
+        let directory = directory.as_ref();
+        let mut value: Value = serde_json::from_slice(&std::fs::read(directory.join(DOCUMENT))?)?;
+        if let Some(children) = value.pointer_mut(CHILDREN).and_then(Value::as_array_mut) {
+            for child in children {
+                if let Value::String(file) = child {
+                    let path = canvas_file(file)?;
+                    *child = serde_json::from_slice(&std::fs::read(directory.join(path))?)?;
+                }
+            }
+        }
+        Self::from_value(value)
+    }
+
+    /// Saves the document split into a directory, with a file per canvas, and removes the files
+    /// of the canvases deleted since the last save.
+    pub fn save_split(&self, directory: impl AsRef<Path>) -> Result<(), FileError> {
+        let directory = directory.as_ref();
+        let canvases = directory.join(CANVASES);
+        std::fs::create_dir_all(&canvases)?;
+        let mut value = serde_json::to_value(self)?;
+        let mut files = HashSet::new();
+        if let Some(children) = value.pointer_mut(CHILDREN).and_then(Value::as_array_mut) {
+            for child in children {
+                let id = child.get("id").and_then(Value::as_str).unwrap_or_default();
+                // Ids differing only by characters file names cannot hold, e.g. `1:2` and `1_2`,
+                // get numbered files.
+                let stem = format!("{CANVASES}/{}", export::sanitize(id));
+                let mut file = format!("{stem}.json");
+                let mut copy = 1;
+                while !files.insert(file.clone()) {
+                    copy += 1;
+                    file = format!("{stem} {copy}.json");
+                }
+                let canvas = std::mem::replace(child, Value::String(file.clone()));
+                save(&directory.join(&file), &canvas)?;
+            }
+        }
+        save(&directory.join(DOCUMENT), &value)?;
+        for entry in std::fs::read_dir(&canvases)? {
+            let name = entry?.file_name();
+            let file = format!("{CANVASES}/{}", name.to_string_lossy());
+            if file.ends_with(".json") && !files.contains(&file) {
+                std::fs::remove_file(canvases.join(name))?;
+            }
+        }
+        Ok(())
+    }
+}
+
+fn save(path: &Path, value: &Value) -> Result<(), FileError> {
+    replace(path, |mut writer| {
+        writer.write_all(to_string(value).as_bytes())?;
+        Ok(writer.flush()?)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use fig_types::node::Node;
+    use serde_json::json;
+
+    use super::*;
+    use crate::document::Format;
+    use crate::fixtures;
+
+    fn layer(id: &str) -> Node {
+        let styles = json!({ "Fill": "S:1", "Text": "S:2", "Effect": "S:3", "Grid": "S:4" });
+        let properties = json!({ "opacity": 0.1, "styles": styles });
+        fixtures::layer("vector", id, "Layer", properties)
+    }
+
+    fn document() -> Document {
+        let mut document = Document::new("Text");
+        let page = &mut document.document.children_mut().unwrap()[0];
+        *page.children_mut().unwrap() = vec![layer("1:1"), layer("1:2")];
+        let mut page = page.clone();
+        page.id = "0:2".to_string();
+        document.document.children_mut().unwrap().push(page);
+        document
+    }
+
+    fn bytes(document: &Document, format: Format) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        document.write(&mut bytes, format).unwrap();
+        bytes
+    }
+
+    /// A fresh directory for the files of a test.
+    fn directory(test: &str) -> PathBuf {
+        let path = std::env::temp_dir().join(format!("fig-file-{}-{test}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&path);
+        std::fs::create_dir_all(&path).unwrap();
+        path
+    }
+
+    #[test]
+    fn unchanged_documents_save_to_the_same_bytes() {
+        let document = document();
+        for format in [Format::Native, Format::Json, Format::Figma] {
+            let saved = bytes(&document, format);
+            // Reading fills the maps of the document again, in another order.
+            let read = Document::read(saved.as_slice()).unwrap();
+            assert_eq!(read, document);
+            assert_eq!(bytes(&read, format), saved);
+        }
+
+        let text = String::from_utf8(bytes(&document, Format::Json)).unwrap();
+        assert!(text.contains("\"opacity\": 0.1,"));
+        assert!(text.contains("\"strokeMiterAngle\": 28.96,"));
+        assert!(text.contains("\"schemaVersion\": 1,"));
+        let styles = text.find("\"styles\": {\n").unwrap();
+        let order = ["Effect", "Fill", "Grid", "Text"].map(|key| text[styles..].find(key).unwrap());
+        assert!(order.is_sorted());
+        // The properties of a node come before its children.
+        let page = text.find("\"id\": \"0:1\"").unwrap();
+        assert!(text[page..].find("\"name\": \"Page 1\"") < text[page..].find("\"children\""));
+        assert!(text[page..].find("\"visible\"") < text[page..].find("\"node\""));
+    }
+
+    #[test]
+    fn split_documents_have_a_file_per_canvas() {
+        let directory = directory("split");
+        let mut document = document();
+        document.save(&directory).unwrap();
+        let files = |directory: &Path| {
+            let mut files: Vec<_> = std::fs::read_dir(directory.join(CANVASES))
+                .unwrap()
+                .map(|entry| entry.unwrap().file_name().into_string().unwrap())
+                .collect();
+            files.sort();
+            files
+        };
+        assert_eq!(files(&directory), ["0_1.json", "0_2.json"]);
+        let index = std::fs::read_to_string(directory.join(DOCUMENT)).unwrap();
+        assert!(index.contains("\"canvases/0_1.json\",\n"));
+        assert_eq!(Document::open(&directory).unwrap(), document);
+
+        document.document.children_mut().unwrap().remove(0);
+        document.save_split(&directory).unwrap();
+        assert_eq!(files(&directory), ["0_2.json"]);
+        let canvas = std::fs::read(directory.join(CANVASES).join("0_2.json")).unwrap();
+        Document::open(&directory)
+            .unwrap()
+            .save(&directory)
+            .unwrap();
+        assert_eq!(
+            std::fs::read(directory.join(CANVASES).join("0_2.json")).unwrap(),
+            canvas
+        );
+        assert_eq!(Document::open(&directory).unwrap(), document);
+        std::fs::remove_dir_all(directory).unwrap();
+    }
+
+    #[test]
+    fn canvas_files_outside_the_canvases_directory_are_rejected() {
+        let directory = directory("outside");
+        document().save_split(&directory).unwrap();
+        let index = std::fs::read_to_string(directory.join(DOCUMENT)).unwrap();
+        let outside = std::env::temp_dir().join("fig-file-outside.json");
+        for file in [
+            "../document.json",
+            "canvases/../document.json",
+            "canvases/a/0_1.json",
+            "canvases/a\\0_1.json",
+            "document.json",
+            outside.to_str().unwrap(),
+        ] {
+            let edited = index.replace("canvases/0_1.json", &file.replace('\\', "\\\\"));
+            std::fs::write(directory.join(DOCUMENT), edited).unwrap();
+            assert!(
+                matches!(
+                    Document::open(&directory),
+                    Err(FileError::InvalidCanvasFile(name)) if name == file
+                ),
+                "{file}"
+            );
+        }
+        std::fs::remove_dir_all(directory).unwrap();
+    }
+
+    #[test]
+    fn canvases_with_ids_sanitized_to_the_same_file_name_are_kept() {
+        let directory = directory("collision");
+        let mut document = document();
+        document.document.children_mut().unwrap()[1].id = "0_1".to_string();
+        document.save_split(&directory).unwrap();
+        let index = std::fs::read_to_string(directory.join(DOCUMENT)).unwrap();
+        assert!(index.contains("\"canvases/0_1 2.json\""));
+        assert_eq!(Document::open(&directory).unwrap(), document);
+        std::fs::remove_dir_all(directory).unwrap();
+    }
+}
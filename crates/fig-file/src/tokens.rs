@@ -0,0 +1,1214 @@
+//! Design tokens in the format of the W3C Design Tokens Community Group (DTCG).
+//!
+//! Variables are exported in a group per collection, nested by the `/` separated segments of
+//! their names. The value of a token is the value of its variable in the default mode of the
+//! collection, the values in every mode are kept in the `com.figma` extension of the token with
+//! the ids, scopes and code syntax of the variable. Aliases become token references. Styles are
+//! exported in a `styles` group and the property definitions of components in a `components`
+//! group.
+//!
+//! Importing a tokens file creates or updates the variables of the tokens with a type variables
+//! can hold, matching collections and variables by name.
+
+use std::collections::{BTreeMap, HashMap};
+
+use fig_types::properties::{
+    Color, ComponentPropertyDefinition, ComponentPropertyDefinitionDefaultValue,
+    ComponentPropertyType, Effect, EffectType, LayoutGridPattern, Paint, PaintType, Style,
+    StyleValue, TypeStyle, Variable, VariableAlias, VariableCollection, VariableDataValue,
+    VariableMode, VariableResolvedDataType,
+};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Map, Value};
+
+use crate::document::Document;
+use crate::values;
+
+/// Namespace of the extensions written in tokens.
+const EXTENSION: &str = "com.figma";
+/// Group holding the styles.
+const STYLES: &str = "styles";
+/// Group holding the property definitions of components.
+const COMPONENTS: &str = "components";
+/// Float variables only scoped to these properties are lengths, exported as dimensions.
+const DIMENSION_SCOPES: [&str; 10] = [
+    "CORNER_RADIUS",
+    "WIDTH_HEIGHT",
+    "GAP",
+    "STROKE_FLOAT",
+    "EFFECT_FLOAT",
+    "FONT_SIZE",
+    "LINE_HEIGHT",
+    "LETTER_SPACING",
+    "PARAGRAPH_SPACING",
+    "PARAGRAPH_INDENT",
+];
+/// Size of a `rem` in pixels, to import dimensions in `rem`.
+const REM: f32 = 16.0;
+
+#[derive(Debug, thiserror::Error)]
+pub enum TokenError {
+    #[error("a tokens file must be a JSON object")]
+    NotAGroup,
+    #[error("token `{token}`: {reason}")]
+    InvalidValue { token: String, reason: String },
+    #[error("token `{token}` refers to `{alias}` which is not a variable")]
+    UnknownAlias { token: String, alias: String },
+    #[error("token `{token}` holds {found:?} values but variable `{variable}` holds {expected:?}")]
+    TypeMismatch {
+        token: String,
+        variable: String,
+        expected: VariableResolvedDataType,
+        found: VariableResolvedDataType,
+    },
+}
+
+/// What importing a tokens file changed.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Import {
+    /// Ids of the created variables.
+    pub created: Vec<String>,
+    /// Ids of the updated variables.
+    pub updated: Vec<String>,
+    /// Paths of the tokens whose type variables cannot hold, e.g. typography or shadows.
+    pub skipped: Vec<String>,
+}
+
+/// Exports the variables, styles and component property definitions of a document as design
+/// tokens. Values computed by expressions and aliases to variables of other files have no
+/// token value and are left out.
+pub fn export(document: &Document) -> Value {
+    let mut root = Map::new();
+    for collection in document.variable_collections.values() {
+        let default_mode = collection
+            .modes
+            .iter()
+            .find(|mode| mode.mode_id == collection.default_mode_id);
+        let group = group(&mut root, std::slice::from_ref(&collection.name));
+        group.insert(
+            "$extensions".to_string(),
+            json!({ EXTENSION: {
+                "id": collection.id,
+                "defaultMode": default_mode.map(|mode| &mode.name),
+            }}),
+        );
+    }
+    for variable in document.variables.values() {
+        let Some(collection) = document
+            .variable_collections
+            .get(&variable.variable_collection_id)
+        else {
+            continue;
+        };
+        if let Some(token) = variable_token(document, collection, variable) {
+            let mut path = vec![collection.name.clone()];
+            path.extend(segments(&variable.name));
+            insert(&mut root, &path, token);
+        }
+    }
+    for (id, style) in &document.styles {
+        if let Some(token) = style.value.as_ref().and_then(style_token) {
+            let mut path = vec![STYLES.to_string()];
+            path.extend(segments(&style.name));
+            insert(&mut root, &path, with_extension(token, style, id));
+        }
+    }
+    let components = document
+        .component_sets
+        .values()
+        .map(|set| (&set.name, &set.component_property_definitions))
+        .chain(
+            document
+                .components
+                .values()
+                .map(|component| (&component.name, &component.component_property_definitions)),
+        );
+    for (name, definitions) in components {
+        for (property, definition) in definitions {
+            // Property names end with a `#` and a unique suffix, except variant properties.
+            let property = property.split('#').next().unwrap_or(property);
+            let mut path = vec![COMPONENTS.to_string()];
+            path.extend(segments(name));
+            path.push(property.to_string());
+            insert(&mut root, &path, property_token(definition));
+        }
+    }
+    Value::Object(root)
+}
+
+/// Creates or updates the variables of a document from design tokens. Color, number, dimension,
+/// boolean and string tokens become variables of the collection named like their top-level
+/// group, named like the path of the token in it, and the other tokens are skipped. Aliases
+/// without a type take the type of the token they refer to. The document is left untouched when
+/// an error is returned.
+pub fn import(document: &mut Document, tokens: &Value) -> Result<Import, TokenError> {
+    let root = tokens.as_object().ok_or(TokenError::NotAGroup)?;
+    let mut importer = Importer {
+        variables: document.variables.clone(),
+        collections: document.variable_collections.clone(),
+        import: Import::default(),
+        aliases: Vec::new(),
+    };
+    let mut groups = Vec::new();
+    for (name, group) in root {
+        if name.starts_with('$') || name == STYLES || name == COMPONENTS {
+            continue;
+        }
+        let Some(group) = group.as_object() else {
+            continue;
+        };
+        let mut found = Vec::new();
+        walk(group, &mut Vec::new(), None, &mut found);
+        groups.push((name, group, found));
+    }
+    let tokens: HashMap<String, (Option<&str>, &Map<String, Value>)> = groups
+        .iter()
+        .flat_map(|(name, _, found)| {
+            found.iter().map(move |(path, token_type, token)| {
+                (format!("{name}.{}", path.join(".")), (*token_type, *token))
+            })
+        })
+        .collect();
+    for (name, group, found) in &groups {
+        let collection = importer.collection(name, group);
+        for (path, _, token) in found {
+            let token_type = token_type(&tokens, &format!("{name}.{}", path.join(".")));
+            importer.token(&collection, path, token_type, token)?;
+        }
+    }
+    importer.resolve_aliases()?;
+    document.variables = importer.variables;
+    document.variable_collections = importer.collections;
+    Ok(importer.import)
+}
+
+/// Splits a name in the segments of its token path.
+fn segments(name: &str) -> Vec<String> {
+    name.split('/')
+        .map(str::trim)
+        .filter(|segment| !segment.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+/// The group at `path`, created if missing.
+fn group<'a>(root: &'a mut Map<String, Value>, path: &[String]) -> &'a mut Map<String, Value> {
+    path.iter().fold(root, |group, segment| {
+        let child = group
+            .entry(segment.clone())
+            .or_insert_with(|| Value::Object(Map::new()));
+        if !child.is_object() {
+            *child = Value::Object(Map::new());
+        }
+        child.as_object_mut().expect("groups are objects")
+    })
+}
+
+fn insert(root: &mut Map<String, Value>, path: &[String], token: Value) {
+    let Some((name, parents)) = path.split_last() else {
+        return;
+    };
+    group(root, parents).insert(name.clone(), token);
+}
+
+/// Adds the id and key of a style to the extensions of its token.
+fn with_extension(mut token: Value, style: &Style, id: &str) -> Value {
+    if !style.description.is_empty() {
+        token["$description"] = json!(style.description);
+    }
+    token["$extensions"] = json!({ EXTENSION: { "id": id, "key": style.key } });
+    token
+}
+
+/// Whether a float variable holds a length in pixels, i.e. it is only offered for dimensions.
+pub fn is_dimension(variable: &Variable) -> bool {
+    !variable.scopes.is_empty()
+        && variable
+            .scopes
+            .iter()
+            .all(|scope| DIMENSION_SCOPES.contains(&scope.as_str()))
+}
+
+fn variable_token(
+    document: &Document,
+    collection: &VariableCollection,
+    variable: &Variable,
+) -> Option<Value> {
+    let token_type = match variable.resolved_type {
+        VariableResolvedDataType::Boolean => "boolean",
+        VariableResolvedDataType::Float if is_dimension(variable) => "dimension",
+        VariableResolvedDataType::Float => "number",
+        VariableResolvedDataType::String => "string",
+        VariableResolvedDataType::Color => "color",
+    };
+    let value = |value: &VariableDataValue| -> Option<Value> {
+        Some(match value {
+            VariableDataValue::Boolean(value) => json!(value),
+            VariableDataValue::Float(value) if token_type == "dimension" => {
+                json!(values::exact_px(*value))
+            }
+            VariableDataValue::Float(value) => json!(values::exact(*value)),
+            VariableDataValue::String(value) => json!(value),
+            VariableDataValue::Color(color) => json!(values::hex(color, 1.0)),
+            VariableDataValue::RGBColor(color) => json!(values::hex(
+                &Color {
+                    r: color.r,
+                    g: color.g,
+                    b: color.b,
+                    a: 1.0,
+                },
+                1.0,
+            )),
+            VariableDataValue::VariableAlias(alias) => reference(document, &alias.id)?,
+            VariableDataValue::Expression(_) => return None,
+        })
+    };
+    let default = value(variable.values_by_mode.get(&collection.default_mode_id)?)?;
+    let modes: Map<String, Value> = collection
+        .modes
+        .iter()
+        .filter_map(|mode| {
+            let mode_value = value(variable.values_by_mode.get(&mode.mode_id)?)?;
+            Some((mode.name.clone(), mode_value))
+        })
+        .collect();
+    let mut token = json!({ "$type": token_type, "$value": default });
+    if !variable.description.is_empty() {
+        token["$description"] = json!(variable.description);
+    }
+    token["$extensions"] = json!({ EXTENSION: {
+        "id": variable.id,
+        "modes": modes,
+        "scopes": variable.scopes,
+        "codeSyntax": variable.code_syntax.iter().collect::<BTreeMap<_, _>>(),
+        "hiddenFromPublishing": variable.hidden_from_publishing,
+    }});
+    Some(token)
+}
+
+/// A reference to the token of a variable, `None` for variables which are not in the document.
+fn reference(document: &Document, id: &str) -> Option<Value> {
+    let variable = document.variables.get(id)?;
+    let collection = document
+        .variable_collections
+        .get(&variable.variable_collection_id)?;
+    let mut path = vec![collection.name.clone()];
+    path.extend(segments(&variable.name));
+    Some(json!(format!("{{{}}}", path.join("."))))
+}
+
+fn style_token(value: &StyleValue) -> Option<Value> {
+    match value {
+        StyleValue::Fill(paints) => {
+            let [paint] = visible(paints)[..] else {
+                return None;
+            };
+            paint_token(paint)
+        }
+        StyleValue::Text(style) => Some(typography(style)),
+        StyleValue::Effect(effects) => {
+            let shadows: Vec<Value> = effects
+                .iter()
+                .filter(|effect| effect.visible)
+                .filter_map(shadow)
+                .collect();
+            match shadows.len() {
+                0 => None,
+                1 => Some(json!({ "$type": "shadow", "$value": shadows[0] })),
+                _ => Some(json!({ "$type": "shadow", "$value": shadows })),
+            }
+        }
+        StyleValue::Grid(grids) => {
+            // Grids are groups of the spacing values laying them out, by pattern.
+            let mut group = Map::new();
+            for grid in grids.iter().filter(|grid| grid.visible) {
+                let pattern = match grid.pattern {
+                    LayoutGridPattern::Columns => "columns",
+                    LayoutGridPattern::Rows => "rows",
+                    LayoutGridPattern::Grid => "grid",
+                };
+                let dimension =
+                    |value| json!({ "$type": "dimension", "$value": values::exact_px(value) });
+                let mut tokens = json!({ "sectionSize": dimension(grid.section_size) });
+                if !matches!(grid.pattern, LayoutGridPattern::Grid) {
+                    tokens["count"] = json!({ "$type": "number", "$value": grid.count });
+                    tokens["gutter"] = dimension(grid.gutter_size);
+                    tokens["offset"] = dimension(grid.offset);
+                }
+                group.insert(pattern.to_string(), tokens);
+            }
+            (!group.is_empty()).then_some(Value::Object(group))
+        }
+    }
+}
+
+fn visible(paints: &[Paint]) -> Vec<&Paint> {
+    paints.iter().filter(|paint| paint.visible).collect()
+}
+
+fn paint_token(paint: &Paint) -> Option<Value> {
+    match paint.paint_type {
+        PaintType::Solid => Some(json!({
+            "$type": "color",
+            "$value": values::hex(&paint.color, paint.opacity),
+        })),
+        PaintType::GradientLinear
+        | PaintType::GradientRadial
+        | PaintType::GradientAngular
+        | PaintType::GradientDiamond => {
+            let stops: Vec<Value> = paint
+                .gradient_stops
+                .iter()
+                .map(|stop| {
+                    json!({
+                        "color": values::hex(&stop.color, paint.opacity),
+                        "position": values::exact(stop.position),
+                    })
+                })
+                .collect();
+            Some(json!({ "$type": "gradient", "$value": stops }))
+        }
+        PaintType::Image | PaintType::Emoji | PaintType::Video => None,
+    }
+}
+
+fn typography(style: &TypeStyle) -> Value {
+    let line_height = match style.font_size > 0.0 {
+        true => style.line_height_px / style.font_size,
+        false => 1.0,
+    };
+    json!({
+        "$type": "typography",
+        "$value": {
+            "fontFamily": style.font_family,
+            "fontSize": values::exact_px(style.font_size),
+            "fontWeight": style.font_weight,
+            "letterSpacing": values::exact_px(style.letter_spacing),
+            "lineHeight": values::exact(line_height),
+        },
+    })
+}
+
+fn shadow(effect: &Effect) -> Option<Value> {
+    let inset = match effect.effect_type {
+        EffectType::DropShadow => false,
+        EffectType::InnerShadow => true,
+        EffectType::LayerBlur | EffectType::BackgroundBlur => return None,
+    };
+    Some(json!({
+        "color": values::hex(&effect.color, 1.0),
+        "offsetX": values::exact_px(effect.offset.x),
+        "offsetY": values::exact_px(effect.offset.y),
+        "blur": values::exact_px(effect.radius),
+        "spread": values::exact_px(effect.spread),
+        "inset": inset,
+    }))
+}
+
+fn property_token(definition: &ComponentPropertyDefinition) -> Value {
+    let value = match &definition.default_value {
+        ComponentPropertyDefinitionDefaultValue::Bool(value) => json!(value),
+        ComponentPropertyDefinitionDefaultValue::String(value) => json!(value),
+    };
+    let (token_type, property_type) = match definition.component_type {
+        ComponentPropertyType::Boolean => ("boolean", "BOOLEAN"),
+        ComponentPropertyType::Text => ("string", "TEXT"),
+        ComponentPropertyType::Variant => ("string", "VARIANT"),
+        ComponentPropertyType::InstanceSwap => ("string", "INSTANCE_SWAP"),
+    };
+    let mut extension = json!({ "type": property_type });
+    if !definition.variant_options.is_empty() {
+        extension["options"] = json!(definition.variant_options);
+    }
+    if !definition.preferred_values.is_empty() {
+        let keys: Vec<&str> = definition
+            .preferred_values
+            .iter()
+            .map(|preferred| preferred.key.as_str())
+            .collect();
+        extension["preferredValues"] = json!(keys);
+    }
+    json!({
+        "$type": token_type,
+        "$value": value,
+        "$extensions": { EXTENSION: extension },
+    })
+}
+
+/// A token found in a group: its path, its type and its definition.
+type Token<'a> = (Vec<String>, Option<&'a str>, &'a Map<String, Value>);
+
+/// The tokens of a group, with their path in it and their type, inherited from the closest group
+/// declaring one.
+fn walk<'a>(
+    group: &'a Map<String, Value>,
+    path: &mut Vec<String>,
+    inherited: Option<&'a str>,
+    tokens: &mut Vec<Token<'a>>,
+) {
+    let inherited = group.get("$type").and_then(Value::as_str).or(inherited);
+    for (name, child) in group {
+        let Some(child) = child.as_object().filter(|_| !name.starts_with('$')) else {
+            continue;
+        };
+        path.push(name.clone());
+        if child.contains_key("$value") {
+            let token_type = child.get("$type").and_then(Value::as_str).or(inherited);
+            tokens.push((path.clone(), token_type, child));
+        } else {
+            walk(child, path, inherited, tokens);
+        }
+        path.pop();
+    }
+}
+
+/// The type of a token, or for an alias declaring none, the type of the token it refers to.
+fn token_type<'a>(
+    tokens: &HashMap<String, (Option<&'a str>, &'a Map<String, Value>)>,
+    path: &str,
+) -> Option<&'a str> {
+    let mut path = path;
+    // Aliases referring to each other in a cycle have no type.
+    for _ in 0..=tokens.len() {
+        let (token_type, token) = tokens.get(path)?;
+        if token_type.is_some() {
+            return *token_type;
+        }
+        path = reference_path(&token["$value"])?;
+    }
+    None
+}
+
+/// The path of the token a value refers to, for references written `{group.token}`.
+fn reference_path(value: &Value) -> Option<&str> {
+    value.as_str()?.strip_prefix('{')?.strip_suffix('}')
+}
+
+fn extension<'a>(object: &'a Map<String, Value>, key: &str) -> Option<&'a Value> {
+    object.get("$extensions")?.get(EXTENSION)?.get(key)
+}
+
+/// A value of a token, still referring to other tokens by path.
+enum Raw {
+    Value(VariableDataValue),
+    Alias(String),
+}
+
+struct Importer {
+    variables: BTreeMap<String, Variable>,
+    collections: BTreeMap<String, VariableCollection>,
+    import: Import,
+    /// Values to resolve once every token is imported: the variable, its mode, the token and
+    /// the path of the token referred to.
+    aliases: Vec<(String, String, String, String)>,
+}
+
+impl Importer {
+    /// The id of the collection named `name`, created if missing.
+    fn collection(&mut self, name: &str, group: &Map<String, Value>) -> String {
+        if let Some(collection) = self
+            .collections
+            .values()
+            .find(|collection| collection.name == name)
+        {
+            return collection.id.clone();
+        }
+        let id = unique(format!("VariableCollectionId:{name}"), |id| {
+            self.collections.contains_key(id)
+        });
+        let default_mode = extension(group, "defaultMode")
+            .and_then(Value::as_str)
+            .unwrap_or("Mode 1");
+        let mode_id = format!("{id}/{default_mode}");
+        self.collections.insert(
+            id.clone(),
+            VariableCollection {
+                id: id.clone(),
+                name: name.to_string(),
+                key: String::new(),
+                modes: vec![VariableMode {
+                    mode_id: mode_id.clone(),
+                    name: default_mode.to_string(),
+                }],
+                default_mode_id: mode_id,
+                remote: false,
+                hidden_from_publishing: false,
+                variable_ids: Vec::new(),
+            },
+        );
+        id
+    }
+
+    /// The id of the mode named `name` of a collection, created if missing.
+    fn mode(&mut self, collection: &str, name: &str) -> String {
+        let collection = self
+            .collections
+            .get_mut(collection)
+            .expect("collections are created first");
+        if let Some(mode) = collection.modes.iter().find(|mode| mode.name == name) {
+            return mode.mode_id.clone();
+        }
+        let mode_id = format!("{}/{name}", collection.id);
+        collection.modes.push(VariableMode {
+            mode_id: mode_id.clone(),
+            name: name.to_string(),
+        });
+        mode_id
+    }
+
+    fn token(
+        &mut self,
+        collection: &str,
+        path: &[String],
+        token_type: Option<&str>,
+        token: &Map<String, Value>,
+    ) -> Result<(), TokenError> {
+        let collection_name = self.collections[collection].name.clone();
+        let token_path = format!("{collection_name}.{}", path.join("."));
+        let resolved_type = match token_type {
+            Some("color") => VariableResolvedDataType::Color,
+            Some("number" | "dimension") => VariableResolvedDataType::Float,
+            Some("boolean") => VariableResolvedDataType::Boolean,
+            Some("string") => VariableResolvedDataType::String,
+            _ => {
+                self.import.skipped.push(token_path);
+                return Ok(());
+            }
+        };
+        let name = path.join("/");
+        let default_mode = self.collections[collection].default_mode_id.clone();
+        let mut values = vec![(default_mode, &token["$value"])];
+        if let Some(Value::Object(modes)) = extension(token, "modes") {
+            for (mode, value) in modes {
+                let mode = self.mode(collection, mode);
+                // The value of the token is the one of the default mode.
+                if mode != values[0].0 {
+                    values.push((mode, value));
+                }
+            }
+        }
+        let existing = self.variables.values().find(|variable| {
+            variable.variable_collection_id == collection && variable.name == name
+        });
+        let id = match existing {
+            Some(variable) if variable.resolved_type != resolved_type => {
+                return Err(TokenError::TypeMismatch {
+                    token: token_path,
+                    variable: variable.id.clone(),
+                    expected: variable.resolved_type.clone(),
+                    found: resolved_type,
+                });
+            }
+            Some(variable) => {
+                self.import.updated.push(variable.id.clone());
+                variable.id.clone()
+            }
+            None => {
+                let id = unique(format!("VariableID:{collection_name}/{name}"), |id| {
+                    self.variables.contains_key(id)
+                });
+                self.variables.insert(
+                    id.clone(),
+                    Variable {
+                        id: id.clone(),
+                        name: name.clone(),
+                        key: String::new(),
+                        variable_collection_id: collection.to_string(),
+                        resolved_type: resolved_type.clone(),
+                        values_by_mode: HashMap::new(),
+                        remote: false,
+                        description: String::new(),
+                        hidden_from_publishing: false,
+                        scopes: Vec::new(),
+                        code_syntax: HashMap::new(),
+                    },
+                );
+                let collection = self
+                    .collections
+                    .get_mut(collection)
+                    .expect("collection exists");
+                collection.variable_ids.push(id.clone());
+                self.import.created.push(id.clone());
+                id
+            }
+        };
+        let mut parsed = Vec::new();
+        for (mode, value) in values {
+            let raw = parse(value, &resolved_type).map_err(|reason| TokenError::InvalidValue {
+                token: token_path.clone(),
+                reason,
+            })?;
+            parsed.push((mode, raw));
+        }
+        let variable = self.variables.get_mut(&id).expect("the variable exists");
+        for (mode, raw) in parsed {
+            match raw {
+                Raw::Value(value) => {
+                    variable.values_by_mode.insert(mode, value);
+                }
+                Raw::Alias(alias) => {
+                    self.aliases
+                        .push((id.clone(), mode, token_path.clone(), alias));
+                }
+            }
+        }
+        if let Some(Value::String(description)) = token.get("$description") {
+            variable.description = description.clone();
+        }
+        if let Some(scopes) = extension(token, "scopes")
+            .and_then(|scopes| serde_json::from_value(scopes.clone()).ok())
+        {
+            variable.scopes = scopes;
+        }
+        if let Some(code_syntax) = extension(token, "codeSyntax")
+            .and_then(|code_syntax| serde_json::from_value(code_syntax.clone()).ok())
+        {
+            variable.code_syntax = code_syntax;
+        }
+        if let Some(Value::Bool(hidden)) = extension(token, "hiddenFromPublishing") {
+            variable.hidden_from_publishing = *hidden;
+        }
+        Ok(())
+    }
+
+    /// Points the aliases of the imported tokens to the variables of the tokens they refer to.
+    fn resolve_aliases(&mut self) -> Result<(), TokenError> {
+        let paths: HashMap<String, String> = self
+            .variables
+            .values()
+            .filter_map(|variable| {
+                let collection = self.collections.get(&variable.variable_collection_id)?;
+                let mut path = vec![collection.name.clone()];
+                path.extend(segments(&variable.name));
+                Some((path.join("."), variable.id.clone()))
+            })
+            .collect();
+        for (id, mode, token, alias) in std::mem::take(&mut self.aliases) {
+            let target = paths
+                .get(&alias)
+                .ok_or(TokenError::UnknownAlias { token, alias })?;
+            let value = VariableDataValue::VariableAlias(VariableAlias {
+                variable_alias_type: "VARIABLE_ALIAS".to_string(),
+                id: target.clone(),
+            });
+            let variable = self.variables.get_mut(&id).expect("the variable exists");
+            variable.values_by_mode.insert(mode, value);
+        }
+        Ok(())
+    }
+}
+
+/// `id`, or `id` followed by the first number making it unused.
+fn unique(id: String, used: impl Fn(&str) -> bool) -> String {
+    if !used(&id) {
+        return id;
+    }
+    (2..)
+        .map(|suffix| format!("{id} {suffix}"))
+        .find(|id| !used(id))
+        .expect("some suffix is unused")
+}
+
+fn parse(value: &Value, resolved_type: &VariableResolvedDataType) -> Result<Raw, String> {
+    if let Some(alias) = reference_path(value) {
+        return Ok(Raw::Alias(alias.to_string()));
+    }
+    let value = match resolved_type {
+        VariableResolvedDataType::Boolean => {
+            VariableDataValue::Boolean(value.as_bool().ok_or("expected a boolean")?)
+        }
+        VariableResolvedDataType::String => {
+            VariableDataValue::String(value.as_str().ok_or("expected a string")?.to_string())
+        }
+        VariableResolvedDataType::Float => VariableDataValue::Float(parse_number(value)?),
+        VariableResolvedDataType::Color => VariableDataValue::Color(parse_color(value)?),
+    };
+    Ok(Raw::Value(value))
+}
+
+/// Parses numbers and dimensions, written as strings with their unit or as objects with a
+/// `value` and a `unit`.
+fn parse_number(value: &Value) -> Result<f32, String> {
+    let (number, unit) = match value {
+        Value::Number(number) => (number.as_f64().unwrap_or_default() as f32, ""),
+        Value::String(text) => {
+            let split = text
+                .find(|character: char| character.is_ascii_alphabetic() || character == '%')
+                .unwrap_or(text.len());
+            let number = text[..split]
+                .trim()
+                .parse()
+                .map_err(|_| format!("`{text}` is not a number"))?;
+            (number, &text[split..])
+        }
+        Value::Object(object) => {
+            let number = object
+                .get("value")
+                .and_then(Value::as_f64)
+                .ok_or("dimensions have a numeric `value`")?;
+            let unit = object.get("unit").and_then(Value::as_str).unwrap_or("");
+            (number as f32, unit)
+        }
+        _ => return Err("expected a number".to_string()),
+    };
+    match unit {
+        "" | "px" => Ok(number),
+        "rem" => Ok(number * REM),
+        unit => Err(format!("unsupported unit `{unit}`")),
+    }
+}
+
+/// Parses colors written as `#rgb`, `#rrggbb` or `#rrggbbaa`, or as objects with sRGB
+/// `components` and an `alpha`.
+fn parse_color(value: &Value) -> Result<Color, String> {
+    match value {
+        Value::String(text) => {
+            let digits = text
+                .strip_prefix('#')
+                .filter(|digits| digits.chars().all(|digit| digit.is_ascii_hexdigit()))
+                .ok_or_else(|| format!("`{text}` is not a hex color"))?;
+            let digits: String = match digits.len() {
+                3 | 4 => digits.chars().flat_map(|digit| [digit, digit]).collect(),
+                6 | 8 => digits.to_string(),
+                _ => return Err(format!("`{text}` is not a hex color")),
+            };
+            let channel = |index: usize| {
+                digits.get(index * 2..index * 2 + 2).map_or(1.0, |pair| {
+                    u8::from_str_radix(pair, 16).unwrap_or_default() as f32 / 255.0
+                })
+            };
+            Ok(Color {
+                r: channel(0),
+                g: channel(1),
+                b: channel(2),
+                a: channel(3),
+            })
+        }
+        Value::Object(object) => {
+            let components: Vec<f32> = object
+                .get("components")
+                .and_then(Value::as_array)
+                .map(|components| {
+                    components
+                        .iter()
+                        .filter_map(Value::as_f64)
+                        .map(|component| component as f32)
+                        .collect()
+                })
+                .unwrap_or_default();
+            let [r, g, b] = components[..] else {
+                return Err("colors have three `components`".to_string());
+            };
+            let alpha = object.get("alpha").and_then(Value::as_f64).unwrap_or(1.0);
+            Ok(Color {
+                r,
+                g,
+                b,
+                a: alpha as f32,
+            })
+        }
+        _ => Err("expected a color".to_string()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const LIGHT: &str = "1:0";
+    const DARK: &str = "1:1";
+
+    fn variable(
+        id: &str,
+        name: &str,
+        resolved_type: VariableResolvedDataType,
+        scopes: &[&str],
+        light: VariableDataValue,
+        dark: VariableDataValue,
+    ) -> Variable {
+        Variable {
+            id: id.to_string(),
+            name: name.to_string(),
+            key: String::new(),
+            variable_collection_id: "VariableCollectionId:1:0".to_string(),
+            resolved_type,
+            values_by_mode: HashMap::from([(LIGHT.to_string(), light), (DARK.to_string(), dark)]),
+            remote: false,
+            description: String::new(),
+            hidden_from_publishing: false,
+            scopes: scopes.iter().map(|scope| scope.to_string()).collect(),
+            code_syntax: HashMap::new(),
+        }
+    }
+
+    fn color(r: f32, g: f32, b: f32, a: f32) -> VariableDataValue {
+        VariableDataValue::Color(Color { r, g, b, a })
+    }
+
+    fn alias(id: &str) -> VariableDataValue {
+        VariableDataValue::VariableAlias(VariableAlias {
+            variable_alias_type: "VARIABLE_ALIAS".to_string(),
+            id: id.to_string(),
+        })
+    }
+
+    fn style(name: &str, value: Value) -> Style {
+        let value: StyleValue = serde_json::from_value(value).unwrap();
+        Style {
+            key: String::new(),
+            name: name.to_string(),
+            description: String::new(),
+            remote: false,
+            style_type: value.style_type(),
+            value: Some(value),
+        }
+    }
+
+    /// A theme collection with a light and a dark mode, a text and an effect style, and a button
+    /// component set.
+    fn document() -> Document {
+        let mut document = Document::new("Tokens");
+        let collection = VariableCollection {
+            id: "VariableCollectionId:1:0".to_string(),
+            name: "Theme".to_string(),
+            key: String::new(),
+            modes: vec![
+                VariableMode {
+                    mode_id: LIGHT.to_string(),
+                    name: "Light".to_string(),
+                },
+                VariableMode {
+                    mode_id: DARK.to_string(),
+                    name: "Dark".to_string(),
+                },
+            ],
+            default_mode_id: LIGHT.to_string(),
+            remote: false,
+            hidden_from_publishing: false,
+            variable_ids: Vec::new(),
+        };
+        let variables = [
+            variable(
+                "VariableID:1:1",
+                "color/primary",
+                VariableResolvedDataType::Color,
+                &[],
+                color(1.0, 0.0, 0.0, 1.0),
+                color(0.0, 0.0, 1.0, 0.2),
+            ),
+            variable(
+                "VariableID:1:2",
+                "color/accent",
+                VariableResolvedDataType::Color,
+                &[],
+                alias("VariableID:1:1"),
+                color(0.0, 1.0, 0.0, 1.0),
+            ),
+            variable(
+                "VariableID:1:3",
+                "space/md",
+                VariableResolvedDataType::Float,
+                &["GAP"],
+                VariableDataValue::Float(16.0),
+                VariableDataValue::Float(12.0),
+            ),
+            variable(
+                "VariableID:1:4",
+                "opacity/disabled",
+                VariableResolvedDataType::Float,
+                &["OPACITY"],
+                VariableDataValue::Float(0.4),
+                VariableDataValue::Float(0.3),
+            ),
+            variable(
+                "VariableID:1:5",
+                "beta",
+                VariableResolvedDataType::Boolean,
+                &[],
+                VariableDataValue::Boolean(true),
+                VariableDataValue::Boolean(false),
+            ),
+        ];
+        document.variables = variables
+            .into_iter()
+            .map(|variable| (variable.id.clone(), variable))
+            .collect();
+        document
+            .variable_collections
+            .insert(collection.id.clone(), collection);
+        document.styles.insert(
+            "S:1".to_string(),
+            style(
+                "Heading/Large",
+                json!({ "Text": {
+                    "fontFamily": "Inter",
+                    "fontPostScriptName": "Inter-Bold",
+                    "paragraphSpacing": 0.0,
+                    "paragraphIndent": 0.0,
+                    "listSpacing": 0.0,
+                    "italic": false,
+                    "fontWeight": 700,
+                    "fontSize": 32.0,
+                    "textCase": "Upper",
+                    "textDecoration": "Underline",
+                    "textAutoResize": "Height",
+                    "textTruncation": "Disabled",
+                    "maxLines": 0,
+                    "textAlignHorizontal": "Left",
+                    "textAlignVertical": "Top",
+                    "letterSpacing": -0.5,
+                    "fills": [],
+                    "hyperlink": { "hyperLinkType": "Url", "url": "", "nodeId": "" },
+                    "opentypeFlags": {},
+                    "lineHeightPx": 40.0,
+                    "lineHeightPercent": 100.0,
+                    "lineHeightPercentFontSize": 125.0,
+                    "lineHeightUnit": "Pixels",
+                    "isOverrideOverTextStyle": false,
+                    "semanticWeight": "Bold",
+                    "semanticItalic": "Normal",
+                }}),
+            ),
+        );
+        document.styles.insert(
+            "S:2".to_string(),
+            style(
+                "Elevation/1",
+                json!({ "Effect": [{
+                    "effectType": "DropShadow",
+                    "visible": true,
+                    "radius": 4.0,
+                    "color": { "r": 0.0, "g": 0.0, "b": 0.0, "a": 0.25 },
+                    "blendMode": "Normal",
+                    "offset": { "x": 0.0, "y": 2.0 },
+                    "spread": 0.0,
+                    "showShadowBehindNode": false,
+                    "boundVariables": {},
+                }]}),
+            ),
+        );
+        document.component_sets.insert(
+            "1:10".to_string(),
+            serde_json::from_value(json!({
+                "key": "",
+                "name": "Button",
+                "description": "",
+                "documentationLinks": [],
+                "remote": false,
+                "componentPropertyDefinitions": {
+                    "Size": {
+                        "componentType": "Variant",
+                        "defaultValue": { "String": "Medium" },
+                        "variantOptions": ["Small", "Medium"],
+                        "preferredValues": [],
+                    },
+                    "Icon#1:2": {
+                        "componentType": "Boolean",
+                        "defaultValue": { "Bool": false },
+                        "variantOptions": [],
+                        "preferredValues": [],
+                    },
+                },
+            }))
+            .unwrap(),
+        );
+        document
+    }
+
+    /// Removes the ids of the extensions, which differ between documents.
+    fn without_ids(value: &mut Value) {
+        if let Value::Object(object) = value {
+            object.remove("id");
+            object.values_mut().for_each(without_ids);
+        }
+    }
+
+    #[test]
+    fn variables_styles_and_components_are_exported() {
+        let tokens = export(&document());
+        let theme = &tokens["Theme"];
+
+        assert_eq!(theme["$extensions"][EXTENSION]["defaultMode"], "Light");
+        let primary = &theme["color"]["primary"];
+        assert_eq!(primary["$type"], "color");
+        assert_eq!(primary["$value"], "#ff0000");
+        assert_eq!(
+            primary["$extensions"][EXTENSION]["modes"]["Dark"],
+            "#0000ff33"
+        );
+        assert_eq!(theme["color"]["accent"]["$value"], "{Theme.color.primary}");
+        assert_eq!(
+            theme["space"]["md"],
+            json!({
+                "$type": "dimension",
+                "$value": "16px",
+                "$extensions": { EXTENSION: {
+                    "id": "VariableID:1:3",
+                    "modes": { "Light": "16px", "Dark": "12px" },
+                    "scopes": ["GAP"],
+                    "codeSyntax": {},
+                    "hiddenFromPublishing": false,
+                }},
+            })
+        );
+        assert_eq!(theme["opacity"]["disabled"]["$type"], "number");
+        assert_eq!(theme["opacity"]["disabled"]["$value"], 0.4);
+        assert_eq!(theme["beta"]["$value"], true);
+
+        let styles = &tokens[STYLES];
+        assert_eq!(
+            styles["Heading"]["Large"]["$value"],
+            json!({
+                "fontFamily": "Inter",
+                "fontSize": "32px",
+                "fontWeight": 700,
+                "letterSpacing": "-0.5px",
+                "lineHeight": 1.25,
+            })
+        );
+        assert_eq!(
+            styles["Elevation"]["1"]["$value"],
+            json!({
+                "color": "#00000040",
+                "offsetX": "0px",
+                "offsetY": "2px",
+                "blur": "4px",
+                "spread": "0px",
+                "inset": false,
+            })
+        );
+
+        let button = &tokens[COMPONENTS]["Button"];
+        assert_eq!(button["Size"]["$value"], "Medium");
+        assert_eq!(
+            button["Size"]["$extensions"][EXTENSION]["options"],
+            json!(["Small", "Medium"])
+        );
+        assert_eq!(button["Icon"]["$type"], "boolean");
+    }
+
+    #[test]
+    fn imported_tokens_export_the_same() {
+        let tokens = export(&document());
+        let mut imported = Document::new("Imported");
+
+        let import = import(&mut imported, &tokens).unwrap();
+        assert_eq!(import.created.len(), 5);
+        assert!(import.updated.is_empty());
+        assert!(import.skipped.is_empty());
+
+        let mut expected = tokens.clone();
+        let mut actual = export(&imported);
+        for tokens in [&mut expected, &mut actual] {
+            let object = tokens.as_object_mut().unwrap();
+            object.remove(STYLES);
+            object.remove(COMPONENTS);
+            without_ids(tokens);
+        }
+        assert_eq!(actual, expected);
+        assert!(imported.validate().is_empty());
+    }
+
+    #[test]
+    fn imports_update_variables_by_name() {
+        let mut document = document();
+        let tokens = json!({
+            "Theme": {
+                "color": {
+                    "$type": "color",
+                    "primary": {
+                        "$value": "#00ff00",
+                        "$extensions": { EXTENSION: { "modes": { "Dark": "#000" } } },
+                    },
+                    "link": { "$value": "{Theme.color.primary}" },
+                },
+                "space": {
+                    "lg": { "$type": "dimension", "$value": { "value": 1.5, "unit": "rem" } },
+                },
+                "font": { "$type": "fontFamily", "$value": "Inter" },
+            },
+        });
+
+        let import = import(&mut document, &tokens).unwrap();
+        assert_eq!(import.updated, ["VariableID:1:1"]);
+        assert_eq!(
+            import.created,
+            ["VariableID:Theme/color/link", "VariableID:Theme/space/lg"]
+        );
+        assert_eq!(import.skipped, ["Theme.font"]);
+
+        let primary = &document.variables["VariableID:1:1"];
+        assert_eq!(primary.values_by_mode[LIGHT], color(0.0, 1.0, 0.0, 1.0));
+        assert_eq!(primary.values_by_mode[DARK], color(0.0, 0.0, 0.0, 1.0));
+        let link = &document.variables["VariableID:Theme/color/link"];
+        assert_eq!(link.values_by_mode[LIGHT], alias("VariableID:1:1"));
+        let large = &document.variables["VariableID:Theme/space/lg"];
+        assert_eq!(large.values_by_mode[LIGHT], VariableDataValue::Float(24.0));
+        let theme = &document.variable_collections["VariableCollectionId:1:0"];
+        assert_eq!(theme.variable_ids.len(), 2);
+    }
+
+    #[test]
+    fn aliases_take_the_type_of_the_token_they_refer_to() {
+        let mut document = Document::new("Tokens");
+        let tokens = json!({
+            "Brand": {
+                "red": { "$type": "color", "$value": "#ff0000" },
+                "danger": { "$value": "{Brand.red}" },
+                "loop": { "$value": "{Brand.cycle}" },
+                "cycle": { "$value": "{Brand.loop}" },
+            },
+            "Semantic": {
+                "error": { "$value": "{Brand.danger}" },
+            },
+        });
+
+        let import = import(&mut document, &tokens).unwrap();
+        assert_eq!(
+            import.created,
+            [
+                "VariableID:Brand/danger",
+                "VariableID:Brand/red",
+                "VariableID:Semantic/error",
+            ]
+        );
+        assert_eq!(import.skipped, ["Brand.cycle", "Brand.loop"]);
+        let error = &document.variables["VariableID:Semantic/error"];
+        assert_eq!(error.resolved_type, VariableResolvedDataType::Color);
+        let semantic = &document.variable_collections[&error.variable_collection_id];
+        assert_eq!(
+            error.values_by_mode[&semantic.default_mode_id],
+            alias("VariableID:Brand/danger")
+        );
+        assert!(document.validate().is_empty());
+    }
+
+    #[test]
+    fn failed_imports_leave_the_document_as_is() {
+        let mut document = document();
+        let before = document.clone();
+
+        let mismatch = json!({ "Theme": { "beta": { "$type": "color", "$value": "#fff" } } });
+        assert!(matches!(
+            import(&mut document, &mismatch),
+            Err(TokenError::TypeMismatch { .. })
+        ));
+        let dangling = json!({
+            "Theme": {
+                "fresh": { "$type": "color", "$value": "#fff" },
+                "link": { "$type": "color", "$value": "{Theme.missing}" },
+            },
+        });
+        assert!(matches!(
+            import(&mut document, &dangling),
+            Err(TokenError::UnknownAlias { .. })
+        ));
+        let invalid = json!({ "Theme": { "space": { "$type": "dimension", "$value": "2em" } } });
+        assert!(matches!(
+            import(&mut document, &invalid),
+            Err(TokenError::InvalidValue { .. })
+        ));
+        assert_eq!(document, before);
+    }
+}
@@ -0,0 +1,684 @@
+//! Referential integrity checks of documents.
+//!
+//! Many properties refer to other nodes or to definitions stored next to the node tree by id,
+//! nothing but this validator guarantees these exist. References are found in the serialized
+//! properties of the nodes, so they are checked wherever the types holding them are embedded.
+
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+
+use fig_types::node::vector::VectorNode;
+use fig_types::node::{Node, NodeType};
+use fig_types::validate::{Checker, Validate, Violation};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::document::Document;
+
+/// A problem found in a document.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Diagnostic {
+    /// Where the problem is: the ids of the nodes from the root down to the node, or for
+    /// definitions stored next to the tree, the registry and the id of the definition, e.g.
+    /// `["variables", "VariableID:1:2"]`.
+    pub path: Vec<String>,
+    /// Path of the property holding the problem, e.g. `fills[0].boundVariables.color`, empty
+    /// when the problem is with the node or definition itself.
+    pub property: String,
+    pub problem: Problem,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", tag = "kind")]
+pub enum Problem {
+    /// Another node has the same id.
+    #[serde(rename_all = "camelCase")]
+    DuplicateId {
+        id: String,
+        /// Path of the first node with this id.
+        first: Vec<String>,
+    },
+    /// A reference to something which does not exist.
+    #[serde(rename_all = "camelCase")]
+    DanglingReference { target: Target, id: String },
+}
+
+/// What a reference points to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum Target {
+    Node,
+    ComponentSet,
+    Style,
+    Variable,
+    VariableCollection,
+    VariableMode,
+    /// An entry of the fill override table of the node
+    FillOverride,
+}
+
+impl fmt::Display for Target {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Target::Node => "node",
+            Target::ComponentSet => "component set",
+            Target::Style => "style",
+            Target::Variable => "variable",
+            Target::VariableCollection => "variable collection",
+            Target::VariableMode => "variable mode",
+            Target::FillOverride => "fill override",
+        })
+    }
+}
+
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.path.join(" > "))?;
+        if !self.property.is_empty() {
+            write!(f, " `{}`", self.property)?;
+        }
+        match &self.problem {
+            Problem::DuplicateId { id, first } => {
+                write!(
+                    f,
+                    ": id `{id}` is already used by the node at {}",
+                    first.join(" > ")
+                )
+            }
+            Problem::DanglingReference { target, id } => {
+                write!(f, ": refers to {target} `{id}` which does not exist")
+            }
+        }
+    }
+}
+
+/// Checks that node ids are unique and that every reference points to an existing node or
+/// definition.
+pub fn validate(document: &Document) -> Vec<Diagnostic> {
+    let mut validator = Validator {
+        document,
+        nodes: HashMap::new(),
+        diagnostics: Vec::new(),
+    };
+    validator.index(&document.document, &mut Vec::new());
+    validator.nodes_references(&document.document, &mut Vec::new());
+    validator.definitions();
+    validator.diagnostics
+}
+
+impl Document {
+    /// Checks the referential integrity of the document, see [`validate`].
+    pub fn validate(&self) -> Vec<Diagnostic> {
+        validate(self)
+    }
+
+    /// The invariants of property values broken in the document, see [`Validate`].
+    pub fn invariants(&self) -> Vec<Violation> {
+        let mut checker = Checker::new(false);
+        checker.tree(&self.document);
+        for (id, style) in &self.styles {
+            checker.scope(&["styles", id], &mut style.clone());
+        }
+        for (id, variable) in &self.variables {
+            checker.scope(&["variables", id], &mut variable.clone());
+        }
+        checker.into_violations()
+    }
+
+    /// Repairs the property values breaking invariants, returning the broken invariants.
+    pub fn repair(&mut self) -> Vec<Violation> {
+        let mut checker = Checker::new(true);
+        self.document.check(&mut checker);
+        for (id, style) in &mut self.styles {
+            checker.scope(&["styles", id], style);
+        }
+        for (id, variable) in &mut self.variables {
+            checker.scope(&["variables", id], variable);
+        }
+        checker.into_violations()
+    }
+}
+
+struct Validator<'a> {
+    document: &'a Document,
+    /// Path of the first node with each id.
+    nodes: HashMap<&'a str, Vec<String>>,
+    diagnostics: Vec<Diagnostic>,
+}
+
+impl<'a> Validator<'a> {
+    fn index(&mut self, node: &'a Node, path: &mut Vec<String>) {
+        path.push(node.id.clone());
+        match self.nodes.get(node.id.as_str()) {
+            Some(first) => self.diagnostics.push(Diagnostic {
+                path: path.clone(),
+                property: String::new(),
+                problem: Problem::DuplicateId {
+                    id: node.id.clone(),
+                    first: first.clone(),
+                },
+            }),
+            None => {
+                self.nodes.insert(&node.id, path.clone());
+            }
+        }
+        for child in node.children() {
+            self.index(child, path);
+        }
+        path.pop();
+    }
+
+    fn exists(&self, target: Target, id: &str) -> bool {
+        let document = self.document;
+        match target {
+            Target::Node => self.nodes.contains_key(id),
+            Target::ComponentSet => document.component_sets.contains_key(id),
+            Target::Style => document.styles.contains_key(id),
+            Target::Variable => document.variables.contains_key(id),
+            Target::VariableCollection => document.variable_collections.contains_key(id),
+            // Modes and overrides are looked up where they are referenced.
+            Target::VariableMode | Target::FillOverride => true,
+        }
+    }
+
+    fn check(&mut self, path: &[String], property: &str, target: Target, id: &str) {
+        if !self.exists(target, id) {
+            self.dangling(path, property, target, id);
+        }
+    }
+
+    fn dangling(&mut self, path: &[String], property: &str, target: Target, id: &str) {
+        self.diagnostics.push(Diagnostic {
+            path: path.to_vec(),
+            property: property.to_string(),
+            problem: Problem::DanglingReference {
+                target,
+                id: id.to_string(),
+            },
+        });
+    }
+
+    fn nodes_references(&mut self, node: &Node, path: &mut Vec<String>) {
+        path.push(node.id.clone());
+        match &node.node {
+            NodeType::Vector(vector) => self.vector(vector, path),
+            NodeType::Rectangle(vector) => self.vector(vector, path),
+            NodeType::Document(_) | NodeType::Canvas(_) => {}
+        }
+        let mut properties = serde_json::to_value(&node.node).expect("nodes always serialize");
+        if let Some(Value::Object(data)) = properties.get_mut("data") {
+            data.remove("children");
+            // Style ids are the values of the `styles` map of the node.
+            if let Some(Value::Object(styles)) = data.get("styles") {
+                for (style_type, id) in styles {
+                    if let Some(id) = id.as_str() {
+                        self.check(path, &format!("styles.{style_type}"), Target::Style, id);
+                    }
+                }
+            }
+            self.references(
+                &Value::Object(std::mem::take(data)),
+                &mut String::new(),
+                path,
+            );
+        }
+        for child in node.children() {
+            self.nodes_references(child, path);
+        }
+        path.pop();
+    }
+
+    /// Checks that the paths of a vector node only use overrides of its fill override table.
+    fn vector<Data>(&mut self, vector: &VectorNode<Data>, path: &[String]) {
+        for (index, geometry) in vector.fill_geometry.iter().enumerate() {
+            let Some(id) = geometry.override_id else {
+                continue;
+            };
+            let overridden = vector
+                .fill_override_table
+                .as_ref()
+                .is_some_and(|table| table.contains_key(&id));
+            if !overridden {
+                let property = format!("fillGeometry[{index}].overrideId");
+                self.dangling(path, &property, Target::FillOverride, &id.to_string());
+            }
+        }
+    }
+
+    /// Checks the references found in serialized properties, wherever they are nested.
+    fn references(&mut self, value: &Value, property: &mut String, path: &[String]) {
+        match value {
+            Value::Object(object) => {
+                // Variable aliases are objects tagged with their type.
+                if object.contains_key("variableAliasType") {
+                    if let Some(Value::String(id)) = object.get("id") {
+                        self.check(path, property, Target::Variable, id);
+                    }
+                }
+                let url_link = object
+                    .get("hyperLinkType")
+                    .is_some_and(|link_type| link_type == "Url");
+                for (key, value) in object {
+                    let target = match key.as_str() {
+                        "transitionNodeId" | "endpointNodeId" | "destinationId" => {
+                            Some(Target::Node)
+                        }
+                        // Hyperlinks to URLs keep an empty node id.
+                        "nodeId" if !url_link => Some(Target::Node),
+                        "componentSetId" => Some(Target::ComponentSet),
+                        "inheritFillStyleId" => Some(Target::Style),
+                        _ => None,
+                    };
+                    let len = property.len();
+                    if !property.is_empty() {
+                        property.push('.');
+                    }
+                    property.push_str(key);
+                    match (target, value) {
+                        // Empty ids mean the reference is not set.
+                        (Some(target), Value::String(id)) if !id.is_empty() => {
+                            self.check(path, property, target, id)
+                        }
+                        _ => self.references(value, property, path),
+                    }
+                    property.truncate(len);
+                }
+            }
+            Value::Array(array) => {
+                for (index, value) in array.iter().enumerate() {
+                    let len = property.len();
+                    property.push_str(&format!("[{index}]"));
+                    self.references(value, property, path);
+                    property.truncate(len);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Checks the references between the definitions stored next to the node tree.
+    fn definitions(&mut self) {
+        let document = self.document;
+        for (id, component) in &document.components {
+            let path = ["components".to_string(), id.clone()];
+            // Remote components live in other files.
+            if !component.remote {
+                self.check(&path, "", Target::Node, id);
+            }
+            if !component.component_set_id.is_empty() {
+                let set = &component.component_set_id;
+                self.check(&path, "componentSetId", Target::ComponentSet, set);
+            }
+        }
+        for (id, collection) in &document.variable_collections {
+            let path = ["variableCollections".to_string(), id.clone()];
+            for (index, variable) in collection.variable_ids.iter().enumerate() {
+                let property = format!("variableIds[{index}]");
+                self.check(&path, &property, Target::Variable, variable);
+            }
+            let modes: HashSet<&str> = collection
+                .modes
+                .iter()
+                .map(|mode| mode.mode_id.as_str())
+                .collect();
+            if !modes.contains(collection.default_mode_id.as_str()) {
+                let mode = &collection.default_mode_id;
+                self.dangling(&path, "defaultModeId", Target::VariableMode, mode);
+            }
+        }
+        for (id, variable) in &document.variables {
+            let path = ["variables".to_string(), id.clone()];
+            let collection_id = &variable.variable_collection_id;
+            let Some(collection) = document.variable_collections.get(collection_id) else {
+                let target = Target::VariableCollection;
+                self.dangling(&path, "variableCollectionId", target, collection_id);
+                continue;
+            };
+            for (mode, value) in &variable.values_by_mode {
+                let property = format!("valuesByMode.{mode}");
+                if !collection.modes.iter().any(|known| known.mode_id == *mode) {
+                    self.dangling(&path, &property, Target::VariableMode, mode);
+                }
+                let value = serde_json::to_value(value).expect("variable values always serialize");
+                self.references(&value, &mut property.clone(), &path);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use fig_types::properties::PaintOverride;
+    use serde_json::json;
+
+    use super::*;
+    use crate::fixtures;
+
+    fn document(layers: Vec<Node>) -> Document {
+        let mut document = Document::new("Validate");
+        let page = &mut document.document.children_mut().unwrap()[0];
+        page.children_mut().unwrap().extend(layers);
+        document
+    }
+
+    /// The diagnostics of the document as they are displayed, sorted.
+    fn diagnostics(document: &Document) -> Vec<String> {
+        let mut diagnostics: Vec<String> = document
+            .validate()
+            .iter()
+            .map(ToString::to_string)
+            .collect();
+        diagnostics.sort();
+        diagnostics
+    }
+
+    fn alias(id: &str) -> Value {
+        json!({ "variableAliasType": "VARIABLE_ALIAS", "id": id })
+    }
+
+    /// A theme collection with a light and a dark mode, holding `VariableID:1:1`.
+    fn themed(document: &mut Document) {
+        document.variable_collections.insert(
+            "VariableCollectionId:1:0".to_string(),
+            serde_json::from_value(json!({
+                "id": "VariableCollectionId:1:0",
+                "name": "Theme",
+                "key": "",
+                "modes": [
+                    { "modeId": "1:0", "name": "Light" },
+                    { "modeId": "1:1", "name": "Dark" },
+                ],
+                "defaultModeId": "1:0",
+                "remote": false,
+                "hiddenFromPublishing": false,
+                "variableIds": ["VariableID:1:1"],
+            }))
+            .unwrap(),
+        );
+        variable(
+            document,
+            "VariableID:1:1",
+            "VariableCollectionId:1:0",
+            json!({}),
+        );
+    }
+
+    fn variable(document: &mut Document, id: &str, collection: &str, values_by_mode: Value) {
+        document.variables.insert(
+            id.to_string(),
+            serde_json::from_value(json!({
+                "id": id,
+                "name": "color/primary",
+                "key": "",
+                "variableCollectionId": collection,
+                "resolvedType": "Color",
+                "valuesByMode": values_by_mode,
+                "remote": false,
+                "description": "",
+                "hiddenFromPublishing": false,
+                "scopes": [],
+                "codeSyntax": {},
+            }))
+            .unwrap(),
+        );
+    }
+
+    /// Sets the fill override table of a vector layer, from override ids and inherited style ids.
+    fn override_fills(layer: &mut Node, overrides: &[(i32, &str)]) {
+        let NodeType::Vector(vector) = &mut layer.node else {
+            panic!("{} is not a vector", layer.id);
+        };
+        let table = overrides.iter().map(|(id, style)| {
+            let paint = PaintOverride {
+                fills: Vec::new(),
+                inherit_fill_style_id: style.to_string(),
+            };
+            (*id, paint)
+        });
+        vector.fill_override_table = Some(table.collect());
+    }
+
+    #[test]
+    fn duplicate_ids_point_to_the_first_node() {
+        let document = document(vec![
+            fixtures::vector("1:1", "First"),
+            fixtures::vector("1:1", "Second"),
+        ]);
+        assert_eq!(
+            diagnostics(&document),
+            ["0:0 > 0:1 > 1:1: id `1:1` is already used by the node at 0:0 > 0:1 > 1:1"]
+        );
+    }
+
+    #[test]
+    fn references_of_layers_are_checked() {
+        let mut fill = fixtures::solid(1.0, 0.0, 0.0);
+        fill["boundVariables"] = json!({ "color": alias("VariableID:9:9") });
+        let mut layer = fixtures::layer(
+            "vector",
+            "1:2",
+            "Button",
+            json!({
+                "transitionNodeId": "9:9",
+                "fills": [fill],
+                "styles": { "Fill": "S:9", "Effect": "S:1" },
+            }),
+        );
+        override_fills(&mut layer, &[(1, "S:8"), (2, "")]);
+        let linked = fixtures::layer(
+            "vector",
+            "1:3",
+            "Link",
+            json!({ "transitionNodeId": "1:2" }),
+        );
+        let mut document = document(vec![layer, linked]);
+        document.styles.insert(
+            "S:1".to_string(),
+            serde_json::from_value(json!({
+                "key": "",
+                "name": "Shadow",
+                "description": "",
+                "remote": false,
+                "styleType": "Effect",
+            }))
+            .unwrap(),
+        );
+        assert_eq!(
+            diagnostics(&document),
+            [
+                "0:0 > 0:1 > 1:2 `fillOverrideTable.1.inheritFillStyleId`: refers to style `S:8` \
+                 which does not exist",
+                "0:0 > 0:1 > 1:2 `fills[0].boundVariables.color`: refers to variable \
+                 `VariableID:9:9` which does not exist",
+                "0:0 > 0:1 > 1:2 `styles.Fill`: refers to style `S:9` which does not exist",
+                "0:0 > 0:1 > 1:2 `transitionNodeId`: refers to node `9:9` which does not exist",
+            ]
+        );
+    }
+
+    #[test]
+    fn references_are_found_wherever_they_are_nested() {
+        let document = document(vec![fixtures::vector("1:1", "Frame")]);
+        let mut validator = Validator {
+            document: &document,
+            nodes: HashMap::new(),
+            diagnostics: Vec::new(),
+        };
+        validator.index(&document.document, &mut Vec::new());
+        let properties = json!({
+            "connectors": [{ "endpointNodeId": "9:1" }, { "endpointNodeId": "1:1" }],
+            "interactions": [{ "action": { "destinationId": "9:2" } }],
+            "instance": { "componentSetId": "9:3" },
+            "links": [
+                { "hyperLinkType": "Url", "url": "https://example.com", "nodeId": "" },
+                { "hyperLinkType": "Url", "url": "https://example.com", "nodeId": "9:4" },
+                { "hyperLinkType": "Node", "url": "", "nodeId": "9:5" },
+                { "hyperLinkType": "Node", "url": "", "nodeId": "" },
+            ],
+        });
+        let path = ["1:1".to_string()];
+        validator.references(&properties, &mut String::new(), &path);
+        let diagnostics: Vec<(&str, &Problem)> = validator
+            .diagnostics
+            .iter()
+            .map(|diagnostic| (diagnostic.property.as_str(), &diagnostic.problem))
+            .collect();
+        let dangling = |target, id: &str| Problem::DanglingReference {
+            target,
+            id: id.to_string(),
+        };
+        assert_eq!(
+            diagnostics,
+            [
+                (
+                    "connectors[0].endpointNodeId",
+                    &dangling(Target::Node, "9:1")
+                ),
+                (
+                    "instance.componentSetId",
+                    &dangling(Target::ComponentSet, "9:3")
+                ),
+                (
+                    "interactions[0].action.destinationId",
+                    &dangling(Target::Node, "9:2")
+                ),
+                ("links[2].nodeId", &dangling(Target::Node, "9:5")),
+            ]
+        );
+    }
+
+    #[test]
+    fn fill_geometry_uses_the_fill_override_table() {
+        let path = |override_id: Option<i32>| json!({ "path": "M0 0L100 0L100 40Z", "windingRule": "NONZERO", "overrideId": override_id });
+        let mut overridden = fixtures::layer(
+            "vector",
+            "1:1",
+            "Overridden",
+            json!({
+                "fillGeometry": [path(None), path(Some(1)), path(Some(2))],
+            }),
+        );
+        override_fills(&mut overridden, &[(1, "")]);
+        let plain = fixtures::layer(
+            "vector",
+            "1:2",
+            "Plain",
+            json!({ "fillGeometry": [path(Some(1))] }),
+        );
+        assert_eq!(
+            diagnostics(&document(vec![overridden, plain])),
+            [
+                "0:0 > 0:1 > 1:1 `fillGeometry[2].overrideId`: refers to fill override `2` \
+                 which does not exist",
+                "0:0 > 0:1 > 1:2 `fillGeometry[0].overrideId`: refers to fill override `1` \
+                 which does not exist",
+            ]
+        );
+    }
+
+    #[test]
+    fn variables_use_the_modes_of_their_collection() {
+        let mut document = document(Vec::new());
+        themed(&mut document);
+        let collection = document
+            .variable_collections
+            .get_mut("VariableCollectionId:1:0")
+            .unwrap();
+        collection.variable_ids.push("VariableID:9:9".to_string());
+        variable(
+            &mut document,
+            "VariableID:1:2",
+            "VariableCollectionId:1:0",
+            json!({ "1:0": alias("VariableID:1:1"), "1:1": alias("VariableID:9:9"), "2:0": 1.0 }),
+        );
+        variable(
+            &mut document,
+            "VariableID:1:3",
+            "VariableCollectionId:9:9",
+            json!({}),
+        );
+        assert_eq!(
+            diagnostics(&document),
+            [
+                "variableCollections > VariableCollectionId:1:0 `variableIds[1]`: refers to \
+                 variable `VariableID:9:9` which does not exist",
+                "variables > VariableID:1:2 `valuesByMode.1:1`: refers to variable \
+                 `VariableID:9:9` which does not exist",
+                "variables > VariableID:1:2 `valuesByMode.2:0`: refers to variable mode `2:0` \
+                 which does not exist",
+                "variables > VariableID:1:3 `variableCollectionId`: refers to variable \
+                 collection `VariableCollectionId:9:9` which does not exist",
+            ]
+        );
+    }
+
+    #[test]
+    fn default_modes_belong_to_their_collection() {
+        let mut document = document(Vec::new());
+        themed(&mut document);
+        let collection = document
+            .variable_collections
+            .get_mut("VariableCollectionId:1:0")
+            .unwrap();
+        collection.default_mode_id = "2:0".to_string();
+        assert_eq!(
+            diagnostics(&document),
+            [
+                "variableCollections > VariableCollectionId:1:0 `defaultModeId`: refers to \
+                 variable mode `2:0` which does not exist"
+            ]
+        );
+    }
+
+    #[test]
+    fn local_components_have_a_node_and_a_set() {
+        let mut document = document(vec![fixtures::vector("1:1", "Button")]);
+        let component = |component_set_id: &str, remote: bool| {
+            serde_json::from_value(json!({
+                "key": "",
+                "name": "Button",
+                "description": "",
+                "componentSetId": component_set_id,
+                "documentationLinks": [],
+                "remote": remote,
+            }))
+            .unwrap()
+        };
+        document
+            .components
+            .insert("1:1".to_string(), component("9:1", false));
+        document
+            .components
+            .insert("9:2".to_string(), component("", false));
+        document
+            .components
+            .insert("9:3".to_string(), component("", true));
+        assert_eq!(
+            diagnostics(&document),
+            [
+                "components > 1:1 `componentSetId`: refers to component set `9:1` which does \
+                 not exist",
+                "components > 9:2: refers to node `9:2` which does not exist",
+            ]
+        );
+    }
+
+    #[test]
+    fn invariants_are_reported_without_repairing_the_document() {
+        let layer = fixtures::layer("vector", "1:1", "Faded", json!({ "opacity": 2.0 }));
+        let mut document = document(vec![layer]);
+        let original = document.clone();
+        let violations = document.invariants();
+        assert_eq!(document, original);
+        assert_eq!(
+            violations
+                .iter()
+                .map(ToString::to_string)
+                .collect::<Vec<_>>(),
+            ["0:0 > 0:1 > 1:1 `opacity`: must be between 0 and 1, got 2"]
+        );
+        assert_eq!(document.repair(), violations);
+        assert!(document.invariants().is_empty());
+    }
+}
@@ -0,0 +1,110 @@
+//! Property values formatted for people and for code: numbers, lengths and colors, and the radii
+//! and stroke weights of layers, whose individual values only apply when they differ.
+
+use fig_types::node::vector::{RectangleData, VectorNode};
+use fig_types::properties::Color;
+use serde_json::Value;
+
+/// A number rounded to two decimals, without trailing zeros.
+pub fn number(value: f32) -> String {
+    let value = (f64::from(value) * 100.0).round() / 100.0;
+    // Avoids printing negative zeros.
+    format!("{}", value + 0.0)
+}
+
+/// A length in pixels, e.g. `12.5px`.
+pub fn px(value: f32) -> String {
+    format!("{}px", number(value))
+}
+
+/// A float as the shortest decimal reading back to it, for values kept exactly instead of rounded
+/// like [`number`].
+pub fn exact(value: f32) -> f64 {
+    value.to_string().parse().unwrap_or(f64::NAN)
+}
+
+/// A length in pixels kept exactly, e.g. `0.125px`.
+pub fn exact_px(value: f32) -> String {
+    format!("{value}px")
+}
+
+/// A color channel, or an opacity, as a byte.
+pub fn channel(value: f32) -> u8 {
+    (value.clamp(0.0, 1.0) * 255.0).round() as u8
+}
+
+/// A color as `#rrggbb` when it is opaque and as `#rrggbbaa` otherwise.
+pub fn hex(color: &Color, opacity: f32) -> String {
+    let hex = format!(
+        "#{:02x}{:02x}{:02x}",
+        channel(color.r),
+        channel(color.g),
+        channel(color.b)
+    );
+    let alpha = color.a * opacity;
+    match alpha < 1.0 {
+        true => format!("{hex}{:02x}", channel(alpha)),
+        false => hex,
+    }
+}
+
+/// The radius of the top left, top right, bottom right and bottom left corners of a rectangle.
+pub fn corner_radii(data: &RectangleData) -> [f32; 4] {
+    match data
+        .rectangle_corner_radii
+        .iter()
+        .any(|radius| *radius > 0.0)
+    {
+        true => data.rectangle_corner_radii,
+        false => [data.corner_radius; 4],
+    }
+}
+
+/// The stroke weight of the top, right, bottom and left sides of a layer.
+pub fn stroke_weights<Data>(vector: &VectorNode<Data>) -> [f32; 4] {
+    let weights = &vector.individual_stroke_weights;
+    let sides = [weights.top, weights.right, weights.bottom, weights.left];
+    match sides.iter().any(|side| *side > 0.0) {
+        true => sides,
+        false => [vector.stroke_weight; 4],
+    }
+}
+
+/// A property value as JSON, or `(unset)` for a property without a value, as listed in reports.
+pub fn property(value: Option<&Value>) -> String {
+    match value {
+        Some(value) => value.to_string(),
+        None => "(unset)".to_string(),
+    }
+}
+
+/// Whether the four values of corners or sides are the same.
+pub fn uniform(values: [f32; 4]) -> bool {
+    values.iter().all(|value| *value == values[0])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn values_are_formatted() {
+        assert_eq!(number(1.0 / 3.0), "0.33");
+        assert_eq!(number(-0.001), "0");
+        assert_eq!(px(12.5), "12.5px");
+        assert_eq!(exact(0.1), 0.1);
+        assert_eq!(exact_px(1.0 / 8.0), "0.125px");
+        let color = Color {
+            r: 1.0,
+            g: 0.5,
+            b: 0.0,
+            a: 1.0,
+        };
+        assert_eq!(hex(&color, 1.0), "#ff8000");
+        assert_eq!(hex(&color, 0.5), "#ff800080");
+        assert_eq!(property(Some(&Value::from(0.5))), "0.5");
+        assert_eq!(property(None), "(unset)");
+        assert!(uniform([2.0; 4]));
+        assert!(!uniform([2.0, 2.0, 0.0, 2.0]));
+    }
+}
@@ -0,0 +1,720 @@
+//! Version history: named and automatic checkpoints of a document, stored in the document itself.
+//!
+//! The latest version holds a full snapshot of the tree and definitions of the document. Every
+//! earlier version holds the delta turning the version after it back into it: the edit operations
+//! to apply to the tree and the definitions to set or remove. A version is rebuilt by applying the
+//! deltas from the latest version back to it.
+
+use std::collections::{BTreeMap, HashSet};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use fig_types::node::Node;
+use fig_types::patch::{self, Patch, PatchOperation, PropertyPath};
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+use crate::document::Document;
+use crate::edit::{EditError, Operation};
+
+#[derive(Debug, thiserror::Error)]
+pub enum VersionError {
+    #[error("no version with id {0}")]
+    VersionNotFound(u32),
+    #[error("no node with id `{node}` in version {version}")]
+    NodeNotFound { version: u32, node: String },
+    #[error("the parent of node `{0}` is not in the document anymore")]
+    ParentNotFound(String),
+    #[error("the root node cannot be restored on its own, restore the whole version instead")]
+    Root,
+    #[error("corrupted version history: the latest version has no snapshot")]
+    MissingSnapshot,
+    #[error("corrupted version history: {0}")]
+    Edit(#[from] EditError),
+    #[error("corrupted version history: {0}")]
+    Json(#[from] serde_json::Error),
+}
+
+/// A checkpoint of a document.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Version {
+    /// Identifies the version in its document, increasing with each checkpoint.
+    pub id: u32,
+    /// Name of a named checkpoint, `None` for automatic checkpoints.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    pub author: String,
+    /// Seconds since the Unix epoch.
+    pub timestamp: u64,
+    #[serde(default)]
+    pub description: String,
+    state: State,
+}
+
+impl Version {
+    /// Whether the checkpoint was taken automatically rather than named by someone.
+    pub fn is_automatic(&self) -> bool {
+        self.name.is_none()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", tag = "state")]
+enum State {
+    /// The whole content of the document, held by the latest version and by the versions whose
+    /// tree cannot be reached from the next one with edit operations.
+    Snapshot(Box<Content>),
+    /// The changes turning the content of the next version into this one.
+    Delta(Delta),
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Content {
+    document: Node,
+    /// The definitions of the document by field, e.g. `styles`, and by id.
+    definitions: BTreeMap<String, Map<String, Value>>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Delta {
+    operations: Vec<Operation>,
+    /// The definitions to set, or to remove when `None`, by field and id.
+    definitions: BTreeMap<String, BTreeMap<String, Option<Value>>>,
+}
+
+/// Which versions [`Document::prune_versions`] keeps.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PrunePolicy {
+    /// Number of most recent automatic checkpoints kept.
+    pub keep_automatic: usize,
+    /// Versions older than this are removed, named checkpoints only if `prune_named` is set.
+    pub max_age: Option<Duration>,
+    pub prune_named: bool,
+}
+
+impl Default for PrunePolicy {
+    fn default() -> Self {
+        Self {
+            keep_automatic: 20,
+            max_age: None,
+            prune_named: false,
+        }
+    }
+}
+
+impl Document {
+    /// Checkpoints the current content of the document as a new version, named unless `name` is
+    /// `None`. Automatic checkpoints are skipped when nothing changed since the latest version.
+    /// Returns the id of the new version.
+    pub fn checkpoint(
+        &mut self,
+        name: Option<&str>,
+        author: &str,
+        description: &str,
+    ) -> Result<Option<u32>, VersionError> {
+        let content = self.content()?;
+        let latest = self.versions.last_mut();
+        if let Some(State::Snapshot(previous)) = latest.as_ref().map(|latest| &latest.state) {
+            if name.is_none() && **previous == content {
+                return Ok(None);
+            }
+        }
+        let id = latest.as_ref().map_or(1, |latest| latest.id + 1);
+        if let Some(latest) = latest {
+            if let State::Snapshot(previous) = &latest.state {
+                if let Some(delta) = delta(&content, previous) {
+                    latest.state = State::Delta(delta);
+                }
+            }
+        }
+        self.versions.push(Version {
+            id,
+            name: name.map(str::to_string),
+            author: author.to_string(),
+            timestamp: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map_or(0, |elapsed| elapsed.as_secs()),
+            description: description.to_string(),
+            state: State::Snapshot(Box::new(content)),
+        });
+        Ok(Some(id))
+    }
+
+    /// The document as it was at a version, without version history.
+    pub fn version(&self, id: u32) -> Result<Document, VersionError> {
+        let mut document = Document::new("");
+        document.set_content(self.content_at(id)?)?;
+        Ok(document)
+    }
+
+    /// Restores the tree and definitions of the document to a version. The version history is
+    /// kept, checkpoint first to be able to come back to the current content.
+    pub fn restore_version(&mut self, id: u32) -> Result<(), VersionError> {
+        let content = self.content_at(id)?;
+        self.set_content(content)
+    }
+
+    /// Restores a node and its subtree as they were at a version. The node stays where it is,
+    /// or goes back to its place in the version if it was deleted since. Nodes of the restored
+    /// subtree moved elsewhere since are moved back into it.
+    pub fn restore_node(&mut self, version: u32, id: &str) -> Result<(), VersionError> {
+        let content = self.content_at(version)?;
+        let node = content
+            .document
+            .find(id)
+            .ok_or_else(|| VersionError::NodeNotFound {
+                version,
+                node: id.to_string(),
+            })?;
+        let place = parent(&self.document, id).or_else(|| parent(&content.document, id));
+        let Some((parent, index)) = place else {
+            return Err(VersionError::Root);
+        };
+        let mut root = self.document.clone();
+        for id in ids(node) {
+            if root.find(id).is_some() {
+                Operation::Delete { id: id.to_string() }.apply(&mut root)?;
+            }
+        }
+        let len = match root.find(&parent).map(Node::children) {
+            Some(children) => children.len(),
+            None => return Err(VersionError::ParentNotFound(id.to_string())),
+        };
+        let insert = Operation::Insert {
+            parent,
+            index: index.min(len),
+            node: Box::new(node.clone()),
+        };
+        insert.apply(&mut root)?;
+        self.document = root;
+        Ok(())
+    }
+
+    /// Removes the versions the policy does not keep, and returns their ids.
+    pub fn prune_versions(&mut self, policy: &PrunePolicy) -> Result<Vec<u32>, VersionError> {
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |elapsed| elapsed.as_secs());
+        let max_age = policy.max_age.map(|max_age| max_age.as_secs());
+        let mut automatic = 0;
+        let mut pruned = Vec::new();
+        let mut kept = Vec::new();
+        let contents = self.contents()?;
+        let versions = std::mem::take(&mut self.versions);
+        for (version, content) in versions.into_iter().zip(contents).rev() {
+            let old =
+                max_age.is_some_and(|max_age| now.saturating_sub(version.timestamp) > max_age);
+            let keep = match version.is_automatic() {
+                true => {
+                    automatic += 1;
+                    automatic <= policy.keep_automatic && !old
+                }
+                false => !(old && policy.prune_named),
+            };
+            match keep {
+                true => kept.push((version, content)),
+                false => pruned.push(version.id),
+            }
+        }
+        kept.reverse();
+        pruned.reverse();
+        self.versions = rebuild(kept);
+        Ok(pruned)
+    }
+
+    fn content(&self) -> Result<Content, serde_json::Error> {
+        let definitions = [
+            ("components", serde_json::to_value(&self.components)?),
+            ("componentSets", serde_json::to_value(&self.component_sets)?),
+            ("styles", serde_json::to_value(&self.styles)?),
+            ("variables", serde_json::to_value(&self.variables)?),
+            (
+                "variableCollections",
+                serde_json::to_value(&self.variable_collections)?,
+            ),
+            ("devSnapshots", serde_json::to_value(&self.dev_snapshots)?),
+        ];
+        Ok(Content {
+            document: self.document.clone(),
+            definitions: definitions
+                .into_iter()
+                .filter_map(|(field, value)| match value {
+                    Value::Object(map) => Some((field.to_string(), map)),
+                    _ => None,
+                })
+                .collect(),
+        })
+    }
+
+    fn set_content(&mut self, content: Content) -> Result<(), VersionError> {
+        let Content {
+            document,
+            mut definitions,
+        } = content;
+        let mut take = |field: &str| Value::Object(definitions.remove(field).unwrap_or_default());
+        self.components = serde_json::from_value(take("components"))?;
+        self.component_sets = serde_json::from_value(take("componentSets"))?;
+        self.styles = serde_json::from_value(take("styles"))?;
+        self.variables = serde_json::from_value(take("variables"))?;
+        self.variable_collections = serde_json::from_value(take("variableCollections"))?;
+        self.dev_snapshots = serde_json::from_value(take("devSnapshots"))?;
+        self.document = document;
+        Ok(())
+    }
+
+    /// The content of a version.
+    fn content_at(&self, id: u32) -> Result<Content, VersionError> {
+        let position = self
+            .versions
+            .iter()
+            .position(|version| version.id == id)
+            .ok_or(VersionError::VersionNotFound(id))?;
+        let mut content = None;
+        for version in self.versions[position..].iter().rev() {
+            content = Some(version.state.apply(content)?);
+        }
+        Ok(content.expect("the version exists"))
+    }
+
+    /// The content of all versions, in order.
+    fn contents(&self) -> Result<Vec<Content>, VersionError> {
+        let mut contents: Vec<Content> = Vec::with_capacity(self.versions.len());
+        for version in self.versions.iter().rev() {
+            contents.push(version.state.apply(contents.last().cloned())?);
+        }
+        contents.reverse();
+        Ok(contents)
+    }
+}
+
+impl State {
+    /// The content of the version holding this state, from the content of the next version.
+    fn apply(&self, next: Option<Content>) -> Result<Content, VersionError> {
+        let (delta, mut content) = match (self, next) {
+            (State::Snapshot(content), _) => return Ok((**content).clone()),
+            (State::Delta(delta), Some(next)) => (delta, next),
+            // Only the latest version has no next version, and it holds a snapshot.
+            (State::Delta(_), None) => return Err(VersionError::MissingSnapshot),
+        };
+        for operation in &delta.operations {
+            operation.apply(&mut content.document)?;
+        }
+        for (field, changes) in &delta.definitions {
+            let definitions = content.definitions.entry(field.clone()).or_default();
+            for (id, definition) in changes {
+                match definition {
+                    Some(definition) => definitions.insert(id.clone(), definition.clone()),
+                    None => definitions.remove(id),
+                };
+            }
+        }
+        Ok(content)
+    }
+}
+
+/// The versions of the two sides of a merge: the versions of ours followed by the versions theirs
+/// checkpointed since the base, renumbered to follow the latest version of ours.
+pub(crate) fn merge_versions(
+    base: &Document,
+    ours: &Document,
+    theirs: &Document,
+) -> Result<Vec<Version>, VersionError> {
+    let since = base.versions.last().map_or(0, |latest| latest.id);
+    let Some(first) = theirs
+        .versions
+        .iter()
+        .position(|version| version.id > since)
+    else {
+        return Ok(ours.versions.clone());
+    };
+    let mut versions: Vec<(Version, Content)> = ours
+        .versions
+        .iter()
+        .cloned()
+        .zip(ours.contents()?)
+        .collect();
+    let mut id = ours.versions.last().map_or(0, |latest| latest.id);
+    let contents = theirs.contents()?.into_iter().skip(first);
+    for (mut version, content) in theirs.versions[first..].iter().cloned().zip(contents) {
+        id += 1;
+        version.id = id;
+        versions.push((version, content));
+    }
+    Ok(rebuild(versions))
+}
+
+/// The states of versions from their content, a snapshot for the latest and deltas before.
+fn rebuild(versions: Vec<(Version, Content)>) -> Vec<Version> {
+    let mut rebuilt: Vec<Version> = Vec::with_capacity(versions.len());
+    let mut next: Option<Content> = None;
+    for (mut version, content) in versions.into_iter().rev() {
+        version.state = match next.as_ref().and_then(|next| delta(next, &content)) {
+            Some(delta) => State::Delta(delta),
+            None => State::Snapshot(Box::new(content.clone())),
+        };
+        rebuilt.push(version);
+        next = Some(content);
+    }
+    rebuilt.reverse();
+    rebuilt
+}
+
+/// The delta turning `next` into `content`, `None` when the trees cannot be turned into one
+/// another with edit operations, e.g. when a node changed type.
+fn delta(next: &Content, content: &Content) -> Option<Delta> {
+    let operations = operations(&next.document, &content.document)?;
+    let mut definitions = BTreeMap::new();
+    let empty = Map::new();
+    let fields: HashSet<&String> = next
+        .definitions
+        .keys()
+        .chain(content.definitions.keys())
+        .collect();
+    for field in fields {
+        let before = next.definitions.get(field).unwrap_or(&empty);
+        let after = content.definitions.get(field).unwrap_or(&empty);
+        let mut changes = BTreeMap::new();
+        for (id, definition) in after {
+            if before.get(id) != Some(definition) {
+                changes.insert(id.clone(), Some(definition.clone()));
+            }
+        }
+        for id in before.keys().filter(|id| !after.contains_key(*id)) {
+            changes.insert(id.clone(), None);
+        }
+        if !changes.is_empty() {
+            definitions.insert(field.clone(), changes);
+        }
+    }
+    Some(Delta {
+        operations,
+        definitions,
+    })
+}
+
+/// Edit operations turning the tree `from` into the tree `to`, `None` when there are none. They are
+/// applied to a copy of `from` as they are found, so each one is valid where it is in the list.
+fn operations(from: &Node, to: &Node) -> Option<Vec<Operation>> {
+    if from.id != to.id {
+        return None;
+    }
+    let mut tree = from.clone();
+    let mut operations = Vec::new();
+    let mut apply = |operation: Operation, tree: &mut Node| {
+        operation.apply(tree).ok()?;
+        operations.push(operation);
+        Some(())
+    };
+    // Parents come before their children, so each node has its parent in place when reached.
+    let mut nodes = Vec::new();
+    preorder(to, None, &mut nodes);
+    for &(node, target) in &nodes {
+        let Some(current) = tree.find(&node.id) else {
+            let mut node = node.clone();
+            if let Some(children) = node.children_mut() {
+                children.clear();
+            }
+            let parent = target.expect("the roots have the same id");
+            let index = children(&tree, parent).len();
+            let node = Box::new(node);
+            apply(
+                Operation::Insert {
+                    parent: parent.to_string(),
+                    index,
+                    node,
+                },
+                &mut tree,
+            )?;
+            continue;
+        };
+        let patch = between(current, node);
+        if !patch.operations.is_empty() {
+            apply(Operation::Patch(patch), &mut tree)?;
+        }
+        if let Some(target) = target {
+            if parent(&tree, &node.id).map(|(parent, _)| parent).as_deref() != Some(target) {
+                let index = children(&tree, target).len();
+                let (id, parent) = (node.id.clone(), target.to_string());
+                apply(Operation::Move { id, parent, index }, &mut tree)?;
+            }
+        }
+    }
+    let kept: HashSet<&str> = nodes.iter().map(|(node, _)| node.id.as_str()).collect();
+    let mut added = Vec::new();
+    unkept(&tree, &kept, &mut added);
+    for id in added {
+        apply(Operation::Delete { id }, &mut tree)?;
+    }
+    for &(node, _) in &nodes {
+        let order: Vec<String> = node
+            .children()
+            .iter()
+            .map(|child| child.id.clone())
+            .collect();
+        let current = children(&tree, &node.id).iter().map(|child| &child.id);
+        if !order.is_empty() && !current.eq(order.iter()) {
+            apply(
+                Operation::Reorder {
+                    parent: node.id.clone(),
+                    order,
+                },
+                &mut tree,
+            )?;
+        }
+    }
+    (tree == *to).then_some(operations)
+}
+
+/// The patch replacing the properties of `from` which differ in `to`.
+fn between(from: &Node, to: &Node) -> Patch {
+    let (from_properties, to_properties) = (patch::properties(from), patch::properties(to));
+    let mut operations = Vec::new();
+    let keys: HashSet<&String> = from_properties.keys().chain(to_properties.keys()).collect();
+    for key in keys {
+        // Unset optional properties serialize to null.
+        let get = |properties: &Map<String, Value>| {
+            properties
+                .get(key)
+                .filter(|value| !value.is_null())
+                .cloned()
+        };
+        let (before, after) = (get(&from_properties), get(&to_properties));
+        if before == after {
+            continue;
+        }
+        let path: PropertyPath = key.parse().expect("property names are paths");
+        operations.push(match (before, after) {
+            (_, None) => PatchOperation::Remove { path },
+            (None, Some(value)) => PatchOperation::Insert { path, value },
+            (Some(_), Some(value)) => PatchOperation::Set { path, value },
+        });
+    }
+    operations.sort_by_key(|operation| match operation {
+        PatchOperation::Set { path, .. }
+        | PatchOperation::Insert { path, .. }
+        | PatchOperation::Remove { path } => path.to_string(),
+    });
+    Patch {
+        id: from.id.clone(),
+        operations,
+    }
+}
+
+fn preorder<'a>(
+    node: &'a Node,
+    parent: Option<&'a str>,
+    nodes: &mut Vec<(&'a Node, Option<&'a str>)>,
+) {
+    nodes.push((node, parent));
+    for child in node.children() {
+        preorder(child, Some(&node.id), nodes);
+    }
+}
+
+/// The ids of the topmost nodes of a tree which are not kept.
+fn unkept(node: &Node, kept: &HashSet<&str>, ids: &mut Vec<String>) {
+    for child in node.children() {
+        match kept.contains(child.id.as_str()) {
+            true => unkept(child, kept, ids),
+            false => ids.push(child.id.clone()),
+        }
+    }
+}
+
+fn children<'a>(root: &'a Node, id: &str) -> &'a [Node] {
+    root.find(id).map_or(&[], Node::children)
+}
+
+/// The id of the parent of a node and the index of the node in its children.
+fn parent(root: &Node, id: &str) -> Option<(String, usize)> {
+    match root.children().iter().position(|child| child.id == id) {
+        Some(index) => Some((root.id.clone(), index)),
+        None => root.children().iter().find_map(|child| parent(child, id)),
+    }
+}
+
+/// Ids of a node and all its descendants.
+fn ids(node: &Node) -> Box<dyn Iterator<Item = &str> + '_> {
+    Box::new(std::iter::once(node.id.as_str()).chain(node.children().iter().flat_map(ids)))
+}
+
+#[cfg(test)]
+mod tests {
+    use fig_types::node::NodeType;
+    use fig_types::properties::{Style, StyleType};
+
+    use super::*;
+    use crate::document::Format;
+    use crate::fixtures;
+
+    fn document() -> Document {
+        let mut document = Document::new("Versions");
+        let pages = document.document.children_mut().unwrap();
+        let mut page = pages[0].clone();
+        *pages[0].children_mut().unwrap() = ["1:1", "1:2", "1:3"]
+            .map(|id| fixtures::vector(id, &format!("Layer {id}")))
+            .to_vec();
+        page.id = "0:2".to_string();
+        pages.push(page);
+        document
+    }
+
+    fn children<'a>(document: &'a mut Document, id: &str) -> &'a mut Vec<Node> {
+        document
+            .document
+            .find_mut(id)
+            .unwrap()
+            .children_mut()
+            .unwrap()
+    }
+
+    fn style(name: &str) -> Style {
+        Style {
+            key: name.to_string(),
+            name: name.to_string(),
+            description: String::new(),
+            remote: false,
+            style_type: StyleType::Fill,
+            value: None,
+        }
+    }
+
+    /// Edits touching every kind of change: properties, additions, deletions, moves, reorders
+    /// and definitions.
+    fn edit(document: &mut Document) {
+        document.document.find_mut("1:1").unwrap().name = "Title".to_string();
+        let NodeType::Vector(vector) = &mut document.document.find_mut("1:3").unwrap().node else {
+            unreachable!();
+        };
+        vector.opacity = 0.5;
+        vector.transition_node_id = Some("1:1".to_string());
+        let moved = children(document, "0:1").remove(1);
+        children(document, "0:2").push(moved);
+        children(document, "0:1").reverse();
+        children(document, "0:1").push(fixtures::vector("1:4", "Added"));
+        document.styles.remove("S:1");
+        document.styles.insert("S:2".to_string(), style("Added"));
+    }
+
+    fn same_content(document: &Document, other: &Document) -> bool {
+        document.content().unwrap() == other.content().unwrap()
+    }
+
+    #[test]
+    fn earlier_versions_are_stored_as_deltas_and_restored() {
+        let mut document = document();
+        document.styles.insert("S:1".to_string(), style("Primary"));
+        let original = document.clone();
+        assert_eq!(
+            document
+                .checkpoint(Some("v1"), "Ada", "Handed to dev")
+                .unwrap(),
+            Some(1)
+        );
+        edit(&mut document);
+        let edited = document.clone();
+        assert_eq!(document.checkpoint(None, "Ada", "").unwrap(), Some(2));
+        // Nothing changed since the latest version.
+        assert_eq!(document.checkpoint(None, "Ada", "").unwrap(), None);
+        assert!(matches!(document.versions[0].state, State::Delta(_)));
+        assert!(matches!(document.versions[1].state, State::Snapshot(_)));
+
+        // Versions survive saving and reading the document.
+        let mut bytes = Vec::new();
+        document.write(&mut bytes, Format::Json).unwrap();
+        let mut document = Document::read(bytes.as_slice()).unwrap();
+        assert!(same_content(&document.version(1).unwrap(), &original));
+        assert!(same_content(&document.version(2).unwrap(), &edited));
+        let version = &document.versions[0];
+        assert_eq!(
+            (
+                version.name.as_deref(),
+                version.author.as_str(),
+                version.description.as_str()
+            ),
+            (Some("v1"), "Ada", "Handed to dev")
+        );
+        assert!(!version.is_automatic() && document.versions[1].is_automatic());
+
+        document.restore_version(1).unwrap();
+        assert!(same_content(&document, &original));
+        assert_eq!(document.versions.len(), 2);
+        assert!(matches!(
+            document.version(3),
+            Err(VersionError::VersionNotFound(3))
+        ));
+    }
+
+    #[test]
+    fn single_nodes_are_restored_in_place() {
+        let mut document = document();
+        document.checkpoint(Some("v1"), "Ada", "").unwrap();
+        edit(&mut document);
+        children(&mut document, "0:1").retain(|node| node.id != "1:3");
+
+        document.restore_node(1, "1:1").unwrap();
+        assert_eq!(document.node("1:1").unwrap().name, "Layer 1:1");
+        // Deleted nodes go back to their place in the version.
+        document.restore_node(1, "1:3").unwrap();
+        // Restoring a page moves its layers back from where they went.
+        document.restore_node(1, "0:1").unwrap();
+        let ids = |document: &Document, id| {
+            let node = document.node(id).unwrap();
+            node.children()
+                .iter()
+                .map(|child| child.id.clone())
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(ids(&document, "0:1"), ["1:1", "1:2", "1:3"]);
+        assert!(ids(&document, "0:2").is_empty());
+        assert!(matches!(
+            document.restore_node(1, "0:0"),
+            Err(VersionError::Root)
+        ));
+        assert!(matches!(
+            document.restore_node(1, "1:4"),
+            Err(VersionError::NodeNotFound { .. })
+        ));
+    }
+
+    #[test]
+    fn pruning_keeps_named_and_recent_versions() {
+        let mut document = document();
+        let mut contents = Vec::new();
+        for (index, name) in [Some("v1"), None, None, Some("v2"), None]
+            .into_iter()
+            .enumerate()
+        {
+            document.document.name = format!("Edit {index}");
+            document.checkpoint(name, "Ada", "").unwrap();
+            contents.push(document.clone());
+        }
+        let removed = document
+            .prune_versions(&PrunePolicy {
+                keep_automatic: 1,
+                ..PrunePolicy::default()
+            })
+            .unwrap();
+        assert_eq!(removed, [2, 3]);
+        let ids: Vec<u32> = document.versions.iter().map(|version| version.id).collect();
+        assert_eq!(ids, [1, 4, 5]);
+        for (id, content) in [(1, &contents[0]), (4, &contents[3]), (5, &contents[4])] {
+            assert!(same_content(&document.version(id).unwrap(), content));
+        }
+
+        document.versions[0].timestamp = 0;
+        let old = PrunePolicy {
+            max_age: Some(Duration::from_secs(3600)),
+            ..PrunePolicy::default()
+        };
+        assert!(document.prune_versions(&old).unwrap().is_empty());
+        let policy = PrunePolicy {
+            prune_named: true,
+            ..old
+        };
+        assert_eq!(document.prune_versions(&policy).unwrap(), [1]);
+        assert!(same_content(&document.version(4).unwrap(), &contents[3]));
+    }
+}
@@ -0,0 +1,513 @@
+//! Evaluation of the easing curves of transitions.
+//!
+//! Cubic bezier curves map the progress of time to the progress of an animation like CSS
+//! `cubic-bezier()` timing functions, over the duration of the transition. Springs are physical
+//! simulations of a mass pulled towards the end of the animation: they define their own duration,
+//! the time they take to settle.
+
+use fig_types::properties::{Easing, EasingFunctionCubicBezier, EasingFunctionSpring, EasingType};
+
+/// Distance to the end under which a spring is considered at rest.
+const REST: f64 = 0.001;
+/// Springs which never settle, e.g. without damping, are stopped after this many milliseconds.
+const MAX_SETTLE: f64 = 60_000.0;
+
+/// An easing curve, mapping time to the progress of an animation.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Curve {
+    Linear,
+    CubicBezier(CubicBezier),
+    Spring(Spring),
+}
+
+impl Curve {
+    /// The curve of an easing, using its functions for the custom types.
+    pub fn new(easing: &Easing) -> Self {
+        match easing.easing_type {
+            EasingType::CustomCubicBezier => {
+                Curve::CubicBezier((&easing.easing_function_cubic_bezier).into())
+            }
+            EasingType::CustomSpring => Curve::Spring((&easing.easing_function_spring).into()),
+            ref preset => Curve::preset(preset).expect("only custom easings have no preset"),
+        }
+    }
+
+    /// The curve of a preset easing type, `None` for the custom types.
+    pub fn preset(easing_type: &EasingType) -> Option<Self> {
+        let bezier = |x1, y1, x2, y2| Curve::CubicBezier(CubicBezier { x1, y1, x2, y2 });
+        let spring = |stiffness, damping| {
+            Curve::Spring(Spring {
+                mass: 1.0,
+                stiffness,
+                damping,
+            })
+        };
+        Some(match easing_type {
+            EasingType::Linear => Curve::Linear,
+            EasingType::EaseIn => bezier(0.42, 0.0, 1.0, 1.0),
+            EasingType::EaseOut => bezier(0.0, 0.0, 0.58, 1.0),
+            EasingType::EaseInAndOut => bezier(0.42, 0.0, 0.58, 1.0),
+            EasingType::EaseInBack => bezier(0.3, -0.05, 0.7, -0.5),
+            EasingType::EaseOutBack => bezier(0.45, 1.45, 0.8, 1.0),
+            EasingType::EaseInAndOutBack => bezier(0.7, -0.4, 0.4, 1.4),
+            EasingType::GentleSpring => spring(100.0, 15.0),
+            EasingType::Quick => spring(300.0, 20.0),
+            EasingType::Bouncy => spring(600.0, 15.0),
+            EasingType::Slow => spring(80.0, 20.0),
+            EasingType::CustomCubicBezier | EasingType::CustomSpring => return None,
+        })
+    }
+
+    /// How long a transition of `duration` milliseconds lasts with this curve: the duration
+    /// itself, or the time a spring takes to settle.
+    pub fn duration(&self, duration: f32) -> f32 {
+        match self {
+            Curve::Linear | Curve::CubicBezier(_) => duration.max(0.0),
+            Curve::Spring(spring) => spring.settle_duration(),
+        }
+    }
+
+    /// Progress of a transition of `duration` milliseconds after `elapsed` milliseconds, from 0
+    /// at the start to 1 at the end. Curves may overshoot on the way.
+    pub fn progress(&self, elapsed: f32, duration: f32) -> f32 {
+        let time = match duration > 0.0 {
+            true => (elapsed / duration).clamp(0.0, 1.0),
+            // Instant transitions jump to the end as soon as they start.
+            false if elapsed > 0.0 => 1.0,
+            false => 0.0,
+        };
+        match self {
+            Curve::Linear => time,
+            Curve::CubicBezier(bezier) => bezier.progress(time),
+            Curve::Spring(spring) => spring.position(elapsed),
+        }
+    }
+
+    /// Progress of a transition of `duration` milliseconds sampled at `fps` frames per second,
+    /// from its first frame to its last, both included.
+    pub fn sample(&self, duration: f32, fps: f32) -> Vec<f32> {
+        let total = self.duration(duration);
+        let frames = (total * fps / 1000.0).ceil().max(1.0) as usize;
+        (0..=frames)
+            .map(|frame| {
+                let elapsed = total * frame as f32 / frames as f32;
+                match frame == frames {
+                    // Springs are only close to the end when they settle.
+                    true => 1.0,
+                    false => self.progress(elapsed, duration),
+                }
+            })
+            .collect()
+    }
+}
+
+/// A cubic bezier timing function from (0, 0) to (1, 1), like CSS `cubic-bezier()`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CubicBezier {
+    pub x1: f32,
+    pub y1: f32,
+    pub x2: f32,
+    pub y2: f32,
+}
+
+impl From<&EasingFunctionCubicBezier> for CubicBezier {
+    fn from(function: &EasingFunctionCubicBezier) -> Self {
+        Self {
+            x1: function.x1,
+            y1: function.y1,
+            x2: function.x2,
+            y2: function.y2,
+        }
+    }
+}
+
+impl CubicBezier {
+    /// Progress of the animation when `time`, between 0 and 1, of the duration has passed.
+    pub fn progress(&self, time: f32) -> f32 {
+        if time <= 0.0 {
+            return 0.0;
+        }
+        if time >= 1.0 {
+            return 1.0;
+        }
+        // Control points outside of the time range would make the curve go back in time.
+        let x1 = f64::from(self.x1.clamp(0.0, 1.0));
+        let x2 = f64::from(self.x2.clamp(0.0, 1.0));
+        let parameter = solve(x1, x2, f64::from(time));
+        bezier(f64::from(self.y1), f64::from(self.y2), parameter) as f32
+    }
+}
+
+/// The coordinate of a point of a cubic bezier curve from 0 to 1, with control points `p1` and
+/// `p2`, at parameter `t`.
+fn bezier(p1: f64, p2: f64, t: f64) -> f64 {
+    let u = 1.0 - t;
+    3.0 * u * u * t * p1 + 3.0 * u * t * t * p2 + t * t * t
+}
+
+fn bezier_derivative(p1: f64, p2: f64, t: f64) -> f64 {
+    let u = 1.0 - t;
+    3.0 * u * u * p1 + 6.0 * u * t * (p2 - p1) + 3.0 * t * t * (1.0 - p2)
+}
+
+/// The parameter at which the x coordinate of the curve is `x`: a few Newton iterations, which
+/// converge fast on most curves, then bisection for the flat ones.
+fn solve(x1: f64, x2: f64, x: f64) -> f64 {
+    const PRECISION: f64 = 1e-7;
+
+    let mut t = x;
+    for _ in 0..8 {
+        let error = bezier(x1, x2, t) - x;
+        if error.abs() < PRECISION {
+            return t;
+        }
+        let derivative = bezier_derivative(x1, x2, t);
+        if derivative.abs() < 1e-6 {
+            break;
+        }
+        t -= error / derivative;
+    }
+    let (mut low, mut high) = (0.0, 1.0);
+    t = x;
+    while high - low > PRECISION {
+        match bezier(x1, x2, t) < x {
+            true => low = t,
+            false => high = t,
+        }
+        t = (low + high) / 2.0;
+    }
+    t
+}
+
+/// A damped spring pulling a mass at rest at 0 towards 1.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Spring {
+    pub mass: f32,
+    pub stiffness: f32,
+    pub damping: f32,
+}
+
+impl From<&EasingFunctionSpring> for Spring {
+    fn from(function: &EasingFunctionSpring) -> Self {
+        Self {
+            mass: function.mass,
+            stiffness: function.stiffness,
+            damping: function.damping,
+        }
+    }
+}
+
+impl Spring {
+    /// Position of the mass after `elapsed` milliseconds.
+    pub fn position(&self, elapsed: f32) -> f32 {
+        if elapsed <= 0.0 {
+            return 0.0;
+        }
+        let Some(motion) = self.motion() else {
+            return 1.0;
+        };
+        motion.position(f64::from(elapsed) / 1000.0) as f32
+    }
+
+    /// Milliseconds until the mass comes to rest at 1.
+    pub fn settle_duration(&self) -> f32 {
+        let Some(motion) = self.motion() else {
+            return 0.0;
+        };
+        // Sampled every millisecond, until oscillations are known to stay within the rest
+        // distance, or until the mass first gets close enough when it does not oscillate.
+        let limit = match motion {
+            Motion::Underdamped {
+                decay, frequency, ..
+            } if decay > 0.0 => {
+                let amplitude = (1.0 + (decay / frequency).powi(2)).sqrt();
+                ((amplitude / REST).ln() / decay * 1000.0).min(MAX_SETTLE)
+            }
+            _ => MAX_SETTLE,
+        };
+        let oscillates = matches!(motion, Motion::Underdamped { .. });
+        let mut settled = 0.0;
+        let mut milliseconds = 0.0;
+        while milliseconds <= limit {
+            if (1.0 - motion.position(milliseconds / 1000.0)).abs() > REST {
+                settled = milliseconds + 1.0;
+            } else if !oscillates {
+                break;
+            }
+            milliseconds += 1.0;
+        }
+        settled.min(MAX_SETTLE) as f32
+    }
+
+    /// The closed form solution of the motion, `None` for springs without mass or stiffness
+    /// which are at rest immediately.
+    fn motion(&self) -> Option<Motion> {
+        let mass = f64::from(self.mass);
+        let stiffness = f64::from(self.stiffness);
+        let damping = f64::from(self.damping).max(0.0);
+        if mass <= 0.0 || stiffness <= 0.0 {
+            return None;
+        }
+        let natural = (stiffness / mass).sqrt();
+        let ratio = damping / (2.0 * (stiffness * mass).sqrt());
+        Some(if ratio < 1.0 {
+            Motion::Underdamped {
+                decay: ratio * natural,
+                frequency: natural * (1.0 - ratio * ratio).sqrt(),
+            }
+        } else if ratio == 1.0 {
+            Motion::Critical { natural }
+        } else {
+            let spread = natural * (ratio * ratio - 1.0).sqrt();
+            Motion::Overdamped {
+                slow: -ratio * natural + spread,
+                fast: -ratio * natural - spread,
+            }
+        })
+    }
+}
+
+/// Solutions of `m x'' + c x' + k (x - 1) = 0` with `x(0) = 0` and `x'(0) = 0`.
+#[derive(Debug, Clone, Copy)]
+enum Motion {
+    Underdamped { decay: f64, frequency: f64 },
+    Critical { natural: f64 },
+    Overdamped { slow: f64, fast: f64 },
+}
+
+impl Motion {
+    /// Position at `t` seconds.
+    fn position(&self, t: f64) -> f64 {
+        match *self {
+            Motion::Underdamped { decay, frequency } => {
+                let oscillation = (frequency * t).cos() + decay / frequency * (frequency * t).sin();
+                1.0 - (-decay * t).exp() * oscillation
+            }
+            Motion::Critical { natural } => 1.0 - (-natural * t).exp() * (1.0 + natural * t),
+            Motion::Overdamped { slow, fast } => {
+                1.0 - (fast * (slow * t).exp() - slow * (fast * t).exp()) / (fast - slow)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_close(actual: f32, expected: f32, tolerance: f32) {
+        assert!(
+            (actual - expected).abs() <= tolerance,
+            "expected {expected}, got {actual}"
+        );
+    }
+
+    /// Positions of a spring every millisecond, integrated numerically with Runge-Kutta.
+    fn integrate(spring: &Spring, milliseconds: usize) -> Vec<f64> {
+        let (m, k, c) = (
+            f64::from(spring.mass),
+            f64::from(spring.stiffness),
+            f64::from(spring.damping),
+        );
+        let acceleration = |x: f64, v: f64| (k * (1.0 - x) - c * v) / m;
+        let step = 0.001 / 10.0;
+        let (mut x, mut v) = (0.0, 0.0);
+        let mut positions = vec![x];
+        for _ in 0..milliseconds {
+            for _ in 0..10 {
+                let (k1x, k1v) = (v, acceleration(x, v));
+                let (k2x, k2v) = (
+                    v + k1v * step / 2.0,
+                    acceleration(x + k1x * step / 2.0, v + k1v * step / 2.0),
+                );
+                let (k3x, k3v) = (
+                    v + k2v * step / 2.0,
+                    acceleration(x + k2x * step / 2.0, v + k2v * step / 2.0),
+                );
+                let (k4x, k4v) = (v + k3v * step, acceleration(x + k3x * step, v + k3v * step));
+                x += (k1x + 2.0 * k2x + 2.0 * k3x + k4x) * step / 6.0;
+                v += (k1v + 2.0 * k2v + 2.0 * k3v + k4v) * step / 6.0;
+            }
+            positions.push(x);
+        }
+        positions
+    }
+
+    #[test]
+    fn cubic_beziers_match_css_timing_functions() {
+        // Reference values of the CSS timing functions at 10%, 25%, 50%, 75% and 90% of the time.
+        let references = [
+            (
+                EasingType::EaseIn,
+                [0.017027, 0.093465, 0.315357, 0.621862, 0.839428],
+            ),
+            (
+                EasingType::EaseOut,
+                [0.160572, 0.378138, 0.684643, 0.906535, 0.982973],
+            ),
+            (
+                EasingType::EaseInAndOut,
+                [0.019722, 0.129162, 0.5, 0.870838, 0.980278],
+            ),
+            (
+                EasingType::EaseInAndOutBack,
+                [-0.044757, -0.047726, 0.356771, 1.026017, 1.049585],
+            ),
+            (EasingType::Linear, [0.1, 0.25, 0.5, 0.75, 0.9]),
+        ];
+        for (easing_type, expected) in references {
+            let curve = Curve::preset(&easing_type).unwrap();
+            for (time, expected) in [0.1, 0.25, 0.5, 0.75, 0.9].into_iter().zip(expected) {
+                assert_close(curve.progress(time * 400.0, 400.0), expected, 1e-5);
+            }
+            assert_eq!(curve.progress(0.0, 400.0), 0.0);
+            assert_eq!(curve.progress(400.0, 400.0), 1.0);
+            assert_eq!(curve.progress(800.0, 400.0), 1.0);
+        }
+        // CSS `ease`
+        let ease = CubicBezier {
+            x1: 0.25,
+            y1: 0.1,
+            x2: 0.25,
+            y2: 1.0,
+        };
+        assert_close(ease.progress(0.5), 0.802403, 1e-5);
+    }
+
+    #[test]
+    fn custom_easings_use_their_functions() {
+        let easing = Easing {
+            easing_type: EasingType::CustomCubicBezier,
+            easing_function_cubic_bezier: EasingFunctionCubicBezier {
+                x1: 0.25,
+                y1: 0.1,
+                x2: 0.25,
+                y2: 1.0,
+            },
+            easing_function_spring: EasingFunctionSpring {
+                mass: 1.0,
+                stiffness: 200.0,
+                damping: 10.0,
+            },
+        };
+        assert!(matches!(Curve::new(&easing), Curve::CubicBezier(_)));
+        assert!(Curve::preset(&easing.easing_type).is_none());
+        let spring = Easing {
+            easing_type: EasingType::CustomSpring,
+            ..easing.clone()
+        };
+        assert_eq!(
+            Curve::new(&spring),
+            Curve::Spring(Spring {
+                mass: 1.0,
+                stiffness: 200.0,
+                damping: 10.0,
+            })
+        );
+        let preset = Easing {
+            easing_type: EasingType::Quick,
+            ..easing
+        };
+        assert_eq!(
+            Curve::new(&preset),
+            Curve::preset(&EasingType::Quick).unwrap()
+        );
+    }
+
+    #[test]
+    fn springs_match_numerical_integration() {
+        let springs = [
+            // Underdamped, critically damped and overdamped.
+            Spring {
+                mass: 1.0,
+                stiffness: 100.0,
+                damping: 15.0,
+            },
+            Spring {
+                mass: 1.0,
+                stiffness: 600.0,
+                damping: 15.0,
+            },
+            Spring {
+                mass: 1.0,
+                stiffness: 100.0,
+                damping: 20.0,
+            },
+            Spring {
+                mass: 2.0,
+                stiffness: 50.0,
+                damping: 40.0,
+            },
+        ];
+        for spring in springs {
+            let reference = integrate(&spring, 2000);
+            for (milliseconds, expected) in reference.iter().enumerate().step_by(50) {
+                assert_close(spring.position(milliseconds as f32), *expected as f32, 1e-4);
+            }
+        }
+    }
+
+    #[test]
+    fn springs_report_when_they_settle() {
+        for easing_type in [
+            EasingType::GentleSpring,
+            EasingType::Quick,
+            EasingType::Bouncy,
+            EasingType::Slow,
+        ] {
+            let Some(Curve::Spring(spring)) = Curve::preset(&easing_type) else {
+                panic!("{easing_type:?} is a spring");
+            };
+            let settle = spring.settle_duration();
+            assert!(
+                settle > 100.0 && settle < 5000.0,
+                "{easing_type:?}: {settle}"
+            );
+            let reference = integrate(&spring, settle as usize + 2000);
+            let distance = |milliseconds: usize| (1.0 - reference[milliseconds]).abs();
+            assert!(distance(settle as usize - 1) > REST, "{easing_type:?}");
+            assert!(
+                (settle as usize..reference.len())
+                    .all(|milliseconds| distance(milliseconds) <= REST),
+                "{easing_type:?}"
+            );
+            assert_eq!(Curve::Spring(spring).duration(300.0), settle);
+        }
+        // The bouncy spring overshoots, the slow one does not.
+        let bouncy = Curve::preset(&EasingType::Bouncy).unwrap();
+        assert!(bouncy
+            .sample(0.0, 60.0)
+            .iter()
+            .any(|progress| *progress > 1.05));
+        let slow = Curve::preset(&EasingType::Slow).unwrap();
+        assert!(slow
+            .sample(0.0, 60.0)
+            .windows(2)
+            .all(|pair| pair[0] <= pair[1]));
+
+        let rigid = Spring {
+            mass: 0.0,
+            stiffness: 100.0,
+            damping: 10.0,
+        };
+        assert_eq!(rigid.settle_duration(), 0.0);
+        assert_eq!(rigid.position(1.0), 1.0);
+        let undamped = Spring {
+            mass: 1.0,
+            stiffness: 100.0,
+            damping: 0.0,
+        };
+        assert_eq!(undamped.settle_duration(), MAX_SETTLE as f32);
+    }
+
+    #[test]
+    fn samples_cover_the_whole_transition() {
+        let linear = Curve::Linear.sample(100.0, 60.0);
+        assert_eq!(linear.len(), 7);
+        assert_eq!(linear[0], 0.0);
+        assert_eq!(linear[6], 1.0);
+        assert_close(linear[3], 0.5, 1e-6);
+        assert_eq!(Curve::Linear.sample(0.0, 60.0), [0.0, 1.0]);
+        assert_eq!(Curve::Linear.progress(10.0, 0.0), 1.0);
+    }
+}
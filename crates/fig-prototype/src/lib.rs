@@ -0,0 +1,7 @@
+//! Deterministic playback of the prototypes described by the interactions of nodes, so they can
+//! be exercised without a renderer, e.g. in unit tests.
+
+pub mod easing;
+pub mod player;
+pub mod smart_animate;
+pub mod variables;
@@ -0,0 +1,1319 @@
+//! The prototype player: a state machine presenting screens and overlays, driven by input events.
+//!
+//! Screens are the top level nodes of the canvases. The player shows one screen at a time, with a
+//! stack of overlays on top of it, and keeps the screens navigated away from for back actions.
+//! Events are matched against the triggers of the interactions of the nodes they target, and the
+//! actions of the matching interactions report what a renderer would have to animate as
+//! [`Outcome`]s. Time only passes through [`Event::Elapsed`], so playback is deterministic.
+
+use std::collections::HashMap;
+
+use fig_file::document::Document;
+use fig_types::node::{Node, NodeType};
+use fig_types::properties::{
+    Action, Interaction, NavigationType, NodeAction, NodeActionTransition, TriggerDevice,
+    TriggerType, UpdateMediaRuntimeAction,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::variables::{EvaluationError, Value, Variables};
+
+/// Most timers run while handling a single [`Event::Elapsed`], past which the timers of the
+/// prototype are taken to start each other endlessly, e.g. two screens navigating to each other
+/// after a timeout of 0.
+const MAX_TIMERS: usize = 1000;
+
+/// Input given to a prototype.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", tag = "event")]
+pub enum Event {
+    /// A click, or tap, on a node.
+    Click { node: String },
+    /// The start of a drag on a node.
+    Drag { node: String },
+    /// The pointer enters a node.
+    HoverStart { node: String },
+    /// The pointer leaves a node, reverting what hovering it navigated to.
+    HoverEnd { node: String },
+    /// A press starts on a node.
+    PressStart { node: String },
+    /// The press ends, reverting what pressing the node navigated to.
+    PressEnd { node: String },
+    #[serde(rename_all = "camelCase")]
+    KeyDown {
+        device: TriggerDevice,
+        key_codes: Vec<i32>,
+    },
+    #[serde(rename_all = "camelCase")]
+    KeyUp {
+        device: TriggerDevice,
+        key_codes: Vec<i32>,
+    },
+    /// A video node reached a time, in seconds.
+    MediaHit { node: String, time: i32 },
+    /// A video node played to its end.
+    MediaEnd { node: String },
+    /// Time passes, in milliseconds.
+    Elapsed { milliseconds: u64 },
+}
+
+/// How the screens and overlays shown changed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum Navigation {
+    /// Another screen replaced the current one and its overlays.
+    Navigate,
+    /// Another screen or overlay replaced the current one, without a history entry.
+    Swap,
+    /// An overlay opened on top of the current screen.
+    Overlay,
+    /// A node was changed to another variant.
+    ChangeTo,
+    /// The topmost overlay closed.
+    Close,
+    /// Back to the previous overlay or screen.
+    Back,
+    /// A hover or press ended, and what it navigated to was reverted.
+    Revert,
+}
+
+/// A change of what is shown, for a renderer to animate.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Transition {
+    pub navigation: Navigation,
+    /// The screen or overlay shown before, or the node changed to another variant.
+    pub from: String,
+    /// The screen or overlay shown after, or the variant the node was changed to.
+    pub to: String,
+    /// The animation of the prototype, `Null` for changes which are not animated.
+    pub transition: NodeActionTransition,
+}
+
+/// What running the actions triggered by an event did.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum Outcome {
+    Transition(Transition),
+    /// The current screen scrolled to a node.
+    #[serde(rename_all = "camelCase")]
+    ScrollTo {
+        node: String,
+        transition: NodeActionTransition,
+    },
+    OpenUrl(String),
+    /// A video node should play, pause, seek...
+    #[serde(rename_all = "camelCase")]
+    Media {
+        node: String,
+        action: UpdateMediaRuntimeAction,
+    },
+    #[serde(rename_all = "camelCase")]
+    VariableChanged {
+        id: String,
+        value: Value,
+    },
+    #[serde(rename_all = "camelCase")]
+    ModeChanged {
+        collection: String,
+        mode: String,
+    },
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum PlayerError {
+    #[error("no node with id `{0}`")]
+    NodeNotFound(String),
+    #[error("node `{0}` is not a screen, only the top level nodes of canvases are")]
+    NotAScreen(String),
+    #[error("node `{0}` is not on the topmost screen or overlay")]
+    NotShown(String),
+    #[error("the condition of a conditional action evaluates to {0}, not a boolean")]
+    Condition(Value),
+    #[error(transparent)]
+    Evaluation(#[from] EvaluationError),
+    #[error("timers kept starting each other, stopped after {MAX_TIMERS} of them at {0} ms")]
+    Timers(u64),
+}
+
+/// The screens and overlays shown, and how to go back from them.
+#[derive(Debug, Clone, PartialEq)]
+struct State {
+    screen: String,
+    overlays: Vec<String>,
+    /// Screens navigated away from, the most recent last.
+    history: Vec<String>,
+    /// Nodes changed to another variant, by id.
+    variants: HashMap<String, String>,
+}
+
+impl State {
+    /// The topmost screen or overlay.
+    fn top(&self) -> &str {
+        self.overlays.last().unwrap_or(&self.screen)
+    }
+
+    fn presents(&self, frame: &str) -> bool {
+        self.screen == frame || self.overlays.iter().any(|overlay| overlay == frame)
+    }
+}
+
+/// Interactions to run at a later time.
+#[derive(Debug, Clone)]
+struct Timer {
+    /// When to run the interaction, in milliseconds since the start.
+    at: u64,
+    node: String,
+    interaction: usize,
+    /// The screen or overlay which has to be shown for the timer to run.
+    frame: String,
+    /// Whether the timer delays a mouse trigger, and is cancelled when the pointer leaves or the
+    /// press ends.
+    delay: bool,
+}
+
+/// A navigation to revert when a hover or press ends.
+#[derive(Debug, Clone)]
+struct Held {
+    node: String,
+    trigger: TriggerType,
+    state: State,
+}
+
+/// Plays the prototype of a document.
+pub struct Player<'a> {
+    /// Nodes of the document, by id.
+    nodes: HashMap<&'a str, &'a Node>,
+    /// Parents of the nodes, by id.
+    parents: HashMap<&'a str, &'a str>,
+    /// Screen or overlay each node is part of, by id.
+    frames: HashMap<&'a str, &'a str>,
+    variables: Variables<'a>,
+    state: State,
+    /// Milliseconds elapsed since the start.
+    clock: u64,
+    timers: Vec<Timer>,
+    held: Vec<Held>,
+}
+
+impl<'a> Player<'a> {
+    /// Starts the prototype of a document on a screen.
+    pub fn new(document: &'a Document, start: &str) -> Result<Self, PlayerError> {
+        let mut player = Self {
+            nodes: HashMap::new(),
+            parents: HashMap::new(),
+            frames: HashMap::new(),
+            variables: Variables::new(document),
+            state: State {
+                screen: start.to_string(),
+                overlays: Vec::new(),
+                history: Vec::new(),
+                variants: HashMap::new(),
+            },
+            clock: 0,
+            timers: Vec::new(),
+            held: Vec::new(),
+        };
+        player.index(&document.document, None, None);
+        if !player.nodes.contains_key(start) {
+            return Err(PlayerError::NodeNotFound(start.to_string()));
+        }
+        if player.frames.get(start) != Some(&start) {
+            return Err(PlayerError::NotAScreen(start.to_string()));
+        }
+        player.schedule_timeouts(start);
+        Ok(player)
+    }
+
+    fn index(&mut self, node: &'a Node, parent: Option<&'a Node>, frame: Option<&'a str>) {
+        self.nodes.insert(&node.id, node);
+        if let Some(parent) = parent {
+            self.parents.insert(&node.id, &parent.id);
+        }
+        let frame = match (parent, frame) {
+            (_, Some(frame)) => Some(frame),
+            (Some(parent), None) if matches!(parent.node, NodeType::Canvas(_)) => {
+                Some(node.id.as_str())
+            }
+            _ => None,
+        };
+        if let Some(frame) = frame {
+            self.frames.insert(&node.id, frame);
+        }
+        for child in node.children() {
+            self.index(child, Some(node), frame);
+        }
+    }
+
+    /// The screen shown, under the overlays.
+    pub fn screen(&self) -> &str {
+        &self.state.screen
+    }
+
+    /// The overlays shown, the topmost last.
+    pub fn overlays(&self) -> &[String] {
+        &self.state.overlays
+    }
+
+    /// The screens navigated away from, the most recent last.
+    pub fn history(&self) -> &[String] {
+        &self.state.history
+    }
+
+    /// The variant a node was changed to, if any.
+    pub fn variant(&self, node: &str) -> Option<&str> {
+        self.state.variants.get(node).map(String::as_str)
+    }
+
+    pub fn variables(&self) -> &Variables<'a> {
+        &self.variables
+    }
+
+    /// Milliseconds elapsed since the start.
+    pub fn clock(&self) -> u64 {
+        self.clock
+    }
+
+    /// Runs the interactions triggered by an event, and returns what they did in order.
+    pub fn handle(&mut self, event: Event) -> Result<Vec<Outcome>, PlayerError> {
+        let mut outcomes = Vec::new();
+        match event {
+            Event::Click { node } => self.pointer(&node, &[TriggerType::OnClick], &mut outcomes)?,
+            Event::Drag { node } => self.pointer(&node, &[TriggerType::OnDrag], &mut outcomes)?,
+            Event::HoverStart { node } => {
+                let triggers = [TriggerType::OnHover, TriggerType::MouseEnter];
+                self.pointer(&node, &triggers, &mut outcomes)?
+            }
+            Event::HoverEnd { node } => {
+                self.release(&node, TriggerType::OnHover, &mut outcomes);
+                self.pointer(&node, &[TriggerType::MouseLeave], &mut outcomes)?
+            }
+            Event::PressStart { node } => {
+                let triggers = [TriggerType::OnPress, TriggerType::MouseDown];
+                self.pointer(&node, &triggers, &mut outcomes)?
+            }
+            Event::PressEnd { node } => {
+                self.release(&node, TriggerType::OnPress, &mut outcomes);
+                self.pointer(&node, &[TriggerType::MouseUp], &mut outcomes)?
+            }
+            Event::KeyDown { device, key_codes } => {
+                self.key(TriggerType::OnKeyDown, &device, &key_codes, &mut outcomes)?
+            }
+            Event::KeyUp { device, key_codes } => {
+                self.key(TriggerType::OnKeyUp, &device, &key_codes, &mut outcomes)?
+            }
+            Event::MediaHit { node, time } => {
+                let id = self.resolve(self.shown(&node)?);
+                let interactions = self.interactions(id).iter().enumerate();
+                let hits: Vec<_> = interactions
+                    .filter(|(_, interaction)| {
+                        interaction.trigger.as_ref().is_some_and(|trigger| {
+                            trigger.trigger_type == TriggerType::OnMediaHit
+                                && trigger.media_hit_time == time
+                        })
+                    })
+                    .map(|(index, _)| index)
+                    .collect();
+                for index in hits {
+                    self.run_interaction(id, index, &mut outcomes)?;
+                }
+            }
+            Event::MediaEnd { node } => {
+                let id = self.resolve(self.shown(&node)?);
+                let triggers = [TriggerType::OnMediaEnd];
+                for index in self.matching(id, &triggers) {
+                    self.run_interaction(id, index, &mut outcomes)?;
+                }
+            }
+            Event::Elapsed { milliseconds } => self.elapse(milliseconds, &mut outcomes)?,
+        }
+        Ok(outcomes)
+    }
+
+    /// Checks that a node is part of the topmost screen or overlay.
+    fn shown(&self, node: &str) -> Result<&'a str, PlayerError> {
+        let (&id, _) = self
+            .nodes
+            .get_key_value(node)
+            .ok_or_else(|| PlayerError::NodeNotFound(node.to_string()))?;
+        if self.frames.get(id).copied() != Some(self.state.top()) {
+            return Err(PlayerError::NotShown(node.to_string()));
+        }
+        Ok(id)
+    }
+
+    /// The node whose interactions apply to a node: the variant it was changed to, if any.
+    fn resolve(&self, node: &'a str) -> &'a str {
+        match self.state.variants.get(node) {
+            Some(variant) => self
+                .nodes
+                .get_key_value(variant.as_str())
+                .map_or(node, |(id, _)| id),
+            None => node,
+        }
+    }
+
+    fn interactions(&self, node: &str) -> &'a [Interaction] {
+        self.nodes.get(node).map_or(&[], |node| node.interactions())
+    }
+
+    /// Indices of the interactions of a node triggered by any of `triggers`.
+    fn matching(&self, node: &str, triggers: &[TriggerType]) -> Vec<usize> {
+        self.interactions(node)
+            .iter()
+            .enumerate()
+            .filter(|(_, interaction)| {
+                interaction
+                    .trigger
+                    .as_ref()
+                    .is_some_and(|trigger| triggers.contains(&trigger.trigger_type))
+            })
+            .map(|(index, _)| index)
+            .collect()
+    }
+
+    /// Runs the interactions of the closest node, from the target up to its screen, with one of
+    /// the triggers.
+    fn pointer(
+        &mut self,
+        node: &str,
+        triggers: &[TriggerType],
+        outcomes: &mut Vec<Outcome>,
+    ) -> Result<(), PlayerError> {
+        let mut target = self.shown(node)?;
+        let (id, indices) = loop {
+            let id = self.resolve(target);
+            let indices = self.matching(id, triggers);
+            if !indices.is_empty() {
+                break (id, indices);
+            }
+            match self.parents.get(target) {
+                Some(parent) if self.frames.contains_key(parent) => target = parent,
+                _ => return Ok(()),
+            }
+        };
+        for index in indices {
+            self.run_interaction(id, index, outcomes)?;
+        }
+        Ok(())
+    }
+
+    /// Runs the key interactions of the nodes of the topmost screen or overlay.
+    fn key(
+        &mut self,
+        trigger_type: TriggerType,
+        device: &TriggerDevice,
+        key_codes: &[i32],
+        outcomes: &mut Vec<Outcome>,
+    ) -> Result<(), PlayerError> {
+        let mut pressed = key_codes.to_vec();
+        pressed.sort_unstable();
+        let top = self.state.top();
+        let mut frame: Vec<_> = self
+            .frames
+            .iter()
+            .filter(|(_, frame)| **frame == top)
+            .map(|(id, _)| *id)
+            .collect();
+        // Sorted so interactions run in the same order on every run.
+        frame.sort_unstable();
+        let mut matches = Vec::new();
+        for id in frame {
+            let id = self.resolve(id);
+            for (index, interaction) in self.interactions(id).iter().enumerate() {
+                let Some(trigger) = &interaction.trigger else {
+                    continue;
+                };
+                let mut codes = trigger.key_codes.clone();
+                codes.sort_unstable();
+                if trigger.trigger_type == trigger_type
+                    && trigger.device == *device
+                    && codes == pressed
+                {
+                    matches.push((id, index));
+                }
+            }
+        }
+        for (id, index) in matches {
+            self.run_interaction(id, index, outcomes)?;
+        }
+        Ok(())
+    }
+
+    /// Runs an interaction of a node now, or schedules it if its trigger has a delay.
+    fn run_interaction(
+        &mut self,
+        node: &'a str,
+        index: usize,
+        outcomes: &mut Vec<Outcome>,
+    ) -> Result<(), PlayerError> {
+        let interaction = &self.interactions(node)[index];
+        let Some(trigger) = &interaction.trigger else {
+            return Ok(());
+        };
+        let mouse = matches!(
+            trigger.trigger_type,
+            TriggerType::MouseEnter
+                | TriggerType::MouseLeave
+                | TriggerType::MouseUp
+                | TriggerType::MouseDown
+        );
+        if mouse && trigger.delay > 0 {
+            self.timers.push(Timer {
+                at: self
+                    .clock
+                    .saturating_add(u64::from(trigger.delay.unsigned_abs())),
+                node: node.to_string(),
+                interaction: index,
+                frame: self.state.top().to_string(),
+                delay: true,
+            });
+            return Ok(());
+        }
+        let before = self.state.clone();
+        self.run(node, &interaction.actions, outcomes)?;
+        let temporary = matches!(
+            trigger.trigger_type,
+            TriggerType::OnHover | TriggerType::OnPress
+        );
+        if temporary && self.state != before {
+            self.held.push(Held {
+                node: node.to_string(),
+                trigger: trigger.trigger_type.clone(),
+                state: before,
+            });
+        }
+        Ok(())
+    }
+
+    /// Ends a hover or press of a node: cancels the delayed interactions it started and restores
+    /// what was shown before it.
+    fn release(&mut self, node: &str, trigger: TriggerType, outcomes: &mut Vec<Outcome>) {
+        let Some(id) = self.nodes.get_key_value(node).map(|(id, _)| *id) else {
+            return;
+        };
+        // Interactions run on the variant a node was changed to, or on its ancestors.
+        let mut ids = vec![id, self.resolve(id)];
+        let mut ancestor = id;
+        while let Some(parent) = self.parents.get(ancestor) {
+            ids.push(parent);
+            ids.push(self.resolve(parent));
+            ancestor = parent;
+        }
+        self.timers
+            .retain(|timer| !(timer.delay && ids.contains(&timer.node.as_str())));
+        let Some(position) = self
+            .held
+            .iter()
+            .rposition(|held| held.trigger == trigger && ids.contains(&held.node.as_str()))
+        else {
+            return;
+        };
+        let held = self.held.remove(position);
+        let from = self.state.top().to_string();
+        self.present(held.state);
+        outcomes.push(Outcome::Transition(Transition {
+            navigation: Navigation::Revert,
+            from,
+            to: self.state.top().to_string(),
+            transition: NodeActionTransition::Null,
+        }));
+    }
+
+    /// Advances the clock, running the timers which expire on the way in order. Stops at the
+    /// last timer run, with an error, once [`MAX_TIMERS`] of them ran.
+    fn elapse(
+        &mut self,
+        milliseconds: u64,
+        outcomes: &mut Vec<Outcome>,
+    ) -> Result<(), PlayerError> {
+        let end = self.clock.saturating_add(milliseconds);
+        let mut run = 0;
+        loop {
+            let next = self
+                .timers
+                .iter()
+                .enumerate()
+                .filter(|(_, timer)| timer.at <= end)
+                .min_by_key(|(index, timer)| (timer.at, *index))
+                .map(|(index, _)| index);
+            let Some(next) = next else {
+                break;
+            };
+            if run == MAX_TIMERS {
+                return Err(PlayerError::Timers(self.clock));
+            }
+            run += 1;
+            let timer = self.timers.remove(next);
+            self.clock = timer.at;
+            let Some((&node, _)) = self.nodes.get_key_value(timer.node.as_str()) else {
+                continue;
+            };
+            let interaction = &self.interactions(node)[timer.interaction];
+            self.run(node, &interaction.actions, outcomes)?;
+        }
+        self.clock = end;
+        Ok(())
+    }
+
+    /// Schedules the timeout interactions of the nodes of a screen or overlay which was just
+    /// presented.
+    fn schedule_timeouts(&mut self, frame: &str) {
+        let mut nodes: Vec<_> = self
+            .frames
+            .iter()
+            .filter(|(_, node_frame)| **node_frame == frame)
+            .map(|(id, _)| *id)
+            .collect();
+        nodes.sort_unstable();
+        for id in nodes {
+            let id = self.resolve(id);
+            for (index, interaction) in self.interactions(id).iter().enumerate() {
+                let Some(trigger) = &interaction.trigger else {
+                    continue;
+                };
+                if trigger.trigger_type == TriggerType::AfterTimeout {
+                    self.timers.push(Timer {
+                        at: self
+                            .clock
+                            .saturating_add(u64::from(trigger.timeout.unsigned_abs())),
+                        node: id.to_string(),
+                        interaction: index,
+                        frame: frame.to_string(),
+                        delay: false,
+                    });
+                }
+            }
+        }
+    }
+
+    /// Shows another set of screens and overlays, dropping the timers of those no longer shown
+    /// and starting those of the newly shown ones.
+    fn present(&mut self, state: State) {
+        let before = std::mem::replace(&mut self.state, state);
+        let state = &self.state;
+        self.timers.retain(|timer| state.presents(&timer.frame));
+        let mut shown: Vec<_> = std::iter::once(&self.state.screen)
+            .chain(&self.state.overlays)
+            .filter(|frame| !before.presents(frame))
+            .cloned()
+            .collect();
+        shown.dedup();
+        for frame in shown {
+            self.schedule_timeouts(&frame);
+        }
+    }
+
+    /// Runs actions triggered on a node.
+    fn run(
+        &mut self,
+        node: &str,
+        actions: &[Action],
+        outcomes: &mut Vec<Outcome>,
+    ) -> Result<(), PlayerError> {
+        for action in actions {
+            match action {
+                Action::BackAction => self.back(outcomes),
+                Action::CloseAction => {
+                    if let Some(overlay) = self.state.overlays.last().cloned() {
+                        let mut state = self.state.clone();
+                        state.overlays.pop();
+                        self.transition(state, Navigation::Close, overlay, outcomes);
+                    }
+                }
+                Action::OpenURLAction(action) => {
+                    outcomes.push(Outcome::OpenUrl(action.url.clone()))
+                }
+                Action::UpdateMediaRuntimeAction(action) => outcomes.push(Outcome::Media {
+                    node: action.destination_id.as_deref().unwrap_or(node).to_string(),
+                    action: action.clone(),
+                }),
+                Action::SetVariableAction(action) => {
+                    let Some(id) = &action.variable_id else {
+                        continue;
+                    };
+                    let value = self.variables.evaluate(&action.variable_value)?;
+                    self.variables.set(id, value.clone())?;
+                    outcomes.push(Outcome::VariableChanged {
+                        id: id.clone(),
+                        value,
+                    });
+                }
+                Action::SetVariableModeAction(action) => {
+                    let (Some(collection), Some(mode)) =
+                        (&action.variable_collection_id, &action.variable_mode_id)
+                    else {
+                        continue;
+                    };
+                    self.variables.set_mode(collection, mode)?;
+                    outcomes.push(Outcome::ModeChanged {
+                        collection: collection.clone(),
+                        mode: mode.clone(),
+                    });
+                }
+                Action::ConditionalAction(action) => {
+                    for block in &action.conditional_blocks {
+                        match self.variables.evaluate(&block.condition)? {
+                            Value::Boolean(true) => {
+                                self.run(node, &block.actions, outcomes)?;
+                                break;
+                            }
+                            Value::Boolean(false) => {}
+                            value => return Err(PlayerError::Condition(value)),
+                        }
+                    }
+                }
+                Action::NodeAction(action) => self.navigate(node, action, outcomes)?,
+            }
+        }
+        Ok(())
+    }
+
+    fn navigate(
+        &mut self,
+        node: &str,
+        action: &NodeAction,
+        outcomes: &mut Vec<Outcome>,
+    ) -> Result<(), PlayerError> {
+        let Some(destination) = &action.destination_id else {
+            return Ok(());
+        };
+        if !self.nodes.contains_key(destination.as_str()) {
+            return Err(PlayerError::NodeNotFound(destination.clone()));
+        }
+        let transition = action.transition.clone();
+        let mut state = self.state.clone();
+        let navigation = match action.navigation.navigation_type {
+            NavigationType::Navigate => {
+                let previous = std::mem::replace(&mut state.screen, destination.clone());
+                state.history.push(previous);
+                state.overlays.clear();
+                Navigation::Navigate
+            }
+            NavigationType::Swap => {
+                // Swapping a screen leaves no history entry.
+                match state.overlays.last_mut() {
+                    Some(overlay) => *overlay = destination.clone(),
+                    None => state.screen = destination.clone(),
+                }
+                Navigation::Swap
+            }
+            NavigationType::Overlay => {
+                state.overlays.push(destination.clone());
+                Navigation::Overlay
+            }
+            NavigationType::ScrollTo => {
+                outcomes.push(Outcome::ScrollTo {
+                    node: destination.clone(),
+                    transition,
+                });
+                return Ok(());
+            }
+            NavigationType::ChangeTo => {
+                // Without instances in the tree, the node holding the interaction is changed.
+                let source = self
+                    .state
+                    .variants
+                    .iter()
+                    .find(|(_, variant)| variant.as_str() == node)
+                    .map_or(node, |(source, _)| source.as_str())
+                    .to_string();
+                state.variants.insert(source, destination.clone());
+                self.present(state);
+                outcomes.push(Outcome::Transition(Transition {
+                    navigation: Navigation::ChangeTo,
+                    from: node.to_string(),
+                    to: destination.clone(),
+                    transition,
+                }));
+                return Ok(());
+            }
+        };
+        let from = self.state.top().to_string();
+        self.present(state);
+        outcomes.push(Outcome::Transition(Transition {
+            navigation,
+            from,
+            to: self.state.top().to_string(),
+            transition,
+        }));
+        Ok(())
+    }
+
+    /// Closes the topmost overlay, or goes back to the previous screen.
+    fn back(&mut self, outcomes: &mut Vec<Outcome>) {
+        let mut state = self.state.clone();
+        let from = self.state.top().to_string();
+        if state.overlays.pop().is_none() {
+            let Some(previous) = state.history.pop() else {
+                return;
+            };
+            state.screen = previous;
+        }
+        self.transition(state, Navigation::Back, from, outcomes);
+    }
+
+    fn transition(
+        &mut self,
+        state: State,
+        navigation: Navigation,
+        from: String,
+        outcomes: &mut Vec<Outcome>,
+    ) {
+        self.present(state);
+        outcomes.push(Outcome::Transition(Transition {
+            navigation,
+            from,
+            to: self.state.top().to_string(),
+            transition: NodeActionTransition::Null,
+        }));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use fig_file::fixtures;
+    use fig_types::properties::{
+        Expression, ExpressionFunction, Variable, VariableAlias, VariableCollection, VariableData,
+        VariableDataType, VariableDataValue, VariableMode, VariableResolvedDataType,
+    };
+    use serde_json::{json, Value as Json};
+
+    use super::*;
+
+    const HOME: &str = "1:1";
+    const DETAILS: &str = "1:2";
+    const MENU: &str = "1:3";
+    const COUNT: &str = "VariableID:1:1";
+
+    fn trigger(trigger_type: &str) -> Json {
+        json!({
+            "triggerType": trigger_type,
+            "timeout": 0,
+            "delay": 0,
+            "deprecatedVersion": false,
+            "device": "keyboard",
+            "keyCodes": [],
+            "mediaHitTime": 0,
+        })
+    }
+
+    fn go(navigation: &str, destination: &str) -> Json {
+        json!({
+            "type": "Node",
+            "destinationId": destination,
+            "navigation": { "navigationType": navigation },
+            "transition": {
+                "simpleTransitionType": "Dissolve",
+                "duration": 300,
+                "easing": {
+                    "easingType": "EaseOut",
+                    "easingFunctionCubicBezier": { "x1": 0.0, "y1": 0.0, "x2": 0.58, "y2": 1.0 },
+                    "easingFunctionSpring": { "mass": 1.0, "stiffness": 100.0, "damping": 15.0 },
+                },
+            },
+            "preserveScrollPosition": false,
+            "overlayRelativePosition": { "x": 0.0, "y": 0.0 },
+            "resetVideoPosition": false,
+            "resetScrollPosition": false,
+            "resetInteractiveComponents": false,
+        })
+    }
+
+    fn screen(id: &str, interactions: Json) -> Node {
+        let mut properties = fixtures::bounds(0.0, 0.0, 375.0, 812.0);
+        properties["interactions"] = interactions;
+        fixtures::layer("rectangle", id, id, properties)
+    }
+
+    /// A document with a canvas of screens, and a `count` variable starting at 0.
+    fn document(screens: Vec<Node>) -> Document {
+        let mut document = Document::new("Prototype");
+        let canvas = &mut document.document.children_mut().unwrap()[0];
+        *canvas.children_mut().unwrap() = screens;
+        let collection = VariableCollection {
+            id: "VariableCollectionId:1:0".to_string(),
+            name: "State".to_string(),
+            key: String::new(),
+            modes: vec![
+                VariableMode {
+                    mode_id: "1:0".to_string(),
+                    name: "Light".to_string(),
+                },
+                VariableMode {
+                    mode_id: "1:1".to_string(),
+                    name: "Dark".to_string(),
+                },
+            ],
+            default_mode_id: "1:0".to_string(),
+            remote: false,
+            hidden_from_publishing: false,
+            variable_ids: vec![COUNT.to_string()],
+        };
+        let count = Variable {
+            id: COUNT.to_string(),
+            name: "count".to_string(),
+            key: String::new(),
+            variable_collection_id: collection.id.clone(),
+            resolved_type: VariableResolvedDataType::Float,
+            values_by_mode: [
+                ("1:0".to_string(), VariableDataValue::Float(0.0)),
+                ("1:1".to_string(), VariableDataValue::Float(10.0)),
+            ]
+            .into(),
+            remote: false,
+            description: String::new(),
+            hidden_from_publishing: false,
+            scopes: Vec::new(),
+            code_syntax: Default::default(),
+        };
+        document.variables = BTreeMap::from([(COUNT.to_string(), count)]);
+        document.variable_collections = BTreeMap::from([(collection.id.clone(), collection)]);
+        document
+    }
+
+    fn click(node: &str) -> Event {
+        Event::Click {
+            node: node.to_string(),
+        }
+    }
+
+    fn navigations(outcomes: &[Outcome]) -> Vec<(Navigation, &str, &str)> {
+        outcomes
+            .iter()
+            .filter_map(|outcome| match outcome {
+                Outcome::Transition(transition) => Some((
+                    transition.navigation,
+                    transition.from.as_str(),
+                    transition.to.as_str(),
+                )),
+                _ => None,
+            })
+            .collect()
+    }
+
+    fn float(value: f32) -> Json {
+        json!({ "variableDataType": "float", "resolvedType": "Float", "value": value })
+    }
+
+    fn count() -> Json {
+        json!({
+            "variableDataType": "variableAlias",
+            "resolvedType": "Float",
+            "value": { "variableAliasType": "VARIABLE_ALIAS", "id": COUNT },
+        })
+    }
+
+    fn expression(function: &str, arguments: [Json; 2], resolved_type: &str) -> Json {
+        json!({
+            "variableDataType": "expression",
+            "resolvedType": resolved_type,
+            "value": { "expressionFunction": function, "expressionArguments": arguments },
+        })
+    }
+
+    #[test]
+    fn navigates_opens_overlays_and_goes_back() {
+        let document = document(vec![
+            screen(
+                HOME,
+                json!([{ "trigger": trigger("OnClick"), "actions": [go("Navigate", DETAILS)] }]),
+            ),
+            screen(
+                DETAILS,
+                json!([{ "trigger": trigger("OnClick"), "actions": [go("Overlay", MENU)] }]),
+            ),
+            screen(
+                MENU,
+                json!([{ "trigger": trigger("OnClick"), "actions": [{ "type": "Back" }] }]),
+            ),
+        ]);
+        let mut player = Player::new(&document, HOME).unwrap();
+
+        let outcomes = player.handle(click(HOME)).unwrap();
+        assert_eq!(
+            navigations(&outcomes),
+            [(Navigation::Navigate, HOME, DETAILS)]
+        );
+        let Outcome::Transition(transition) = &outcomes[0] else {
+            panic!("expected a transition, got {outcomes:?}");
+        };
+        assert!(matches!(
+            transition.transition,
+            NodeActionTransition::SimpleTransition(_)
+        ));
+        assert_eq!(player.history(), [HOME]);
+
+        let outcomes = player.handle(click(DETAILS)).unwrap();
+        assert_eq!(
+            navigations(&outcomes),
+            [(Navigation::Overlay, DETAILS, MENU)]
+        );
+        assert_eq!(player.overlays(), [MENU]);
+        // The screen under an overlay does not receive events.
+        assert!(matches!(
+            player.handle(click(DETAILS)),
+            Err(PlayerError::NotShown(_))
+        ));
+
+        let outcomes = player.handle(click(MENU)).unwrap();
+        assert_eq!(navigations(&outcomes), [(Navigation::Back, MENU, DETAILS)]);
+        assert!(player.overlays().is_empty());
+        let outcomes = player.handle(click(DETAILS)).unwrap();
+        assert_eq!(
+            navigations(&outcomes),
+            [(Navigation::Overlay, DETAILS, MENU)]
+        );
+        player.handle(click(MENU)).unwrap();
+        let outcomes = player.handle(click(MENU));
+        assert!(matches!(outcomes, Err(PlayerError::NotShown(_))));
+    }
+
+    #[test]
+    fn back_returns_through_the_history_and_swaps_leave_none() {
+        let document = document(vec![
+            screen(
+                HOME,
+                json!([{ "trigger": trigger("OnClick"), "actions": [go("Navigate", DETAILS)] }]),
+            ),
+            screen(
+                DETAILS,
+                json!([
+                    { "trigger": trigger("OnClick"), "actions": [go("Swap", MENU)] },
+                ]),
+            ),
+            screen(
+                MENU,
+                json!([{ "trigger": trigger("OnClick"), "actions": [{ "type": "Back" }] }]),
+            ),
+        ]);
+        let mut player = Player::new(&document, HOME).unwrap();
+        player.handle(click(HOME)).unwrap();
+        let outcomes = player.handle(click(DETAILS)).unwrap();
+        assert_eq!(navigations(&outcomes), [(Navigation::Swap, DETAILS, MENU)]);
+        assert_eq!(player.history(), [HOME]);
+        let outcomes = player.handle(click(MENU)).unwrap();
+        assert_eq!(navigations(&outcomes), [(Navigation::Back, MENU, HOME)]);
+        assert_eq!(player.screen(), HOME);
+        assert!(player.history().is_empty());
+    }
+
+    #[test]
+    fn hover_and_press_navigations_revert_when_they_end() {
+        let document = document(vec![
+            screen(
+                HOME,
+                json!([
+                    { "trigger": trigger("OnHover"), "actions": [go("Overlay", MENU)] },
+                    { "trigger": trigger("OnPress"), "actions": [go("Navigate", DETAILS)] },
+                ]),
+            ),
+            screen(DETAILS, json!([])),
+            screen(MENU, json!([])),
+        ]);
+        let mut player = Player::new(&document, HOME).unwrap();
+        let node = HOME.to_string();
+        player
+            .handle(Event::HoverStart { node: node.clone() })
+            .unwrap();
+        assert_eq!(player.overlays(), [MENU]);
+        let outcomes = player
+            .handle(Event::HoverEnd { node: node.clone() })
+            .unwrap();
+        assert_eq!(navigations(&outcomes), [(Navigation::Revert, MENU, HOME)]);
+        assert!(player.overlays().is_empty());
+
+        player
+            .handle(Event::PressStart { node: node.clone() })
+            .unwrap();
+        assert_eq!(player.screen(), DETAILS);
+        let outcomes = player.handle(Event::PressEnd { node }).unwrap();
+        assert_eq!(
+            navigations(&outcomes),
+            [(Navigation::Revert, DETAILS, HOME)]
+        );
+        assert_eq!(player.screen(), HOME);
+        assert!(player.history().is_empty());
+    }
+
+    #[test]
+    fn timeouts_and_delays_run_as_time_passes() {
+        let mut timeout = trigger("AfterTimeout");
+        timeout["timeout"] = json!(2000);
+        let mut delayed = trigger("MouseEnter");
+        delayed["delay"] = json!(500);
+        let document = document(vec![
+            screen(
+                HOME,
+                json!([
+                    { "trigger": timeout, "actions": [go("Navigate", DETAILS)] },
+                    { "trigger": trigger("OnClick"), "actions": [go("Navigate", MENU)] },
+                ]),
+            ),
+            screen(
+                DETAILS,
+                json!([
+                    { "trigger": delayed, "actions": [go("Overlay", MENU)] },
+                ]),
+            ),
+            screen(MENU, json!([])),
+        ]);
+        let mut player = Player::new(&document, HOME).unwrap();
+        let elapsed = |milliseconds| Event::Elapsed { milliseconds };
+        assert!(player.handle(elapsed(1999)).unwrap().is_empty());
+        let outcomes = player.handle(elapsed(1)).unwrap();
+        assert_eq!(
+            navigations(&outcomes),
+            [(Navigation::Navigate, HOME, DETAILS)]
+        );
+        assert_eq!(player.clock(), 2000);
+
+        // Leaving before the delay cancels the interaction.
+        let node = DETAILS.to_string();
+        player
+            .handle(Event::HoverStart { node: node.clone() })
+            .unwrap();
+        player.handle(elapsed(200)).unwrap();
+        player
+            .handle(Event::HoverEnd { node: node.clone() })
+            .unwrap();
+        assert!(player.handle(elapsed(1000)).unwrap().is_empty());
+        player.handle(Event::HoverStart { node }).unwrap();
+        let outcomes = player.handle(elapsed(500)).unwrap();
+        assert_eq!(
+            navigations(&outcomes),
+            [(Navigation::Overlay, DETAILS, MENU)]
+        );
+
+        // Timeouts of screens navigated away from are dropped.
+        let mut player = Player::new(&document, HOME).unwrap();
+        player.handle(click(HOME)).unwrap();
+        assert!(player.handle(elapsed(5000)).unwrap().is_empty());
+        assert_eq!(player.screen(), MENU);
+    }
+
+    #[test]
+    fn the_clock_stops_at_its_end() {
+        let mut timeout = trigger("AfterTimeout");
+        timeout["timeout"] = json!(1000);
+        let document = document(vec![
+            screen(
+                HOME,
+                json!([{ "trigger": trigger("OnClick"), "actions": [go("Navigate", DETAILS)] }]),
+            ),
+            screen(
+                DETAILS,
+                json!([{ "trigger": timeout, "actions": [go("Navigate", MENU)] }]),
+            ),
+            screen(MENU, json!([])),
+        ]);
+        let mut player = Player::new(&document, HOME).unwrap();
+        let elapsed = |milliseconds| Event::Elapsed { milliseconds };
+        player.handle(elapsed(u64::MAX - 10)).unwrap();
+        player.handle(elapsed(u64::MAX)).unwrap();
+        assert_eq!(player.clock(), u64::MAX);
+
+        // Timers started at the end of time expire right away.
+        player.handle(click(HOME)).unwrap();
+        let outcomes = player.handle(elapsed(0)).unwrap();
+        assert_eq!(
+            navigations(&outcomes),
+            [(Navigation::Navigate, DETAILS, MENU)]
+        );
+        assert_eq!(player.clock(), u64::MAX);
+    }
+
+    #[test]
+    fn timers_starting_each_other_endlessly_are_stopped() {
+        let mut timeout = trigger("AfterTimeout");
+        timeout["timeout"] = json!(0);
+        let document = document(vec![
+            screen(
+                HOME,
+                json!([{ "trigger": timeout, "actions": [go("Navigate", DETAILS)] }]),
+            ),
+            screen(
+                DETAILS,
+                json!([{ "trigger": timeout, "actions": [go("Navigate", HOME)] }]),
+            ),
+        ]);
+        let mut player = Player::new(&document, HOME).unwrap();
+        assert!(matches!(
+            player.handle(Event::Elapsed { milliseconds: 1 }),
+            Err(PlayerError::Timers(0))
+        ));
+        assert_eq!(player.clock(), 0);
+        assert_eq!(player.history().len(), MAX_TIMERS);
+    }
+
+    #[test]
+    fn keys_match_the_device_and_codes() {
+        let mut keys = trigger("OnKeyDown");
+        keys["keyCodes"] = json!([16, 13]);
+        let mut controller = trigger("OnKeyDown");
+        controller["device"] = json!("xboxOne");
+        controller["keyCodes"] = json!([0]);
+        let document = document(vec![
+            screen(
+                HOME,
+                json!([
+                    { "trigger": keys, "actions": [go("Navigate", DETAILS)] },
+                    { "trigger": controller, "actions": [go("Navigate", MENU)] },
+                ]),
+            ),
+            screen(DETAILS, json!([])),
+            screen(MENU, json!([])),
+        ]);
+        let mut player = Player::new(&document, HOME).unwrap();
+        let key = |device, key_codes: &[i32]| Event::KeyDown {
+            device,
+            key_codes: key_codes.to_vec(),
+        };
+        assert!(player
+            .handle(key(TriggerDevice::Keyboard, &[13]))
+            .unwrap()
+            .is_empty());
+        assert!(player
+            .handle(key(TriggerDevice::Ps4, &[0]))
+            .unwrap()
+            .is_empty());
+        player
+            .handle(key(TriggerDevice::Keyboard, &[13, 16]))
+            .unwrap();
+        assert_eq!(player.screen(), DETAILS);
+        let mut player = Player::new(&document, HOME).unwrap();
+        player.handle(key(TriggerDevice::XboxOne, &[0])).unwrap();
+        assert_eq!(player.screen(), MENU);
+    }
+
+    #[test]
+    fn conditionals_run_against_variables() {
+        let increment = json!({
+            "type": "SetVariable",
+            "variableId": COUNT,
+            "variableValue": expression("Addition", [count(), float(1.0)], "Float"),
+        });
+        let conditional = json!({
+            "type": "Conditional",
+            "conditionalBlocks": [
+                {
+                    "condition": expression("GreaterThanOrEqual", [count(), float(3.0)], "Boolean"),
+                    "actions": [go("Navigate", DETAILS)],
+                },
+                {
+                    "condition": { "variableDataType": "boolean", "resolvedType": "Boolean", "value": true },
+                    "actions": [{ "type": "Url", "url": "https://example.com" }],
+                },
+            ],
+        });
+        let document = document(vec![
+            screen(
+                HOME,
+                json!([{ "trigger": trigger("OnClick"), "actions": [increment, conditional] }]),
+            ),
+            screen(DETAILS, json!([])),
+        ]);
+        let mut player = Player::new(&document, HOME).unwrap();
+        let outcomes = player.handle(click(HOME)).unwrap();
+        assert_eq!(
+            outcomes,
+            [
+                Outcome::VariableChanged {
+                    id: COUNT.to_string(),
+                    value: Value::Float(1.0),
+                },
+                Outcome::OpenUrl("https://example.com".to_string()),
+            ]
+        );
+        player.handle(click(HOME)).unwrap();
+        let outcomes = player.handle(click(HOME)).unwrap();
+        assert_eq!(
+            navigations(&outcomes),
+            [(Navigation::Navigate, HOME, DETAILS)]
+        );
+        assert_eq!(player.variables().get(COUNT).unwrap(), Value::Float(3.0));
+    }
+
+    #[test]
+    fn modes_change_the_values_of_variables() {
+        let mode = json!({
+            "type": "SetVariableMode",
+            "variableCollectionId": "VariableCollectionId:1:0",
+            "variableModeId": "1:1",
+        });
+        let document = document(vec![screen(
+            HOME,
+            json!([{ "trigger": trigger("OnClick"), "actions": [mode] }]),
+        )]);
+        let mut player = Player::new(&document, HOME).unwrap();
+        assert_eq!(player.variables().get(COUNT).unwrap(), Value::Float(0.0));
+        player.handle(click(HOME)).unwrap();
+        assert_eq!(player.variables().get(COUNT).unwrap(), Value::Float(10.0));
+
+        let negated = VariableData {
+            variable_data_type: VariableDataType::Expression,
+            resolved_type: VariableResolvedDataType::Float,
+            value: VariableDataValue::Expression(Expression {
+                expression_function: ExpressionFunction::Negate,
+                expression_arguments: vec![VariableData {
+                    variable_data_type: VariableDataType::VariableAlias,
+                    resolved_type: VariableResolvedDataType::Float,
+                    value: VariableDataValue::VariableAlias(VariableAlias {
+                        variable_alias_type: "VARIABLE_ALIAS".to_string(),
+                        id: COUNT.to_string(),
+                    }),
+                }],
+            }),
+        };
+        let value = player.variables().evaluate(&negated).unwrap();
+        assert_eq!(value, Value::Float(-10.0));
+    }
+
+    #[test]
+    fn scrolls_and_changes_variants() {
+        let document = document(vec![
+            screen(
+                HOME,
+                json!([
+                    { "trigger": trigger("OnClick"), "actions": [go("ScrollTo", DETAILS)] },
+                    { "trigger": trigger("OnDrag"), "actions": [go("ChangeTo", MENU)] },
+                ]),
+            ),
+            screen(DETAILS, json!([])),
+            screen(
+                MENU,
+                json!([{ "trigger": trigger("OnDrag"), "actions": [go("ChangeTo", HOME)] }]),
+            ),
+        ]);
+        let mut player = Player::new(&document, HOME).unwrap();
+        let outcomes = player.handle(click(HOME)).unwrap();
+        assert!(matches!(&outcomes[..], [Outcome::ScrollTo { node, .. }] if node == DETAILS));
+        assert_eq!(player.screen(), HOME);
+
+        let drag = || Event::Drag {
+            node: HOME.to_string(),
+        };
+        let outcomes = player.handle(drag()).unwrap();
+        assert_eq!(navigations(&outcomes), [(Navigation::ChangeTo, HOME, MENU)]);
+        assert_eq!(player.variant(HOME), Some(MENU));
+        // The node now has the interactions of its variant.
+        let outcomes = player.handle(drag()).unwrap();
+        assert_eq!(navigations(&outcomes), [(Navigation::ChangeTo, MENU, HOME)]);
+        assert_eq!(player.variant(HOME), Some(HOME));
+    }
+
+    #[test]
+    fn starts_on_screens_only() {
+        let document = document(vec![screen(HOME, json!([]))]);
+        assert!(matches!(
+            Player::new(&document, "0:1"),
+            Err(PlayerError::NotAScreen(_))
+        ));
+        assert!(matches!(
+            Player::new(&document, "9:9"),
+            Err(PlayerError::NodeNotFound(_))
+        ));
+    }
+}
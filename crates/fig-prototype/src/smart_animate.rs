@@ -0,0 +1,809 @@
+//! Smart Animate transitions between two frames.
+//!
+//! Layers of the source and destination frames are matched by name: two layers match when the
+//! names of their ancestors up to the frame and their own names are the same, and layers sharing
+//! such a path are matched in order. Matched layers have their transforms, sizes, opacity,
+//! fills, strokes, corner radii and effects interpolated, the others fade out or in. The
+//! properties which cannot be interpolated switch halfway through the transition.
+
+use std::collections::HashMap;
+use std::f32::consts::PI;
+
+use fig_types::node::vector::VectorNode;
+use fig_types::node::{CanvasNode, Node, NodeType};
+use fig_types::properties::{Color, ColorStop, Effect, Paint, Rectangle, Size, Transform, Vector};
+
+use crate::easing::Curve;
+
+/// A layer of the transition, present in the source frame, the destination frame or both.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Layer<'a> {
+    pub from: Option<&'a Node>,
+    pub to: Option<&'a Node>,
+}
+
+/// A frame of the transition, ready to be rendered.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Frame {
+    /// Time since the start of the transition, in milliseconds.
+    pub elapsed: f32,
+    /// Progress of the transition along its easing curve.
+    pub progress: f32,
+    /// A canvas holding the layers of the transition at this frame, back to front, in absolute
+    /// coordinates.
+    pub node: Node,
+}
+
+/// A Smart Animate transition from one frame to another.
+#[derive(Debug, Clone)]
+pub struct SmartAnimate<'a> {
+    from: &'a Node,
+    to: &'a Node,
+    layers: Vec<Layer<'a>>,
+}
+
+impl<'a> SmartAnimate<'a> {
+    pub fn new(from: &'a Node, to: &'a Node) -> Self {
+        Self {
+            from,
+            to,
+            layers: match_layers(from, to),
+        }
+    }
+
+    /// The layers of the transition, back to front: the layers of the destination frame in
+    /// order, with the layers only in the source frame after the source layer preceding them.
+    pub fn layers(&self) -> &[Layer<'a>] {
+        &self.layers
+    }
+
+    /// The layers of the transition when it has progressed by `progress`, from 0 at the source
+    /// frame to 1 at the destination frame.
+    pub fn at(&self, progress: f32) -> Vec<Node> {
+        self.layers
+            .iter()
+            .map(|layer| match (layer.from, layer.to) {
+                (Some(from), Some(to)) => blend(from, to, progress),
+                (Some(from), None) => fade(from, 1.0 - progress),
+                (None, Some(to)) => fade(to, progress),
+                (None, None) => unreachable!("layers are in at least one of the frames"),
+            })
+            .collect()
+    }
+
+    /// The frames of a transition of `duration` milliseconds along `curve`, at `fps` frames per
+    /// second, from the source frame to the destination frame both included.
+    pub fn frames(&self, curve: &Curve, duration: f32, fps: f32) -> Vec<Frame> {
+        let total = curve.duration(duration);
+        let samples = curve.sample(duration, fps);
+        let last = samples.len() - 1;
+        samples
+            .into_iter()
+            .enumerate()
+            .map(|(index, progress)| Frame {
+                elapsed: total * index as f32 / last as f32,
+                progress,
+                node: Node {
+                    id: self.to.id.clone(),
+                    name: self.to.name.clone(),
+                    visible: true,
+                    node: NodeType::Canvas(CanvasNode {
+                        children: self.at(progress),
+                        background_color: Color {
+                            r: 0.0,
+                            g: 0.0,
+                            b: 0.0,
+                            a: 0.0,
+                        },
+                        flow_starting_points: Vec::new(),
+                        prototype_device: None,
+                        measurements: Vec::new(),
+                    }),
+                    rotation: 0.0,
+                },
+            })
+            .collect()
+    }
+
+    pub fn from(&self) -> &'a Node {
+        self.from
+    }
+
+    pub fn to(&self) -> &'a Node {
+        self.to
+    }
+}
+
+/// Matches the layers of two frames. The frames always match each other, whatever their names.
+pub fn match_layers<'a>(from: &'a Node, to: &'a Node) -> Vec<Layer<'a>> {
+    let sources = keyed(from);
+    let mut unmatched: HashMap<_, _> = sources
+        .iter()
+        .enumerate()
+        .map(|(index, (key, _))| (key.clone(), index))
+        .collect();
+    let mut layers = Vec::new();
+    // Position in `layers` of each matched source layer.
+    let mut positions = HashMap::new();
+    for (key, node) in keyed(to) {
+        let from = unmatched.remove(&key);
+        if let Some(index) = from {
+            positions.insert(index, layers.len());
+        }
+        layers.push(Layer {
+            from: from.map(|index| sources[index].1),
+            to: Some(node),
+        });
+    }
+    // Layers only in the source frame stay above the source layer preceding them.
+    let mut after: HashMap<usize, Vec<Layer>> = HashMap::new();
+    let mut anchor = 0;
+    for (index, (_, node)) in sources.iter().enumerate() {
+        match positions.get(&index) {
+            Some(position) => anchor = *position,
+            None => after.entry(anchor).or_default().push(Layer {
+                from: Some(node),
+                to: None,
+            }),
+        }
+    }
+    layers
+        .into_iter()
+        .enumerate()
+        .flat_map(|(position, layer)| {
+            std::iter::once(layer).chain(after.remove(&position).unwrap_or_default())
+        })
+        .collect()
+}
+
+/// The layers of a frame in drawing order, with the names of their ancestors below the frame and
+/// their own, and how many layers before them share these names.
+fn keyed(frame: &Node) -> Vec<((Vec<&str>, usize), &Node)> {
+    fn walk<'a>(
+        node: &'a Node,
+        path: &mut Vec<&'a str>,
+        counts: &mut HashMap<Vec<&'a str>, usize>,
+        layers: &mut Vec<((Vec<&'a str>, usize), &'a Node)>,
+    ) {
+        let count = counts.entry(path.clone()).or_default();
+        layers.push(((path.clone(), *count), node));
+        *count += 1;
+        for child in node.children() {
+            path.push(&child.name);
+            walk(child, path, counts, layers);
+            path.pop();
+        }
+    }
+
+    let mut layers = Vec::new();
+    walk(frame, &mut Vec::new(), &mut HashMap::new(), &mut layers);
+    layers
+}
+
+/// A copy of a layer without its children, which are layers of their own.
+fn layer(node: &Node) -> Node {
+    let mut layer = node.clone();
+    if let Some(children) = layer.children_mut() {
+        children.clear();
+    }
+    layer
+}
+
+/// A layer only in one of the frames, at `opacity` times its own.
+fn fade(node: &Node, opacity: f32) -> Node {
+    let mut layer = layer(node);
+    if let Some(properties) = Properties::of(&layer) {
+        Properties {
+            opacity: properties.opacity * opacity.clamp(0.0, 1.0),
+            ..properties
+        }
+        .apply(&mut layer);
+    }
+    layer
+}
+
+/// A matched layer, `progress` of the way from the source to the destination.
+fn blend(from: &Node, to: &Node, progress: f32) -> Node {
+    let mut layer = layer(if progress < 0.5 { from } else { to });
+    // The layer keeps the identity it has in the destination frame throughout.
+    layer.id = to.id.clone();
+    layer.name = to.name.clone();
+    layer.visible = from.visible || to.visible;
+    layer.rotation = lerp(from.rotation, to.rotation, progress);
+    if let (Some(mut start), Some(mut end)) = (Properties::of(from), Properties::of(to)) {
+        // Hidden layers are shown transparent, so that they fade in or out.
+        if !from.visible {
+            start.opacity = 0.0;
+        }
+        if !to.visible {
+            end.opacity = 0.0;
+        }
+        start.blend(&end, progress).apply(&mut layer);
+    }
+    layer
+}
+
+/// The properties of vector layers which are interpolated.
+#[derive(Debug, Clone, PartialEq)]
+struct Properties {
+    opacity: f32,
+    absolute_bounding_box: Rectangle,
+    size: Size,
+    relative_transform: Transform,
+    fills: Vec<Paint>,
+    strokes: Vec<Paint>,
+    stroke_weight: f32,
+    effects: Vec<Effect>,
+    /// The single and individual corner radii of rectangles.
+    corner_radii: Option<(f32, [f32; 4])>,
+}
+
+impl Properties {
+    fn of(node: &Node) -> Option<Self> {
+        fn vector<Data>(
+            vector: &VectorNode<Data>,
+            corner_radii: Option<(f32, [f32; 4])>,
+        ) -> Properties {
+            Properties {
+                opacity: vector.opacity,
+                absolute_bounding_box: vector.absolute_bounding_box.clone(),
+                size: vector.size.clone(),
+                relative_transform: vector.relative_transform.clone(),
+                fills: vector.fills.clone(),
+                strokes: vector.strokes.clone(),
+                stroke_weight: vector.stroke_weight,
+                effects: vector.effects.clone(),
+                corner_radii,
+            }
+        }
+
+        match &node.node {
+            NodeType::Vector(data) => Some(vector(data, None)),
+            NodeType::Rectangle(data) => Some(vector(
+                data,
+                Some((
+                    data.additional_data.corner_radius,
+                    data.additional_data.rectangle_corner_radii,
+                )),
+            )),
+            NodeType::Document(_) | NodeType::Canvas(_) => None,
+        }
+    }
+
+    fn apply(self, node: &mut Node) {
+        fn vector<Data>(vector: &mut VectorNode<Data>, properties: Properties) {
+            vector.opacity = properties.opacity;
+            vector.absolute_bounding_box = properties.absolute_bounding_box;
+            vector.size = properties.size;
+            vector.relative_transform = properties.relative_transform;
+            vector.fills = properties.fills;
+            vector.strokes = properties.strokes;
+            vector.stroke_weight = properties.stroke_weight;
+            vector.effects = properties.effects;
+            // The render bounds of the frames do not hold in between.
+            vector.absolute_render_bounds = None;
+        }
+
+        match &mut node.node {
+            NodeType::Vector(data) => vector(data, self),
+            NodeType::Rectangle(data) => {
+                if let Some((corner_radius, rectangle_corner_radii)) = self.corner_radii {
+                    data.additional_data.corner_radius = corner_radius;
+                    data.additional_data.rectangle_corner_radii = rectangle_corner_radii;
+                }
+                vector(data, self)
+            }
+            NodeType::Document(_) | NodeType::Canvas(_) => {}
+        }
+    }
+
+    fn blend(&self, to: &Self, progress: f32) -> Self {
+        let t = progress;
+        let from_box = &self.absolute_bounding_box;
+        let to_box = &to.absolute_bounding_box;
+        Self {
+            opacity: unit(lerp(self.opacity, to.opacity, t)),
+            absolute_bounding_box: Rectangle {
+                x: lerp(from_box.x, to_box.x, t),
+                y: lerp(from_box.y, to_box.y, t),
+                width: lerp(from_box.width, to_box.width, t).max(0.0),
+                height: lerp(from_box.height, to_box.height, t).max(0.0),
+            },
+            size: Size {
+                width: lerp(self.size.width, to.size.width, t).max(0.0),
+                height: lerp(self.size.height, to.size.height, t).max(0.0),
+            },
+            relative_transform: blend_transform(
+                &self.relative_transform,
+                &to.relative_transform,
+                t,
+            ),
+            fills: blend_list(
+                &self.fills,
+                &to.fills,
+                t,
+                paint_matches,
+                blend_paint,
+                clear_paint,
+            )
+            .unwrap_or_else(|| halfway(&self.fills, &to.fills, t).clone()),
+            strokes: blend_list(
+                &self.strokes,
+                &to.strokes,
+                t,
+                paint_matches,
+                blend_paint,
+                clear_paint,
+            )
+            .unwrap_or_else(|| halfway(&self.strokes, &to.strokes, t).clone()),
+            stroke_weight: lerp(self.stroke_weight, to.stroke_weight, t).max(0.0),
+            effects: blend_list(
+                &self.effects,
+                &to.effects,
+                t,
+                effect_matches,
+                blend_effect,
+                clear_effect,
+            )
+            .unwrap_or_else(|| halfway(&self.effects, &to.effects, t).clone()),
+            corner_radii: match (self.corner_radii, to.corner_radii) {
+                (Some((from, from_corners)), Some((to, to_corners))) => Some((
+                    lerp(from, to, t).max(0.0),
+                    std::array::from_fn(|corner| {
+                        lerp(from_corners[corner], to_corners[corner], t).max(0.0)
+                    }),
+                )),
+                (from, to) => *halfway(&from, &to, t),
+            },
+        }
+    }
+}
+
+fn lerp(from: f32, to: f32, progress: f32) -> f32 {
+    from + (to - from) * progress
+}
+
+/// Keeps values which must stay between 0 and 1 in range when curves overshoot.
+fn unit(value: f32) -> f32 {
+    value.clamp(0.0, 1.0)
+}
+
+/// The value of a property which cannot be interpolated.
+fn halfway<'b, T>(from: &'b T, to: &'b T, progress: f32) -> &'b T {
+    if progress < 0.5 {
+        from
+    } else {
+        to
+    }
+}
+
+/// Interpolates two lists of paints or effects item by item when they match, or fades in or out
+/// the items of a list when the other is empty. `None` when the lists cannot be interpolated.
+fn blend_list<T>(
+    from: &[T],
+    to: &[T],
+    progress: f32,
+    matches: fn(&T, &T) -> bool,
+    blend: fn(&T, &T, f32) -> T,
+    clear: fn(&T) -> T,
+) -> Option<Vec<T>> {
+    if from.is_empty() {
+        return Some(
+            to.iter()
+                .map(|to| blend(&clear(to), to, progress))
+                .collect(),
+        );
+    }
+    if to.is_empty() {
+        return Some(
+            from.iter()
+                .map(|from| blend(from, &clear(from), progress))
+                .collect(),
+        );
+    }
+    if from.len() != to.len() || !from.iter().zip(to).all(|(from, to)| matches(from, to)) {
+        return None;
+    }
+    Some(
+        from.iter()
+            .zip(to)
+            .map(|(from, to)| blend(from, to, progress))
+            .collect(),
+    )
+}
+
+fn blend_color(from: &Color, to: &Color, progress: f32) -> Color {
+    Color {
+        r: unit(lerp(from.r, to.r, progress)),
+        g: unit(lerp(from.g, to.g, progress)),
+        b: unit(lerp(from.b, to.b, progress)),
+        a: unit(lerp(from.a, to.a, progress)),
+    }
+}
+
+fn blend_vector(from: &Vector, to: &Vector, progress: f32) -> Vector {
+    Vector {
+        x: lerp(from.x, to.x, progress),
+        y: lerp(from.y, to.y, progress),
+    }
+}
+
+fn paint_matches(from: &Paint, to: &Paint) -> bool {
+    from.paint_type == to.paint_type
+}
+
+fn blend_paint(from: &Paint, to: &Paint, progress: f32) -> Paint {
+    let mut paint = halfway(from, to, progress).clone();
+    paint.visible = from.visible || to.visible;
+    paint.opacity = unit(lerp(
+        if from.visible { from.opacity } else { 0.0 },
+        if to.visible { to.opacity } else { 0.0 },
+        progress,
+    ));
+    paint.color = blend_color(&from.color, &to.color, progress);
+    if from.gradient_handle_positions.len() == to.gradient_handle_positions.len() {
+        paint.gradient_handle_positions = from
+            .gradient_handle_positions
+            .iter()
+            .zip(&to.gradient_handle_positions)
+            .map(|(from, to)| blend_vector(from, to, progress))
+            .collect();
+    }
+    if from.gradient_stops.len() == to.gradient_stops.len() {
+        paint.gradient_stops = from
+            .gradient_stops
+            .iter()
+            .zip(&to.gradient_stops)
+            .map(|(from, to)| ColorStop {
+                position: unit(lerp(from.position, to.position, progress)),
+                color: blend_color(&from.color, &to.color, progress),
+                bound_variables: halfway(from, to, progress).bound_variables.clone(),
+            })
+            .collect();
+    }
+    paint.rotation = lerp(from.rotation, to.rotation, progress);
+    paint
+}
+
+fn clear_paint(paint: &Paint) -> Paint {
+    Paint {
+        opacity: 0.0,
+        ..paint.clone()
+    }
+}
+
+fn effect_matches(from: &Effect, to: &Effect) -> bool {
+    from.effect_type == to.effect_type
+}
+
+fn blend_effect(from: &Effect, to: &Effect, progress: f32) -> Effect {
+    let mut effect = halfway(from, to, progress).clone();
+    effect.visible = from.visible || to.visible;
+    effect.radius = lerp(from.radius, to.radius, progress).max(0.0);
+    effect.color = blend_color(
+        &if from.visible {
+            from.color.clone()
+        } else {
+            clear_effect(from).color
+        },
+        &if to.visible {
+            to.color.clone()
+        } else {
+            clear_effect(to).color
+        },
+        progress,
+    );
+    effect.offset = blend_vector(&from.offset, &to.offset, progress);
+    effect.spread = lerp(from.spread, to.spread, progress);
+    effect
+}
+
+fn clear_effect(effect: &Effect) -> Effect {
+    Effect {
+        radius: 0.0,
+        color: Color {
+            a: 0.0,
+            ..effect.color.clone()
+        },
+        offset: Vector { x: 0.0, y: 0.0 },
+        spread: 0.0,
+        ..effect.clone()
+    }
+}
+
+/// Interpolates transforms by their translation, rotation, scale and skew, so that rotating
+/// layers keep their size on the way.
+fn blend_transform(from: &Transform, to: &Transform, progress: f32) -> Transform {
+    let from = Decomposed::of(from);
+    let to = Decomposed::of(to);
+    // Rotate the shortest way around.
+    let mut turn = to.angle - from.angle;
+    if turn > PI {
+        turn -= 2.0 * PI;
+    } else if turn < -PI {
+        turn += 2.0 * PI;
+    }
+    Decomposed {
+        angle: from.angle + turn * progress,
+        scale_x: lerp(from.scale_x, to.scale_x, progress),
+        scale_y: lerp(from.scale_y, to.scale_y, progress),
+        skew: lerp(from.skew, to.skew, progress),
+        x: lerp(from.x, to.x, progress),
+        y: lerp(from.y, to.y, progress),
+    }
+    .transform()
+}
+
+/// An affine transform as a rotation of a scale and skew, then a translation.
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Decomposed {
+    angle: f32,
+    scale_x: f32,
+    scale_y: f32,
+    skew: f32,
+    x: f32,
+    y: f32,
+}
+
+impl Decomposed {
+    fn of(transform: &Transform) -> Self {
+        let at = |row: usize, column: usize| {
+            transform
+                .matrix
+                .get(row)
+                .and_then(|row| row.get(column))
+                .copied()
+                .unwrap_or(if row == column { 1.0 } else { 0.0 })
+        };
+        let (a, c, x) = (at(0, 0), at(0, 1), at(0, 2));
+        let (b, d, y) = (at(1, 0), at(1, 1), at(1, 2));
+        let scale_x = a.hypot(b);
+        if scale_x == 0.0 {
+            // Degenerate transforms have no angle, keep their second column as is.
+            return Self {
+                angle: 0.0,
+                scale_x,
+                scale_y: d,
+                skew: c,
+                x,
+                y,
+            };
+        }
+        Self {
+            angle: b.atan2(a),
+            scale_x,
+            scale_y: (a * d - b * c) / scale_x,
+            skew: (a * c + b * d) / scale_x,
+            x,
+            y,
+        }
+    }
+
+    fn transform(&self) -> Transform {
+        let (sin, cos) = self.angle.sin_cos();
+        Transform {
+            matrix: vec![
+                vec![
+                    self.scale_x * cos,
+                    self.skew * cos - self.scale_y * sin,
+                    self.x,
+                ],
+                vec![
+                    self.scale_x * sin,
+                    self.skew * sin + self.scale_y * cos,
+                    self.y,
+                ],
+            ],
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use fig_file::fixtures;
+    use serde_json::{json, Value as Json};
+
+    use super::*;
+
+    fn rectangle(id: &str, name: &str, x: f32, width: f32) -> Json {
+        let layer = fixtures::layer(
+            "rectangle",
+            id,
+            name,
+            fixtures::bounds(x, 0.0, width, 100.0),
+        );
+        serde_json::to_value(layer).unwrap()
+    }
+
+    fn shadow(radius: f32, y: f32) -> Json {
+        let mut shadow = fixtures::effect("DropShadow", radius);
+        shadow["offset"]["y"] = json!(y);
+        shadow
+    }
+
+    fn group(id: &str, name: &str, children: Vec<Json>) -> Json {
+        json!({
+            "id": id,
+            "name": name,
+            "visible": true,
+            "rotation": 0.0,
+            "node": { "type": "canvas", "data": {
+                "children": children,
+                "backgroundColor": { "r": 1.0, "g": 1.0, "b": 1.0, "a": 1.0 },
+            }},
+        })
+    }
+
+    fn node(json: Json) -> Node {
+        serde_json::from_value(json).unwrap()
+    }
+
+    fn vector(node: &Node) -> &VectorNode<fig_types::node::vector::RectangleData> {
+        match &node.node {
+            NodeType::Rectangle(vector) => vector,
+            node => panic!("not a rectangle: {node:?}"),
+        }
+    }
+
+    fn ids<'a>(layers: &[Layer<'a>]) -> Vec<(Option<&'a str>, Option<&'a str>)> {
+        layers
+            .iter()
+            .map(|layer| {
+                (
+                    layer.from.map(|node| node.id.as_str()),
+                    layer.to.map(|node| node.id.as_str()),
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn layers_match_by_name_path() {
+        let from = node(group(
+            "1:1",
+            "Home",
+            vec![
+                rectangle("1:2", "Title", 0.0, 100.0),
+                group("1:3", "Card", vec![rectangle("1:4", "Title", 0.0, 100.0)]),
+                rectangle("1:5", "Dot", 0.0, 10.0),
+                rectangle("1:6", "Dot", 20.0, 10.0),
+                rectangle("1:7", "Banner", 0.0, 100.0),
+            ],
+        ));
+        let to = node(group(
+            "2:1",
+            "Details",
+            vec![
+                group("2:3", "Card", vec![rectangle("2:4", "Title", 0.0, 200.0)]),
+                rectangle("2:5", "Dot", 40.0, 10.0),
+                rectangle("2:8", "Close", 0.0, 20.0),
+            ],
+        ));
+
+        assert_eq!(
+            ids(&match_layers(&from, &to)),
+            [
+                (Some("1:1"), Some("2:1")),
+                (Some("1:2"), None),
+                (Some("1:3"), Some("2:3")),
+                (Some("1:4"), Some("2:4")),
+                (Some("1:5"), Some("2:5")),
+                (Some("1:6"), None),
+                (Some("1:7"), None),
+                (None, Some("2:8")),
+            ]
+        );
+    }
+
+    #[test]
+    fn matched_layers_are_interpolated() {
+        let mut start = rectangle("1:1", "Box", 0.0, 100.0);
+        start["node"]["data"]["fills"] = json!([fixtures::solid(1.0, 0.0, 0.0)]);
+        start["node"]["data"]["effects"] = json!([shadow(4.0, 2.0)]);
+        start["node"]["data"]["opacity"] = json!(0.5);
+        let mut end = rectangle("2:1", "Box", 100.0, 200.0);
+        end["node"]["data"]["fills"] = json!([fixtures::solid(0.0, 0.0, 1.0)]);
+        end["node"]["data"]["effects"] = json!([shadow(12.0, 6.0)]);
+        end["node"]["data"]["additionalData"]["cornerRadius"] = json!(8.0);
+        end["node"]["data"]["additionalData"]["rectangleCornerRadii"] = json!([8.0, 8.0, 8.0, 8.0]);
+        let (from, to) = (node(start), node(end));
+        let animation = SmartAnimate::new(&from, &to);
+
+        let first = &animation.at(0.0)[0];
+        assert_eq!(first.id, "2:1");
+        assert_eq!(first.node, from.node);
+        assert_eq!(animation.at(1.0)[0], to);
+
+        let middle = &animation.at(0.5)[0];
+        let middle = vector(middle);
+        assert_eq!(
+            middle.absolute_bounding_box,
+            Rectangle {
+                x: 50.0,
+                y: 0.0,
+                width: 150.0,
+                height: 100.0
+            }
+        );
+        assert_eq!(middle.size.width, 150.0);
+        assert_eq!(middle.relative_transform.matrix[0][2], 50.0);
+        assert_eq!(middle.opacity, 0.75);
+        let color = &middle.fills[0].color;
+        assert_eq!((color.r, color.g, color.b), (0.5, 0.0, 0.5));
+        assert_eq!(middle.effects[0].radius, 8.0);
+        assert_eq!(middle.effects[0].offset.y, 4.0);
+        assert_eq!(middle.additional_data.corner_radius, 4.0);
+        assert_eq!(middle.additional_data.rectangle_corner_radii, [4.0; 4]);
+    }
+
+    #[test]
+    fn rotations_keep_the_size_of_layers() {
+        let from = node(rectangle("1:1", "Box", 0.0, 100.0));
+        let mut end = rectangle("2:1", "Box", 0.0, 100.0);
+        end["rotation"] = json!(90.0);
+        end["node"]["data"]["relativeTransform"] =
+            json!({ "matrix": [[0.0, -2.0, 0.0], [2.0, 0.0, 0.0]] });
+        let to = node(end);
+
+        let middle = SmartAnimate::new(&from, &to).at(0.5).remove(0);
+        assert_eq!(middle.rotation, 45.0);
+        let matrix = &vector(&middle).relative_transform.matrix;
+        let half = std::f32::consts::FRAC_1_SQRT_2 * 1.5;
+        for (actual, expected) in matrix[0].iter().zip([half, -half, 0.0]) {
+            assert!((actual - expected).abs() < 1e-5, "{matrix:?}");
+        }
+        for (actual, expected) in matrix[1].iter().zip([half, half, 0.0]) {
+            assert!((actual - expected).abs() < 1e-5, "{matrix:?}");
+        }
+    }
+
+    #[test]
+    fn unmatched_layers_fade() {
+        let from = node(group(
+            "1:1",
+            "Home",
+            vec![rectangle("1:2", "Old", 0.0, 100.0)],
+        ));
+        let to = node(group(
+            "2:1",
+            "Details",
+            vec![rectangle("2:2", "New", 0.0, 100.0)],
+        ));
+        let animation = SmartAnimate::new(&from, &to);
+
+        let layers = animation.at(0.25);
+        assert_eq!(layers.len(), 3);
+        assert_eq!(layers[1].id, "1:2");
+        assert_eq!(vector(&layers[1]).opacity, 0.75);
+        assert_eq!(layers[2].id, "2:2");
+        assert_eq!(vector(&layers[2]).opacity, 0.25);
+        // Groups are drawn without their children, which are layers of their own.
+        assert!(layers[0].children().is_empty());
+    }
+
+    #[test]
+    fn frames_go_from_the_source_to_the_destination() {
+        let from = node(rectangle("1:1", "Box", 0.0, 100.0));
+        let to = node(rectangle("2:1", "Box", 300.0, 100.0));
+        let frames = SmartAnimate::new(&from, &to).frames(&Curve::Linear, 300.0, 10.0);
+
+        assert_eq!(frames.len(), 4);
+        assert_eq!(
+            frames
+                .iter()
+                .map(|frame| (frame.elapsed, frame.progress))
+                .collect::<Vec<_>>(),
+            [
+                (0.0, 0.0),
+                (100.0, 1.0 / 3.0),
+                (200.0, 2.0 / 3.0),
+                (300.0, 1.0)
+            ]
+        );
+        let last = &frames[3].node;
+        assert_eq!(last.id, "2:1");
+        assert_eq!(last.children(), [to]);
+        let first = vector(&frames[0].node.children()[0]);
+        assert_eq!(first.absolute_bounding_box.x, 0.0);
+    }
+}
@@ -0,0 +1,251 @@
+//! Values of the variables of a document while a prototype plays, and evaluation of the variable
+//! data and expressions of its actions.
+
+use std::collections::HashMap;
+use std::fmt;
+
+use fig_file::document::Document;
+use fig_types::properties::{
+    Color, Expression, ExpressionFunction, VariableData, VariableDataValue,
+};
+use serde::{Deserialize, Serialize};
+
+/// Aliases are followed at most this many times, deeper chains are assumed to be cycles.
+const MAX_ALIAS_DEPTH: usize = 32;
+
+/// A variable value with aliases and expressions resolved.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum Value {
+    Boolean(bool),
+    Float(f32),
+    String(String),
+    Color(Color),
+}
+
+impl fmt::Display for Value {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Value::Boolean(value) => write!(f, "{value}"),
+            Value::Float(value) => write!(f, "{value}"),
+            Value::String(value) => f.write_str(value),
+            Value::Color(Color { r, g, b, a }) => write!(f, "rgba({r}, {g}, {b}, {a})"),
+        }
+    }
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum EvaluationError {
+    #[error("no variable with id `{0}`")]
+    UnknownVariable(String),
+    #[error("no variable collection with id `{0}`")]
+    UnknownCollection(String),
+    #[error("variable collection `{collection}` has no mode `{mode}`")]
+    UnknownMode { collection: String, mode: String },
+    #[error("variable `{0}` has no value in the current mode of its collection")]
+    MissingValue(String),
+    #[error("variable `{0}` is aliased in a cycle")]
+    AliasCycle(String),
+    #[error("{function:?} takes {expected} argument(s), got {got}")]
+    Arity {
+        function: ExpressionFunction,
+        expected: usize,
+        got: usize,
+    },
+    #[error("{function:?} cannot be applied to {value}")]
+    Type {
+        function: ExpressionFunction,
+        value: Value,
+    },
+    #[error("{0:?} is not supported")]
+    Unsupported(ExpressionFunction),
+}
+
+/// The variables of a document, with the values and modes set by the actions run so far.
+#[derive(Debug, Clone)]
+pub struct Variables<'a> {
+    document: &'a Document,
+    /// Values set by actions, by variable id. They replace the values of every mode.
+    values: HashMap<String, Value>,
+    /// Modes set by actions, by collection id.
+    modes: HashMap<String, String>,
+}
+
+impl<'a> Variables<'a> {
+    pub fn new(document: &'a Document) -> Self {
+        Self {
+            document,
+            values: HashMap::new(),
+            modes: HashMap::new(),
+        }
+    }
+
+    /// The current mode of a collection, its default mode until another is set.
+    pub fn mode(&self, collection: &str) -> Result<&str, EvaluationError> {
+        if let Some(mode) = self.modes.get(collection) {
+            return Ok(mode);
+        }
+        self.document
+            .variable_collections
+            .get(collection)
+            .map(|collection| collection.default_mode_id.as_str())
+            .ok_or_else(|| EvaluationError::UnknownCollection(collection.to_string()))
+    }
+
+    pub fn set_mode(&mut self, collection: &str, mode: &str) -> Result<(), EvaluationError> {
+        let known = self
+            .document
+            .variable_collections
+            .get(collection)
+            .ok_or_else(|| EvaluationError::UnknownCollection(collection.to_string()))?;
+        if !known.modes.iter().any(|known| known.mode_id == mode) {
+            return Err(EvaluationError::UnknownMode {
+                collection: collection.to_string(),
+                mode: mode.to_string(),
+            });
+        }
+        self.modes.insert(collection.to_string(), mode.to_string());
+        Ok(())
+    }
+
+    /// The current value of a variable.
+    pub fn get(&self, id: &str) -> Result<Value, EvaluationError> {
+        self.get_at(id, 0)
+    }
+
+    pub fn set(&mut self, id: &str, value: Value) -> Result<(), EvaluationError> {
+        if !self.document.variables.contains_key(id) {
+            return Err(EvaluationError::UnknownVariable(id.to_string()));
+        }
+        self.values.insert(id.to_string(), value);
+        Ok(())
+    }
+
+    /// Evaluates variable data, resolving aliases to the current values of the variables.
+    pub fn evaluate(&self, data: &VariableData) -> Result<Value, EvaluationError> {
+        self.resolve(&data.value, 0)
+    }
+
+    fn get_at(&self, id: &str, depth: usize) -> Result<Value, EvaluationError> {
+        if let Some(value) = self.values.get(id) {
+            return Ok(value.clone());
+        }
+        let variable = self
+            .document
+            .variables
+            .get(id)
+            .ok_or_else(|| EvaluationError::UnknownVariable(id.to_string()))?;
+        let mode = self.mode(&variable.variable_collection_id)?;
+        let value = variable
+            .values_by_mode
+            .get(mode)
+            .ok_or_else(|| EvaluationError::MissingValue(id.to_string()))?;
+        if depth == MAX_ALIAS_DEPTH {
+            return Err(EvaluationError::AliasCycle(id.to_string()));
+        }
+        self.resolve(value, depth + 1)
+    }
+
+    fn resolve(&self, value: &VariableDataValue, depth: usize) -> Result<Value, EvaluationError> {
+        Ok(match value {
+            VariableDataValue::Boolean(value) => Value::Boolean(*value),
+            VariableDataValue::Float(value) => Value::Float(*value),
+            VariableDataValue::String(value) => Value::String(value.clone()),
+            VariableDataValue::Color(color) => Value::Color(color.clone()),
+            VariableDataValue::RGBColor(color) => Value::Color(Color {
+                r: color.r,
+                g: color.g,
+                b: color.b,
+                a: 1.0,
+            }),
+            VariableDataValue::VariableAlias(alias) => self.get_at(&alias.id, depth)?,
+            VariableDataValue::Expression(expression) => self.expression(expression, depth)?,
+        })
+    }
+
+    fn expression(&self, expression: &Expression, depth: usize) -> Result<Value, EvaluationError> {
+        use ExpressionFunction::*;
+
+        let function = expression.expression_function.clone();
+        let arguments = expression
+            .expression_arguments
+            .iter()
+            .map(|argument| self.resolve(&argument.value, depth))
+            .collect::<Result<Vec<_>, _>>()?;
+        let arity = |expected: usize| match arguments.len() == expected {
+            true => Ok(()),
+            false => Err(EvaluationError::Arity {
+                function: function.clone(),
+                expected,
+                got: arguments.len(),
+            }),
+        };
+        let mismatch = |value: &Value| EvaluationError::Type {
+            function: function.clone(),
+            value: value.clone(),
+        };
+        let float = |value: &Value| match value {
+            Value::Float(value) => Ok(*value),
+            value => Err(mismatch(value)),
+        };
+        let boolean = |value: &Value| match value {
+            Value::Boolean(value) => Ok(*value),
+            value => Err(mismatch(value)),
+        };
+        Ok(match function {
+            Addition => {
+                arity(2)?;
+                match (&arguments[0], &arguments[1]) {
+                    (Value::Float(left), Value::Float(right)) => Value::Float(left + right),
+                    // Adding to a string concatenates, like in Figma.
+                    (left @ Value::String(_), right) | (left, right @ Value::String(_)) => {
+                        Value::String(format!("{left}{right}"))
+                    }
+                    (left, _) => return Err(mismatch(left)),
+                }
+            }
+            Subtraction | Multiplication | Division => {
+                arity(2)?;
+                let (left, right) = (float(&arguments[0])?, float(&arguments[1])?);
+                Value::Float(match function {
+                    Subtraction => left - right,
+                    Multiplication => left * right,
+                    _ => left / right,
+                })
+            }
+            Equals => {
+                arity(2)?;
+                Value::Boolean(arguments[0] == arguments[1])
+            }
+            LessThan | LessThanOrEqual | GreaterThan | GreaterThanOrEqual => {
+                arity(2)?;
+                let (left, right) = (float(&arguments[0])?, float(&arguments[1])?);
+                Value::Boolean(match function {
+                    LessThan => left < right,
+                    LessThanOrEqual => left <= right,
+                    GreaterThan => left > right,
+                    _ => left >= right,
+                })
+            }
+            Not => {
+                arity(1)?;
+                Value::Boolean(!boolean(&arguments[0])?)
+            }
+            Negate => {
+                arity(1)?;
+                Value::Float(-float(&arguments[0])?)
+            }
+            And | Or => {
+                let values = arguments
+                    .iter()
+                    .map(boolean)
+                    .collect::<Result<Vec<_>, _>>()?;
+                Value::Boolean(match function {
+                    And => values.iter().all(|value| *value),
+                    _ => values.iter().any(|value| *value),
+                })
+            }
+            VarModeLookup => return Err(EvaluationError::Unsupported(function)),
+        })
+    }
+}
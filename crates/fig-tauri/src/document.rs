@@ -0,0 +1,263 @@
+use std::path::PathBuf;
+use std::sync::Mutex;
+use std::time::Duration;
+
+use fig_file::document::Document;
+use fig_file::edit::{History, HistorySummary};
+use fig_file::journal::{Journal, DEFAULT_AUTOSAVE_INTERVAL};
+use fig_file::validate::Diagnostic;
+use fig_types::node::Node;
+use fig_types::validate::Violation;
+use serde::Serialize;
+use tauri::{AppHandle, Manager, State};
+
+use crate::recovery::notify;
+use crate::spatial::CanvasIndex;
+
+/// The document being edited, where it is stored and its undo history.
+pub struct Session {
+  pub document: Document,
+  /// Where the document was opened from or last saved to, `None` for new documents.
+  pub path: Option<PathBuf>,
+  pub history: History,
+  /// Whether the document changed since it was last saved.
+  pub dirty: bool,
+  /// Crash recovery journal, kept while the document has unsaved changes.
+  pub journal: Option<Journal>,
+  /// Where journals of untitled documents are kept, `None` until the app is set up.
+  pub recovery_dir: Option<PathBuf>,
+  pub autosave_interval: Duration,
+}
+
+impl Session {
+  pub fn new(document: Document, path: Option<PathBuf>) -> Self {
+    Self {
+      document,
+      path,
+      history: History::new(),
+      dirty: false,
+      journal: None,
+      recovery_dir: None,
+      autosave_interval: DEFAULT_AUTOSAVE_INTERVAL,
+    }
+  }
+
+  fn info(&self) -> DocumentInfo {
+    DocumentInfo {
+      name: self.document.name().to_string(),
+      path: self.path.clone(),
+      dirty: self.dirty,
+      canvases: self
+        .document
+        .canvases()
+        .map(|canvas| CanvasSummary {
+          id: canvas.id.clone(),
+          name: canvas.name.clone(),
+        })
+        .collect(),
+      history: self.history.summary(),
+    }
+  }
+}
+
+impl Default for Session {
+  fn default() -> Self {
+    Self::new(Document::new("Untitled"), None)
+  }
+}
+
+#[derive(Default)]
+pub struct SessionState(pub Mutex<Session>);
+
+#[derive(Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CanvasSummary {
+  pub id: String,
+  pub name: String,
+}
+
+#[derive(Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DocumentInfo {
+  pub name: String,
+  pub path: Option<PathBuf>,
+  pub dirty: bool,
+  pub canvases: Vec<CanvasSummary>,
+  pub history: HistorySummary,
+}
+
+/// Payload of the `document-changed` event, emitted whenever the document held by the Rust side
+/// changes so the webview can refetch what it displays.
+#[derive(Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DocumentChanged {
+  /// Ids of the changed nodes, empty when the whole document was replaced.
+  pub nodes: Vec<String>,
+  pub history: HistorySummary,
+  pub dirty: bool,
+}
+
+/// An opened document with the integrity problems found in it, which are reported but do not
+/// prevent editing.
+#[derive(Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Opened {
+  #[serde(flatten)]
+  pub info: DocumentInfo,
+  pub diagnostics: Vec<Diagnostic>,
+  /// Property values breaking invariants, already fixed if a repair was asked for.
+  pub violations: Vec<Violation>,
+}
+
+pub const DOCUMENT_CHANGED: &str = "document-changed";
+
+/// Notifies the webview that nodes of the document changed.
+pub fn emit_change(app: &AppHandle, session: &Session, nodes: Vec<String>) {
+  let payload = DocumentChanged {
+    nodes,
+    history: session.history.summary(),
+    dirty: session.dirty,
+  };
+  // Nothing to do if no window is listening anymore.
+  let _ = app.emit_all(DOCUMENT_CHANGED, payload);
+}
+
+/// Replaces the open document. Unsaved changes of the previous one, and their journal, are only
+/// thrown away when `discard` is set, otherwise the webview has to save or discard them first.
+pub fn replace(
+  app: &AppHandle,
+  state: &SessionState,
+  index: &CanvasIndex,
+  mut session: Session,
+  discard: bool,
+) -> Result<DocumentInfo, String> {
+  let mut current = state.0.lock().unwrap();
+  if current.dirty && !discard {
+    return Err(format!(
+      "`{}` has unsaved changes, save or discard them first",
+      current.document.name()
+    ));
+  }
+  notify(app, current.close_journal());
+  session.recovery_dir = current.recovery_dir.take();
+  session.autosave_interval = current.autosave_interval;
+  *current = session;
+  index.clear();
+  emit_change(app, &current, Vec::new());
+  Ok(current.info())
+}
+
+#[tauri::command]
+pub fn document_new(
+  app: AppHandle,
+  state: State<'_, SessionState>,
+  index: State<'_, CanvasIndex>,
+  name: Option<String>,
+  discard: Option<bool>,
+) -> Result<DocumentInfo, String> {
+  let document = Document::new(name.as_deref().unwrap_or("Untitled"));
+  let session = Session::new(document, None);
+  replace(&app, &state, &index, session, discard.unwrap_or(false))
+}
+
+#[tauri::command]
+pub fn document_open(
+  app: AppHandle,
+  state: State<'_, SessionState>,
+  index: State<'_, CanvasIndex>,
+  path: PathBuf,
+  repair: Option<bool>,
+  discard: Option<bool>,
+) -> Result<Opened, String> {
+  if Journal::path_for(&path).exists() {
+    return Err(format!(
+      "`{}` has unsaved changes from a previous session, recover or discard them first",
+      path.display()
+    ));
+  }
+  let mut document = Document::open(&path).map_err(|error| error.to_string())?;
+  let diagnostics = document.validate();
+  let repair = repair.unwrap_or(false);
+  let violations = match repair {
+    true => document.repair(),
+    false => document.invariants(),
+  };
+  let mut session = Session::new(document, Some(path));
+  // Repairs are only written when the user saves.
+  session.dirty = repair && !violations.is_empty();
+  let info = replace(&app, &state, &index, session, discard.unwrap_or(false))?;
+  Ok(Opened {
+    info,
+    diagnostics,
+    violations,
+  })
+}
+
+/// Saves the document where it was opened from or last saved to.
+#[tauri::command]
+pub fn document_save(app: AppHandle, state: State<'_, SessionState>) -> Result<DocumentInfo, String> {
+  let mut session = state.0.lock().unwrap();
+  let path = session
+    .path
+    .clone()
+    .ok_or("the document has never been saved, use save as")?;
+  session
+    .document
+    .save(&path)
+    .map_err(|error| error.to_string())?;
+  session.dirty = false;
+  notify(&app, session.close_journal());
+  emit_change(&app, &session, Vec::new());
+  Ok(session.info())
+}
+
+#[tauri::command]
+pub fn document_save_as(
+  app: AppHandle,
+  state: State<'_, SessionState>,
+  path: PathBuf,
+) -> Result<DocumentInfo, String> {
+  let mut session = state.0.lock().unwrap();
+  session
+    .document
+    .save(&path)
+    .map_err(|error| error.to_string())?;
+  notify(&app, session.close_journal());
+  session.path = Some(path);
+  session.dirty = false;
+  emit_change(&app, &session, Vec::new());
+  Ok(session.info())
+}
+
+/// Sets the interval between two autosave snapshots of the recovery journal.
+#[tauri::command]
+pub fn document_set_autosave_interval(state: State<'_, SessionState>, seconds: u64) {
+  let interval = Duration::from_secs(seconds);
+  let mut session = state.0.lock().unwrap();
+  session.autosave_interval = interval;
+  if let Some(journal) = &mut session.journal {
+    journal.set_autosave_interval(interval);
+  }
+}
+
+/// Name, location, canvases and history of the open document.
+#[tauri::command]
+pub fn document_info(state: State<'_, SessionState>) -> DocumentInfo {
+  state.0.lock().unwrap().info()
+}
+
+/// A canvas with all its layers.
+#[tauri::command]
+pub fn document_canvas(state: State<'_, SessionState>, id: String) -> Result<Node, String> {
+  let session = state.0.lock().unwrap();
+  let canvas = session.document.canvases().find(|canvas| canvas.id == id);
+  canvas.cloned().ok_or_else(|| format!("no canvas with id `{id}`"))
+}
+
+/// Any node of the document with its subtree.
+#[tauri::command]
+pub fn document_node(state: State<'_, SessionState>, id: String) -> Result<Node, String> {
+  let session = state.0.lock().unwrap();
+  let node = session.document.node(&id).cloned();
+  node.ok_or_else(|| format!("no node with id `{id}`"))
+}
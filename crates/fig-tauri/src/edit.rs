@@ -0,0 +1,112 @@
+use fig_file::edit::{HistorySummary, Operation};
+use fig_file::journal::Entry;
+use tauri::{AppHandle, State};
+
+use crate::document::{emit_change, Session, SessionState};
+use crate::recovery::notify;
+use crate::spatial::CanvasIndex;
+
+/// Journals the change and keeps the spatial index and the webview in sync after operations
+/// changed the document.
+fn changed(
+  app: &AppHandle,
+  session: &mut Session,
+  index: &CanvasIndex,
+  entry: Entry,
+  operations: &[Operation],
+) {
+  session.dirty = true;
+  notify(app, session.record(&entry));
+  index.sync(&session.document, operations);
+  let mut nodes: Vec<String> = operations
+    .iter()
+    .flat_map(Operation::targets)
+    .map(String::from)
+    .collect();
+  nodes.sort_unstable();
+  nodes.dedup();
+  emit_change(app, session, nodes);
+}
+
+/// Applies operations as a single undoable transaction. Passing the same `coalesce` key for
+/// consecutive calls, e.g. for every step of a drag, merges them into one undo step.
+#[tauri::command]
+pub fn edit_apply(
+  app: AppHandle,
+  state: State<'_, SessionState>,
+  index: State<'_, CanvasIndex>,
+  name: String,
+  operations: Vec<Operation>,
+  coalesce: Option<String>,
+) -> Result<HistorySummary, String> {
+  let mut session = state.0.lock().unwrap();
+  let Session {
+    document, history, ..
+  } = &mut *session;
+  let result = match &coalesce {
+    Some(key) => history.apply_coalesced(&mut document.document, &name, key, &operations),
+    None => history.apply(&mut document.document, &name, &operations),
+  };
+  result.map_err(|error| error.to_string())?;
+  let entry = Entry::Apply {
+    name,
+    operations: operations.clone(),
+    coalesce,
+  };
+  changed(&app, &mut session, &index, entry, &operations);
+  Ok(session.history.summary())
+}
+
+/// Closes the current coalesced transaction, e.g. when a drag ends.
+#[tauri::command]
+pub fn edit_end_coalescing(app: AppHandle, state: State<'_, SessionState>) {
+  let mut session = state.0.lock().unwrap();
+  session.history.end_coalescing();
+  notify(&app, session.record(&Entry::EndCoalescing));
+}
+
+#[tauri::command]
+pub fn edit_undo(
+  app: AppHandle,
+  state: State<'_, SessionState>,
+  index: State<'_, CanvasIndex>,
+) -> Result<HistorySummary, String> {
+  let mut session = state.0.lock().unwrap();
+  let Session {
+    document, history, ..
+  } = &mut *session;
+  let undone = history
+    .undo(&mut document.document)
+    .map_err(|error| error.to_string())?
+    .map(|transaction| transaction.operations().to_vec());
+  if let Some(operations) = undone {
+    changed(&app, &mut session, &index, Entry::Undo, &operations);
+  }
+  Ok(session.history.summary())
+}
+
+#[tauri::command]
+pub fn edit_redo(
+  app: AppHandle,
+  state: State<'_, SessionState>,
+  index: State<'_, CanvasIndex>,
+) -> Result<HistorySummary, String> {
+  let mut session = state.0.lock().unwrap();
+  let Session {
+    document, history, ..
+  } = &mut *session;
+  let redone = history
+    .redo(&mut document.document)
+    .map_err(|error| error.to_string())?
+    .map(|transaction| transaction.operations().to_vec());
+  if let Some(operations) = redone {
+    changed(&app, &mut session, &index, Entry::Redo, &operations);
+  }
+  Ok(session.history.summary())
+}
+
+/// Names of the transactions on the undo and redo stacks, most recent first.
+#[tauri::command]
+pub fn edit_history(state: State<'_, SessionState>) -> HistorySummary {
+  state.0.lock().unwrap().history.summary()
+}
@@ -3,6 +3,11 @@
 
 use tauri::Manager;
 
+mod document;
+mod edit;
+mod recovery;
+mod spatial;
+
 fn main() {
   #[cfg(any(
     target_os = "linux",
@@ -13,7 +18,37 @@ fn main() {
   ))]
   std::env::set_var("WEBKIT_DISABLE_COMPOSITING_MODE", "1");
   tauri::Builder::default()
+    .manage(document::SessionState::default())
+    .manage(spatial::CanvasIndex::default())
+    .invoke_handler(tauri::generate_handler![
+      document::document_new,
+      document::document_open,
+      document::document_save,
+      document::document_save_as,
+      document::document_set_autosave_interval,
+      document::document_info,
+      document::document_canvas,
+      document::document_node,
+      edit::edit_apply,
+      edit::edit_end_coalescing,
+      edit::edit_undo,
+      edit::edit_redo,
+      edit::edit_history,
+      recovery::recovery_check,
+      recovery::recovery_restore,
+      recovery::recovery_discard,
+      spatial::spatial_load,
+      spatial::spatial_hit_test,
+      spatial::spatial_marquee,
+      spatial::spatial_visible,
+    ])
     .setup(|app| {
+      let recovery_dir = app.path_resolver().app_data_dir();
+      if let Some(dir) = &recovery_dir {
+        let _ = std::fs::create_dir_all(dir);
+      }
+      app.state::<document::SessionState>().0.lock().unwrap().recovery_dir = recovery_dir;
+
       let window = app.get_window("main").unwrap();
 
       // Set the window size to monitor size at startup
@@ -27,6 +62,18 @@ fn main() {
 
       Ok(())
     })
-    .run(tauri::generate_context!())
-    .expect("error while running tauri application");
+    .build(tauri::generate_context!())
+    .expect("error while running tauri application")
+    .run(|app, event| {
+      // Unsaved changes stay in the journal to be recovered on the next start.
+      if let tauri::RunEvent::Exit = event {
+        let state = app.state::<document::SessionState>();
+        let mut session = state.0.lock().unwrap();
+        if !session.dirty {
+          // No window is left to tell about a failure, a leftover journal is offered for
+          // recovery on the next start.
+          let _ = session.close_journal();
+        }
+      }
+    });
 }
@@ -0,0 +1,181 @@
+use std::path::{Path, PathBuf};
+
+use fig_file::journal::{self, Entry, Journal};
+use serde::Serialize;
+use tauri::{AppHandle, Manager, State};
+
+use crate::document::{replace, DocumentInfo, Session, SessionState};
+use crate::spatial::CanvasIndex;
+
+/// File in the recovery directory holding the path of the journal of the open document, so it
+/// can be found after a crash even when it is stored next to the document.
+const POINTER: &str = "journal-path";
+/// Journal of an untitled document, in the recovery directory.
+const UNTITLED: &str = "untitled.journal";
+
+pub const RECOVERY_FAILED: &str = "recovery-failed";
+
+/// Payload of the `recovery-failed` event, emitted when the recovery journal cannot be written or
+/// removed, so the webview can warn that unsaved changes would not survive a crash.
+#[derive(Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RecoveryFailed {
+  pub message: String,
+}
+
+/// Notifies the webview of a failure of the recovery journal.
+pub fn notify(app: &AppHandle, result: Result<(), String>) {
+  if let Err(message) = result {
+    // Nothing to do if no window is listening anymore.
+    let _ = app.emit_all(RECOVERY_FAILED, RecoveryFailed { message });
+  }
+}
+
+impl Session {
+  fn journal_path(&self) -> Option<PathBuf> {
+    match &self.path {
+      Some(path) => Some(Journal::path_for(path)),
+      None => self.recovery_dir.as_ref().map(|dir| dir.join(UNTITLED)),
+    }
+  }
+
+  /// Records a change in the recovery journal, which is started on the first unsaved change.
+  /// Failing to write the journal does not fail the edit, the error is returned to be reported
+  /// and the next edit starts a new journal.
+  pub fn record(&mut self, entry: &Entry) -> Result<(), String> {
+    let result = match &mut self.journal {
+      Some(journal) => journal
+        .record(entry)
+        .and_then(|()| journal.autosave(&self.document, &self.history))
+        .map(|_| ()),
+      // Nothing to recover until the document itself changes.
+      None if *entry == Entry::EndCoalescing => Ok(()),
+      None => self.start_journal(),
+    };
+    result.map_err(|error| {
+      self.journal = None;
+      format!("failed to write the recovery journal: {error}")
+    })
+  }
+
+  fn start_journal(&mut self) -> Result<(), fig_file::document::FileError> {
+    let Some(path) = self.journal_path() else {
+      return Ok(());
+    };
+    let mut journal = Journal::create(&path, &self.document, &self.history, self.path.clone())?;
+    journal.set_autosave_interval(self.autosave_interval);
+    if let Some(dir) = &self.recovery_dir {
+      std::fs::write(dir.join(POINTER), path.as_os_str().as_encoded_bytes())?;
+    }
+    self.journal = Some(journal);
+    Ok(())
+  }
+
+  /// Removes the journal once the changes it holds are saved or discarded, returning the error to
+  /// be reported if it cannot be.
+  pub fn close_journal(&mut self) -> Result<(), String> {
+    let Some(journal) = self.journal.take() else {
+      return Ok(());
+    };
+    if let Some(dir) = &self.recovery_dir {
+      let _ = std::fs::remove_file(dir.join(POINTER));
+    }
+    journal
+      .close()
+      .map_err(|error| format!("failed to remove the recovery journal: {error}"))
+  }
+
+  /// The journal left by a previous session: the one of the document at `path`, or the one of
+  /// the document open when the app last exited.
+  fn leftover_journal(&self, path: Option<&Path>) -> Option<PathBuf> {
+    let journal = match path {
+      Some(path) => Journal::path_for(path),
+      None => {
+        let pointer = std::fs::read_to_string(self.recovery_dir.as_ref()?.join(POINTER)).ok()?;
+        PathBuf::from(pointer)
+      }
+    };
+    let current = self.journal.as_ref().map(Journal::path);
+    (journal.exists() && current != Some(journal.as_path())).then_some(journal)
+  }
+}
+
+/// Unsaved changes left by a session which did not shut down cleanly.
+#[derive(Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RecoveryInfo {
+  pub name: String,
+  /// Where the document is saved, `None` if it was never saved.
+  pub path: Option<PathBuf>,
+  /// Number of edits made since the last autosave.
+  pub edits: usize,
+  /// Whether the last edit was only partially written and is lost.
+  pub truncated: bool,
+}
+
+/// Looks for changes left by a previous session, either to the document at `path` or, without a
+/// path, to the document open when the app last exited.
+#[tauri::command]
+pub fn recovery_check(
+  state: State<'_, SessionState>,
+  path: Option<PathBuf>,
+) -> Result<Option<RecoveryInfo>, String> {
+  let session = state.0.lock().unwrap();
+  let Some(journal) = session.leftover_journal(path.as_deref()) else {
+    return Ok(None);
+  };
+  let recovery = journal::recover(journal).map_err(|error| error.to_string())?;
+  Ok(Some(RecoveryInfo {
+    name: recovery.document.name().to_string(),
+    path: recovery.source,
+    edits: recovery.replayed,
+    truncated: recovery.truncated,
+  }))
+}
+
+/// Opens the document recovered from the changes left by a previous session. Unsaved changes of
+/// the open document are kept unless `discard` is set, see [`replace`].
+#[tauri::command]
+pub fn recovery_restore(
+  app: AppHandle,
+  state: State<'_, SessionState>,
+  index: State<'_, CanvasIndex>,
+  path: Option<PathBuf>,
+  discard: Option<bool>,
+) -> Result<DocumentInfo, String> {
+  let journal = state
+    .0
+    .lock()
+    .unwrap()
+    .leftover_journal(path.as_deref())
+    .ok_or("there are no changes to recover")?;
+  let recovery = journal::recover(&journal).map_err(|error| error.to_string())?;
+  let mut session = Session::new(recovery.document, recovery.source);
+  session.history = recovery.history;
+  session.history.end_coalescing();
+  session.dirty = true;
+  let info = replace(&app, &state, &index, session, discard.unwrap_or(false))?;
+  // The recovered changes are still unsaved, they move to a journal of the new session.
+  let mut session = state.0.lock().unwrap();
+  if session.journal_path().as_ref() != Some(&journal) {
+    let _ = std::fs::remove_file(&journal);
+  }
+  let started = session.start_journal();
+  notify(
+    &app,
+    started.map_err(|error| format!("failed to write the recovery journal: {error}")),
+  );
+  Ok(info)
+}
+
+/// Discards the changes left by a previous session.
+#[tauri::command]
+pub fn recovery_discard(state: State<'_, SessionState>, path: Option<PathBuf>) {
+  let session = state.0.lock().unwrap();
+  if let Some(journal) = session.leftover_journal(path.as_deref()) {
+    let _ = std::fs::remove_file(journal);
+  }
+  if let Some(dir) = session.recovery_dir.as_ref().filter(|_| session.journal.is_none()) {
+    let _ = std::fs::remove_file(dir.join(POINTER));
+  }
+}
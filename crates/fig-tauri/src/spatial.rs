@@ -0,0 +1,124 @@
+use std::sync::Mutex;
+
+use fig_file::document::Document;
+use fig_file::edit::Operation;
+use fig_file::spatial::{Bounds, Point, SpatialIndex};
+use fig_types::node::{Node, NodeType};
+use fig_types::patch::PathSegment;
+use tauri::State;
+
+use crate::document::SessionState;
+
+/// Spatial index of the canvas currently displayed by the webview.
+#[derive(Default)]
+pub struct CanvasIndex(Mutex<Indexed>);
+
+#[derive(Default)]
+struct Indexed {
+  /// Id of the indexed canvas.
+  canvas: Option<String>,
+  index: SpatialIndex,
+}
+
+impl CanvasIndex {
+  pub fn clear(&self) {
+    *self.0.lock().unwrap() = Indexed::default();
+  }
+
+  /// Updates the index after operations were applied to the document. Moved, resized, hidden or
+  /// locked layers are updated in place, structural changes and changes of masks rebuild the
+  /// index.
+  pub fn sync(&self, document: &Document, operations: &[Operation]) {
+    let mut indexed = self.0.lock().unwrap();
+    let Indexed { canvas, index } = &mut *indexed;
+    let Some(canvas_id) = canvas.as_deref() else {
+      return;
+    };
+    let Some(canvas) = document.canvases().find(|canvas| canvas.id == canvas_id) else {
+      *indexed = Indexed::default();
+      return;
+    };
+    let rebuild =
+      |operation: &Operation| operation.is_structural() || changes_masks(canvas, operation);
+    if operations.iter().any(rebuild) {
+      index.rebuild(canvas);
+      return;
+    }
+    for id in operations.iter().flat_map(Operation::targets) {
+      if let Some(node) = canvas.find(id).filter(|node| node.id != canvas.id) {
+        index.upsert(node);
+      }
+    }
+  }
+}
+
+/// Whether a patch turns a layer into a mask or back, or shows or hides a mask, which changes the
+/// layers clipped in front of it.
+fn changes_masks(canvas: &Node, operation: &Operation) -> bool {
+  let Operation::Patch(patch) = operation else {
+    return false;
+  };
+  let is_mask = || {
+    canvas.find(&patch.id).is_some_and(|node| match &node.node {
+      NodeType::Vector(vector) => vector.is_mask,
+      NodeType::Rectangle(vector) => vector.is_mask,
+      NodeType::Document(_) | NodeType::Canvas(_) => false,
+    })
+  };
+  patch
+    .operations
+    .iter()
+    .any(|operation| match operation.path().segments() {
+      [PathSegment::Key(key), ..] if key == "isMask" => true,
+      [PathSegment::Key(key), ..] if key == "visible" => is_mask(),
+      _ => false,
+    })
+}
+
+/// Indexes every layer of a canvas of the open document, replacing the previously indexed canvas.
+#[tauri::command]
+pub fn spatial_load(
+  state: State<'_, SessionState>,
+  index: State<'_, CanvasIndex>,
+  canvas: String,
+) -> Result<(), String> {
+  let session = state.0.lock().unwrap();
+  let node = session
+    .document
+    .canvases()
+    .find(|node| node.id == canvas)
+    .ok_or_else(|| format!("no canvas with id `{canvas}`"))?;
+  *index.0.lock().unwrap() = Indexed {
+    index: SpatialIndex::from_canvas(node),
+    canvas: Some(canvas),
+  };
+  Ok(())
+}
+
+/// Id of the top most selectable layer under the cursor.
+#[tauri::command]
+pub fn spatial_hit_test(index: State<'_, CanvasIndex>, point: Point) -> Option<String> {
+  index
+    .0
+    .lock()
+    .unwrap()
+    .index
+    .hit_test(point)
+    .map(String::from)
+}
+
+/// Ids of the selectable layers touched by a marquee selection.
+#[tauri::command]
+pub fn spatial_marquee(index: State<'_, CanvasIndex>, area: Bounds) -> Vec<String> {
+  to_owned(index.0.lock().unwrap().index.marquee(area))
+}
+
+/// Ids of the layers to draw for the current viewport, from back to front.
+#[tauri::command]
+pub fn spatial_visible(index: State<'_, CanvasIndex>, viewport: Bounds) -> Vec<String> {
+  to_owned(index.0.lock().unwrap().index.visible_in(viewport))
+}
+
+fn to_owned(ids: Vec<&str>) -> Vec<String> {
+  ids.into_iter().map(String::from).collect()
+}
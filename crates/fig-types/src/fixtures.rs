@@ -0,0 +1,100 @@
+//! Nodes shared by the tests of the modules.
+
+use serde_json::{json, Value};
+
+use crate::node::Node;
+
+/// A paint of a type, with gradient handles.
+pub(crate) fn paint(paint_type: &str) -> Value {
+  json!({
+    "paintType": paint_type,
+    "visible": true,
+    "opacity": 1.0,
+    "color": { "r": 0.2, "g": 0.4, "b": 0.6, "a": 1.0 },
+    "blendMode": "PassThrough",
+    "gradientHandlePositions": [
+      { "x": 0.0, "y": 0.5 },
+      { "x": 1.0, "y": 0.5 },
+      { "x": 0.0, "y": 1.0 },
+    ],
+    "gradientStops": [],
+    "scaleMode": "Fill",
+    "rotation": 0.0,
+    "imageRef": "",
+    "filters": {
+      "exposure": 0.0,
+      "contrast": 0.0,
+      "saturation": 0.0,
+      "temperature": 0.0,
+      "tint": 0.0,
+      "highlights": 0.0,
+      "shadows": 0.0,
+    },
+    "gifRef": "",
+  })
+}
+
+/// A 100 by 50 rectangle `1:2` with rounded corners and a gradient fill.
+pub(crate) fn rectangle() -> Value {
+  json!({
+    "id": "1:2",
+    "name": "Card",
+    "visible": true,
+    "rotation": 0.0,
+    "node": { "type": "rectangle", "data": {
+      "locked": false,
+      "exportSettings": [],
+      "blendMode": "Normal",
+      "preserveRatio": false,
+      "layoutAlign": "Inherit",
+      "layoutGrow": 0,
+      "constraint": { "vertical": "Top", "horizontal": "LeftRight" },
+      "transitionEasing": "EaseInAndOut",
+      "opacity": 1.0,
+      "absoluteBoundingBox": { "x": 0.0, "y": 0.0, "width": 100.0, "height": 50.0 },
+      "effects": [],
+      "size": { "width": 100.0, "height": 50.0 },
+      "relativeTransform": { "matrix": [[1.0, 0.0, 0.0], [0.0, 1.0, 0.0]] },
+      "isMask": false,
+      "fills": [paint("GradientLinear")],
+      "fillGeometry": [],
+      "strokes": [],
+      "strokeWeight": 1.0,
+      "individualStrokeWeights": { "top": 0.0, "right": 0.0, "bottom": 0.0, "left": 0.0 },
+      "strokeCap": "None",
+      "strokeJoin": "Miter",
+      "strokeDashes": [],
+      "strokeMiterAngle": 28.96,
+      "strokeGeometry": [],
+      "strokeAlign": "Inside",
+      "styles": { "Fill": "S:1" },
+      "annotations": [],
+      "additionalData": {
+        "cornerRadius": 8.0,
+        "rectangleCornerRadii": [8.0, 8.0, 8.0, 8.0],
+        "cornerSmoothing": 0.6,
+      },
+    }},
+  })
+}
+
+/// A document `0:0` with a page `0:1` holding [`rectangle`].
+pub(crate) fn document() -> Node {
+  let tree = json!({
+    "id": "0:0",
+    "name": "Document",
+    "visible": true,
+    "rotation": 0.0,
+    "node": { "type": "document", "data": { "children": [{
+      "id": "0:1",
+      "name": "Page 1",
+      "visible": true,
+      "rotation": 0.0,
+      "node": { "type": "canvas", "data": {
+        "children": [rectangle()],
+        "backgroundColor": { "r": 1.0, "g": 1.0, "b": 1.0, "a": 1.0 },
+      }},
+    }]}},
+  });
+  serde_json::from_value(tree).unwrap()
+}
@@ -1,2 +1,7 @@
+#[cfg(test)]
+mod fixtures;
 pub mod node;
+pub mod patch;
+pub mod profile;
 pub mod properties;
+pub mod validate;
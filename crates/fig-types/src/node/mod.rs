@@ -4,27 +4,74 @@ use serde::{Deserialize, Serialize};
 use ts_rs::TS;
 use vector::{EmptyData, RectangleData, VectorNode};
 
-use crate::properties::Color;
+use crate::properties::{Color, FlowStartingPoint, Interaction, Measurement, PrototypeDevice};
 
 pub mod vector;
 
-#[derive(Serialize, Deserialize, TS)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS)]
 #[serde(rename_all = "camelCase")]
 #[ts(export, export_to = "nodes/")]
 pub struct Node {
     /// A string uniquely identifying this node within the document.
-    id: String,
+    pub id: String,
     /// The name given to the node by the user in the tool.
-    name: String,
+    pub name: String,
     /// Whether or not the node is visible on the canvas.
-    visible: bool,
+    pub visible: bool,
     /// The type of the node.
-    node: NodeType,
+    pub node: NodeType,
     /// The rotation of the node, if not 0.
-    rotation: i32,
+    pub rotation: f32,
 }
 
-#[derive(Serialize, Deserialize, TS)]
+impl Node {
+    /// The direct children of this node, empty for nodes that cannot hold children.
+    pub fn children(&self) -> &[Node] {
+        match &self.node {
+            NodeType::Document(document) => &document.children,
+            NodeType::Canvas(canvas) => &canvas.children,
+            NodeType::Vector(_) | NodeType::Rectangle(_) => &[],
+        }
+    }
+
+    /// The direct children of this node, `None` for nodes that cannot hold children.
+    pub fn children_mut(&mut self) -> Option<&mut Vec<Node>> {
+        match &mut self.node {
+            NodeType::Document(document) => Some(&mut document.children),
+            NodeType::Canvas(canvas) => Some(&mut canvas.children),
+            NodeType::Vector(_) | NodeType::Rectangle(_) => None,
+        }
+    }
+
+    /// The prototype interactions of this node, empty for nodes that cannot have any.
+    pub fn interactions(&self) -> &[Interaction] {
+        match &self.node {
+            NodeType::Vector(vector) => &vector.interactions,
+            NodeType::Rectangle(vector) => &vector.interactions,
+            NodeType::Document(_) | NodeType::Canvas(_) => &[],
+        }
+    }
+
+    /// Looks up a node by id in the subtree rooted at this node.
+    pub fn find(&self, id: &str) -> Option<&Node> {
+        if self.id == id {
+            return Some(self);
+        }
+        self.children().iter().find_map(|child| child.find(id))
+    }
+
+    /// Looks up a node by id in the subtree rooted at this node.
+    pub fn find_mut(&mut self, id: &str) -> Option<&mut Node> {
+        if self.id == id {
+            return Some(self);
+        }
+        self.children_mut()?
+            .iter_mut()
+            .find_map(|child| child.find_mut(id))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS)]
 #[serde(rename_all = "lowercase")]
 #[serde(tag = "type", content = "data")]
 // #[ts(export, export_to = "nodes/")]
@@ -35,21 +82,31 @@ pub enum NodeType {
     Rectangle(VectorNode<RectangleData>),
 }
 
-#[derive(Serialize, Deserialize, TS)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS)]
 #[ts(export, export_to = "nodes/")]
 pub struct DocumentNode {
     /// An array of canvases attached to the document.
-    children: Vec<Node>,
+    pub children: Vec<Node>,
 }
 
-#[derive(Serialize, Deserialize, TS)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS)]
 #[serde(rename_all = "camelCase")]
 #[ts(export, export_to = "nodes/")]
 pub struct CanvasNode {
     /// An array of top level layers on the canvas
-    children: Vec<Node>,
+    pub children: Vec<Node>,
     //// Background color of the canvas.
-    background_color: Color,
+    pub background_color: Color,
+    /// The starting points of the prototype flows of the canvas, the frames its prototypes are
+    /// launched from.
+    #[serde(default)]
+    pub flow_starting_points: Vec<FlowStartingPoint>,
+    /// The device prototypes of the canvas are presented in.
+    #[ts(optional)]
+    pub prototype_device: Option<PrototypeDevice>,
+    /// The distances between layers of the canvas pinned in Dev Mode.
+    #[serde(default)]
+    pub measurements: Vec<Measurement>,
     //// An array of export settings representing images to export from the canvas
     // export_settings: Vec<ExportSetting>
 }
@@ -0,0 +1,805 @@
+use std::collections::HashSet;
+use std::fmt;
+use std::str::FromStr;
+
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use ts_rs::TS;
+
+use crate::node::{Node, NodeType};
+
+/// Properties that identify a node or hold other nodes and can therefore not be patched.
+const IMMUTABLE: [&str; 2] = ["id", "children"];
+
+/// A set of changes to the properties of a single node, applied atomically.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS)]
+#[ts(export, export_to = "patch/")]
+pub struct Patch {
+  /// Id of the patched node
+  pub id: String,
+  /// Operations applied in order
+  pub operations: Vec<PatchOperation>,
+}
+
+/// A change to a single property of a node. Paths use the serialized (camelCase) property names,
+/// with dots to access fields and brackets to index arrays, e.g. `fills[0].opacity` or
+/// `constraint.horizontal`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS)]
+#[serde(rename_all = "camelCase", tag = "op")]
+#[ts(export, export_to = "patch/")]
+pub enum PatchOperation {
+  /// Replaces the value of an existing property or array element
+  Set {
+    #[ts(type = "string")]
+    path: PropertyPath,
+    #[ts(type = "unknown")]
+    value: Value,
+  },
+  /// Inserts a value in an array at the index ending the path, adds a new key to a map or sets
+  /// an unset optional property
+  Insert {
+    #[ts(type = "string")]
+    path: PropertyPath,
+    #[ts(type = "unknown")]
+    value: Value,
+  },
+  /// Removes an array element or a map key, or unsets an optional property
+  Remove {
+    #[ts(type = "string")]
+    path: PropertyPath,
+  },
+}
+
+/// Errors returned when a patch does not match the schema of the patched node
+#[derive(Debug, Clone, PartialEq, thiserror::Error)]
+pub enum PatchError {
+  #[error("invalid path `{path}`: {reason}")]
+  InvalidPath { path: String, reason: &'static str },
+  #[error("patch for node `{expected}` applied to node `{found}`")]
+  WrongNode { expected: String, found: String },
+  #[error("`{0}` cannot be patched")]
+  Immutable(String),
+  #[error("`{0}` does not exist")]
+  NotFound(String),
+  #[error("`{0}` already exists")]
+  AlreadyExists(String),
+  #[error("`{0}` is not an array")]
+  NotAnArray(String),
+  #[error("`{0}` is not an object")]
+  NotAnObject(String),
+  #[error("index {index} is out of bounds for `{path}` of length {len}")]
+  OutOfBounds {
+    path: String,
+    index: usize,
+    len: usize,
+  },
+  #[error("invalid value at `{path}`: {message}")]
+  InvalidValue { path: String, message: String },
+}
+
+/// One step of a property path
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum PathSegment {
+  /// A field of an object or a key of a map
+  Key(String),
+  /// An element of an array
+  Index(usize),
+}
+
+impl From<&str> for PathSegment {
+  fn from(key: &str) -> Self {
+    PathSegment::Key(key.to_string())
+  }
+}
+
+impl From<String> for PathSegment {
+  fn from(key: String) -> Self {
+    PathSegment::Key(key)
+  }
+}
+
+impl From<usize> for PathSegment {
+  fn from(index: usize) -> Self {
+    PathSegment::Index(index)
+  }
+}
+
+/// A parsed property path such as `fills[0].opacity`
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct PropertyPath(Vec<PathSegment>);
+
+impl PropertyPath {
+  /// A path made of segments, which callers ensure start with a key.
+  pub(crate) fn from_segments(segments: Vec<PathSegment>) -> Self {
+    PropertyPath(segments)
+  }
+
+  pub fn segments(&self) -> &[PathSegment] {
+    &self.0
+  }
+
+  /// The path of the property or element holding this one, `None` for a property of the node
+  pub fn parent(&self) -> Option<PropertyPath> {
+    match self.0.len() {
+      0 | 1 => None,
+      len => Some(PropertyPath(self.0[..len - 1].to_vec())),
+    }
+  }
+
+  /// Whether the path is `other` or a path inside it
+  pub fn starts_with(&self, other: &PropertyPath) -> bool {
+    self.0.starts_with(&other.0)
+  }
+
+  /// The path made of the first `len` segments, used in error messages
+  fn prefix(&self, len: usize) -> String {
+    PropertyPath(self.0[..len].to_vec()).to_string()
+  }
+}
+
+impl FromStr for PropertyPath {
+  type Err = PatchError;
+
+  fn from_str(path: &str) -> Result<Self, Self::Err> {
+    let invalid = |reason| PatchError::InvalidPath {
+      path: path.to_string(),
+      reason,
+    };
+    let mut segments = Vec::new();
+    let mut rest = path;
+    while !rest.is_empty() {
+      if let Some(indexed) = rest.strip_prefix('[') {
+        let (index, tail) = indexed
+          .split_once(']')
+          .ok_or_else(|| invalid("unclosed bracket"))?;
+        let index = index
+          .parse()
+          .map_err(|_| invalid("array indices must be non-negative integers"))?;
+        segments.push(PathSegment::Index(index));
+        rest = tail;
+      } else {
+        let key = match segments.is_empty() {
+          true => rest,
+          false => rest
+            .strip_prefix('.')
+            .ok_or_else(|| invalid("expected `.` or `[` after a segment"))?,
+        };
+        let end = key.find(['.', '[', ']']).unwrap_or(key.len());
+        if end == 0 {
+          return Err(invalid("empty property name"));
+        }
+        segments.push(PathSegment::Key(key[..end].to_string()));
+        rest = &key[end..];
+      }
+    }
+    match segments.first() {
+      Some(PathSegment::Key(_)) => Ok(PropertyPath(segments)),
+      Some(PathSegment::Index(_)) => Err(invalid("paths must start with a property name")),
+      None => Err(invalid("empty path")),
+    }
+  }
+}
+
+impl TryFrom<String> for PropertyPath {
+  type Error = PatchError;
+
+  fn try_from(path: String) -> Result<Self, Self::Error> {
+    path.parse()
+  }
+}
+
+impl From<PropertyPath> for String {
+  fn from(path: PropertyPath) -> Self {
+    path.to_string()
+  }
+}
+
+impl fmt::Display for PropertyPath {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    for (position, segment) in self.0.iter().enumerate() {
+      match segment {
+        PathSegment::Key(key) if position == 0 => write!(f, "{key}")?,
+        PathSegment::Key(key) => write!(f, ".{key}")?,
+        PathSegment::Index(index) => write!(f, "[{index}]")?,
+      }
+    }
+    Ok(())
+  }
+}
+
+impl Patch {
+  /// Applies the patch to a node and returns the patch reverting it. Either every operation is
+  /// applied, or an error is returned and the node is left untouched.
+  pub fn apply(&self, node: &mut Node) -> Result<Patch, PatchError> {
+    if node.id != self.id {
+      return Err(PatchError::WrongNode {
+        expected: self.id.clone(),
+        found: node.id.clone(),
+      });
+    }
+    // Children are not properties, they are taken out so they do not go through serde.
+    let children = node.children_mut().map(std::mem::take);
+    let result = self.patch(node);
+    let inverse = result.map(|(patched, inverse)| {
+      *node = patched;
+      inverse
+    });
+    if let Some(children) = children {
+      *node
+        .children_mut()
+        .expect("patches cannot change the type of a node") = children;
+    }
+    Ok(Patch {
+      id: self.id.clone(),
+      operations: inverse?,
+    })
+  }
+
+  fn patch(&self, node: &Node) -> Result<(Node, Vec<PatchOperation>), PatchError> {
+    let (properties, header) = fields(node);
+    let mut properties = Value::Object(properties);
+
+    let mut inverse = Vec::with_capacity(self.operations.len());
+    for operation in &self.operations {
+      inverse.push(operation.apply(&mut properties)?);
+    }
+    inverse.reverse();
+
+    let Value::Object(properties) = properties else {
+      unreachable!("operations cannot replace the root object")
+    };
+    let patched_properties = properties.clone();
+    let (header, data): (Map<String, Value>, Map<String, Value>) = properties
+      .into_iter()
+      .partition(|(key, _)| header.contains(key));
+    let data = Value::Object(data);
+    let node_type = match &node.node {
+      NodeType::Document(_) => NodeType::Document(deserialize(data)?),
+      NodeType::Canvas(_) => NodeType::Canvas(deserialize(data)?),
+      NodeType::Vector(_) => NodeType::Vector(deserialize(data)?),
+      NodeType::Rectangle(_) => NodeType::Rectangle(deserialize(data)?),
+    };
+    let Header {
+      id,
+      name,
+      visible,
+      rotation,
+    } = deserialize(Value::Object(header))?;
+    let patched = Node {
+      id,
+      name,
+      visible,
+      node: node_type,
+      rotation,
+    };
+    // Node types ignore unknown fields, a property missing once the node is serialized again was
+    // never one of its fields.
+    let (properties, _) = fields(&patched);
+    if let Some(path) = dropped(
+      &Value::Object(patched_properties),
+      &Value::Object(properties),
+      &mut Vec::new(),
+    ) {
+      return Err(PatchError::NotFound(path.to_string()));
+    }
+    Ok((patched, inverse))
+  }
+}
+
+/// The serialized properties of a node, and the names of those which are fields of the node rather
+/// than of its type specific data.
+fn fields(node: &Node) -> (Map<String, Value>, HashSet<String>) {
+  // Node fields and the fields of its data are exposed as a single set of properties.
+  let Value::Object(mut properties) = serde_json::to_value(node).expect("nodes always serialize")
+  else {
+    unreachable!("nodes serialize to objects")
+  };
+  let Some(Value::Object(mut node_type)) = properties.remove("node") else {
+    unreachable!("node types serialize to objects")
+  };
+  let header: HashSet<String> = properties.keys().cloned().collect();
+  if let Some(Value::Object(data)) = node_type.remove("data") {
+    properties.extend(data);
+  }
+  (properties, header)
+}
+
+/// The path of the first property set in `patched` which is missing from `kept`, the properties the
+/// patched node serializes to.
+fn dropped(patched: &Value, kept: &Value, path: &mut Vec<PathSegment>) -> Option<PropertyPath> {
+  let inside: Vec<(PathSegment, &Value, Option<&Value>)> = match (patched, kept) {
+    (Value::Object(patched), Value::Object(kept)) => patched
+      .iter()
+      .map(|(key, value)| (PathSegment::Key(key.clone()), value, kept.get(key)))
+      .collect(),
+    (Value::Array(patched), Value::Array(kept)) => patched
+      .iter()
+      .enumerate()
+      .map(|(index, value)| (PathSegment::Index(index), value, kept.get(index)))
+      .collect(),
+    _ => return None,
+  };
+  for (segment, patched, kept) in inside {
+    path.push(segment);
+    match kept {
+      _ if patched.is_null() => {}
+      Some(Value::Null) | None => return Some(PropertyPath(path.clone())),
+      Some(kept) => {
+        if let Some(dropped) = dropped(patched, kept, path) {
+          return Some(dropped);
+        }
+      }
+    }
+    path.pop();
+  }
+  None
+}
+
+/// The properties of a node patches can change, by name: the fields of the node and of its type
+/// specific data, without its id and children.
+pub fn properties(node: &Node) -> Map<String, Value> {
+  let (mut properties, _) = fields(node);
+  for key in IMMUTABLE {
+    properties.remove(key);
+  }
+  properties
+}
+
+/// A property whose value differs between two versions of a node
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS)]
+#[ts(export, export_to = "patch/")]
+pub struct PropertyChange {
+  #[ts(type = "string")]
+  pub path: PropertyPath,
+  /// Value in the first version, absent when the property was added
+  #[ts(type = "unknown")]
+  pub before: Option<Value>,
+  /// Value in the second version, absent when the property was removed
+  #[ts(type = "unknown")]
+  pub after: Option<Value>,
+}
+
+/// The properties whose values differ between two versions of a node, in the order of their
+/// paths. Objects and arrays are compared field by field and element by element, so only the
+/// values which changed are reported. Children are not properties and are not compared.
+pub fn changes(before: &Node, after: &Node) -> Vec<PropertyChange> {
+  let (before, after) = (properties(before), properties(after));
+  let mut changes = Vec::new();
+  compare(
+    Some(&Value::Object(before)),
+    Some(&Value::Object(after)),
+    &mut Vec::new(),
+    &mut changes,
+  );
+  changes
+}
+
+fn compare(
+  before: Option<&Value>,
+  after: Option<&Value>,
+  path: &mut Vec<PathSegment>,
+  changes: &mut Vec<PropertyChange>,
+) {
+  // Unset optional properties serialize to null.
+  let before = before.filter(|value| !value.is_null());
+  let after = after.filter(|value| !value.is_null());
+  match (before, after) {
+    (Some(Value::Object(before)), Some(Value::Object(after))) => {
+      let mut keys: Vec<&String> = before.keys().chain(after.keys()).collect();
+      keys.sort_unstable();
+      keys.dedup();
+      for key in keys {
+        path.push(PathSegment::Key(key.clone()));
+        compare(before.get(key), after.get(key), path, changes);
+        path.pop();
+      }
+    }
+    (Some(Value::Array(before)), Some(Value::Array(after))) => {
+      for index in 0..before.len().max(after.len()) {
+        path.push(PathSegment::Index(index));
+        compare(before.get(index), after.get(index), path, changes);
+        path.pop();
+      }
+    }
+    (before, after) if before != after => changes.push(PropertyChange {
+      path: PropertyPath(path.clone()),
+      before: before.cloned(),
+      after: after.cloned(),
+    }),
+    _ => {}
+  }
+}
+
+/// The fields of a node outside of its type specific data
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Header {
+  id: String,
+  name: String,
+  visible: bool,
+  rotation: f32,
+}
+
+fn deserialize<T: DeserializeOwned>(value: Value) -> Result<T, PatchError> {
+  serde_path_to_error::deserialize(value).map_err(|error| PatchError::InvalidValue {
+    path: error.path().to_string(),
+    message: error.inner().to_string(),
+  })
+}
+
+impl PatchOperation {
+  pub fn path(&self) -> &PropertyPath {
+    match self {
+      PatchOperation::Set { path, .. }
+      | PatchOperation::Insert { path, .. }
+      | PatchOperation::Remove { path } => path,
+    }
+  }
+
+  /// Applies the operation to the serialized properties of a node, returning the operation
+  /// reverting it.
+  fn apply(&self, properties: &mut Value) -> Result<PatchOperation, PatchError> {
+    let path = self.path();
+    let segments = path.segments();
+    if let Some(PathSegment::Key(key)) = segments.first() {
+      if IMMUTABLE.contains(&key.as_str()) {
+        return Err(PatchError::Immutable(key.clone()));
+      }
+    }
+    let (last, parents) = segments.split_last().expect("paths are never empty");
+    let mut parent = properties;
+    for (depth, segment) in parents.iter().enumerate() {
+      parent = match (segment, parent) {
+        (PathSegment::Key(key), Value::Object(object)) => object.get_mut(key),
+        (PathSegment::Index(index), Value::Array(array)) => array.get_mut(*index),
+        (PathSegment::Key(_), _) => return Err(PatchError::NotAnObject(path.prefix(depth))),
+        (PathSegment::Index(_), _) => return Err(PatchError::NotAnArray(path.prefix(depth))),
+      }
+      .ok_or_else(|| PatchError::NotFound(path.prefix(depth + 1)))?;
+    }
+
+    let depth = parents.len();
+    let not_found = || PatchError::NotFound(path.to_string());
+    let out_of_bounds = |index, len| PatchError::OutOfBounds {
+      path: path.prefix(depth),
+      index,
+      len,
+    };
+    let set = |previous| PatchOperation::Set {
+      path: path.clone(),
+      value: previous,
+    };
+    let insert = |previous| PatchOperation::Insert {
+      path: path.clone(),
+      value: previous,
+    };
+    let remove = || PatchOperation::Remove { path: path.clone() };
+    match (self, last, parent) {
+      (PatchOperation::Set { value, .. }, PathSegment::Key(key), Value::Object(object)) => {
+        let target = object.get_mut(key).ok_or_else(not_found)?;
+        Ok(set(std::mem::replace(target, value.clone())))
+      }
+      (PatchOperation::Set { value, .. }, PathSegment::Index(index), Value::Array(array)) => {
+        let len = array.len();
+        let target = array
+          .get_mut(*index)
+          .ok_or_else(|| out_of_bounds(*index, len))?;
+        Ok(set(std::mem::replace(target, value.clone())))
+      }
+      (PatchOperation::Insert { value, .. }, PathSegment::Key(key), Value::Object(object)) => {
+        match object.get(key) {
+          Some(Value::Null) | None => object.insert(key.clone(), value.clone()),
+          Some(_) => return Err(PatchError::AlreadyExists(path.to_string())),
+        };
+        Ok(remove())
+      }
+      (PatchOperation::Insert { value, .. }, PathSegment::Index(index), Value::Array(array)) => {
+        if *index > array.len() {
+          return Err(out_of_bounds(*index, array.len()));
+        }
+        array.insert(*index, value.clone());
+        Ok(remove())
+      }
+      (PatchOperation::Remove { .. }, PathSegment::Key(key), Value::Object(object)) => {
+        match object.remove(key) {
+          Some(Value::Null) | None => Err(not_found()),
+          Some(previous) => Ok(insert(previous)),
+        }
+      }
+      (PatchOperation::Remove { .. }, PathSegment::Index(index), Value::Array(array)) => {
+        if *index >= array.len() {
+          return Err(out_of_bounds(*index, array.len()));
+        }
+        Ok(insert(array.remove(*index)))
+      }
+      (_, PathSegment::Key(_), _) => Err(PatchError::NotAnObject(path.prefix(depth))),
+      (_, PathSegment::Index(_), _) => Err(PatchError::NotAnArray(path.prefix(depth))),
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use serde_json::json;
+
+  use super::*;
+  use crate::fixtures::{document, paint, rectangle};
+
+  fn node() -> Node {
+    serde_json::from_value(rectangle()).unwrap()
+  }
+
+  fn path(path: &str) -> PropertyPath {
+    path.parse().unwrap()
+  }
+
+  fn set(path: &str, value: Value) -> PatchOperation {
+    PatchOperation::Set {
+      path: self::path(path),
+      value,
+    }
+  }
+
+  fn insert(path: &str, value: Value) -> PatchOperation {
+    PatchOperation::Insert {
+      path: self::path(path),
+      value,
+    }
+  }
+
+  fn remove(path: &str) -> PatchOperation {
+    PatchOperation::Remove {
+      path: self::path(path),
+    }
+  }
+
+  fn patch(operations: Vec<PatchOperation>) -> Patch {
+    Patch {
+      id: "1:2".to_string(),
+      operations,
+    }
+  }
+
+  /// The error of a patch, checking it left the node as it was.
+  fn error(operations: Vec<PatchOperation>) -> String {
+    let mut node = node();
+    let error = patch(operations).apply(&mut node).unwrap_err();
+    assert_eq!(node, self::node());
+    error.to_string()
+  }
+
+  #[test]
+  fn paths_are_parsed_and_written() {
+    let parsed = path("fills[0].color.r");
+    assert_eq!(
+      parsed.segments(),
+      [
+        PathSegment::from("fills"),
+        PathSegment::from(0),
+        PathSegment::from("color"),
+        PathSegment::from("r"),
+      ]
+    );
+    assert_eq!(parsed.to_string(), "fills[0].color.r");
+    assert_eq!(parsed.parent(), Some(path("fills[0].color")));
+    assert_eq!(path("fills").parent(), None);
+    assert!(parsed.starts_with(&path("fills[0]")));
+    assert!(!path("fills").starts_with(&parsed));
+    assert_eq!(path("matrix[1][2]").to_string(), "matrix[1][2]");
+
+    let errors = [
+      "",
+      "[0]",
+      "fills[0",
+      "fills[-1]",
+      "fills..color",
+      "fills[0]color",
+    ]
+    .map(|path| path.parse::<PropertyPath>().unwrap_err().to_string());
+    assert_eq!(
+      errors,
+      [
+        "invalid path ``: empty path",
+        "invalid path `[0]`: paths must start with a property name",
+        "invalid path `fills[0`: unclosed bracket",
+        "invalid path `fills[-1]`: array indices must be non-negative integers",
+        "invalid path `fills..color`: empty property name",
+        "invalid path `fills[0]color`: expected `.` or `[` after a segment",
+      ]
+    );
+  }
+
+  #[test]
+  fn operations_are_applied_and_reverted() {
+    let mut node = node();
+    let patch = patch(vec![
+      set("name", json!("Button")),
+      set("opacity", json!(0.5)),
+      set("fills[0].color.r", json!(1.0)),
+      insert("fills[0]", paint("Solid")),
+      insert("styles.Effect", json!("S:2")),
+      remove("styles.Fill"),
+      insert(
+        "absoluteRenderBounds",
+        json!({ "x": 0.0, "y": 0.0, "width": 1.0, "height": 1.0 }),
+      ),
+    ]);
+    let inverse = patch.apply(&mut node).unwrap();
+
+    let properties = properties(&node);
+    assert_eq!(node.name, "Button");
+    assert_eq!(properties["opacity"], json!(0.5));
+    assert_eq!(properties["fills"][0]["paintType"], "Solid");
+    assert_eq!(properties["fills"][1]["color"]["r"], json!(1.0));
+    assert_eq!(properties["styles"], json!({ "Effect": "S:2" }));
+    assert_eq!(properties["absoluteRenderBounds"]["width"], json!(1.0));
+    assert_eq!(
+      inverse.operations[..2],
+      [
+        remove("absoluteRenderBounds"),
+        insert("styles.Fill", json!("S:1")),
+      ]
+    );
+    assert_eq!(inverse.operations.last(), Some(&set("name", json!("Card"))));
+
+    let patched = node.clone();
+    let redo = inverse.apply(&mut node).unwrap();
+    assert_eq!(node, self::node());
+    redo.apply(&mut node).unwrap();
+    assert_eq!(node, patched);
+  }
+
+  #[test]
+  fn removing_an_optional_property_unsets_it() {
+    let mut node = node();
+    patch(vec![remove("styles")]).apply(&mut node).unwrap();
+    assert_eq!(properties(&node)["styles"], Value::Null);
+    assert_eq!(
+      error(vec![remove("absoluteRenderBounds")]),
+      "`absoluteRenderBounds` does not exist"
+    );
+  }
+
+  #[test]
+  fn inserted_properties_must_be_fields_of_the_node() {
+    let mut node = node();
+    let bounds = json!({ "x": 0.0, "y": 0.0, "width": 100.0, "height": 50.0 });
+    let inverse = patch(vec![insert("absoluteRenderBounds", bounds.clone())])
+      .apply(&mut node)
+      .unwrap();
+    assert_eq!(properties(&node)["absoluteRenderBounds"], bounds);
+    assert_eq!(inverse.operations, vec![remove("absoluteRenderBounds")]);
+
+    assert_eq!(
+      error(vec![insert("opactiy", json!(0.5))]),
+      "`opactiy` does not exist"
+    );
+    let mut fill = paint("Solid");
+    fill["colr"] = json!({ "r": 1.0, "g": 0.0, "b": 0.0, "a": 1.0 });
+    assert_eq!(
+      error(vec![insert("fills[1]", fill)]),
+      "`fills[1].colr` does not exist"
+    );
+  }
+
+  #[test]
+  fn failing_patches_leave_the_node_untouched() {
+    // The first operations applied before the last one failed.
+    assert_eq!(
+      error(vec![
+        set("opacity", json!(0.5)),
+        remove("fills[0]"),
+        remove("fills[0]")
+      ]),
+      "index 0 is out of bounds for `fills` of length 0"
+    );
+    assert_eq!(
+      error(vec![set("opacity", json!("opaque"))]),
+      "invalid value at `opacity`: invalid type: string \"opaque\", expected f32"
+    );
+    assert!(error(vec![set("fills[0].paintType", json!("Unknown"))])
+      .starts_with("invalid value at `fills[0].paintType`: unknown variant `Unknown`"));
+    assert_eq!(
+      error(vec![remove("strokeDashes")]),
+      "invalid value at `.`: missing field `strokeDashes`"
+    );
+    assert!(error(vec![set("visible", json!(1))]).starts_with("invalid value at `visible`"));
+  }
+
+  #[test]
+  fn invalid_operations_are_reported() {
+    let errors = [
+      vec![set("id", json!("1:3"))],
+      vec![remove("children")],
+      vec![set("missing", json!(1))],
+      vec![set("constraint.missing.vertical", json!(1))],
+      vec![insert("styles.Fill", json!("S:2"))],
+      vec![set("opacity[0]", json!(1))],
+      vec![set("fills.color", json!(1))],
+      vec![set("fills[0].color.r.g", json!(1))],
+      vec![insert("fills[2]", paint("Solid"))],
+    ]
+    .map(error);
+    assert_eq!(
+      errors,
+      [
+        "`id` cannot be patched",
+        "`children` cannot be patched",
+        "`missing` does not exist",
+        "`constraint.missing` does not exist",
+        "`styles.Fill` already exists",
+        "`opacity` is not an array",
+        "`fills` is not an object",
+        "`fills[0].color.r` is not an object",
+        "index 2 is out of bounds for `fills` of length 1",
+      ]
+    );
+
+    let mut canvas = document().children()[0].clone();
+    let error = patch(vec![]).apply(&mut canvas).unwrap_err();
+    assert_eq!(
+      error,
+      PatchError::WrongNode {
+        expected: "1:2".to_string(),
+        found: "0:1".to_string(),
+      }
+    );
+  }
+
+  #[test]
+  fn patches_keep_the_children_of_nodes() {
+    let mut document = document();
+    let patch = Patch {
+      id: "0:0".to_string(),
+      operations: vec![set("name", json!("Renamed"))],
+    };
+    patch.apply(&mut document).unwrap();
+    assert_eq!(document.name, "Renamed");
+    assert_eq!(document.children()[0].children()[0].id, "1:2");
+  }
+
+  #[test]
+  fn changes_are_listed_by_path() {
+    let before = node();
+    let mut after = node();
+    patch(vec![
+      set("opacity", json!(0.5)),
+      insert("fills[1]", paint("Solid")),
+      set("fills[0].color.r", json!(1.0)),
+      remove("styles.Fill"),
+    ])
+    .apply(&mut after)
+    .unwrap();
+    let listed: Vec<(String, Option<Value>, Option<Value>)> = changes(&before, &after)
+      .into_iter()
+      .map(|change| (change.path.to_string(), change.before, change.after))
+      .collect();
+    assert_eq!(listed.len(), 4);
+    assert_eq!(
+      listed[0],
+      (
+        "fills[0].color.r".to_string(),
+        Some(json!(0.2f32)),
+        Some(json!(1.0))
+      )
+    );
+    assert_eq!((listed[1].0.as_str(), &listed[1].1), ("fills[1]", &None));
+    assert_eq!(
+      listed[2..],
+      [
+        ("opacity".to_string(), Some(json!(1.0)), Some(json!(0.5))),
+        ("styles.Fill".to_string(), Some(json!("S:1")), None),
+      ]
+    );
+    assert!(changes(&before, &before).is_empty());
+    // Children are nodes of their own, not properties.
+    let mut emptied = document();
+    emptied.children_mut().unwrap()[0]
+      .children_mut()
+      .unwrap()
+      .clear();
+    assert!(changes(&document(), &emptied).is_empty());
+  }
+}
@@ -0,0 +1,920 @@
+//! Serialization profiles: the native encoding of the types, or the naming Figma uses.
+//!
+//! The Figma profile writes enum variants in `SCREAMING_SNAKE_CASE` (`GRADIENT_LINEAR`,
+//! `DROP_SHADOW`) and nodes with their type in a flat `type` field next to all their properties,
+//! like the files of the Figma REST API. Field names are the same in both profiles. Reading with
+//! the Figma profile also accepts native variant names.
+
+use serde::de::{self, DeserializeOwned, DeserializeSeed, IntoDeserializer, Visitor};
+use serde::ser;
+use serde::{Deserialize, Serialize};
+use serde_json::{Error, Map, Value};
+
+/// Fields of a native node outside of its type specific data.
+const HEADER: [&str; 4] = ["id", "name", "visible", "rotation"];
+
+/// Struct name of nodes, which are laid out differently by the Figma profile.
+const NODE: &str = "Node";
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum Profile {
+  /// The encoding given by the serde attributes of the types
+  #[default]
+  Native,
+  /// The naming of Figma JSON files and plugins
+  Figma,
+}
+
+/// Serializes a value with the naming of a profile.
+pub fn to_value<T: Serialize + ?Sized>(value: &T, profile: Profile) -> Result<Value, Error> {
+  match profile {
+    Profile::Native => serde_json::to_value(value),
+    Profile::Figma => value.serialize(FigmaSerializer),
+  }
+}
+
+/// Deserializes a value written with the naming of a profile.
+pub fn from_value<T: DeserializeOwned>(value: Value, profile: Profile) -> Result<T, Error> {
+  match profile {
+    Profile::Native => serde_json::from_value(value),
+    Profile::Figma => T::deserialize(FigmaDeserializer(value)),
+  }
+}
+
+/// Converts a native variant name, in PascalCase, camelCase or lowercase, to the
+/// `SCREAMING_SNAKE_CASE` used by Figma. Acronyms stay in one word: `OpenURLAction` becomes
+/// `OPEN_URL_ACTION`.
+pub fn screaming_snake_case(name: &str) -> String {
+  let chars: Vec<char> = name.chars().collect();
+  let mut result = String::with_capacity(name.len() + 4);
+  for (index, &character) in chars.iter().enumerate() {
+    if index > 0 && character.is_uppercase() {
+      let previous = chars[index - 1];
+      let ends_acronym =
+        previous.is_uppercase() && chars.get(index + 1).is_some_and(|next| next.is_lowercase());
+      if previous.is_lowercase() || previous.is_ascii_digit() || ends_acronym {
+        result.push('_');
+      }
+    }
+    result.extend(character.to_uppercase());
+  }
+  result
+}
+
+/// The native name of the variant written `name`, which is left as is when no variant matches.
+fn native_variant(name: String, variants: &[&str]) -> String {
+  if variants.contains(&name.as_str()) {
+    return name;
+  }
+  variants
+    .iter()
+    .find(|variant| screaming_snake_case(variant) == name)
+    .map_or(name, |variant| variant.to_string())
+}
+
+/// Properties held under `additionalData` in native nodes of a type, `None` for unsupported types.
+fn additional_data(node_type: &str) -> Option<&'static [&'static str]> {
+  match node_type {
+    "document" | "canvas" | "vector" => Some(&[]),
+    "rectangle" => Some(&["cornerRadius", "rectangleCornerRadii", "cornerSmoothing"]),
+    _ => None,
+  }
+}
+
+/// Moves the type and the properties of a serialized native node next to its header.
+fn flatten_node(mut node: Map<String, Value>) -> Map<String, Value> {
+  let Some(Value::Object(mut node_type)) = node.remove("node") else {
+    return node;
+  };
+  if let Some(Value::String(type_name)) = node_type.remove("type") {
+    node.insert(
+      "type".to_string(),
+      Value::from(screaming_snake_case(&type_name)),
+    );
+  }
+  if let Some(Value::Object(mut data)) = node_type.remove("data") {
+    if let Some(Value::Object(shape)) = data.remove("additionalData") {
+      node.extend(shape);
+    }
+    node.extend(data);
+  }
+  node
+}
+
+/// Nests the properties of a flat node back under its type. Children are left flat, they are
+/// restored when deserialized as nodes themselves.
+fn nest_node(properties: Map<String, Value>) -> Result<Map<String, Value>, Error> {
+  let type_name = match properties.get("type") {
+    Some(Value::String(type_name)) => type_name.to_lowercase(),
+    _ => String::new(),
+  };
+  let Some(shape) = additional_data(&type_name) else {
+    let id = properties.get("id").and_then(Value::as_str).unwrap_or("");
+    return Err(de::Error::custom(format_args!(
+      "unsupported node type `{}` of node `{id}`",
+      type_name.to_uppercase()
+    )));
+  };
+  let mut node = Map::new();
+  // Figma leaves out the defaults of the header.
+  node.insert("visible".to_string(), Value::Bool(true));
+  node.insert("rotation".to_string(), Value::from(0.0));
+  let mut data = Map::new();
+  let mut additional_data = Map::new();
+  for (key, value) in properties {
+    if key == "type" {
+      continue;
+    } else if HEADER.contains(&key.as_str()) {
+      node.insert(key, value);
+    } else if shape.contains(&key.as_str()) {
+      additional_data.insert(key, value);
+    } else {
+      data.insert(key, value);
+    }
+  }
+  if matches!(type_name.as_str(), "vector" | "rectangle") {
+    data.insert("additionalData".to_string(), Value::Object(additional_data));
+  }
+  let mut node_type = Map::new();
+  node_type.insert("type".to_string(), Value::from(type_name));
+  node_type.insert("data".to_string(), Value::Object(data));
+  node.insert("node".to_string(), Value::Object(node_type));
+  Ok(node)
+}
+
+/// Serializes to JSON values with the Figma naming.
+struct FigmaSerializer;
+
+impl ser::Serializer for FigmaSerializer {
+  type Ok = Value;
+  type Error = Error;
+  type SerializeSeq = SerializeArray;
+  type SerializeTuple = SerializeArray;
+  type SerializeTupleStruct = SerializeArray;
+  type SerializeTupleVariant = SerializeArray;
+  type SerializeMap = SerializeObject;
+  type SerializeStruct = SerializeObject;
+  type SerializeStructVariant = SerializeObject;
+
+  fn serialize_bool(self, value: bool) -> Result<Value, Error> {
+    Ok(Value::Bool(value))
+  }
+
+  fn serialize_i8(self, value: i8) -> Result<Value, Error> {
+    Ok(Value::from(value))
+  }
+
+  fn serialize_i16(self, value: i16) -> Result<Value, Error> {
+    Ok(Value::from(value))
+  }
+
+  fn serialize_i32(self, value: i32) -> Result<Value, Error> {
+    Ok(Value::from(value))
+  }
+
+  fn serialize_i64(self, value: i64) -> Result<Value, Error> {
+    Ok(Value::from(value))
+  }
+
+  fn serialize_u8(self, value: u8) -> Result<Value, Error> {
+    Ok(Value::from(value))
+  }
+
+  fn serialize_u16(self, value: u16) -> Result<Value, Error> {
+    Ok(Value::from(value))
+  }
+
+  fn serialize_u32(self, value: u32) -> Result<Value, Error> {
+    Ok(Value::from(value))
+  }
+
+  fn serialize_u64(self, value: u64) -> Result<Value, Error> {
+    Ok(Value::from(value))
+  }
+
+  fn serialize_f32(self, value: f32) -> Result<Value, Error> {
+    serde_json::value::Serializer.serialize_f32(value)
+  }
+
+  fn serialize_f64(self, value: f64) -> Result<Value, Error> {
+    serde_json::value::Serializer.serialize_f64(value)
+  }
+
+  fn serialize_char(self, value: char) -> Result<Value, Error> {
+    Ok(Value::String(value.to_string()))
+  }
+
+  fn serialize_str(self, value: &str) -> Result<Value, Error> {
+    Ok(Value::from(value))
+  }
+
+  fn serialize_bytes(self, value: &[u8]) -> Result<Value, Error> {
+    serde_json::value::Serializer.serialize_bytes(value)
+  }
+
+  fn serialize_none(self) -> Result<Value, Error> {
+    Ok(Value::Null)
+  }
+
+  fn serialize_some<T: Serialize + ?Sized>(self, value: &T) -> Result<Value, Error> {
+    value.serialize(self)
+  }
+
+  fn serialize_unit(self) -> Result<Value, Error> {
+    Ok(Value::Null)
+  }
+
+  fn serialize_unit_struct(self, _name: &'static str) -> Result<Value, Error> {
+    Ok(Value::Null)
+  }
+
+  fn serialize_unit_variant(
+    self,
+    _name: &'static str,
+    _index: u32,
+    variant: &'static str,
+  ) -> Result<Value, Error> {
+    Ok(Value::String(screaming_snake_case(variant)))
+  }
+
+  fn serialize_newtype_struct<T: Serialize + ?Sized>(
+    self,
+    _name: &'static str,
+    value: &T,
+  ) -> Result<Value, Error> {
+    value.serialize(self)
+  }
+
+  fn serialize_newtype_variant<T: Serialize + ?Sized>(
+    self,
+    _name: &'static str,
+    _index: u32,
+    variant: &'static str,
+    value: &T,
+  ) -> Result<Value, Error> {
+    let mut object = Map::new();
+    object.insert(screaming_snake_case(variant), value.serialize(self)?);
+    Ok(Value::Object(object))
+  }
+
+  fn serialize_seq(self, len: Option<usize>) -> Result<SerializeArray, Error> {
+    Ok(SerializeArray {
+      values: Vec::with_capacity(len.unwrap_or(0)),
+      variant: None,
+    })
+  }
+
+  fn serialize_tuple(self, len: usize) -> Result<SerializeArray, Error> {
+    self.serialize_seq(Some(len))
+  }
+
+  fn serialize_tuple_struct(
+    self,
+    _name: &'static str,
+    len: usize,
+  ) -> Result<SerializeArray, Error> {
+    self.serialize_seq(Some(len))
+  }
+
+  fn serialize_tuple_variant(
+    self,
+    _name: &'static str,
+    _index: u32,
+    variant: &'static str,
+    len: usize,
+  ) -> Result<SerializeArray, Error> {
+    Ok(SerializeArray {
+      values: Vec::with_capacity(len),
+      variant: Some(screaming_snake_case(variant)),
+    })
+  }
+
+  fn serialize_map(self, _len: Option<usize>) -> Result<SerializeObject, Error> {
+    Ok(SerializeObject::new("", None))
+  }
+
+  fn serialize_struct(self, name: &'static str, _len: usize) -> Result<SerializeObject, Error> {
+    Ok(SerializeObject::new(name, None))
+  }
+
+  fn serialize_struct_variant(
+    self,
+    name: &'static str,
+    _index: u32,
+    variant: &'static str,
+    _len: usize,
+  ) -> Result<SerializeObject, Error> {
+    Ok(SerializeObject::new(
+      name,
+      Some(screaming_snake_case(variant)),
+    ))
+  }
+}
+
+/// Wraps the content of an enum variant in an object keyed by the variant name.
+fn tag(variant: Option<String>, content: Value) -> Value {
+  match variant {
+    Some(variant) => Value::Object(Map::from_iter([(variant, content)])),
+    None => content,
+  }
+}
+
+struct SerializeArray {
+  values: Vec<Value>,
+  variant: Option<String>,
+}
+
+impl SerializeArray {
+  fn push<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), Error> {
+    self.values.push(value.serialize(FigmaSerializer)?);
+    Ok(())
+  }
+
+  fn finish(self) -> Result<Value, Error> {
+    Ok(tag(self.variant, Value::Array(self.values)))
+  }
+}
+
+impl ser::SerializeSeq for SerializeArray {
+  type Ok = Value;
+  type Error = Error;
+
+  fn serialize_element<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), Error> {
+    self.push(value)
+  }
+
+  fn end(self) -> Result<Value, Error> {
+    self.finish()
+  }
+}
+
+impl ser::SerializeTuple for SerializeArray {
+  type Ok = Value;
+  type Error = Error;
+
+  fn serialize_element<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), Error> {
+    self.push(value)
+  }
+
+  fn end(self) -> Result<Value, Error> {
+    self.finish()
+  }
+}
+
+impl ser::SerializeTupleStruct for SerializeArray {
+  type Ok = Value;
+  type Error = Error;
+
+  fn serialize_field<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), Error> {
+    self.push(value)
+  }
+
+  fn end(self) -> Result<Value, Error> {
+    self.finish()
+  }
+}
+
+impl ser::SerializeTupleVariant for SerializeArray {
+  type Ok = Value;
+  type Error = Error;
+
+  fn serialize_field<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), Error> {
+    self.push(value)
+  }
+
+  fn end(self) -> Result<Value, Error> {
+    self.finish()
+  }
+}
+
+struct SerializeObject {
+  /// Name of the serialized struct, empty for maps
+  name: &'static str,
+  object: Map<String, Value>,
+  variant: Option<String>,
+  key: Option<String>,
+}
+
+impl SerializeObject {
+  fn new(name: &'static str, variant: Option<String>) -> Self {
+    Self {
+      name,
+      object: Map::new(),
+      variant,
+      key: None,
+    }
+  }
+
+  fn insert<T: Serialize + ?Sized>(&mut self, key: String, value: &T) -> Result<(), Error> {
+    self.object.insert(key, value.serialize(FigmaSerializer)?);
+    Ok(())
+  }
+
+  fn finish(self) -> Result<Value, Error> {
+    let object = match self.name {
+      NODE => flatten_node(self.object),
+      _ => self.object,
+    };
+    Ok(tag(self.variant, Value::Object(object)))
+  }
+}
+
+impl ser::SerializeMap for SerializeObject {
+  type Ok = Value;
+  type Error = Error;
+
+  fn serialize_key<T: Serialize + ?Sized>(&mut self, key: &T) -> Result<(), Error> {
+    // Keys are enum variants, strings or numbers, all written as strings like JSON requires.
+    self.key = Some(match key.serialize(FigmaSerializer)? {
+      Value::String(key) => key,
+      Value::Number(key) => key.to_string(),
+      Value::Bool(key) => key.to_string(),
+      _ => {
+        return Err(ser::Error::custom(
+          "map keys must be strings, numbers or unit variants",
+        ))
+      }
+    });
+    Ok(())
+  }
+
+  fn serialize_value<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), Error> {
+    let key = self
+      .key
+      .take()
+      .expect("serialize_value is called after serialize_key");
+    self.insert(key, value)
+  }
+
+  fn end(self) -> Result<Value, Error> {
+    self.finish()
+  }
+}
+
+impl ser::SerializeStruct for SerializeObject {
+  type Ok = Value;
+  type Error = Error;
+
+  fn serialize_field<T: Serialize + ?Sized>(
+    &mut self,
+    key: &'static str,
+    value: &T,
+  ) -> Result<(), Error> {
+    self.insert(key.to_string(), value)
+  }
+
+  fn end(self) -> Result<Value, Error> {
+    self.finish()
+  }
+}
+
+impl ser::SerializeStructVariant for SerializeObject {
+  type Ok = Value;
+  type Error = Error;
+
+  fn serialize_field<T: Serialize + ?Sized>(
+    &mut self,
+    key: &'static str,
+    value: &T,
+  ) -> Result<(), Error> {
+    self.insert(key.to_string(), value)
+  }
+
+  fn end(self) -> Result<Value, Error> {
+    self.finish()
+  }
+}
+
+/// Deserializes JSON values written with the Figma naming.
+struct FigmaDeserializer(Value);
+
+impl<'de> de::Deserializer<'de> for FigmaDeserializer {
+  type Error = Error;
+
+  fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+    match self.0 {
+      Value::Array(values) => visitor.visit_seq(Array(values.into_iter())),
+      Value::Object(object) => visitor.visit_map(Object::new(object, &[])),
+      value => value.deserialize_any(visitor),
+    }
+  }
+
+  fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+    match self.0 {
+      Value::Null => visitor.visit_none(),
+      _ => visitor.visit_some(self),
+    }
+  }
+
+  fn deserialize_newtype_struct<V: Visitor<'de>>(
+    self,
+    _name: &'static str,
+    visitor: V,
+  ) -> Result<V::Value, Error> {
+    visitor.visit_newtype_struct(self)
+  }
+
+  fn deserialize_struct<V: Visitor<'de>>(
+    self,
+    name: &'static str,
+    fields: &'static [&'static str],
+    visitor: V,
+  ) -> Result<V::Value, Error> {
+    match self.0 {
+      Value::Object(object) => {
+        let object = match name {
+          NODE => nest_node(object)?,
+          _ => object,
+        };
+        visitor.visit_map(Object::new(object, fields))
+      }
+      value => FigmaDeserializer(value).deserialize_any(visitor),
+    }
+  }
+
+  fn deserialize_enum<V: Visitor<'de>>(
+    self,
+    _name: &'static str,
+    variants: &'static [&'static str],
+    visitor: V,
+  ) -> Result<V::Value, Error> {
+    let (variant, content) = match self.0 {
+      Value::String(variant) => (variant, None),
+      Value::Object(object) if object.len() == 1 => {
+        let (variant, content) = object.into_iter().next().expect("the object has one entry");
+        (variant, Some(content))
+      }
+      _ => {
+        return Err(de::Error::custom(
+          "expected a string or an object with a single key",
+        ))
+      }
+    };
+    visitor.visit_enum(Enum {
+      variant: native_variant(variant, variants),
+      content,
+    })
+  }
+
+  serde::forward_to_deserialize_any! {
+    bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string bytes byte_buf unit
+    unit_struct seq tuple tuple_struct map identifier ignored_any
+  }
+}
+
+struct Array(std::vec::IntoIter<Value>);
+
+impl<'de> de::SeqAccess<'de> for Array {
+  type Error = Error;
+
+  fn next_element_seed<T: DeserializeSeed<'de>>(
+    &mut self,
+    seed: T,
+  ) -> Result<Option<T::Value>, Error> {
+    self
+      .0
+      .next()
+      .map(|value| seed.deserialize(FigmaDeserializer(value)))
+      .transpose()
+  }
+
+  fn size_hint(&self) -> Option<usize> {
+    Some(self.0.len())
+  }
+}
+
+struct Object {
+  entries: std::vec::IntoIter<(String, Value)>,
+  value: Option<Value>,
+}
+
+impl Object {
+  /// Entries are visited in the order of the declared fields: tags of adjacently tagged enums
+  /// come before their content, which serde would otherwise buffer without the Figma naming.
+  fn new(mut object: Map<String, Value>, fields: &[&str]) -> Self {
+    let mut entries = Vec::with_capacity(object.len());
+    for field in fields {
+      if let Some(value) = object.remove(*field) {
+        entries.push((field.to_string(), value));
+      }
+    }
+    entries.extend(object);
+    Self {
+      entries: entries.into_iter(),
+      value: None,
+    }
+  }
+}
+
+impl<'de> de::MapAccess<'de> for Object {
+  type Error = Error;
+
+  fn next_key_seed<K: DeserializeSeed<'de>>(&mut self, seed: K) -> Result<Option<K::Value>, Error> {
+    let Some((key, value)) = self.entries.next() else {
+      return Ok(None);
+    };
+    self.value = Some(value);
+    seed.deserialize(Key(key)).map(Some)
+  }
+
+  fn next_value_seed<V: DeserializeSeed<'de>>(&mut self, seed: V) -> Result<V::Value, Error> {
+    let value = self
+      .value
+      .take()
+      .expect("next_value_seed is called after next_key_seed");
+    seed.deserialize(FigmaDeserializer(value))
+  }
+
+  fn size_hint(&self) -> Option<usize> {
+    Some(self.entries.len())
+  }
+}
+
+/// Deserializes map keys, which JSON always stores as strings.
+struct Key(String);
+
+macro_rules! parse_key {
+  ($($method:ident => $visit:ident,)*) => {
+    $(
+      fn $method<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        match self.0.parse() {
+          Ok(number) => visitor.$visit(number),
+          Err(_) => visitor.visit_string(self.0),
+        }
+      }
+    )*
+  };
+}
+
+impl<'de> de::Deserializer<'de> for Key {
+  type Error = Error;
+
+  fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+    visitor.visit_string(self.0)
+  }
+
+  fn deserialize_enum<V: Visitor<'de>>(
+    self,
+    name: &'static str,
+    variants: &'static [&'static str],
+    visitor: V,
+  ) -> Result<V::Value, Error> {
+    FigmaDeserializer(Value::String(self.0)).deserialize_enum(name, variants, visitor)
+  }
+
+  fn deserialize_newtype_struct<V: Visitor<'de>>(
+    self,
+    _name: &'static str,
+    visitor: V,
+  ) -> Result<V::Value, Error> {
+    visitor.visit_newtype_struct(self)
+  }
+
+  parse_key! {
+    deserialize_i8 => visit_i8,
+    deserialize_i16 => visit_i16,
+    deserialize_i32 => visit_i32,
+    deserialize_i64 => visit_i64,
+    deserialize_u8 => visit_u8,
+    deserialize_u16 => visit_u16,
+    deserialize_u32 => visit_u32,
+    deserialize_u64 => visit_u64,
+    deserialize_f32 => visit_f32,
+    deserialize_f64 => visit_f64,
+    deserialize_bool => visit_bool,
+  }
+
+  serde::forward_to_deserialize_any! {
+    i128 u128 char str string bytes byte_buf option unit unit_struct seq tuple tuple_struct map
+    struct identifier ignored_any
+  }
+}
+
+struct Enum {
+  variant: String,
+  content: Option<Value>,
+}
+
+impl<'de> de::EnumAccess<'de> for Enum {
+  type Error = Error;
+  type Variant = Variant;
+
+  fn variant_seed<V: DeserializeSeed<'de>>(self, seed: V) -> Result<(V::Value, Variant), Error> {
+    let variant = seed.deserialize(self.variant.into_deserializer())?;
+    Ok((variant, Variant(self.content)))
+  }
+}
+
+struct Variant(Option<Value>);
+
+impl<'de> de::VariantAccess<'de> for Variant {
+  type Error = Error;
+
+  fn unit_variant(self) -> Result<(), Error> {
+    match self.0 {
+      None | Some(Value::Null) => Ok(()),
+      Some(_) => Err(de::Error::custom("expected a unit variant")),
+    }
+  }
+
+  fn newtype_variant_seed<T: DeserializeSeed<'de>>(self, seed: T) -> Result<T::Value, Error> {
+    match self.0 {
+      Some(content) => seed.deserialize(FigmaDeserializer(content)),
+      None => Err(de::Error::custom("expected a newtype variant")),
+    }
+  }
+
+  fn tuple_variant<V: Visitor<'de>>(self, _len: usize, visitor: V) -> Result<V::Value, Error> {
+    match self.0 {
+      Some(Value::Array(values)) => visitor.visit_seq(Array(values.into_iter())),
+      _ => Err(de::Error::custom("expected a tuple variant")),
+    }
+  }
+
+  fn struct_variant<V: Visitor<'de>>(
+    self,
+    fields: &'static [&'static str],
+    visitor: V,
+  ) -> Result<V::Value, Error> {
+    match self.0 {
+      Some(Value::Object(object)) => visitor.visit_map(Object::new(object, fields)),
+      _ => Err(de::Error::custom("expected a struct variant")),
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use serde_json::json;
+
+  use super::*;
+  use crate::fixtures::{document, paint};
+  use crate::node::Node;
+  use crate::properties::{Effect, Interaction, Paint};
+
+  fn interaction() -> Interaction {
+    let easing = json!({
+      "easingType": "EaseInAndOut",
+      "easingFunctionCubicBezier": { "x1": 0.42, "y1": 0.0, "x2": 0.58, "y2": 1.0 },
+      "easingFunctionSpring": { "mass": 1.0, "stiffness": 100.0, "damping": 15.0 },
+    });
+    let interaction = json!({
+      "trigger": {
+        "triggerType": "OnKeyDown",
+        "timeout": 0,
+        "delay": 0,
+        "deprecatedVersion": false,
+        "device": "xboxOne",
+        "keyCodes": [13],
+        "mediaHitTime": 0,
+      },
+      "actions": [{ "type": "Back" }, {
+        "type": "Node",
+        "destinationId": "1:2",
+        "navigation": { "navigationType": "ScrollTo" },
+        "transition": {
+          "simpleTransitionType": "SmartAnimate",
+          "duration": 300,
+          "easing": easing,
+        },
+        "preserveScrollPosition": false,
+        "overlayRelativePosition": { "x": 0.0, "y": 0.0 },
+        "resetVideoPosition": false,
+        "resetScrollPosition": true,
+        "resetInteractiveComponents": false,
+      }],
+    });
+    serde_json::from_value(interaction).unwrap()
+  }
+
+  fn round_trip<T>(value: &T)
+  where
+    T: Serialize + DeserializeOwned + PartialEq + std::fmt::Debug,
+  {
+    for profile in [Profile::Native, Profile::Figma] {
+      let serialized = to_value(value, profile).unwrap();
+      let deserialized: T = from_value(serialized, profile).unwrap();
+      assert_eq!(&deserialized, value, "{profile:?} round trip");
+    }
+  }
+
+  #[test]
+  fn screaming_snake_case_names() {
+    for (name, expected) in [
+      ("GradientLinear", "GRADIENT_LINEAR"),
+      ("DropShadow", "DROP_SHADOW"),
+      ("OpenURLAction", "OPEN_URL_ACTION"),
+      ("RGBColor", "RGB_COLOR"),
+      ("xboxOne", "XBOX_ONE"),
+      ("ps4", "PS4"),
+      ("rectangle", "RECTANGLE"),
+      ("JPG", "JPG"),
+    ] {
+      assert_eq!(screaming_snake_case(name), expected);
+    }
+  }
+
+  #[test]
+  fn figma_profile_names_enum_variants() {
+    let paint: Paint = serde_json::from_value(paint("GradientLinear")).unwrap();
+    let figma = to_value(&paint, Profile::Figma).unwrap();
+    assert_eq!(figma["paintType"], "GRADIENT_LINEAR");
+    assert_eq!(figma["blendMode"], "PASS_THROUGH");
+    assert_eq!(figma["scaleMode"], "FILL");
+    assert_eq!(
+      to_value(&paint, Profile::Native).unwrap()["paintType"],
+      "GradientLinear"
+    );
+    round_trip(&paint);
+
+    let effect = json!({
+      "effectType": "DropShadow",
+      "visible": true,
+      "radius": 4.0,
+      "color": { "r": 0.0, "g": 0.0, "b": 0.0, "a": 0.25 },
+      "blendMode": "Normal",
+      "offset": { "x": 0.0, "y": 2.0 },
+      "spread": 0.0,
+      "showShadowBehindNode": false,
+      "boundVariables": { "color": { "variableAliasType": "VARIABLE_ALIAS", "id": "V:1" } },
+    });
+    let effect: Effect = serde_json::from_value(effect).unwrap();
+    assert_eq!(
+      to_value(&effect, Profile::Figma).unwrap()["effectType"],
+      "DROP_SHADOW"
+    );
+    round_trip(&effect);
+  }
+
+  #[test]
+  fn figma_profile_names_variants_with_content() {
+    let interaction = interaction();
+    let figma = to_value(&interaction, Profile::Figma).unwrap();
+    assert_eq!(figma["trigger"]["triggerType"], "ON_KEY_DOWN");
+    assert_eq!(figma["trigger"]["device"], "XBOX_ONE");
+    assert_eq!(figma["actions"][0], json!({ "type": "BACK" }));
+    let action = &figma["actions"][1];
+    assert_eq!(action["type"], "NODE");
+    assert_eq!(action["navigation"]["navigationType"], "SCROLL_TO");
+    let transition = &action["transition"];
+    assert_eq!(transition["simpleTransitionType"], "SMART_ANIMATE");
+    assert_eq!(transition["easing"]["easingType"], "EASE_IN_AND_OUT");
+    round_trip(&interaction);
+  }
+
+  #[test]
+  fn figma_profile_flattens_nodes() {
+    let document = document();
+    let figma = to_value(&document, Profile::Figma).unwrap();
+    assert_eq!(figma["type"], "DOCUMENT");
+    let canvas = &figma["children"][0];
+    assert_eq!(canvas["type"], "CANVAS");
+    let rectangle = &canvas["children"][0];
+    assert_eq!(rectangle["type"], "RECTANGLE");
+    assert_eq!(rectangle["id"], "1:2");
+    assert_eq!(rectangle["cornerRadius"], 8.0);
+    assert_eq!(rectangle["strokeJoin"], "MITER");
+    assert_eq!(rectangle["constraint"]["horizontal"], "LEFT_RIGHT");
+    assert_eq!(rectangle["styles"]["FILL"], "S:1");
+    assert!(rectangle.get("node").is_none());
+    assert!(rectangle.get("additionalData").is_none());
+    round_trip(&document);
+  }
+
+  #[test]
+  fn figma_profile_reads_native_names_and_defaults() {
+    let mut rectangle =
+      to_value(&document(), Profile::Figma).unwrap()["children"][0]["children"][0].clone();
+    let properties = rectangle.as_object_mut().unwrap();
+    properties.remove("visible");
+    properties.remove("rotation");
+    properties.insert("blendMode".to_string(), Value::from("Multiply"));
+    let node: Node = from_value(rectangle, Profile::Figma).unwrap();
+    assert!(node.visible);
+    assert_eq!(node.rotation, 0.0);
+    let crate::node::NodeType::Rectangle(vector) = &node.node else {
+      panic!("expected a rectangle, got {:?}", node.node);
+    };
+    assert_eq!(vector.blend_mode, crate::properties::BlendMode::Multiply);
+    assert_eq!(vector.additional_data.corner_smoothing, 0.6);
+  }
+
+  #[test]
+  fn native_profile_reads_misspelled_variants() {
+    let paint: Paint = serde_json::from_value(paint("GradientLienear")).unwrap();
+    assert_eq!(
+      paint.paint_type,
+      crate::properties::PaintType::GradientLinear
+    );
+  }
+
+  #[test]
+  fn figma_profile_rejects_unsupported_node_types() {
+    let frame = json!({ "id": "2:1", "name": "Frame", "type": "FRAME", "children": [] });
+    let error = from_value::<Node>(frame, Profile::Figma).unwrap_err();
+    assert!(error
+      .to_string()
+      .contains("unsupported node type `FRAME` of node `2:1`"));
+  }
+}
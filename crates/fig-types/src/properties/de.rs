@@ -0,0 +1,642 @@
+//! Deserialization of the internally tagged and untagged enums of the prototyping model.
+//!
+//! Serde buffers the content of such enums before picking a variant, which would hide it from the
+//! deserializer of the Figma [`profile`](crate::profile). These implementations pick the variant
+//! from the tag or the shape of the value instead, and deserialize the content straight from the
+//! input.
+
+use std::fmt;
+
+use serde::de::value::MapAccessDeserializer;
+use serde::de::{self, IgnoredAny, MapAccess, Visitor};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use serde_json::{Map, Value};
+
+use super::{
+  Action, ActionType, Color, DirectionalTransition, DirectionalTransitionDirection,
+  DirectionalTransitionType, Easing, Expression, ExpressionFunction, NodeActionTransition,
+  RGBColor, SimpleTransition, SimpleTransitionType, VariableAlias, VariableData, VariableDataValue,
+};
+
+/// Field holding the type of an action.
+const TAG: &str = "type";
+
+impl Serialize for Action {
+  fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+    #[derive(Serialize)]
+    struct Tagged<'a, T> {
+      #[serde(rename = "type")]
+      action_type: ActionType,
+      #[serde(flatten)]
+      action: &'a T,
+    }
+
+    let action_type = self.action_type();
+    match self {
+      Action::BackAction | Action::CloseAction => Tagged {
+        action_type,
+        action: &(),
+      }
+      .serialize(serializer),
+      Action::OpenURLAction(action) => Tagged {
+        action_type,
+        action,
+      }
+      .serialize(serializer),
+      Action::UpdateMediaRuntimeAction(action) => Tagged {
+        action_type,
+        action,
+      }
+      .serialize(serializer),
+      Action::SetVariableAction(action) => Tagged {
+        action_type,
+        action,
+      }
+      .serialize(serializer),
+      Action::SetVariableModeAction(action) => Tagged {
+        action_type,
+        action,
+      }
+      .serialize(serializer),
+      Action::ConditionalAction(action) => Tagged {
+        action_type,
+        action,
+      }
+      .serialize(serializer),
+      Action::NodeAction(action) => Tagged {
+        action_type,
+        action,
+      }
+      .serialize(serializer),
+    }
+  }
+}
+
+impl<'de> Deserialize<'de> for Action {
+  fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+    // Declaring the tag as the only field lets the Figma profile list it first.
+    deserializer.deserialize_struct("Action", &[TAG], ActionVisitor)
+  }
+}
+
+impl Action {
+  /// Deserializes the content of an action of the given type.
+  fn from_content<'de, D: Deserializer<'de>>(
+    action_type: ActionType,
+    content: D,
+  ) -> Result<Self, D::Error> {
+    Ok(match action_type {
+      ActionType::Back => {
+        IgnoredAny::deserialize(content)?;
+        Action::BackAction
+      }
+      ActionType::Close => {
+        IgnoredAny::deserialize(content)?;
+        Action::CloseAction
+      }
+      ActionType::Url => Action::OpenURLAction(Deserialize::deserialize(content)?),
+      ActionType::UpdateMediaRuntime => {
+        Action::UpdateMediaRuntimeAction(Deserialize::deserialize(content)?)
+      }
+      ActionType::SetVariable => Action::SetVariableAction(Deserialize::deserialize(content)?),
+      ActionType::SetVariableMode => {
+        Action::SetVariableModeAction(Deserialize::deserialize(content)?)
+      }
+      ActionType::Conditional => Action::ConditionalAction(Deserialize::deserialize(content)?),
+      ActionType::Node => Action::NodeAction(Deserialize::deserialize(content)?),
+    })
+  }
+}
+
+struct ActionVisitor;
+
+impl<'de> Visitor<'de> for ActionVisitor {
+  type Value = Action;
+
+  fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    f.write_str("an action with its type in a `type` field")
+  }
+
+  fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Action, A::Error> {
+    let Some(key) = map.next_key::<String>()? else {
+      return Err(de::Error::missing_field(TAG));
+    };
+    if key == TAG {
+      let action_type = map.next_value()?;
+      return Action::from_content(action_type, MapAccessDeserializer::new(map));
+    }
+    // Maps which do not keep the order of their entries may hold the tag anywhere, the entries are
+    // buffered until it is found. This only happens with the native profile.
+    let mut entries = Map::new();
+    entries.insert(key, map.next_value()?);
+    while let Some((key, value)) = map.next_entry::<String, Value>()? {
+      entries.insert(key, value);
+    }
+    let action_type = entries
+      .remove(TAG)
+      .ok_or_else(|| de::Error::missing_field(TAG))?;
+    let action_type = ActionType::deserialize(action_type).map_err(de::Error::custom)?;
+    Action::from_content(action_type, Value::Object(entries)).map_err(de::Error::custom)
+  }
+}
+
+impl<'de> Deserialize<'de> for NodeActionTransition {
+  fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+    deserializer.deserialize_any(TransitionVisitor)
+  }
+}
+
+/// Fields of both kinds of transitions.
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct TransitionFields {
+  simple_transition_type: Option<SimpleTransitionType>,
+  directional_transition_type: Option<DirectionalTransitionType>,
+  direction: Option<DirectionalTransitionDirection>,
+  duration: Option<i32>,
+  easing: Option<Easing>,
+  match_layers: Option<bool>,
+}
+
+struct TransitionVisitor;
+
+impl<'de> Visitor<'de> for TransitionVisitor {
+  type Value = NodeActionTransition;
+
+  fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    f.write_str("a simple or directional transition, or null")
+  }
+
+  fn visit_unit<E: de::Error>(self) -> Result<Self::Value, E> {
+    Ok(NodeActionTransition::Null)
+  }
+
+  fn visit_none<E: de::Error>(self) -> Result<Self::Value, E> {
+    Ok(NodeActionTransition::Null)
+  }
+
+  fn visit_map<A: MapAccess<'de>>(self, map: A) -> Result<Self::Value, A::Error> {
+    let fields = TransitionFields::deserialize(MapAccessDeserializer::new(map))?;
+    let duration = fields
+      .duration
+      .ok_or_else(|| de::Error::missing_field("duration"))?;
+    let easing = fields
+      .easing
+      .ok_or_else(|| de::Error::missing_field("easing"))?;
+    if let Some(simple_transition_type) = fields.simple_transition_type {
+      return Ok(NodeActionTransition::SimpleTransition(SimpleTransition {
+        simple_transition_type,
+        duration,
+        easing,
+      }));
+    }
+    let Some(directional_transition_type) = fields.directional_transition_type else {
+      return Err(de::Error::missing_field("simpleTransitionType"));
+    };
+    Ok(NodeActionTransition::DirectionalTransition(
+      DirectionalTransition {
+        directional_transition_type,
+        direction: fields
+          .direction
+          .ok_or_else(|| de::Error::missing_field("direction"))?,
+        duration,
+        easing,
+        match_layers: fields.match_layers.unwrap_or(false),
+      },
+    ))
+  }
+}
+
+impl<'de> Deserialize<'de> for VariableDataValue {
+  fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+    deserializer.deserialize_any(VariableValueVisitor)
+  }
+}
+
+/// Fields of the values of variables which are objects.
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct VariableValueFields {
+  r: Option<f32>,
+  g: Option<f32>,
+  b: Option<f32>,
+  a: Option<f32>,
+  variable_alias_type: Option<String>,
+  id: Option<String>,
+  expression_function: Option<ExpressionFunction>,
+  expression_arguments: Option<Vec<VariableData>>,
+}
+
+struct VariableValueVisitor;
+
+impl<'de> Visitor<'de> for VariableValueVisitor {
+  type Value = VariableDataValue;
+
+  fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    f.write_str("a boolean, number, string, color, variable alias or expression")
+  }
+
+  fn visit_bool<E: de::Error>(self, value: bool) -> Result<Self::Value, E> {
+    Ok(VariableDataValue::Boolean(value))
+  }
+
+  fn visit_i64<E: de::Error>(self, value: i64) -> Result<Self::Value, E> {
+    Ok(VariableDataValue::Float(value as f32))
+  }
+
+  fn visit_u64<E: de::Error>(self, value: u64) -> Result<Self::Value, E> {
+    Ok(VariableDataValue::Float(value as f32))
+  }
+
+  fn visit_f64<E: de::Error>(self, value: f64) -> Result<Self::Value, E> {
+    Ok(VariableDataValue::Float(value as f32))
+  }
+
+  fn visit_str<E: de::Error>(self, value: &str) -> Result<Self::Value, E> {
+    Ok(VariableDataValue::String(value.to_string()))
+  }
+
+  fn visit_map<A: MapAccess<'de>>(self, map: A) -> Result<Self::Value, A::Error> {
+    let fields = VariableValueFields::deserialize(MapAccessDeserializer::new(map))?;
+    Ok(match fields {
+      VariableValueFields {
+        variable_alias_type: Some(variable_alias_type),
+        id: Some(id),
+        ..
+      } => VariableDataValue::VariableAlias(VariableAlias {
+        variable_alias_type,
+        id,
+      }),
+      VariableValueFields {
+        expression_function: Some(expression_function),
+        expression_arguments: Some(expression_arguments),
+        ..
+      } => VariableDataValue::Expression(Expression {
+        expression_function,
+        expression_arguments,
+      }),
+      VariableValueFields {
+        r: Some(r),
+        g: Some(g),
+        b: Some(b),
+        a: Some(a),
+        ..
+      } => VariableDataValue::Color(Color { r, g, b, a }),
+      VariableValueFields {
+        r: Some(r),
+        g: Some(g),
+        b: Some(b),
+        a: None,
+        ..
+      } => VariableDataValue::RGBColor(RGBColor { r, g, b }),
+      _ => {
+        return Err(de::Error::custom(
+          "expected a color, a variable alias or an expression",
+        ))
+      }
+    })
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use serde::de::DeserializeOwned;
+  use serde_json::json;
+
+  use super::*;
+  use crate::fixtures::rectangle;
+  use crate::node::{Node, NodeType};
+  use crate::profile::{self, Profile};
+  use crate::properties::{
+    ConditionalAction, ConditionalBlock, DevStatus, DevStatusType, EasingFunctionCubicBezier,
+    EasingFunctionSpring, EasingType, Interaction, Navigation, NavigationType, NodeAction,
+    OpenURLAction, SetVariableAction, SetVariableModeAction, Trigger, TriggerDevice, TriggerType,
+    UpdateMediaRuntimeAction, UpdateMediaRuntimeActionMediaAction, VariableDataType,
+    VariableResolvedDataType, Vector,
+  };
+
+  fn round_trip<T>(value: &T)
+  where
+    T: Serialize + DeserializeOwned + PartialEq + fmt::Debug,
+  {
+    for profile in [Profile::Native, Profile::Figma] {
+      let serialized = profile::to_value(value, profile).unwrap();
+      let deserialized: T = profile::from_value(serialized.clone(), profile).unwrap();
+      assert_eq!(&deserialized, value, "{profile:?} round trip");
+      // Text keeps the order of the entries, unlike maps of values.
+      let text = serialized.to_string();
+      let deserialized: T =
+        profile::from_value(serde_json::from_str(&text).unwrap(), profile).unwrap();
+      assert_eq!(
+        &deserialized, value,
+        "{profile:?} round trip through {text}"
+      );
+    }
+    let text = serde_json::to_string(value).unwrap();
+    assert_eq!(&serde_json::from_str::<T>(&text).unwrap(), value);
+  }
+
+  fn easing() -> Easing {
+    Easing {
+      easing_type: EasingType::EaseInAndOut,
+      easing_function_cubic_bezier: EasingFunctionCubicBezier {
+        x1: 0.42,
+        y1: 0.0,
+        x2: 0.58,
+        y2: 1.0,
+      },
+      easing_function_spring: EasingFunctionSpring {
+        mass: 1.0,
+        stiffness: 100.0,
+        damping: 15.0,
+      },
+    }
+  }
+
+  fn node_action(transition: NodeActionTransition) -> Action {
+    Action::NodeAction(NodeAction {
+      destination_id: Some("1:2".to_string()),
+      navigation: Navigation {
+        navigation_type: NavigationType::Overlay,
+      },
+      transition,
+      preserve_scroll_position: false,
+      overlay_relative_position: Vector { x: 8.0, y: 16.0 },
+      reset_video_position: false,
+      reset_scroll_position: true,
+      reset_interactive_components: false,
+    })
+  }
+
+  fn alias(id: &str) -> VariableDataValue {
+    VariableDataValue::VariableAlias(VariableAlias {
+      variable_alias_type: "VARIABLE_ALIAS".to_string(),
+      id: id.to_string(),
+    })
+  }
+
+  fn data(variable_data_type: VariableDataType, value: VariableDataValue) -> VariableData {
+    VariableData {
+      variable_data_type,
+      resolved_type: VariableResolvedDataType::Float,
+      value,
+    }
+  }
+
+  /// `count + 1 > 3`
+  fn condition() -> VariableData {
+    let sum = Expression {
+      expression_function: ExpressionFunction::Addition,
+      expression_arguments: vec![
+        data(VariableDataType::VariableAlias, alias("VariableID:1:1")),
+        data(VariableDataType::Float, VariableDataValue::Float(1.0)),
+      ],
+    };
+    let comparison = Expression {
+      expression_function: ExpressionFunction::GreaterThan,
+      expression_arguments: vec![
+        data(
+          VariableDataType::Expression,
+          VariableDataValue::Expression(sum),
+        ),
+        data(VariableDataType::Float, VariableDataValue::Float(3.0)),
+      ],
+    };
+    VariableData {
+      variable_data_type: VariableDataType::Expression,
+      resolved_type: VariableResolvedDataType::Boolean,
+      value: VariableDataValue::Expression(comparison),
+    }
+  }
+
+  fn actions() -> Vec<Action> {
+    vec![
+      Action::BackAction,
+      Action::CloseAction,
+      Action::OpenURLAction(OpenURLAction {
+        url: "https://example.com".to_string(),
+      }),
+      Action::UpdateMediaRuntimeAction(UpdateMediaRuntimeAction {
+        destination_id: None,
+        media_action: UpdateMediaRuntimeActionMediaAction::SkipForward,
+        amount_to_skip: 5,
+        new_timestamp: 0,
+      }),
+      Action::SetVariableAction(SetVariableAction {
+        variable_id: Some("VariableID:1:1".to_string()),
+        variable_value: data(VariableDataType::Float, VariableDataValue::Float(2.5)),
+      }),
+      Action::SetVariableModeAction(SetVariableModeAction {
+        variable_collection_id: Some("VariableCollectionId:1:0".to_string()),
+        variable_mode_id: Some("1:1".to_string()),
+      }),
+      Action::ConditionalAction(ConditionalAction {
+        conditional_blocks: vec![
+          ConditionalBlock {
+            condition: condition(),
+            actions: vec![Action::CloseAction],
+          },
+          ConditionalBlock {
+            condition: data(VariableDataType::Boolean, VariableDataValue::Boolean(true)),
+            actions: vec![node_action(NodeActionTransition::Null)],
+          },
+        ],
+      }),
+      node_action(NodeActionTransition::SimpleTransition(SimpleTransition {
+        simple_transition_type: SimpleTransitionType::SmartAnimate,
+        duration: 300,
+        easing: easing(),
+      })),
+      node_action(NodeActionTransition::DirectionalTransition(
+        DirectionalTransition {
+          directional_transition_type: DirectionalTransitionType::SlideIn,
+          direction: DirectionalTransitionDirection::Left,
+          duration: 250,
+          easing: easing(),
+          match_layers: true,
+        },
+      )),
+    ]
+  }
+
+  #[test]
+  fn actions_are_tagged_with_their_type() {
+    let actions = actions();
+    let native = serde_json::to_value(&actions).unwrap();
+    assert_eq!(native[0], json!({ "type": "Back" }));
+    assert_eq!(native[2]["type"], "Url");
+    assert_eq!(native[2]["url"], "https://example.com");
+    let figma = profile::to_value(&actions, Profile::Figma).unwrap();
+    let types: Vec<_> = figma
+      .as_array()
+      .unwrap()
+      .iter()
+      .map(|action| action["type"].as_str().unwrap())
+      .collect();
+    assert_eq!(
+      types,
+      [
+        "BACK",
+        "CLOSE",
+        "URL",
+        "UPDATE_MEDIA_RUNTIME",
+        "SET_VARIABLE",
+        "SET_VARIABLE_MODE",
+        "CONDITIONAL",
+        "NODE",
+        "NODE"
+      ]
+    );
+    assert_eq!(figma[3]["mediaAction"], "SKIP_FORWARD");
+    for action in &actions {
+      round_trip(action);
+    }
+  }
+
+  #[test]
+  fn actions_read_the_tag_anywhere() {
+    let action: Action =
+      serde_json::from_str(r#"{ "url": "https://example.com", "type": "Url" }"#).unwrap();
+    assert_eq!(action.action_type(), ActionType::Url);
+    let error = serde_json::from_str::<Action>(r#"{ "url": "https://example.com" }"#);
+    assert!(error
+      .unwrap_err()
+      .to_string()
+      .contains("missing field `type`"));
+  }
+
+  #[test]
+  fn transitions_are_untagged() {
+    let actions = actions();
+    let native = serde_json::to_value(&actions).unwrap();
+    assert_eq!(
+      native[6]["conditionalBlocks"][1]["actions"][0]["transition"],
+      Value::Null
+    );
+    assert_eq!(
+      native[7]["transition"]["simpleTransitionType"],
+      "SmartAnimate"
+    );
+    let figma = profile::to_value(&actions, Profile::Figma).unwrap();
+    let transition = &figma[8]["transition"];
+    assert_eq!(transition["directionalTransitionType"], "SLIDE_IN");
+    assert_eq!(transition["direction"], "LEFT");
+    assert_eq!(transition["easing"]["easingType"], "EASE_IN_AND_OUT");
+  }
+
+  #[test]
+  fn variable_values_are_untagged() {
+    let values = [
+      (VariableDataValue::Boolean(true), json!(true)),
+      (VariableDataValue::Float(0.5), json!(0.5)),
+      (
+        VariableDataValue::String("Label".to_string()),
+        json!("Label"),
+      ),
+      (
+        VariableDataValue::Color(Color {
+          r: 1.0,
+          g: 0.5,
+          b: 0.0,
+          a: 0.25,
+        }),
+        json!({ "r": 1.0, "g": 0.5, "b": 0.0, "a": 0.25 }),
+      ),
+      (
+        VariableDataValue::RGBColor(RGBColor {
+          r: 1.0,
+          g: 0.5,
+          b: 0.0,
+        }),
+        json!({ "r": 1.0, "g": 0.5, "b": 0.0 }),
+      ),
+      (
+        alias("VariableID:1:2"),
+        json!({ "variableAliasType": "VARIABLE_ALIAS", "id": "VariableID:1:2" }),
+      ),
+    ];
+    for (value, expected) in values {
+      assert_eq!(serde_json::to_value(&value).unwrap(), expected);
+      round_trip(&value);
+    }
+    let integer: VariableDataValue = serde_json::from_value(json!(3)).unwrap();
+    assert_eq!(integer, VariableDataValue::Float(3.0));
+    assert!(serde_json::from_value::<VariableDataValue>(json!({ "r": 1.0 })).is_err());
+  }
+
+  #[test]
+  fn expressions_use_the_profile_naming() {
+    let condition = condition();
+    let figma = profile::to_value(&condition, Profile::Figma).unwrap();
+    assert_eq!(figma["variableDataType"], "EXPRESSION");
+    let comparison = &figma["value"];
+    assert_eq!(comparison["expressionFunction"], "GREATER_THAN");
+    let sum = &comparison["expressionArguments"][0]["value"];
+    assert_eq!(sum["expressionFunction"], "ADDITION");
+    assert_eq!(
+      sum["expressionArguments"][0]["variableDataType"],
+      "VARIABLE_ALIAS"
+    );
+    assert_eq!(sum["expressionArguments"][1]["value"], 1.0);
+    round_trip(&condition);
+  }
+
+  #[test]
+  fn interactions_and_dev_status_round_trip() {
+    let interaction = Interaction {
+      trigger: Some(Trigger {
+        trigger_type: TriggerType::AfterTimeout,
+        timeout: 800,
+        delay: 0,
+        deprecated_version: false,
+        device: TriggerDevice::Keyboard,
+        key_codes: Vec::new(),
+        media_hit_time: 0,
+      }),
+      actions: actions(),
+    };
+    round_trip(&interaction);
+    round_trip(&Interaction {
+      trigger: None,
+      actions: Vec::new(),
+    });
+    let dev_status = DevStatus {
+      dev_status_type: DevStatusType::ReadyForDev,
+      description: "Spacing updated".to_string(),
+    };
+    let figma = profile::to_value(&dev_status, Profile::Figma).unwrap();
+    assert_eq!(figma["devStatusType"], "READY_FOR_DEV");
+    round_trip(&dev_status);
+  }
+
+  #[test]
+  fn integer_geometry_is_read_as_floats() {
+    // Files written when geometry was stored as integers.
+    let mut node = rectangle();
+    let data = &mut node["node"]["data"];
+    data["absoluteBoundingBox"] = json!({ "x": 10, "y": -20, "width": 100, "height": 50 });
+    data["size"] = json!({ "width": 100, "height": 50 });
+    data["relativeTransform"] = json!({ "matrix": [[1, 0, 10], [0, 1, -20]] });
+    data["fills"][0]["gradientStops"] = json!([
+      { "position": 0, "color": { "r": 0, "g": 0, "b": 0, "a": 1 }, "boundVariables": {} },
+      { "position": 1, "color": { "r": 1, "g": 1, "b": 1, "a": 1 }, "boundVariables": {} },
+    ]);
+    let node: Node = serde_json::from_value(node).unwrap();
+    let NodeType::Rectangle(rectangle) = &node.node else {
+      panic!("not a rectangle: {node:?}")
+    };
+    let bounds = &rectangle.absolute_bounding_box;
+    assert_eq!(
+      [bounds.x, bounds.y, bounds.width, bounds.height],
+      [10.0, -20.0, 100.0, 50.0]
+    );
+    assert_eq!([rectangle.size.width, rectangle.size.height], [100.0, 50.0]);
+    assert_eq!(
+      rectangle.relative_transform.matrix,
+      [[1.0, 0.0, 10.0], [0.0, 1.0, -20.0]]
+    );
+    let stops = &rectangle.fills[0].gradient_stops;
+    assert_eq!([stops[0].position, stops[1].position], [0.0, 1.0]);
+    assert_eq!(stops[1].color.r, 1.0);
+  }
+}
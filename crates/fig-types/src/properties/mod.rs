@@ -4,46 +4,48 @@ use std::collections::HashMap;
 use serde::{Deserialize, Serialize};
 use ts_rs::TS;
 
+mod de;
+
 /// An RGBA color
-#[derive(Debug, Serialize, Deserialize, TS)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS)]
 #[ts(export, export_to = "properties/color/")]
 pub struct Color {
   /// Red channel value, between 0 and 1
-  r: f32,
+  pub r: f32,
   /// Green channel value, between 0 and 1
-  g: f32,
+  pub g: f32,
   /// Blue channel value, between 0 and 1
-  b: f32,
+  pub b: f32,
   /// Alpha channel value, between 0 and 1
-  a: f32,
+  pub a: f32,
 }
 
 /// An RBG Color
-#[derive(Debug, Serialize, Deserialize, TS)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS)]
 #[ts(export, export_to = "properties/color/")]
 pub struct RGBColor {
   /// Red channel value, between 0 and 1
-  r: f32,
+  pub r: f32,
   /// Green channel value, between 0 and 1
-  g: f32,
+  pub g: f32,
   /// Blue channel value, between 0 and 1
-  b: f32,
+  pub b: f32,
 }
 
 /// Format and size to export an asset at
-#[derive(Debug, Serialize, Deserialize, TS)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS)]
 #[ts(export, export_to = "properties/export/")]
 pub struct ExportSetting {
   /// File suffix to append to all filenames
-  suffix: String,
+  pub suffix: String,
   /// Image type, string enum that supports values JPG, PNG, and SVG
-  format: FormatType,
+  pub format: FormatType,
   /// Constraint that determines sizing of exported asset
-  constraint: Constraint,
+  pub constraint: Constraint,
 }
 
 /// Enum describing format type
-#[derive(Debug, Serialize, Deserialize, TS)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS)]
 #[ts(export, export_to = "properties/export/")]
 pub enum FormatType {
   JPG,
@@ -52,20 +54,20 @@ pub enum FormatType {
 }
 
 /// Sizing constraint for exports
-#[derive(Debug, Serialize, Deserialize, TS)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS)]
 #[ts(export, export_to = "properties/constraint/")]
 pub struct Constraint {
   /// Type of constraint to apply; string enum with potential values below
   /// SCALE: Scale by value
   /// WIDTH: Scale proportionally and set width to value
   /// HEIGHT: Scale proportionally and set height to value
-  constraint_type: ConstraintType,
+  pub constraint_type: ConstraintType,
   /// See ctype property for effect of this field
-  value: f32,
+  pub value: f32,
 }
 
 /// Enum describing type of constraint
-#[derive(Debug, Serialize, Deserialize, TS)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS)]
 #[ts(export, export_to = "properties/constraint/")]
 pub enum ConstraintType {
   /// Scale by value
@@ -77,36 +79,36 @@ pub enum ConstraintType {
 }
 
 /// A rectangle that expresses a bounding box in absolute coordinates
-#[derive(Debug, Serialize, Deserialize, TS)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS)]
 #[ts(export, export_to = "properties/")]
 pub struct Rectangle {
   /// X coordinate of top left corner of the rectangle
-  x: i32,
+  pub x: f32,
   /// Y coordinate of top left corner of the rectangle
-  y: i32,
+  pub y: f32,
   /// Width of the rectangle
-  width: i32,
+  pub width: f32,
   /// Height of the rectangle
-  height: i32,
+  pub height: f32,
 }
 
 /// Information about the arc properties of an ellipse. 0° is the x axis and increasing angles
 /// rotate clockwise
-#[derive(Debug, Serialize, Deserialize, TS)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS)]
 #[serde(rename_all = "camelCase")]
 #[ts(export, export_to = "properties/")]
 pub struct ArcData {
   /// Start of the sweep in radians
-  starting_angle_number: i32,
+  pub starting_angle_number: f32,
   /// End of the sweep in radians
-  ending_angle: i32,
+  pub ending_angle: f32,
   /// Inner radius value between 0 and 1
-  inner_radius: f32,
+  pub inner_radius: f32,
 }
 
 /// Enum describing how layer blends with layers below
 /// This type is a string enum with the following possible values
-#[derive(Debug, Serialize, Deserialize, TS)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS)]
 #[ts(export, export_to = "properties/")]
 pub enum BlendMode {
   /// Normal blends:
@@ -145,7 +147,7 @@ pub enum BlendMode {
 }
 
 /// Enum describing how mask layer operates on the pixels of the layers it masks.
-#[derive(Debug, Serialize, Deserialize, TS)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS)]
 #[ts(export, export_to = "properties/")]
 pub enum MaskType {
   /// The mask node's alpha channel will be used to determine the opacity of each pixel in the
@@ -161,7 +163,7 @@ pub enum MaskType {
 }
 
 /// Enum describing animation easing curves
-#[derive(Debug, Serialize, Deserialize, TS)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS)]
 #[ts(export, export_to = "properties/easing/")]
 pub enum EasingType {
   /// Ease in with an animation curve similar to CSS ease-in.
@@ -172,33 +174,49 @@ pub enum EasingType {
   EaseInAndOut,
   /// No easing, similar to CSS linear.
   Linear,
+  /// Ease in, pulling back before starting to move.
+  EaseInBack,
+  /// Ease out, overshooting before settling.
+  EaseOutBack,
+  /// Ease in and then out, pulling back and overshooting.
+  EaseInAndOutBack,
+  /// A cubic bezier curve given by `easingFunctionCubicBezier`.
+  CustomCubicBezier,
   /// Gentle spring animation similar to react-spring.
   GentleSpring,
+  /// Quick spring animation, settling fast.
+  Quick,
+  /// Bouncy spring animation, overshooting several times.
+  Bouncy,
+  /// Slow spring animation.
+  Slow,
+  /// A spring given by `easingFunctionSpring`.
+  CustomSpring,
 }
 
 /// A flow starting point used when launching a prototype to enter Presentation view.
-#[derive(Debug, Serialize, Deserialize, TS)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS)]
 #[serde(rename_all = "camelCase")]
 #[ts(export, export_to = "properties/")]
 pub struct FlowStartingPoint {
   /// Unique identifier specifying the frame
-  node_id: String,
+  pub node_id: String,
   /// Name of flow
-  name: String,
+  pub name: String,
 }
 
 /// Layout constraint relative to containing Frame
-#[derive(Debug, Serialize, Deserialize, TS)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS)]
 #[ts(export, export_to = "properties/layout/")]
 pub struct LayoutConstraint {
   /// Vertical constraint as an enum
-  vertical: LayoutConstraintVertical,
+  pub vertical: LayoutConstraintVertical,
   /// Horizontal constraint as an enum
-  horizontal: LayoutConstraintHorizontal,
+  pub horizontal: LayoutConstraintHorizontal,
 }
 
 /// Enum describing vertical layout constraint
-#[derive(Debug, Serialize, Deserialize, TS)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS)]
 #[ts(export, export_to = "properties/layout/")]
 pub enum LayoutConstraintVertical {
   /// Node is laid out relative to top of the containing frame
@@ -215,7 +233,7 @@ pub enum LayoutConstraintVertical {
 }
 
 /// Enum describing horizontal layout constraint
-#[derive(Debug, Serialize, Deserialize, TS)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS)]
 #[ts(export, export_to = "properties/layout/")]
 pub enum LayoutConstraintHorizontal {
   /// Node is laid out relative to left of the containing frame
@@ -232,7 +250,7 @@ pub enum LayoutConstraintHorizontal {
 }
 
 /// Guides to align and place objects within a frame
-#[derive(Debug, Serialize, Deserialize, TS)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS)]
 #[serde(rename_all = "camelCase")]
 #[ts(export, export_to = "properties/layout/")]
 pub struct LayoutGrid {
@@ -240,13 +258,13 @@ pub struct LayoutGrid {
   /// COLUMNS: Vertical grid
   /// ROWS: Horizontal grid
   /// GRID: Square grid
-  pattern: LayoutGridPattern,
+  pub pattern: LayoutGridPattern,
   /// Width of column grid or height of row grid or square grid spacing
-  section_size: i32,
+  pub section_size: f32,
   /// Is the grid currently visible?
-  visible: bool,
+  pub visible: bool,
   /// Color of the grid
-  color: Color,
+  pub color: Color,
 
   /// The following properties are only meaningful for directional grids (COLUMNS or ROWS)
 
@@ -254,19 +272,19 @@ pub struct LayoutGrid {
   /// MIN: Grid starts at the left or top of the frame
   /// STRETCH: Grid is stretched to fit the frame
   /// CENTER: Grid is center aligned
-  alignment: LayoutGridAlignment,
+  pub alignment: LayoutGridAlignment,
   /// Spacing in between columns and rows
-  gutter_size: i32,
+  pub gutter_size: f32,
   /// Spacing before the first column or row
-  offset: i32,
+  pub offset: f32,
   /// Number of columns or rows
-  count: i32,
+  pub count: i32,
   /// A mapping of field to the VariableAlias of the bound variable
-  bound_variables: HashMap<String, VariableAlias>,
+  pub bound_variables: HashMap<String, VariableAlias>,
 }
 
 /// Enum describing pattern of LayoutGrid
-#[derive(Debug, Serialize, Deserialize, TS)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS)]
 #[ts(export, export_to = "properties/layout/")]
 pub enum LayoutGridPattern {
   /// Vertical grid
@@ -278,7 +296,7 @@ pub enum LayoutGridPattern {
 }
 
 /// Enum describing alignment of LayoutGrid
-#[derive(Debug, Serialize, Deserialize, TS)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS)]
 #[ts(export, export_to = "properties/layout/")]
 pub enum LayoutGridAlignment {
   /// Grid starts at the left or top of the frame
@@ -291,7 +309,7 @@ pub enum LayoutGridAlignment {
 
 /// Determines if the layer should stretch along the parent’s counter axis. This property is only
 /// provided for direct children of auto-layout frames.
-#[derive(Debug, Serialize, Deserialize, TS)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS)]
 #[ts(export, export_to = "properties/layout/")]
 pub enum LayoutAlign {
   Inherit,
@@ -302,37 +320,37 @@ pub enum LayoutAlign {
 }
 
 /// A visual effect such as a shadow or blur
-#[derive(Debug, Serialize, Deserialize, TS)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS)]
 #[serde(rename_all = "camelCase")]
 #[ts(export, export_to = "properties/")]
 pub struct Effect {
   /// Type of effect as a string enum
-  effect_type: EffectType,
+  pub effect_type: EffectType,
   /// Is the effect active?
-  visible: bool,
+  pub visible: bool,
   /// Radius of the blur effect (applies to shadows as well)
-  radius: i32,
+  pub radius: f32,
 
   /// The following properties are for shadows only:
 
   /// The color of the shadow
-  color: Color,
+  pub color: Color,
   /// Blend mode of the shadow
-  blend_mode: BlendMode,
+  pub blend_mode: BlendMode,
   /// How far the shadow is projected in the x and y directions
-  offset: Vector,
+  pub offset: Vector,
   /// How far the shadow spreads
-  spread: i32,
+  pub spread: f32,
   /// default : 0
   /// Whether to show the shadow behind translucent or transparent pixels (applies only to drop
   /// shadows)
-  show_shadow_behind_node: bool,
+  pub show_shadow_behind_node: bool,
   /// A mapping of field to the VariableAlias of the bound variable.
-  bound_variables: HashMap<String, VariableAlias>,
+  pub bound_variables: HashMap<String, VariableAlias>,
 }
 
 /// Enum describing the type of an effect
-#[derive(Debug, Serialize, Deserialize, TS)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS)]
 #[ts(export, export_to = "properties/")]
 pub enum EffectType {
   InnerShadow,
@@ -342,22 +360,22 @@ pub enum EffectType {
 }
 
 /// A link to either a URL or another frame (node) in the document
-#[derive(Debug, Serialize, Deserialize, TS)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS)]
 #[serde(rename_all = "camelCase")]
 #[ts(export, export_to = "properties/")]
 pub struct HyperLink {
   /// Type of hyperlink
   /// URL
   /// NODE
-  hyper_link_type: HyperLinkType,
+  pub hyper_link_type: HyperLinkType,
   /// URL being linked to, if URL type
-  url: String,
+  pub url: String,
   /// ID of frame hyperlink points to, if NODE type
-  node_id: String,
+  pub node_id: String,
 }
 
 /// Enum describing the typed of an hyper link
-#[derive(Debug, Serialize, Deserialize, TS)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS)]
 #[ts(export, export_to = "properties/")]
 pub enum HyperLinkType {
   Url,
@@ -365,15 +383,15 @@ pub enum HyperLinkType {
 }
 
 /// Represents a link to documentation for a component.
-#[derive(Debug, Serialize, Deserialize, TS)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS)]
 #[ts(export, export_to = "properties/")]
 pub struct DocumentationLink {
   /// Should be a valid URI (e.g. https://www.figma.com).
-  uri: String,
+  pub uri: String,
 }
 
 /// A solid color, gradient, or image texture that can be applied as fills or strokes
-#[derive(Debug, Serialize, Deserialize, TS)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS)]
 #[serde(rename_all = "camelCase")]
 #[ts(export, export_to = "properties/paint/")]
 pub struct Paint {
@@ -386,35 +404,35 @@ pub struct Paint {
   /// IMAGE
   /// EMOJI
   /// VIDEO
-  paint_type: PaintType,
+  pub paint_type: PaintType,
   /// Is the paint enabled?
-  visible: bool,
+  pub visible: bool,
   /// default : true
   /// Overall opacity of paint (colors within the paint can also have opacity values which would
   /// blend with this)
-  opacity: f32,
+  pub opacity: f32,
   /// default : true
 
   /// For solid paints :
 
   /// Solid color of the paint
-  color: Color,
+  pub color: Color,
 
   /// For gradient paints :
 
   /// How this node blends with nodes behind it in the scene (see blend mode section for more
   /// details)
-  blend_mode: BlendMode,
+  pub blend_mode: BlendMode,
   /// This field contains three vectors, each of which are a position in normalized object space
   /// (normalized object space is if the top left corner of the bounding box of the object is (0,
   /// 0) and the bottom right is (1,1)). The first position corresponds to the start of the
   /// gradient (value 0 for the purposes of calculating gradient stops), the second position is
   /// the end of the gradient (value 1), and the third handle position determines the width of
   /// the gradient. See image examples below :
-  gradient_handle_positions: Vec<Vector>, // 3 vecs only
+  pub gradient_handle_positions: Vec<Vector>, // 3 vecs only
   /// Positions of key points along the gradient axis with the colors anchored there. Colors
   /// along the gradient are interpolated smoothly between neighboring gradient stops.
-  gradient_stops: Vec<ColorStop>,
+  pub gradient_stops: Vec<ColorStop>,
 
   /// For image paints :
 
@@ -423,34 +441,34 @@ pub struct Paint {
   /// FIT
   /// TILE
   /// STRETCH
-  scale_mode: PaintScaleMode,
+  pub scale_mode: PaintScaleMode,
   /// Affine transform applied to the image, only present if scaleMode is STRETCH
   #[ts(optional)]
-  image_transform: Option<Transform>,
+  pub image_transform: Option<Transform>,
   /// Amount image is scaled by in tiling, only present if scaleMode is TILE
   #[ts(optional)]
-  scaling_factor: Option<i32>,
+  pub scaling_factor: Option<f32>,
   /// Image rotation, in degrees.
-  rotation: i32,
+  pub rotation: f32,
   /// A reference to an image embedded in this node. To download the image using this reference,
   /// use the GET file images endpoint to retrieve the mapping from image references to image
   /// URLs
-  image_ref: String,
+  pub image_ref: String,
   /// Defines what image filters have been applied to this paint, if any. If this property is not
   /// defined, no filters have been applied.
-  filters: ImageFilters,
+  pub filters: ImageFilters,
   /// default : {}
   /// A reference to the GIF embedded in this node, if the image is a GIF. To download the image
   /// using this reference, use the GET file images endpoint to retrieve the mapping from image
   /// references to image URLs
-  gif_ref: String,
+  pub gif_ref: String,
   /// A mapping of field to the VariableAlias of the bound variable.
   #[ts(optional)]
-  bound_variables: Option<HashMap<String, VariableAlias>>,
+  pub bound_variables: Option<HashMap<String, VariableAlias>>,
 }
 
 /// Enum describing the scale mode of a paint
-#[derive(Debug, Serialize, Deserialize, TS)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS)]
 #[ts(export, export_to = "properties/paint/")]
 pub enum PaintScaleMode {
   Fill,
@@ -460,11 +478,13 @@ pub enum PaintScaleMode {
 }
 
 /// Enum describing the type of a paint
-#[derive(Debug, Serialize, Deserialize, TS)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS)]
 #[ts(export, export_to = "properties/paint/")]
 pub enum PaintType {
   Solid,
-  GradientLienear,
+  // Earlier versions wrote the misspelled name.
+  #[serde(alias = "GradientLienear")]
+  GradientLinear,
   GradientRadial,
   GradientAngular,
   GradientDiamond,
@@ -474,134 +494,134 @@ pub enum PaintType {
 }
 
 /// Defines a single path
-#[derive(Debug, Serialize, Deserialize, TS)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS)]
 #[serde(rename_all = "camelCase")]
 #[ts(export, export_to = "properties/")]
 pub struct Path {
   /// A series of [path commands](https://developer.mozilla.org/en-US/docs/Web/SVG/Attribute/d#path_commands) that encodes how to draw the path.
-  path: String,
+  pub path: String,
   /// The winding rule for the path (same as in SVGs). This determines whether a given point in
   /// space is inside or outside the path.
   #[ts(optional)]
-  winding_rule: Option<String>,
+  pub winding_rule: Option<String>,
   /// If there is a per-region fill, this refers to an ID in the fillOverrideTable.
   #[ts(optional)]
-  override_id: Option<i32>,
+  pub override_id: Option<i32>,
 }
 
 /// A 2d vector
-#[derive(Debug, Serialize, Deserialize, TS)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS)]
 #[ts(export, export_to = "properties/")]
 pub struct Vector {
   /// X coordinate of the vector
-  x: i32,
+  pub x: f32,
   /// Y coordinate of the vector
-  y: i32,
+  pub y: f32,
 }
 
 /// A width and a height
-#[derive(Debug, Serialize, Deserialize, TS)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS)]
 #[ts(export, export_to = "properties/")]
 pub struct Size {
   /// the width of a size
-  width: i32,
+  pub width: f32,
   /// the height of a size
-  height: i32,
+  pub height: f32,
 }
 
 /// A 2x3 affine transformation matrix
-#[derive(Debug, Serialize, Deserialize, TS)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS)]
 #[ts(export, export_to = "properties/")]
 pub struct Transform {
   /// A 2D affine transformation matrix that can be used to calculate the affine transforms
   /// applied to a layer, including scaling, rotation, shearing, and translation. The form of
   /// the matrix is given as an array of 2 arrays of 3 numbers each. E.g. the identity matrix
   /// would be    [[1, 0, 0],
-  matrix: Vec<Vec<i32>>, // 2 vecs containing each a vec of 3 i32 number
+  pub matrix: Vec<Vec<f32>>, // 2 vecs containing each a vec of 3 numbers
 }
 
 /// Defines the image filters applied to an image paint. All values are from -1 to 1.
-#[derive(Debug, Serialize, Deserialize, TS)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS)]
 #[serde(rename_all = "camelCase")]
 #[ts(export, export_to = "properties/")]
 pub struct ImageFilters {
   /// default: 0
-  exposure: i32,
+  pub exposure: f32,
   /// default: 0
-  contrast: i32,
+  pub contrast: f32,
   /// default: 0
-  saturation: i32,
+  pub saturation: f32,
   /// default: 0
-  temperature: i32,
+  pub temperature: f32,
   /// default: 0
-  tint: i32,
+  pub tint: f32,
   /// default: 0
-  highlights: i32,
+  pub highlights: f32,
   /// default: 0
-  shadows: i32,
+  pub shadows: f32,
 }
 
 /// A stop in a gradient paint that contains information about the stop position, color, and bound
 /// variables
-#[derive(Debug, Serialize, Deserialize, TS)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS)]
 #[serde(rename_all = "camelCase")]
 #[ts(export, export_to = "properties/")]
 pub struct ColorStop {
   /// Value between 0 and 1 representing position along gradient axis
-  position: i32,
+  pub position: f32,
   /// Color attached to corresponding position
-  color: Color,
+  pub color: Color,
   /// Color variable that is attached to the stop if any
-  bound_variables: HashMap<String, VariableAlias>,
+  pub bound_variables: HashMap<String, VariableAlias>,
 }
 
 /// Paint metadata to override default paints
-#[derive(Debug, Serialize, Deserialize, TS)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS)]
 #[serde(rename_all = "camelCase")]
 #[ts(export, export_to = "properties/paint/")]
 pub struct PaintOverride {
   /// Paints applied to characters
-  fills: Vec<Paint>,
+  pub fills: Vec<Paint>,
   /// ID of style node, if any, that this inherits fill data from
-  inherit_fill_style_id: String,
+  pub inherit_fill_style_id: String,
 }
 
 /// Metadata for character formatting
-#[derive(Debug, Serialize, Deserialize, TS)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS)]
 #[serde(rename_all = "camelCase")]
 #[ts(export, export_to = "properties/typeStyle/")]
 pub struct TypeStyle {
   /// Font family of text (standard name)
-  font_family: String,
+  pub font_family: String,
   /// PostScript font name
-  font_post_script_name: String,
+  pub font_post_script_name: String,
   /// Space between paragraphs in px, 0 if not present
   /// default: 0
-  paragraph_spacing: i32,
+  pub paragraph_spacing: f32,
   /// Paragraph indentation in px, 0 if not present
   /// default: 0
-  paragraph_indent: i32,
+  pub paragraph_indent: f32,
   /// Space between list items in px, 0 if not present
   /// default: 0
-  list_spacing: i32,
+  pub list_spacing: f32,
   /// Whether or not text is italicized
-  italic: bool,
+  pub italic: bool,
   /// Numeric font weight
-  font_weight: i32,
+  pub font_weight: i32,
   /// Font size in px
-  font_size: i32,
+  pub font_size: f32,
   /// Text casing applied to the node, default is the original casing
   /// UPPER
   /// LOWER
   /// TITLE
   /// SMALL_CAPS
   /// SMALL_CAPS_FORCED
-  text_case: TypeStyleTextCase,
+  pub text_case: TypeStyleTextCase,
   /// default: ORIGINAL
   /// Text decoration applied to the node, default is none
   /// STRIKETHROUGH
   /// UNDERLINE
-  text_decoration: TypeStyleTextDecoration,
+  pub text_decoration: TypeStyleTextDecoration,
   /// default: NONE
   /// Dimensions along which text will auto resize, default is that the text does not
   /// auto-resize. TRUNCATE means that the text will be shortened and trailing text will be
@@ -610,69 +630,69 @@ pub struct TypeStyle {
   /// instead. HEIGHT
   /// WIDTH_AND_HEIGHT
   /// [DEPRECATED] TRUNCATE
-  text_auto_resize: TypeStyleTextAutoResize,
+  pub text_auto_resize: TypeStyleTextAutoResize,
   /// default: NONE
   /// Whether this text node will truncate with an ellipsis when the text contents is larger than
   /// the text node. DISABLED
   /// ENDING
-  text_truncation: TypeStyleTextTruncation,
+  pub text_truncation: TypeStyleTextTruncation,
   /// default: DISABLED
   /// When textTruncation: "ENDING" is set, maxLinesdetermines how many lines a text node can
   /// grow to before it truncates
-  max_lines: i32,
+  pub max_lines: i32,
   /// default: null
   /// Horizontal text alignment as string enum
   /// LEFT
   /// RIGHT
   /// CENTER
   /// JUSTIFIED
-  text_align_horizontal: TypeStyleTextAlignHorizontal,
+  pub text_align_horizontal: TypeStyleTextAlignHorizontal,
   /// Vertical text alignment as string enum
   /// TOP
   /// CENTER
   /// BOTTOM
-  text_align_vertical: TypeStyleTextAlignVertical,
+  pub text_align_vertical: TypeStyleTextAlignVertical,
   /// Space between characters in px
-  letter_spacing: i32,
+  pub letter_spacing: f32,
   /// Paints applied to characters
-  fills: Vec<Paint>,
+  pub fills: Vec<Paint>,
   /// Link to a URL or frame
-  hyperlink: HyperLink,
+  pub hyperlink: HyperLink,
   /// A map of OpenType feature flags to 1 or 0, 1 if it is enabled and 0 if it is disabled. Note
   /// that some flags aren't reflected here. For example, SMCP (small caps) is still represented
   /// by the textCase field.
-  opentype_flags: HashMap<String, i32>,
+  pub opentype_flags: HashMap<String, i32>,
   /// default: {}
   /// Line height in px
-  line_height_px: i32,
+  pub line_height_px: f32,
   /// Line height as a percentage of normal line height. This is deprecated; in a future version
   /// of the API only lineHeightPx and lineHeightPercentFontSize will be returned.
-  line_height_percent: i32,
+  pub line_height_percent: f32,
   /// default: 100
   /// Line height as a percentage of the font size. Only returned when lineHeightPercent is not
   /// 100.
-  line_height_percent_font_size: i32,
+  pub line_height_percent_font_size: f32,
   /// The unit of the line height value specified by the user.
   /// PIXELS
   /// FONT_SIZE_%
   /// INTRINSIC_%
-  line_height_unit: TypeStyleLineHeightUnit,
+  pub line_height_unit: TypeStyleLineHeightUnit,
   /// Whether or not there are overrides over a text style. The possible fields to override are
   /// semanticWeight, semanticItalic, hyperlink, and textDecoration. If this is true, then those
   /// fields are overrides if present.
-  is_override_over_text_style: bool,
+  pub is_override_over_text_style: bool,
   /// Indicates how the font weight was overridden when there is a text style override.
   /// BOLD
   /// NORMAL
-  semantic_weight: TypeStyleSemanticWeight,
+  pub semantic_weight: TypeStyleSemanticWeight,
   /// Indicates how the font style was overridden when there is a text style override.
   /// ITALIC
   /// NORMAL
-  semantic_italic: TypeStyleSemanticItalic,
+  pub semantic_italic: TypeStyleSemanticItalic,
 }
 
 /// Enum describing the text vertical align of TypeStyle
-#[derive(Debug, Serialize, Deserialize, TS)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS)]
 #[ts(export, export_to = "properties/typeStyle/")]
 pub enum TypeStyleTextAlignVertical {
   Top,
@@ -681,7 +701,7 @@ pub enum TypeStyleTextAlignVertical {
 }
 
 /// Enum describing the text horizontal align of TypeStyle
-#[derive(Debug, Serialize, Deserialize, TS)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS)]
 #[ts(export, export_to = "properties/typeStyle/")]
 pub enum TypeStyleTextAlignHorizontal {
   Left,
@@ -691,7 +711,7 @@ pub enum TypeStyleTextAlignHorizontal {
 }
 
 /// Enum describing the text truncation of TypeStyle
-#[derive(Debug, Serialize, Deserialize, TS)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS)]
 #[ts(export, export_to = "properties/typeStyle/")]
 pub enum TypeStyleTextTruncation {
   Disabled,
@@ -699,7 +719,7 @@ pub enum TypeStyleTextTruncation {
 }
 
 /// Enum describing the text auto resize of TypeStyle
-#[derive(Debug, Serialize, Deserialize, TS)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS)]
 #[ts(export, export_to = "properties/typeStyle/")]
 pub enum TypeStyleTextAutoResize {
   Height,
@@ -709,7 +729,7 @@ pub enum TypeStyleTextAutoResize {
 }
 
 /// Enum describing the text decoration of TypeStyle
-#[derive(Debug, Serialize, Deserialize, TS)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS)]
 #[ts(export, export_to = "properties/typeStyle/")]
 pub enum TypeStyleTextDecoration {
   StrikeThrought,
@@ -717,7 +737,7 @@ pub enum TypeStyleTextDecoration {
 }
 
 /// Enum describing the line heignt unit of TypeStyle
-#[derive(Debug, Serialize, Deserialize, TS)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS)]
 #[ts(export, export_to = "properties/typeStyle/")]
 pub enum TypeStyleLineHeightUnit {
   Pixels,
@@ -726,7 +746,7 @@ pub enum TypeStyleLineHeightUnit {
 }
 
 /// Enum describing the semantic weight of TypeStyle
-#[derive(Debug, Serialize, Deserialize, TS)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS)]
 #[ts(export, export_to = "properties/typeStyle/")]
 pub enum TypeStyleSemanticWeight {
   Bold,
@@ -734,7 +754,7 @@ pub enum TypeStyleSemanticWeight {
 }
 
 /// Enum describing the semantic italic of TypeStyle
-#[derive(Debug, Serialize, Deserialize, TS)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS)]
 #[ts(export, export_to = "properties/typeStyle/")]
 pub enum TypeStyleSemanticItalic {
   Italic,
@@ -742,7 +762,7 @@ pub enum TypeStyleSemanticItalic {
 }
 
 /// Enum describing the text case of TypeStyle
-#[derive(Debug, Serialize, Deserialize, TS)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS)]
 #[ts(export, export_to = "properties/typeStyle/")]
 pub enum TypeStyleTextCase {
   Upper,
@@ -753,65 +773,109 @@ pub enum TypeStyleTextCase {
 }
 
 /// A description of a main component. Helps you identify which component instances are attached to
-#[derive(Debug, Serialize, Deserialize, TS)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS)]
 #[serde(rename_all = "camelCase")]
 #[ts(export, export_to = "properties/component/")]
 pub struct Component {
   /// The key of the component
-  key: String,
+  pub key: String,
   /// The name of the component
-  name: String,
+  pub name: String,
   /// The description of the component as entered in the editor
-  description: String,
+  pub description: String,
   /// The ID of the component set if the component belongs to one
-  component_set_id: String,
+  pub component_set_id: String,
   /// The documentation links for this component.
-  documentation_links: Vec<DocumentationLink>,
+  pub documentation_links: Vec<DocumentationLink>,
   /// Whether this component is a remote component that doesn't live in this file
-  remote: bool,
+  pub remote: bool,
+  /// The properties instances of the component can set, by property name. Only present on
+  /// components which are not variants of a component set
+  #[serde(default)]
+  pub component_property_definitions: HashMap<String, ComponentPropertyDefinition>,
 }
 
 /// A description of a component set, which is a node containing a set of variants of a component
-#[derive(Debug, Serialize, Deserialize, TS)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS)]
 #[serde(rename_all = "camelCase")]
 #[ts(export, export_to = "properties/component/")]
 pub struct ComponentSet {
   /// The key of the component set
-  key: String,
+  pub key: String,
   /// The name of the component set
-  name: String,
+  pub name: String,
   /// The description of the component set as entered in the editor
-  description: String,
+  pub description: String,
   /// The documentation links for this component set.
-  documentation_links: Vec<DocumentationLink>,
+  pub documentation_links: Vec<DocumentationLink>,
   /// Whether this component set is a remote component set that doesn't live in this file
-  remote: bool,
+  pub remote: bool,
+  /// The properties instances of the variants of the set can set, by property name
+  #[serde(default)]
+  pub component_property_definitions: HashMap<String, ComponentPropertyDefinition>,
 }
 
 /// A set of properties that can be applied to nodes and published. Styles for a property can be
 /// created in the corresponding property's panel while editing a file
-#[derive(Debug, Serialize, Deserialize, TS)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS)]
 #[serde(rename_all = "camelCase")]
 #[ts(export, export_to = "properties/")]
 pub struct Style {
   /// The key of the style
-  key: String,
+  pub key: String,
   /// The name of the style
-  name: String,
+  pub name: String,
   /// The description of the style
-  description: String,
+  pub description: String,
   /// Whether this style is a remote style that doesn't live in this file
-  remote: bool,
+  pub remote: bool,
   /// The type of style as string enum
   /// FILL
   /// TEXT
   /// EFFECT
   /// GRID
-  style_type: StyleType,
+  pub style_type: StyleType,
+  /// The properties the style applies, absent for remote styles whose definition lives in
+  /// another file
+  #[serde(default)]
+  #[ts(optional)]
+  pub value: Option<StyleValue>,
+}
+
+impl Style {
+  /// Whether the style applies `value`, which has to match its type.
+  pub fn accepts(&self, value: &StyleValue) -> bool {
+    self.style_type == value.style_type()
+  }
+}
+
+/// The properties applied by a style, one variant per type of style
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS)]
+#[ts(export, export_to = "properties/")]
+pub enum StyleValue {
+  /// The paints of a fill style
+  Fill(Vec<Paint>),
+  /// The character formatting of a text style
+  Text(Box<TypeStyle>),
+  /// The effects of an effect style
+  Effect(Vec<Effect>),
+  /// The layout grids of a grid style
+  Grid(Vec<LayoutGrid>),
+}
+
+impl StyleValue {
+  pub fn style_type(&self) -> StyleType {
+    match self {
+      StyleValue::Fill(_) => StyleType::Fill,
+      StyleValue::Text(_) => StyleType::Text,
+      StyleValue::Effect(_) => StyleType::Effect,
+      StyleValue::Grid(_) => StyleType::Grid,
+    }
+  }
 }
 
 /// Enum describing the type of style
-#[derive(Debug, Serialize, Deserialize, TS, Hash, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize, TS)]
 #[ts(export, export_to = "properties/")]
 pub enum StyleType {
   Fill,
@@ -823,7 +887,7 @@ pub enum StyleType {
 /// Geometric shape type. Most shape types have the same name as their tooltip but there are a few
 /// exceptions. ENG_DATABASE: Cylinder, ENG_QUEUE: Horizontal cylinder, ENG_FILE: File, ENG_FOLDER:
 /// Folder.
-#[derive(Debug, Serialize, Deserialize, TS)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS)]
 #[ts(export, export_to = "properties/")]
 pub enum ShapeType {
   Square,
@@ -858,28 +922,28 @@ pub enum ShapeType {
 }
 
 /// Stores canvas location for a connector start/end point.
-#[derive(Debug, Serialize, Deserialize, TS)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS)]
 #[serde(rename_all = "camelCase")]
 #[ts(export, export_to = "properties/")]
 pub struct ConnectorEndpoint {
   /// ConnectorEndpoint with endpointNodeId and position only:
 
   /// Node ID this endpoint attaches to.
-  endpoint_node_id: String,
+  pub endpoint_node_id: String,
   /// Canvas location as x & y coordinate.
   /// ConnectorEndpoint with endpointNodeId and magnet only:
-  position: Vector,
+  pub position: Vector,
   /// The magnet type is a string enum
   /// AUTO
   /// TOP
   /// BOTTOM
   /// LEFT
   /// RIGHT
-  magnet: ConnectorMagnet,
+  pub magnet: ConnectorMagnet,
 }
 
 /// Enum describing magnet type
-#[derive(Debug, Serialize, Deserialize, TS)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS)]
 #[ts(export, export_to = "properties/")]
 pub enum ConnectorMagnet {
   Auto,
@@ -890,7 +954,7 @@ pub enum ConnectorMagnet {
 }
 
 /// Connector line type
-#[derive(Debug, Serialize, Deserialize, TS)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS)]
 #[ts(export, export_to = "properties/")]
 pub enum ConnectorLineType {
   Elbowed,
@@ -898,33 +962,34 @@ pub enum ConnectorLineType {
 }
 
 /// Connector text background.
-#[derive(Debug, Serialize, Deserialize, TS)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS)]
 #[serde(rename_all = "camelCase")]
 #[ts(export, export_to = "properties/")]
 pub struct ConnectorTextBackground {
   /// Radius of each corner of the rectangle if a single radius is set for all corners
-  corner_radius: f32,
+  pub corner_radius: f32,
   /// An array of fill paints applied to the node
-  fills: Vec<Paint>,
+  pub fills: Vec<Paint>,
 }
 
 /// Component property definition
-#[derive(Debug, Serialize, Deserialize, TS)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS)]
 #[serde(rename_all = "camelCase")]
 #[ts(export, export_to = "properties/component/")]
 pub struct ComponentPropertyDefinition {
   /// Type of this component property
-  component_type: ComponentPropertyType,
+  pub component_type: ComponentPropertyType,
   /// Initial value of this property for instances
-  default_value: ComponentPropertyDefinitionDefaultValue, // enum boolString, traits, generics ?
+  pub default_value: ComponentPropertyDefinitionDefaultValue, /* enum boolString, traits,
+                                                               * generics ? */
   /// All possible values for this property. Only exists on VARIANT properties
-  variant_options: Vec<String>,
+  pub variant_options: Vec<String>,
   /// List of user-defined preferred values for this property. Only exists on INSTANCE_SWAP
   /// properties
-  preferred_values: Vec<InstanceSwapPreferredValue>,
+  pub preferred_values: Vec<InstanceSwapPreferredValue>,
 }
 
-#[derive(Debug, Serialize, Deserialize, TS)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS)]
 #[ts(export, export_to = "properties/component/")]
 pub enum ComponentPropertyDefinitionDefaultValue {
   Bool(bool),
@@ -932,22 +997,22 @@ pub enum ComponentPropertyDefinitionDefaultValue {
 }
 
 /// Component property
-#[derive(Debug, Serialize, Deserialize, TS)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS)]
 #[serde(rename_all = "camelCase")]
 #[ts(export, export_to = "properties/component/")]
 pub struct ComponentProperty {
   /// Type of this component property
-  component_property_type: ComponentPropertyType,
+  pub component_property_type: ComponentPropertyType,
   /// Value of this property set on this instance
-  value: ComponentPropertyValue,
+  pub value: ComponentPropertyValue,
   /// List of user-defined preferred values for this property. Only exists on INSTANCE_SWAP
   /// properties
-  preferred_values: Vec<InstanceSwapPreferredValue>,
+  pub preferred_values: Vec<InstanceSwapPreferredValue>,
   /// A mapping of field to the VariableAlias of the bound variable.
-  bound_variables: HashMap<String, VariableAlias>,
+  pub bound_variables: HashMap<String, VariableAlias>,
 }
 
-#[derive(Debug, Serialize, Deserialize, TS)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS)]
 #[ts(export, export_to = "properties/component/")]
 pub enum ComponentPropertyValue {
   Bool(bool),
@@ -955,7 +1020,7 @@ pub enum ComponentPropertyValue {
 }
 
 /// Component property type
-#[derive(Debug, Serialize, Deserialize, TS)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS)]
 #[ts(export, export_to = "properties/component/")]
 pub enum ComponentPropertyType {
   Boolean,
@@ -965,17 +1030,17 @@ pub enum ComponentPropertyType {
 }
 
 /// Instance swap preferred value
-#[derive(Debug, Serialize, Deserialize, TS)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS)]
 #[ts(export, export_to = "properties/")]
 pub struct InstanceSwapPreferredValue {
   /// Type of node for this preferred value
-  istance_swap_preferred_value_type: InstanceSwapPreferredValueType,
+  pub istance_swap_preferred_value_type: InstanceSwapPreferredValueType,
   /// Key of this component or component set
-  key: String,
+  pub key: String,
 }
 
 /// Enum describing InstanceSwapPreferredValueType
-#[derive(Debug, Serialize, Deserialize, TS)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS)]
 #[ts(export, export_to = "properties/")]
 pub enum InstanceSwapPreferredValueType {
   Component,
@@ -983,19 +1048,18 @@ pub enum InstanceSwapPreferredValueType {
 }
 
 /// The device used to view a prototype
-#[derive(Debug, Serialize, Deserialize, TS)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS)]
 #[serde(rename_all = "camelCase")]
 #[ts(export, export_to = "properties/")]
 pub struct PrototypeDevice {
-  prototype_device_type: PrototypeDeviceType,
-  size: Size,
-  /// rotation'NONE' | 'CCW_90'
-  preset_identifier: String,
-  rotation: PrototypeDeviceRotation,
+  pub prototype_device_type: PrototypeDeviceType,
+  pub size: Size,
+  pub preset_identifier: String,
+  pub rotation: PrototypeDeviceRotation,
 }
 
-/// Enum desccribin PrototypeDeviceRotation
-#[derive(Debug, Serialize, Deserialize, TS)]
+/// Enum describing PrototypeDeviceRotation
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS)]
 #[ts(export, export_to = "properties/")]
 pub enum PrototypeDeviceRotation {
   None,
@@ -1003,7 +1067,7 @@ pub enum PrototypeDeviceRotation {
 }
 
 /// Enum describing PrototypeDeviceType
-#[derive(Debug, Serialize, Deserialize, TS)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS)]
 #[ts(export, export_to = "properties/")]
 pub enum PrototypeDeviceType {
   None,
@@ -1013,22 +1077,25 @@ pub enum PrototypeDeviceType {
 }
 
 /// A note and pinned properties left on a node in Dev Mode
-#[derive(Debug, Serialize, Deserialize, TS)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS)]
 #[ts(export, export_to = "properties/")]
 pub struct Annotation {
-  label: String,
-  properties: Vec<AnnotationProperty>,
+  #[serde(default)]
+  pub label: String,
+  #[serde(default)]
+  pub properties: Vec<AnnotationProperty>,
 }
 
 /// A pinned property in an Annotation
-#[derive(Debug, Serialize, Deserialize, TS)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS)]
 #[ts(export, export_to = "properties/")]
 pub struct AnnotationProperty {
-  annotation_property_type: AnnotationPropertyType,
+  #[serde(rename = "type")]
+  pub annotation_property_type: AnnotationPropertyType,
 }
 
 /// Enum describing AnnotationPropertyType
-#[derive(Debug, Serialize, Deserialize, TS)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS)]
 #[ts(export, export_to = "properties/")]
 pub enum AnnotationPropertyType {
   Width,
@@ -1059,38 +1126,39 @@ pub enum AnnotationPropertyType {
 }
 
 /// A pinned distance between two nodes in Dev Mode
-#[derive(Debug, Serialize, Deserialize, TS)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS)]
 #[serde(rename_all = "camelCase")]
 #[ts(export, export_to = "properties/measurement/")]
 pub struct Measurement {
-  id: String,
-  start: MeasurementStartEnd,
-  end: MeasurementStartEnd,
-  offset: MeasurementOffset,
+  pub id: String,
+  pub start: MeasurementStartEnd,
+  pub end: MeasurementStartEnd,
+  pub offset: MeasurementOffset,
   /// When manually overridden, the displayed value of the measurement
-  free_text: String,
+  #[serde(default)]
+  pub free_text: String,
 }
 
-/// Enum describing the offest of a Measurement
-#[derive(Debug, Serialize, Deserialize, TS)]
-#[serde(rename_all = "camelCase")]
+/// Where the line of a Measurement is drawn, tagged with its `type`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS)]
+#[serde(tag = "type")]
 #[ts(export, export_to = "properties/measurement/")]
 pub enum MeasurementOffset {
-  MeasurementOffsetInner(MeasurementOffsetInner),
-  MeasurementOffsetOuter(MeasurementOffsetOuter),
+  Inner(MeasurementOffsetInner),
+  Outer(MeasurementOffsetOuter),
 }
 
 /// The node and side a measurement is pinned to
-#[derive(Debug, Serialize, Deserialize, TS)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS)]
 #[serde(rename_all = "camelCase")]
 #[ts(export, export_to = "properties/measurement/")]
 pub struct MeasurementStartEnd {
-  node_id: String,
-  side: MeasurementStartEndSide,
+  pub node_id: String,
+  pub side: MeasurementStartEndSide,
 }
 
 /// Enum describing MeasurementStartEndSide
-#[derive(Debug, Serialize, Deserialize, TS)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS)]
 #[ts(export, export_to = "properties/measurement/")]
 pub enum MeasurementStartEndSide {
   Top,
@@ -1100,39 +1168,39 @@ pub enum MeasurementStartEndSide {
 }
 
 /// Measurement offset relative to the inside of the start node
-#[derive(Debug, Serialize, Deserialize, TS)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS)]
 #[serde(rename_all = "camelCase")]
 #[ts(export, export_to = "properties/measurement/")]
 pub struct MeasurementOffsetInner {
-  measurement_offset_inner_type: String, // always 'INNER'
-  relative: i32,
+  /// Position of the line along the measured sides, from -1 at their start to 1 at their end
+  pub relative: f32,
 }
 
-/// Measurement offset relative to the outside of the start nod
-#[derive(Debug, Serialize, Deserialize, TS)]
+/// Measurement offset relative to the outside of the start node
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS)]
 #[serde(rename_all = "camelCase")]
 #[ts(export, export_to = "properties/measurement/")]
 pub struct MeasurementOffsetOuter {
-  measurement_offset_outer_type: String, // always 'OUTER'
-  relative: i32,
+  /// Distance of the line from the measured sides, in pixels
+  pub fixed: f32,
 }
 
 /// Individual stroke weights
-#[derive(Debug, Serialize, Deserialize, TS)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS)]
 #[ts(export, export_to = "properties/stroke/")]
 pub struct StrokeWeights {
   /// The top stroke weight
-  top: i32,
+  pub top: f32,
   /// The right stroke weight
-  right: i32,
+  pub right: f32,
   /// The bottom stroke weight
-  bottom: i32,
+  pub bottom: f32,
   /// The left stroke weight
-  left: i32,
+  pub left: f32,
 }
 
 /// Position of stroke relative to vector outline, as a string enum
-#[derive(Debug, Serialize, Deserialize, TS)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS)]
 #[ts(export, export_to = "properties/stroke/")]
 pub enum StrokeAlign {
   /// Stroke drawn inside the shape boundary
@@ -1144,7 +1212,7 @@ pub enum StrokeAlign {
 }
 
 /// A string enum with values describing the end caps of vector paths.
-#[derive(Debug, Serialize, Deserialize, TS)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS)]
 #[ts(export, export_to = "properties/stroke/")]
 pub enum StrokeCap {
   None,
@@ -1164,50 +1232,52 @@ pub enum StrokeCap {
 }
 
 /// A string enum describing how corners in vector paths are rendered.
-#[derive(Debug, Serialize, Deserialize, TS)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS)]
 #[ts(export, export_to = "properties/stroke/")]
 pub enum StrokeJoin {
-  Mitter,
+  // Earlier versions wrote the misspelled name.
+  #[serde(alias = "Mitter")]
+  Miter,
   Bevel,
   Round,
 }
 
 /// Fields directly overridden on an instance. Inherited overrides are not included.
-#[derive(Debug, Serialize, Deserialize, TS)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS)]
 #[serde(rename_all = "camelCase")]
 #[ts(export, export_to = "properties/")]
 pub struct Overrides {
   /// A unique ID for a node
-  id: String,
+  pub id: String,
   /// An array of properties
-  overridden_fields: Vec<String>,
+  pub overridden_fields: Vec<String>,
 }
 
 /// Contains a variable alias.
-#[derive(Debug, Serialize, Deserialize, TS)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS)]
 #[serde(rename_all = "camelCase")]
 #[ts(export, export_to = "properties/")]
 pub struct VariableAlias {
-  variable_alias_type: String, // always 'VARIABLE_ALIAS'
+  pub variable_alias_type: String, // always 'VARIABLE_ALIAS'
   /// The id of the variable that the current variable is aliased to. This variable can be a
   /// local or remote variable, and both can be retrieved via the GET
   /// /v1/files/:file_key/variables/local endpoint.
-  id: String,
+  pub id: String,
 }
 
 /// Represents a handoff (or dev) status applied to a node.
-#[derive(Debug, Serialize, Deserialize, TS)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS)]
 #[serde(rename_all = "camelCase")]
 #[ts(export, export_to = "properties/")]
 pub struct DevStatus {
-  dev_status_type: DevStatusType,
+  pub dev_status_type: DevStatusType,
   /// An optional field where the designer can add more information about the design and what has
   /// changed.
-  description: String,
+  pub description: String,
 }
 
 /// Enum describing the type of DevStatus
-#[derive(Debug, Serialize, Deserialize, TS)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS)]
 #[ts(export, export_to = "properties/")]
 pub enum DevStatusType {
   ReadyForDev,
@@ -1215,14 +1285,14 @@ pub enum DevStatusType {
 }
 
 /// An interaction in the Figma viewer, containing a trigger and one or more actions.
-#[derive(Debug, Serialize, Deserialize, TS)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS)]
 #[ts(export, export_to = "properties/interaction/")]
 pub struct Interaction {
   /// The user event that initiates the interaction.
   #[ts(optional)]
-  trigger: Option<Trigger>,
+  pub trigger: Option<Trigger>,
   /// The actions that are performed when the trigger is activated.
-  actions: Vec<Action>,
+  pub actions: Vec<Action>,
 }
 
 /// A prototyping Trigger describes the user input needed to cause an interaction to happen.
@@ -1232,31 +1302,31 @@ pub struct Interaction {
 /// duration of time before the action occurs. Both timeout and delay values are in milliseconds.
 /// The "ON_MEDIA_HIT" and "ON_MEDIA_END" trigger types can only trigger from a video. They fire
 /// when a video reaches a certain time or ends. The timestamp value is in seconds.'
-#[derive(Debug, Serialize, Deserialize, TS)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS)]
 #[serde(rename_all = "camelCase")]
 #[ts(export, export_to = "properties/interaction/")]
 pub struct Trigger {
-  trigger_type: TriggerType,
+  pub trigger_type: TriggerType,
 
   /// For "AFTER_TIMEOUT" events:
-  timeout: i32,
+  pub timeout: i32,
 
   /// For "MOUSE_ENTER", "MOUSE_LEAVE", "MOUSE_UP", and "MOUSE_DOWN" events:
-  delay: i32,
+  pub delay: i32,
   /// Whether this is a deprecated version of the trigger that was left unchanged for backwards
   /// compatibility. If not present, the trigger is the latest version.
-  deprecated_version: bool,
+  pub deprecated_version: bool,
 
   /// For "ON_KEY_DOWN" events:
-  device: TriggerDevice,
-  key_codes: Vec<i32>,
+  pub device: TriggerDevice,
+  pub key_codes: Vec<i32>,
 
   /// For "ON_MEDIA_HIT" events:
-  media_hit_time: i32,
+  pub media_hit_time: i32,
 }
 
 /// Enum describing the device of a trigger
-#[derive(Debug, Serialize, Deserialize, TS)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS)]
 #[serde(rename_all = "camelCase")]
 #[ts(export, export_to = "properties/interaction/")]
 pub enum TriggerDevice {
@@ -1268,7 +1338,7 @@ pub enum TriggerDevice {
 }
 
 /// Enum describing the type of a trigger,
-#[derive(Debug, Serialize, Deserialize, TS)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS)]
 #[ts(export, export_to = "properties/interaction/")]
 pub enum TriggerType {
   OnClick,
@@ -1286,56 +1356,92 @@ pub enum TriggerType {
   OnMediaHit,
 }
 
-/// An action that is performed when a trigger is activated.
-#[derive(Debug, Serialize, Deserialize, TS)]
-#[ts(export, export_to = "properties/interaction/")]
+/// An action that is performed when a trigger is activated. Actions are tagged with their
+/// [`ActionType`] in a `type` field next to their content.
+#[derive(Debug, Clone, PartialEq, TS)]
+#[ts(export, export_to = "properties/interaction/", tag = "type")]
 pub enum Action {
   /// An action can be one of the following types:
+  #[ts(rename = "Back")]
   BackAction,
+  #[ts(rename = "Close")]
   CloseAction,
+  #[ts(rename = "Url")]
   OpenURLAction(OpenURLAction),
+  #[ts(rename = "UpdateMediaRuntime")]
   UpdateMediaRuntimeAction(UpdateMediaRuntimeAction),
+  #[ts(rename = "SetVariable")]
   SetVariableAction(SetVariableAction),
+  #[ts(rename = "SetVariableMode")]
   SetVariableModeAction(SetVariableModeAction),
+  #[ts(rename = "Conditional")]
   ConditionalAction(ConditionalAction),
+  #[ts(rename = "Node")]
   NodeAction(NodeAction),
 }
 
+impl Action {
+  pub fn action_type(&self) -> ActionType {
+    match self {
+      Action::BackAction => ActionType::Back,
+      Action::CloseAction => ActionType::Close,
+      Action::OpenURLAction(_) => ActionType::Url,
+      Action::UpdateMediaRuntimeAction(_) => ActionType::UpdateMediaRuntime,
+      Action::SetVariableAction(_) => ActionType::SetVariable,
+      Action::SetVariableModeAction(_) => ActionType::SetVariableMode,
+      Action::ConditionalAction(_) => ActionType::Conditional,
+      Action::NodeAction(_) => ActionType::Node,
+    }
+  }
+}
+
+/// Enum describing the type of an action
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[ts(export, export_to = "properties/interaction/")]
+pub enum ActionType {
+  Back,
+  Close,
+  Url,
+  UpdateMediaRuntime,
+  SetVariable,
+  SetVariableMode,
+  Conditional,
+  Node,
+}
+
 /// An action that opens a URL.
-#[derive(Debug, Serialize, Deserialize, TS)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS)]
 #[serde(rename_all = "camelCase")]
 #[ts(export, export_to = "properties/interaction/")]
 pub struct OpenURLAction {
-  open_url_action_type: String, // always 'URL'
-  url: String,
+  pub url: String,
 }
 
 /// An action that affects a video node in the Figma viewer. For example, to play, pause, or skip.
-#[derive(Debug, Serialize, Deserialize, TS)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS)]
 #[serde(rename_all = "camelCase")]
 #[ts(export, export_to = "properties/interaction/")]
 pub struct UpdateMediaRuntimeAction {
-  update_media_runtime_type: String, // always 'UPDATE_MEDIA_RUNTIME'
   /// The node ID of the media node to update. If destinationId is null, the action will update
   /// the media node that contains the action.
   #[ts(optional)]
-  destination_id: Option<String>,
+  pub destination_id: Option<String>,
   /// The action to perform on the media node.
-  media_action: UpdateMediaRuntimeActionMediaAction,
+  pub media_action: UpdateMediaRuntimeActionMediaAction,
 
   /// For "SKIP_FORWARD" and "SKIP_BACKWARDS" actions:
 
   /// The amount of time to skip in seconds.
-  amount_to_skip: i32,
+  pub amount_to_skip: i32,
 
   /// For SKIP_TO actions:
 
   /// The new time to skip to in seconds.
-  new_timestamp: i32,
+  pub new_timestamp: i32,
 }
 
 /// Enum describing the media action of UpdateMediaRuntimeAction
-#[derive(Debug, Serialize, Deserialize, TS)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS)]
 #[ts(export, export_to = "properties/interaction/")]
 pub enum UpdateMediaRuntimeActionMediaAction {
   Play,
@@ -1350,38 +1456,39 @@ pub enum UpdateMediaRuntimeActionMediaAction {
 }
 
 /// An action that navigates to a specific node in the Figma viewer.
-#[derive(Debug, Serialize, Deserialize, TS)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS)]
 #[serde(rename_all = "camelCase")]
 #[ts(export, export_to = "properties/interaction/")]
 pub struct NodeAction {
-  node_action_type: String, // alwasy 'NODE'
   #[ts(optional)]
-  destination_id: Option<String>,
-  navigation: Navigation,
-  transition: NodeActionTransition,
+  pub destination_id: Option<String>,
+  pub navigation: Navigation,
+  pub transition: NodeActionTransition,
   /// Whether the scroll offsets of any scrollable elements in the current screen or overlay are
   /// preserved when navigating to the destination. This is applicable only if the layout of both
   /// the current frame and its destination are the same.
-  preserve_scroll_position: bool,
+  pub preserve_scroll_position: bool,
   /// Applicable only when navigation is "OVERLAY" and the destination is a frame with
   /// overlayPosition equal to "MANUAL". This value represents the offset by which the overlay is
   /// opened relative to this node.
-  overlay_relative_position: Vector,
+  pub overlay_relative_position: Vector,
   /// When true, all videos within the destination frame will reset their memorized playback
   /// position to 00:00 before starting to play.
-  reset_video_position: bool,
+  pub reset_video_position: bool,
   /// Whether the scroll offsets of any scrollable elements in the current screen or overlay
   /// reset when navigating to the destination. This is applicable only if the layout of both the
   /// current frame and its destination are the same.
-  reset_scroll_position: bool,
+  pub reset_scroll_position: bool,
   /// Whether the state of any interactive components in the current screen or overlay reset when
   /// navigating to the destination. This is applicable if there are interactive components in
   /// the destination frame.
-  reset_interactive_components: bool,
+  pub reset_interactive_components: bool,
 }
 
-/// Enum describing the transition of a node action
-#[derive(Debug, Serialize, Deserialize, TS)]
+/// Enum describing the transition of a node action, written as the transition itself or `null`
+/// for none.
+#[derive(Debug, Clone, PartialEq, Serialize, TS)]
+#[serde(untagged)]
 #[ts(export, export_to = "properties/transition/")]
 pub enum NodeActionTransition {
   SimpleTransition(SimpleTransition),
@@ -1397,15 +1504,15 @@ pub enum NodeActionTransition {
 ///   navigation history.
 /// - "SCROLL_TO": Scrolls to the destination on the current screen.
 /// - "CHANGE_TO": Changes the closest ancestor instance of source node to the specified variant.'
-#[derive(Debug, Serialize, Deserialize, TS)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS)]
 #[serde(rename_all = "camelCase")]
 #[ts(export, export_to = "properties/")]
 pub struct Navigation {
-  navigation_type: NavigationType,
+  pub navigation_type: NavigationType,
 }
 
 /// Enum describing the type of an animation
-#[derive(Debug, Serialize, Deserialize, TS)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS)]
 #[ts(export, export_to = "properties/")]
 pub enum NavigationType {
   Navigate,
@@ -1416,19 +1523,19 @@ pub enum NavigationType {
 }
 
 /// Describes an animation used when navigating in a prototype.
-#[derive(Debug, Serialize, Deserialize, TS)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS)]
 #[serde(rename_all = "camelCase")]
 #[ts(export, export_to = "properties/transition/")]
 pub struct SimpleTransition {
-  simple_transition_type: SimpleTransitionType,
+  pub simple_transition_type: SimpleTransitionType,
   /// The duration of the transition in milliseconds.
-  duration: i32,
+  pub duration: i32,
   /// The easing curve of the transition.
-  easing: Easing,
+  pub easing: Easing,
 }
 
 /// Enum describing the type of a simple transition
-#[derive(Debug, Serialize, Deserialize, TS)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS)]
 #[ts(export, export_to = "properties/transition/")]
 pub enum SimpleTransitionType {
   Dissolve,
@@ -1437,24 +1544,24 @@ pub enum SimpleTransitionType {
 }
 
 /// Describes an animation used when navigating in a prototype.
-#[derive(Debug, Serialize, Deserialize, TS)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS)]
 #[serde(rename_all = "camelCase")]
 #[ts(export, export_to = "properties/transition/")]
 pub struct DirectionalTransition {
-  directional_transition_type: DirectionalTransitionType,
-  direction: DirectionalTransitionDirection,
+  pub directional_transition_type: DirectionalTransitionType,
+  pub direction: DirectionalTransitionDirection,
   /// The duration of the transition in milliseconds.
-  duration: i32,
+  pub duration: i32,
   /// The easing curve of the transition.
-  easing: Easing,
+  pub easing: Easing,
   /// When the transition type is "SMART_ANIMATE" or when matchLayers is true, then the
   /// transition will be performed using smart animate, which attempts to match corresponding
   /// layers and interpolate other properties during the animation.
-  match_layers: bool,
+  pub match_layers: bool,
 }
 
 /// Enum describing the type of a DirectionalTransition
-#[derive(Debug, Serialize, Deserialize, TS)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS)]
 #[ts(export, export_to = "properties/transition/")]
 pub enum DirectionalTransitionType {
   MoveIn,
@@ -1465,7 +1572,7 @@ pub enum DirectionalTransitionType {
 }
 
 /// Enum describing the directions of DirectionalTransition
-#[derive(Debug, Serialize, Deserialize, TS)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS)]
 #[ts(export, export_to = "properties/transition/")]
 pub enum DirectionalTransitionDirection {
   Left,
@@ -1475,90 +1582,89 @@ pub enum DirectionalTransitionDirection {
 }
 
 /// Describes an easing curve.
-#[derive(Debug, Serialize, Deserialize, TS)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS)]
 #[serde(rename_all = "camelCase")]
 #[ts(export, export_to = "properties/easing/")]
 pub struct Easing {
   /// The type of easing curve.
-  easing_type: EasingType,
+  pub easing_type: EasingType,
   /// A cubic bezier curve that defines the easing.
-  easing_function_cubic_bezier: EasingFunctionCubicBezier,
+  pub easing_function_cubic_bezier: EasingFunctionCubicBezier,
   /// A spring function that defines the easing.
-  easing_function_spring: EasingFunctionSpring,
+  pub easing_function_spring: EasingFunctionSpring,
 }
 
 /// A cubic bezier curve that defines the easing.
-#[derive(Debug, Serialize, Deserialize, TS)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS)]
 #[ts(export, export_to = "properties/easing/")]
 pub struct EasingFunctionCubicBezier {
   /// The x component of the first control point.
-  x1: i32,
+  pub x1: f32,
   /// The y component of the first control point.
-  y1: i32,
+  pub y1: f32,
   /// The x component of the second control point.
-  x2: i32,
+  pub x2: f32,
   /// The y component of the second control point.
-  y2: i32,
+  pub y2: f32,
 }
 
 /// A spring function that defines the easing.
-#[derive(Debug, Serialize, Deserialize, TS)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS)]
 #[ts(export, export_to = "properties/easing/")]
 pub struct EasingFunctionSpring {
-  mass: i32,
-  stiffness: i32,
-  damping: i32,
+  pub mass: f32,
+  pub stiffness: f32,
+  pub damping: f32,
 }
 
 /// An action that sets a variable to a specific value.
-#[derive(Debug, Serialize, Deserialize, TS)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS)]
 #[serde(rename_all = "camelCase")]
 #[ts(export, export_to = "properties/")]
 pub struct SetVariableAction {
-  set_variable_action_type: String, // always 'SET_VARIABLE'
   #[ts(optional)]
-  variable_id: Option<String>,
-  variable_value: VariableData,
+  pub variable_id: Option<String>,
+  pub variable_value: VariableData,
 }
 
 /// An action that sets a variable to a specific mode.
-#[derive(Debug, Serialize, Deserialize, TS)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS)]
 #[serde(rename_all = "camelCase")]
 #[ts(export, export_to = "properties/")]
 pub struct SetVariableModeAction {
-  set_variable_mode_action_type: String, // always 'SET_VARIABLE_MODE'
   #[ts(optional)]
-  variable_collection_id: Option<String>,
+  pub variable_collection_id: Option<String>,
   #[ts(optional)]
-  variable_mode_id: Option<String>,
+  pub variable_mode_id: Option<String>,
 }
 
 /// An action that checks if a condition is met before performing certain actions by using an
 /// if/else conditional statement.
-#[derive(Debug, Serialize, Deserialize, TS)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS)]
 #[serde(rename_all = "camelCase")]
 #[ts(export, export_to = "properties/")]
 pub struct ConditionalAction {
-  conditional_action_type: String, // always 'CONDITIONAL'
-  conditional_blocks: Vec<ConditionalBlock>,
+  pub conditional_blocks: Vec<ConditionalBlock>,
 }
 
 /// A value to set a variable to during prototyping.
-#[derive(Debug, Serialize, Deserialize, TS)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS)]
 #[serde(rename_all = "camelCase")]
 #[ts(export, export_to = "properties/")]
 pub struct VariableData {
-  variable_data_type: VariableDataType,
-  resolved_type: VariableResolvedDataType,
-  value: VariableDataValue,
+  pub variable_data_type: VariableDataType,
+  pub resolved_type: VariableResolvedDataType,
+  pub value: VariableDataValue,
 }
 
-/// Enum decribing the value of a variable data
-#[derive(Debug, Serialize, Deserialize, TS)]
+/// Enum decribing the value of a variable data, written as the value itself. Colors with an alpha
+/// channel are read as [`Color`], the others as [`RGBColor`].
+#[derive(Debug, Clone, PartialEq, Serialize, TS)]
+#[serde(untagged)]
 #[ts(export, export_to = "properties/")]
 pub enum VariableDataValue {
-  Bool(bool),
-  I32(i32),
+  Boolean(bool),
+  Float(f32),
   String(String),
   Color(Color),
   RGBColor(RGBColor),
@@ -1567,7 +1673,7 @@ pub enum VariableDataValue {
 }
 
 /// Defines the types of data a VariableData object can hold.
-#[derive(Debug, Serialize, Deserialize, TS)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS)]
 #[serde(rename_all = "camelCase")]
 #[ts(export, export_to = "properties/")]
 pub enum VariableDataType {
@@ -1580,7 +1686,7 @@ pub enum VariableDataType {
 }
 
 /// Defines the types of data a VariableData object can eventually equal.
-#[derive(Debug, Serialize, Deserialize, TS)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS)]
 #[ts(export, export_to = "properties/")]
 pub enum VariableResolvedDataType {
   Boolean,
@@ -1589,18 +1695,81 @@ pub enum VariableResolvedDataType {
   Color,
 }
 
+/// A variable defined in a document, with a value for each mode of its collection.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export, export_to = "properties/variable/")]
+pub struct Variable {
+  /// The unique identifier of this variable
+  pub id: String,
+  /// The name of this variable
+  pub name: String,
+  /// The key of this variable
+  pub key: String,
+  /// The id of the variable collection that contains this variable
+  pub variable_collection_id: String,
+  /// The resolved type of the variable
+  pub resolved_type: VariableResolvedDataType,
+  /// The values for each mode of this variable, by mode id
+  pub values_by_mode: HashMap<String, VariableDataValue>,
+  /// Whether this variable is remote
+  pub remote: bool,
+  /// The description of this variable
+  pub description: String,
+  /// Whether this variable is hidden when publishing the current file as a library
+  pub hidden_from_publishing: bool,
+  /// An array of scopes in the UI where this variable is shown
+  pub scopes: Vec<String>,
+  /// Code syntax definitions for this variable, by platform (WEB, ANDROID or iOS)
+  pub code_syntax: HashMap<String, String>,
+}
+
+/// A grouping of related variables that share the same modes.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export, export_to = "properties/variable/")]
+pub struct VariableCollection {
+  /// The unique identifier of this variable collection
+  pub id: String,
+  /// The name of this variable collection
+  pub name: String,
+  /// The key of this variable collection
+  pub key: String,
+  /// The modes of this variable collection
+  pub modes: Vec<VariableMode>,
+  /// The id of the default mode
+  pub default_mode_id: String,
+  /// Whether this variable collection is remote
+  pub remote: bool,
+  /// Whether this variable collection is hidden when publishing the current file as a library
+  pub hidden_from_publishing: bool,
+  /// The ids of the variables in the collection, in the order they are displayed
+  pub variable_ids: Vec<String>,
+}
+
+/// A mode of a variable collection
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export, export_to = "properties/variable/")]
+pub struct VariableMode {
+  /// The unique identifier of this mode
+  pub mode_id: String,
+  /// The name of this mode
+  pub name: String,
+}
+
 /// Defines the Expression object, which contains a list of VariableData objects strung together by
 /// operators (VariableData).
-#[derive(Debug, Serialize, Deserialize, TS)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS)]
 #[serde(rename_all = "camelCase")]
 #[ts(export, export_to = "properties/")]
 pub struct Expression {
-  expression_function: ExpressionFunction,
-  expression_arguments: Vec<VariableData>,
+  pub expression_function: ExpressionFunction,
+  pub expression_arguments: Vec<VariableData>,
 }
 
 /// Defines the list of operators available to use in an Expression.
-#[derive(Debug, Serialize, Deserialize, TS)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS)]
 #[ts(export, export_to = "properties/")]
 pub enum ExpressionFunction {
   Addition,
@@ -1622,10 +1791,10 @@ pub enum ExpressionFunction {
 /// Either the if or else conditional blocks. The if block contains a condition to check. If that
 /// condition is met then it will run those list of actions, else it will run the actions in the
 /// else block.
-#[derive(Debug, Serialize, Deserialize, TS)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS)]
 #[serde(rename_all = "camelCase")]
 #[ts(export, export_to = "properties/")]
 pub struct ConditionalBlock {
-  condition: VariableData,
-  actions: Vec<Action>,
+  pub condition: VariableData,
+  pub actions: Vec<Action>,
 }
@@ -0,0 +1,703 @@
+use std::collections::HashMap;
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+use crate::node::vector::{EllipseData, EmptyData, RectangleData, VectorNode};
+use crate::node::{CanvasNode, DocumentNode, Node, NodeType};
+use crate::patch::{PathSegment, PropertyPath};
+use crate::properties::{
+  ArcData, Color, ColorStop, Effect, ImageFilters, Measurement, MeasurementOffset, Paint,
+  PaintOverride, PaintType, RGBColor, Style, StyleValue, Transform, Variable, VariableDataValue,
+  Vector,
+};
+
+/// Gradient handles used to complete paints missing some: a gradient going left to right across
+/// the whole node.
+const DEFAULT_GRADIENT_HANDLES: [(f32, f32); 3] = [(0.0, 0.5), (1.0, 0.5), (0.0, 1.0)];
+
+/// The identity matrix, used to complete transforms missing some rows or columns.
+const IDENTITY: [[f32; 3]; 2] = [[1.0, 0.0, 0.0], [0.0, 1.0, 0.0]];
+
+/// A value breaking a constraint its type cannot express.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Violation {
+  /// Ids of the nodes from the root down to the node holding the value, empty for values outside
+  /// of a node tree.
+  pub path: Vec<String>,
+  /// Path of the value in the serialized properties of the node, e.g. `fills[0].color.r`.
+  pub property: String,
+  pub message: String,
+}
+
+impl fmt::Display for Violation {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    if !self.path.is_empty() {
+      write!(f, "{} ", self.path.join(" > "))?;
+    }
+    write!(f, "`{}`: {}", self.property, self.message)
+  }
+}
+
+/// Values with invariants beyond what their types enforce.
+pub trait Validate {
+  /// Checks the invariants of the value and its fields, repairing what the checker asks for.
+  fn check(&mut self, checker: &mut Checker);
+
+  /// The invariants broken by the value.
+  fn validate(&self) -> Vec<Violation>
+  where
+    Self: Clone,
+  {
+    // Checks are written once for both modes, and take the value mutably for repairs.
+    let mut copy = self.clone();
+    let mut checker = Checker::new(false);
+    copy.check(&mut checker);
+    checker.violations
+  }
+
+  /// Clamps out of range values, truncates collections which are too long and completes those
+  /// which are too short. Returns the invariants which were broken before the repair.
+  fn repair(&mut self) -> Vec<Violation> {
+    let mut checker = Checker::new(true);
+    self.check(&mut checker);
+    checker.violations
+  }
+}
+
+/// Walks values to collect the invariants they break, and repairs them if asked to.
+pub struct Checker {
+  repair: bool,
+  path: Vec<String>,
+  property: Vec<PathSegment>,
+  violations: Vec<Violation>,
+}
+
+impl Checker {
+  pub fn new(repair: bool) -> Self {
+    Self {
+      repair,
+      path: Vec::new(),
+      property: Vec::new(),
+      violations: Vec::new(),
+    }
+  }
+
+  pub fn into_violations(self) -> Vec<Violation> {
+    self.violations
+  }
+
+  /// Checks a field of the current value.
+  pub fn field<T: Validate + ?Sized>(&mut self, name: impl Into<PathSegment>, value: &mut T) {
+    self.property.push(name.into());
+    value.check(self);
+    self.property.pop();
+  }
+
+  /// Checks the elements of an array field of the current value.
+  pub fn elements<T: Validate>(&mut self, name: impl Into<PathSegment>, values: &mut [T]) {
+    self.property.push(name.into());
+    for (index, value) in values.iter_mut().enumerate() {
+      self.field(index, value);
+    }
+    self.property.pop();
+  }
+
+  /// Checks a value reported under its own path rather than as a property of the current one,
+  /// e.g. a node under its id.
+  pub fn scope<T: Validate + ?Sized>(&mut self, path: &[&str], value: &mut T) {
+    let property = std::mem::take(&mut self.property);
+    let len = self.path.len();
+    self.path.extend(path.iter().map(|id| id.to_string()));
+    value.check(self);
+    self.path.truncate(len);
+    self.property = property;
+  }
+
+  /// Checks a tree of nodes without taking it mutably, each node under the ids of its ancestors.
+  /// Nodes are checked on copies of their own properties rather than on a copy of the whole tree,
+  /// so repairs asked for by the checker are not applied.
+  pub fn tree(&mut self, node: &Node) {
+    self.scope(&[&node.id], &mut without_children(&node.node));
+    self.path.push(node.id.clone());
+    for child in node.children() {
+      self.tree(child);
+    }
+    self.path.pop();
+  }
+
+  /// Reports a broken invariant of a field of the current value.
+  pub fn report(&mut self, name: impl Into<PathSegment>, message: String) {
+    self.property.push(name.into());
+    let property = PropertyPath::from_segments(self.property.clone()).to_string();
+    self.property.pop();
+    self.violations.push(Violation {
+      path: self.path.clone(),
+      property,
+      message,
+    });
+  }
+
+  /// Checks that a number field is within an inclusive range, clamping it when repairing.
+  pub fn range<T>(&mut self, name: impl Into<PathSegment>, value: &mut T, min: T, max: T)
+  where
+    T: PartialOrd + Copy + fmt::Display,
+  {
+    if min <= *value && *value <= max {
+      return;
+    }
+    self.report(
+      name,
+      format!("must be between {min} and {max}, got {value}"),
+    );
+    if self.repair {
+      // Values which compare to nothing (NaN) go to the minimum.
+      *value = match *value > max {
+        true => max,
+        false => min,
+      };
+    }
+  }
+
+  /// Checks that an array field has at most `max` elements, dropping the extra ones when
+  /// repairing.
+  pub fn at_most<T>(&mut self, name: impl Into<PathSegment>, values: &mut Vec<T>, max: usize) {
+    if values.len() <= max {
+      return;
+    }
+    let message = format!("must have at most {max} element(s), got {}", values.len());
+    self.report(name, message);
+    if self.repair {
+      values.truncate(max);
+    }
+  }
+
+  /// Checks that an array field has exactly as many elements as `defaults`, dropping the extra
+  /// ones or completing it with the missing defaults when repairing.
+  pub fn exactly<T: Clone>(
+    &mut self,
+    name: impl Into<PathSegment>,
+    values: &mut Vec<T>,
+    defaults: &[T],
+  ) {
+    if values.len() == defaults.len() {
+      return;
+    }
+    let message = format!(
+      "must have exactly {} element(s), got {}",
+      defaults.len(),
+      values.len()
+    );
+    self.report(name, message);
+    if self.repair {
+      values.truncate(defaults.len());
+      let missing = defaults[values.len()..].to_vec();
+      values.extend(missing);
+    }
+  }
+}
+
+impl<T: Validate> Validate for Option<T> {
+  fn check(&mut self, checker: &mut Checker) {
+    if let Some(value) = self {
+      value.check(checker);
+    }
+  }
+}
+
+/// A copy of the properties of a node, without its children.
+fn without_children(node: &NodeType) -> NodeType {
+  match node {
+    NodeType::Document(_) => NodeType::Document(DocumentNode {
+      children: Vec::new(),
+    }),
+    NodeType::Canvas(canvas) => NodeType::Canvas(CanvasNode {
+      children: Vec::new(),
+      background_color: canvas.background_color.clone(),
+      flow_starting_points: canvas.flow_starting_points.clone(),
+      prototype_device: canvas.prototype_device.clone(),
+      measurements: canvas.measurements.clone(),
+    }),
+    NodeType::Vector(_) | NodeType::Rectangle(_) => node.clone(),
+  }
+}
+
+impl Validate for Node {
+  fn check(&mut self, checker: &mut Checker) {
+    checker.scope(&[&self.id], &mut self.node);
+  }
+}
+
+impl Validate for NodeType {
+  fn check(&mut self, checker: &mut Checker) {
+    match self {
+      NodeType::Document(document) => document.check(checker),
+      NodeType::Canvas(canvas) => canvas.check(checker),
+      NodeType::Vector(vector) => vector.check(checker),
+      NodeType::Rectangle(vector) => vector.check(checker),
+    }
+  }
+}
+
+impl Validate for DocumentNode {
+  fn check(&mut self, checker: &mut Checker) {
+    for child in &mut self.children {
+      child.check(checker);
+    }
+  }
+}
+
+impl Validate for CanvasNode {
+  fn check(&mut self, checker: &mut Checker) {
+    checker.field("backgroundColor", &mut self.background_color);
+    checker.elements("measurements", &mut self.measurements);
+    for child in &mut self.children {
+      child.check(checker);
+    }
+  }
+}
+
+impl<Data: Validate> Validate for VectorNode<Data> {
+  fn check(&mut self, checker: &mut Checker) {
+    checker.range("opacity", &mut self.opacity, 0.0, 1.0);
+    checker.elements("effects", &mut self.effects);
+    checker.field("relativeTransform", &mut self.relative_transform);
+    checker.elements("fills", &mut self.fills);
+    checker.field("fillOverrideTable", &mut self.fill_override_table);
+    checker.elements("strokes", &mut self.strokes);
+    checker.at_most("annotations", &mut self.annotations, 1);
+    checker.field("additionalData", &mut self.additional_data);
+  }
+}
+
+impl Validate for EmptyData {
+  fn check(&mut self, _checker: &mut Checker) {}
+}
+
+impl Validate for RectangleData {
+  fn check(&mut self, checker: &mut Checker) {
+    checker.range("cornerSmoothing", &mut self.corner_smoothing, 0.0, 1.0);
+  }
+}
+
+impl Validate for EllipseData {
+  fn check(&mut self, checker: &mut Checker) {
+    checker.field("arcData", &mut self.arc_data);
+  }
+}
+
+impl Validate for ArcData {
+  fn check(&mut self, checker: &mut Checker) {
+    checker.range("innerRadius", &mut self.inner_radius, 0.0, 1.0);
+  }
+}
+
+impl Validate for Color {
+  fn check(&mut self, checker: &mut Checker) {
+    checker.range("r", &mut self.r, 0.0, 1.0);
+    checker.range("g", &mut self.g, 0.0, 1.0);
+    checker.range("b", &mut self.b, 0.0, 1.0);
+    checker.range("a", &mut self.a, 0.0, 1.0);
+  }
+}
+
+impl Validate for RGBColor {
+  fn check(&mut self, checker: &mut Checker) {
+    checker.range("r", &mut self.r, 0.0, 1.0);
+    checker.range("g", &mut self.g, 0.0, 1.0);
+    checker.range("b", &mut self.b, 0.0, 1.0);
+  }
+}
+
+impl Validate for Paint {
+  fn check(&mut self, checker: &mut Checker) {
+    checker.range("opacity", &mut self.opacity, 0.0, 1.0);
+    checker.field("color", &mut self.color);
+    let gradient = matches!(
+      self.paint_type,
+      PaintType::GradientLinear
+        | PaintType::GradientRadial
+        | PaintType::GradientAngular
+        | PaintType::GradientDiamond
+    );
+    // Other paints have no use for handles, whatever they hold is left alone.
+    if gradient {
+      let defaults = DEFAULT_GRADIENT_HANDLES.map(|(x, y)| Vector { x, y });
+      let handles = &mut self.gradient_handle_positions;
+      checker.exactly("gradientHandlePositions", handles, &defaults);
+    }
+    checker.elements("gradientStops", &mut self.gradient_stops);
+    checker.field("imageTransform", &mut self.image_transform);
+    checker.field("filters", &mut self.filters);
+  }
+}
+
+impl Validate for ColorStop {
+  fn check(&mut self, checker: &mut Checker) {
+    checker.range("position", &mut self.position, 0.0, 1.0);
+    checker.field("color", &mut self.color);
+  }
+}
+
+impl Validate for PaintOverride {
+  fn check(&mut self, checker: &mut Checker) {
+    checker.elements("fills", &mut self.fills);
+  }
+}
+
+impl Validate for ImageFilters {
+  fn check(&mut self, checker: &mut Checker) {
+    checker.range("exposure", &mut self.exposure, -1.0, 1.0);
+    checker.range("contrast", &mut self.contrast, -1.0, 1.0);
+    checker.range("saturation", &mut self.saturation, -1.0, 1.0);
+    checker.range("temperature", &mut self.temperature, -1.0, 1.0);
+    checker.range("tint", &mut self.tint, -1.0, 1.0);
+    checker.range("highlights", &mut self.highlights, -1.0, 1.0);
+    checker.range("shadows", &mut self.shadows, -1.0, 1.0);
+  }
+}
+
+impl Validate for Effect {
+  fn check(&mut self, checker: &mut Checker) {
+    checker.field("color", &mut self.color);
+  }
+}
+
+impl Validate for Measurement {
+  fn check(&mut self, checker: &mut Checker) {
+    if let MeasurementOffset::Inner(inner) = &mut self.offset {
+      checker.property.push("offset".into());
+      checker.range("relative", &mut inner.relative, -1.0, 1.0);
+      checker.property.pop();
+    }
+  }
+}
+
+impl Validate for Transform {
+  fn check(&mut self, checker: &mut Checker) {
+    let defaults = IDENTITY.map(|row| row.to_vec());
+    checker.exactly("matrix", &mut self.matrix, &defaults);
+    checker.property.push("matrix".into());
+    for (index, row) in self.matrix.iter_mut().enumerate() {
+      let defaults = &IDENTITY[index.min(IDENTITY.len() - 1)];
+      checker.exactly(index, row, defaults);
+    }
+    checker.property.pop();
+  }
+}
+
+impl<K: fmt::Display + Ord, T: Validate> Validate for HashMap<K, T> {
+  fn check(&mut self, checker: &mut Checker) {
+    // Sorted so reports come in the same order on every run.
+    let mut entries: Vec<_> = self.iter_mut().collect();
+    entries.sort_unstable_by_key(|(key, _)| *key);
+    for (key, value) in entries {
+      checker.field(key.to_string(), value);
+    }
+  }
+}
+
+impl Validate for Style {
+  fn check(&mut self, checker: &mut Checker) {
+    checker.field("value", &mut self.value);
+  }
+}
+
+impl Validate for StyleValue {
+  fn check(&mut self, checker: &mut Checker) {
+    match self {
+      StyleValue::Fill(paints) => checker.elements("Fill", paints),
+      StyleValue::Effect(effects) => checker.elements("Effect", effects),
+      StyleValue::Text(_) | StyleValue::Grid(_) => {}
+    }
+  }
+}
+
+impl Validate for Variable {
+  fn check(&mut self, checker: &mut Checker) {
+    checker.field("valuesByMode", &mut self.values_by_mode);
+  }
+}
+
+impl Validate for VariableDataValue {
+  fn check(&mut self, checker: &mut Checker) {
+    match self {
+      VariableDataValue::RGBColor(color) => color.check(checker),
+      VariableDataValue::Color(color) => color.check(checker),
+      VariableDataValue::Boolean(_)
+      | VariableDataValue::Float(_)
+      | VariableDataValue::String(_)
+      | VariableDataValue::VariableAlias(_)
+      | VariableDataValue::Expression(_) => {}
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use serde_json::{json, Value};
+
+  use super::*;
+  use crate::fixtures;
+
+  fn displayed(violations: &[Violation]) -> Vec<String> {
+    violations.iter().map(ToString::to_string).collect()
+  }
+
+  /// Breaks the card of the fixtures by setting the value at `pointer` to `broken`, then checks
+  /// that validating it reports `reported` without changing it, and that repairing it reports the
+  /// same violations and sets the value to `repaired`.
+  fn assert_repaired(pointer: &str, broken: Value, reported: &[&str], repaired: Value) {
+    let mut card = fixtures::rectangle();
+    *card.pointer_mut(pointer).unwrap() = broken;
+    let mut node: Node = serde_json::from_value(card).unwrap();
+    let original = node.clone();
+
+    let violations = node.validate();
+    assert_eq!(displayed(&violations), reported);
+    assert_eq!(node, original);
+
+    assert_eq!(node.repair(), violations);
+    let value = serde_json::to_value(&node).unwrap();
+    assert_eq!(value.pointer(pointer).unwrap(), &repaired);
+    assert!(node.validate().is_empty());
+  }
+
+  #[test]
+  fn valid_nodes_break_no_invariants() {
+    let mut document = fixtures::document();
+    assert!(document.validate().is_empty());
+    assert!(document.repair().is_empty());
+    assert_eq!(document, fixtures::document());
+  }
+
+  #[test]
+  fn numbers_out_of_range_are_clamped() {
+    let data = "/node/data";
+    assert_repaired(
+      &format!("{data}/opacity"),
+      json!(1.5),
+      &["1:2 `opacity`: must be between 0 and 1, got 1.5"],
+      json!(1.0),
+    );
+    assert_repaired(
+      &format!("{data}/additionalData/cornerSmoothing"),
+      json!(-0.5),
+      &["1:2 `additionalData.cornerSmoothing`: must be between 0 and 1, got -0.5"],
+      json!(0.0),
+    );
+    assert_repaired(
+      &format!("{data}/fills/0/color/g"),
+      json!(2.0),
+      &["1:2 `fills[0].color.g`: must be between 0 and 1, got 2"],
+      json!(1.0),
+    );
+    assert_repaired(
+      &format!("{data}/fills/0/filters"),
+      json!({
+        "exposure": -2.0,
+        "contrast": 0.0,
+        "saturation": 3.0,
+        "temperature": 0.0,
+        "tint": 0.0,
+        "highlights": 0.0,
+        "shadows": 0.0,
+      }),
+      &[
+        "1:2 `fills[0].filters.exposure`: must be between -1 and 1, got -2",
+        "1:2 `fills[0].filters.saturation`: must be between -1 and 1, got 3",
+      ],
+      json!({
+        "exposure": -1.0,
+        "contrast": 0.0,
+        "saturation": 1.0,
+        "temperature": 0.0,
+        "tint": 0.0,
+        "highlights": 0.0,
+        "shadows": 0.0,
+      }),
+    );
+    assert_repaired(
+      &format!("{data}/fills/0/gradientStops"),
+      json!([{
+        "position": 1.25,
+        "color": { "r": 0.0, "g": 0.0, "b": 0.0, "a": 1.0 },
+        "boundVariables": {},
+      }]),
+      &["1:2 `fills[0].gradientStops[0].position`: must be between 0 and 1, got 1.25"],
+      json!([{
+        "position": 1.0,
+        "color": { "r": 0.0, "g": 0.0, "b": 0.0, "a": 1.0 },
+        "boundVariables": {},
+      }]),
+    );
+  }
+
+  #[test]
+  fn numbers_which_are_not_numbers_go_to_the_minimum() {
+    let mut value = f32::NAN;
+    let mut checker = Checker::new(false);
+    checker.range("opacity", &mut value, 0.0, 1.0);
+    assert!(value.is_nan());
+    checker.repair = true;
+    checker.range("opacity", &mut value, 0.0, 1.0);
+    assert_eq!(value, 0.0);
+    assert_eq!(
+      displayed(&checker.into_violations()),
+      [
+        "`opacity`: must be between 0 and 1, got NaN",
+        "`opacity`: must be between 0 and 1, got NaN",
+      ]
+    );
+  }
+
+  #[test]
+  fn transforms_are_completed_from_the_identity() {
+    let pointer = "/node/data/relativeTransform/matrix";
+    assert_repaired(
+      pointer,
+      json!([[2.0, 0.0, 10.0]]),
+      &["1:2 `relativeTransform.matrix`: must have exactly 2 element(s), got 1"],
+      json!([[2.0, 0.0, 10.0], [0.0, 1.0, 0.0]]),
+    );
+    assert_repaired(
+      pointer,
+      json!([[1.0, 0.0], [0.0, 1.0, 5.0, 7.0], [0.0, 0.0, 1.0]]),
+      &[
+        "1:2 `relativeTransform.matrix`: must have exactly 2 element(s), got 3",
+        "1:2 `relativeTransform.matrix[0]`: must have exactly 3 element(s), got 2",
+        "1:2 `relativeTransform.matrix[1]`: must have exactly 3 element(s), got 4",
+      ],
+      json!([[1.0, 0.0, 0.0], [0.0, 1.0, 5.0]]),
+    );
+  }
+
+  #[test]
+  fn gradients_have_three_handles() {
+    let pointer = "/node/data/fills/0/gradientHandlePositions";
+    assert_repaired(
+      pointer,
+      json!([{ "x": 0.5, "y": 0.0 }]),
+      &["1:2 `fills[0].gradientHandlePositions`: must have exactly 3 element(s), got 1"],
+      json!([{ "x": 0.5, "y": 0.0 }, { "x": 1.0, "y": 0.5 }, { "x": 0.0, "y": 1.0 }]),
+    );
+    let handles = json!([
+      { "x": 0.0, "y": 0.0 },
+      { "x": 1.0, "y": 1.0 },
+      { "x": 0.0, "y": 1.0 },
+      { "x": 1.0, "y": 0.0 },
+    ]);
+    assert_repaired(
+      pointer,
+      handles.clone(),
+      &["1:2 `fills[0].gradientHandlePositions`: must have exactly 3 element(s), got 4"],
+      json!(handles.as_array().unwrap()[..3]),
+    );
+
+    // Other paints keep whatever handles they hold.
+    let mut card = fixtures::rectangle();
+    card["node"]["data"]["fills"] = json!([fixtures::paint("Solid")]);
+    card["node"]["data"]["fills"][0]["gradientHandlePositions"] = json!([{ "x": 0.5, "y": 0.0 }]);
+    let mut node: Node = serde_json::from_value(card).unwrap();
+    assert!(node.validate().is_empty());
+    assert!(node.repair().is_empty());
+  }
+
+  #[test]
+  fn layers_have_at_most_one_annotation() {
+    assert_repaired(
+      "/node/data/annotations",
+      json!([{ "label": "Primary", "properties": [] }, { "label": "Hover", "properties": [] }]),
+      &["1:2 `annotations`: must have at most 1 element(s), got 2"],
+      json!([{ "label": "Primary", "properties": [] }]),
+    );
+  }
+
+  #[test]
+  fn violations_are_reported_under_the_ids_of_the_ancestors() {
+    let mut document = fixtures::document();
+    let page = &mut document.children_mut().unwrap()[0];
+    let NodeType::Canvas(canvas) = &mut page.node else {
+      unreachable!();
+    };
+    canvas.background_color.a = 1.5;
+    canvas.measurements = vec![serde_json::from_value(json!({
+      "id": "m:1",
+      "start": { "nodeId": "1:2", "side": "Top" },
+      "end": { "nodeId": "1:2", "side": "Bottom" },
+      "offset": { "type": "Inner", "relative": -2.0 },
+    }))
+    .unwrap()];
+    let NodeType::Rectangle(card) = &mut canvas.children[0].node else {
+      unreachable!();
+    };
+    card.opacity = -1.0;
+
+    let violations = document.validate();
+    assert_eq!(
+      displayed(&violations),
+      [
+        "0:0 > 0:1 `backgroundColor.a`: must be between 0 and 1, got 1.5",
+        "0:0 > 0:1 `measurements[0].offset.relative`: must be between -1 and 1, got -2",
+        "0:0 > 0:1 > 1:2 `opacity`: must be between 0 and 1, got -1",
+      ]
+    );
+    // Checking the tree node by node finds the same violations without copying it.
+    let mut checker = Checker::new(false);
+    checker.tree(&document);
+    assert_eq!(checker.into_violations(), violations);
+
+    assert_eq!(document.repair(), violations);
+    assert!(document.validate().is_empty());
+  }
+
+  #[test]
+  fn styles_and_variables_are_checked() {
+    let mut style: Style = serde_json::from_value(json!({
+      "key": "",
+      "name": "Brand",
+      "description": "",
+      "remote": false,
+      "styleType": "Fill",
+      "value": { "Fill": [fixtures::paint("Solid")] },
+    }))
+    .unwrap();
+    let Some(StyleValue::Fill(paints)) = &mut style.value else {
+      unreachable!();
+    };
+    paints[0].opacity = 4.0;
+    assert_eq!(
+      displayed(&style.repair()),
+      ["`value.Fill[0].opacity`: must be between 0 and 1, got 4"]
+    );
+    assert!(style.validate().is_empty());
+
+    let mut variable: Variable = serde_json::from_value(json!({
+      "id": "VariableID:1:1",
+      "name": "color/primary",
+      "key": "",
+      "variableCollectionId": "VariableCollectionId:1:0",
+      "resolvedType": "Color",
+      "valuesByMode": {
+        "1:1": { "r": 0.0, "g": 0.0, "b": 0.0, "a": -1.0 },
+        "1:0": { "r": 1.5, "g": 0.0, "b": 0.0, "a": 1.0 },
+      },
+      "remote": false,
+      "description": "",
+      "hiddenFromPublishing": false,
+      "scopes": [],
+      "codeSyntax": {},
+    }))
+    .unwrap();
+    assert_eq!(
+      displayed(&variable.validate()),
+      [
+        "`valuesByMode.1:0.r`: must be between 0 and 1, got 1.5",
+        "`valuesByMode.1:1.a`: must be between 0 and 1, got -1",
+      ]
+    );
+    variable.repair();
+    assert!(variable.validate().is_empty());
+  }
+}
@@ -1,9 +1,25 @@
 // This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
 import type { Color } from "../properties/color/Color";
+import type { FlowStartingPoint } from "../properties/FlowStartingPoint";
+import type { Measurement } from "../properties/measurement/Measurement";
 import type { Node } from "./Node";
+import type { PrototypeDevice } from "../properties/PrototypeDevice";
 
 export type CanvasNode = { 
 /**
  * An array of top level layers on the canvas
  */
-children: Array<Node>, backgroundColor: Color, };
+children: Array<Node>, backgroundColor: Color, 
+/**
+ * The starting points of the prototype flows of the canvas, the frames its prototypes are
+ * launched from.
+ */
+flowStartingPoints: Array<FlowStartingPoint>, 
+/**
+ * The device prototypes of the canvas are presented in.
+ */
+prototypeDevice?: PrototypeDevice, 
+/**
+ * The distances between layers of the canvas pinned in Dev Mode.
+ */
+measurements: Array<Measurement>, };
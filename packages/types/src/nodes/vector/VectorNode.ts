@@ -1,9 +1,11 @@
 // This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
 import type { Annotation } from "../../properties/Annotation";
 import type { BlendMode } from "../../properties/BlendMode";
+import type { DevStatus } from "../../properties/DevStatus";
 import type { EasingType } from "../../properties/easing/EasingType";
 import type { Effect } from "../../properties/Effect";
 import type { ExportSetting } from "../../properties/export/ExportSetting";
+import type { Interaction } from "../../properties/interaction/Interaction";
 import type { LayoutAlign } from "../../properties/layout/LayoutAlign";
 import type { LayoutConstraint } from "../../properties/layout/LayoutConstraint";
 import type { Paint } from "../../properties/paint/Paint";
@@ -62,6 +64,10 @@ transitionDuration?: number,
  * The easing curve used in the prototyping transition on this node
  */
 transitionEasing: EasingType, 
+/**
+ * The interactions of the node in prototypes, triggered by the user or by time passing.
+ */
+interactions: Array<Interaction>, 
 /**
  * Opacity of the node
  */
@@ -163,4 +169,8 @@ styles?: { [key in StyleType]?: string },
  * An array of annotations displaying notes and pinned properties of nodes in Dev Mode.
  * Currently a maximum of 1 annotation is supported.
  */
-annotations: Array<Annotation>, additionalData: Data, };
+annotations: Array<Annotation>, 
+/**
+ * Whether the node is ready for development or implemented, set by designers in Dev Mode.
+ */
+devStatus?: DevStatus, additionalData: Data, };
@@ -0,0 +1,15 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { PatchOperation } from "./PatchOperation";
+
+/**
+ * A set of changes to the properties of a single node, applied atomically.
+ */
+export type Patch = { 
+/**
+ * Id of the patched node
+ */
+id: string, 
+/**
+ * Operations applied in order
+ */
+operations: Array<PatchOperation>, };
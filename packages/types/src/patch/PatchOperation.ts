@@ -0,0 +1,8 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * A change to a single property of a node. Paths use the serialized (camelCase) property names,
+ * with dots to access fields and brackets to index arrays, e.g. `fills[0].opacity` or
+ * `constraint.horizontal`.
+ */
+export type PatchOperation = { "op": "set", path: string, value: unknown, } | { "op": "insert", path: string, value: unknown, } | { "op": "remove", path: string, };
@@ -0,0 +1,14 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * A property whose value differs between two versions of a node
+ */
+export type PropertyChange = { path: string, 
+/**
+ * Value in the first version, absent when the property was added
+ */
+before: unknown, 
+/**
+ * Value in the second version, absent when the property was removed
+ */
+after: unknown, };
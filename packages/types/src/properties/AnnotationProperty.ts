@@ -4,4 +4,4 @@ import type { AnnotationPropertyType } from "./AnnotationPropertyType";
 /**
  * A pinned property in an Annotation
  */
-export type AnnotationProperty = { annotation_property_type: AnnotationPropertyType, };
+export type AnnotationProperty = { type: AnnotationPropertyType, };
@@ -6,8 +6,4 @@ import type { Size } from "./Size";
 /**
  * The device used to view a prototype
  */
-export type PrototypeDevice = { prototypeDeviceType: PrototypeDeviceType, size: Size, 
-/**
- * rotation'NONE' | 'CCW_90'
- */
-presetIdentifier: string, rotation: PrototypeDeviceRotation, };
+export type PrototypeDevice = { prototypeDeviceType: PrototypeDeviceType, size: Size, presetIdentifier: string, rotation: PrototypeDeviceRotation, };
@@ -1,6 +1,6 @@
 // This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
 
 /**
- * Enum desccribin PrototypeDeviceRotation
+ * Enum describing PrototypeDeviceRotation
  */
 export type PrototypeDeviceRotation = "None" | "CCW90";
@@ -1,5 +1,6 @@
 // This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
 import type { StyleType } from "./StyleType";
+import type { StyleValue } from "./StyleValue";
 
 /**
  * A set of properties that can be applied to nodes and published. Styles for a property can be
@@ -29,4 +30,9 @@ remote: boolean,
  * EFFECT
  * GRID
  */
-styleType: StyleType, };
+styleType: StyleType, 
+/**
+ * The properties the style applies, absent for remote styles whose definition lives in
+ * another file
+ */
+value?: StyleValue, };
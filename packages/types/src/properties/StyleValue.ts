@@ -0,0 +1,10 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { Effect } from "./Effect";
+import type { LayoutGrid } from "./layout/LayoutGrid";
+import type { Paint } from "./paint/Paint";
+import type { TypeStyle } from "./typeStyle/TypeStyle";
+
+/**
+ * The properties applied by a style, one variant per type of style
+ */
+export type StyleValue = { "Fill": Array<Paint> } | { "Text": TypeStyle } | { "Effect": Array<Effect> } | { "Grid": Array<LayoutGrid> };
@@ -5,6 +5,7 @@ import type { RGBColor } from "./color/RGBColor";
 import type { VariableAlias } from "./VariableAlias";
 
 /**
- * Enum decribing the value of a variable data
+ * Enum decribing the value of a variable data, written as the value itself. Colors with an alpha
+ * channel are read as [`Color`], the others as [`RGBColor`].
  */
-export type VariableDataValue = { "Bool": boolean } | { "I32": number } | { "String": string } | { "Color": Color } | { "RGBColor": RGBColor } | { "VariableAlias": VariableAlias } | { "Expression": Expression };
+export type VariableDataValue = boolean | number | string | Color | RGBColor | VariableAlias | Expression;
@@ -1,4 +1,5 @@
 // This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { ComponentPropertyDefinition } from "./ComponentPropertyDefinition";
 import type { DocumentationLink } from "../DocumentationLink";
 
 /**
@@ -28,4 +29,9 @@ documentationLinks: Array<DocumentationLink>,
 /**
  * Whether this component is a remote component that doesn't live in this file
  */
-remote: boolean, };
+remote: boolean, 
+/**
+ * The properties instances of the component can set, by property name. Only present on
+ * components which are not variants of a component set
+ */
+componentPropertyDefinitions: { [key in string]?: ComponentPropertyDefinition }, };
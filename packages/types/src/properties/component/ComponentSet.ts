@@ -1,4 +1,5 @@
 // This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { ComponentPropertyDefinition } from "./ComponentPropertyDefinition";
 import type { DocumentationLink } from "../DocumentationLink";
 
 /**
@@ -24,4 +25,8 @@ documentationLinks: Array<DocumentationLink>,
 /**
  * Whether this component set is a remote component set that doesn't live in this file
  */
-remote: boolean, };
+remote: boolean, 
+/**
+ * The properties instances of the variants of the set can set, by property name
+ */
+componentPropertyDefinitions: { [key in string]?: ComponentPropertyDefinition }, };
@@ -3,4 +3,4 @@
 /**
  * Enum describing animation easing curves
  */
-export type EasingType = "EaseIn" | "EaseOut" | "EaseInAndOut" | "Linear" | "GentleSpring";
+export type EasingType = "EaseIn" | "EaseOut" | "EaseInAndOut" | "Linear" | "EaseInBack" | "EaseOutBack" | "EaseInAndOutBack" | "CustomCubicBezier" | "GentleSpring" | "Quick" | "Bouncy" | "Slow" | "CustomSpring";
@@ -7,6 +7,7 @@ import type { SetVariableModeAction } from "../SetVariableModeAction";
 import type { UpdateMediaRuntimeAction } from "./UpdateMediaRuntimeAction";
 
 /**
- * An action that is performed when a trigger is activated.
+ * An action that is performed when a trigger is activated. Actions are tagged with their
+ * [`ActionType`] in a `type` field next to their content.
  */
-export type Action = "BackAction" | "CloseAction" | { "OpenURLAction": OpenURLAction } | { "UpdateMediaRuntimeAction": UpdateMediaRuntimeAction } | { "SetVariableAction": SetVariableAction } | { "SetVariableModeAction": SetVariableModeAction } | { "ConditionalAction": ConditionalAction } | { "NodeAction": NodeAction };
+export type Action = { "type": "Back" } | { "type": "Close" } | { "type": "Url" } & OpenURLAction | { "type": "UpdateMediaRuntime" } & UpdateMediaRuntimeAction | { "type": "SetVariable" } & SetVariableAction | { "type": "SetVariableMode" } & SetVariableModeAction | { "type": "Conditional" } & ConditionalAction | { "type": "Node" } & NodeAction;
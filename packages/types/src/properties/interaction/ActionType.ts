@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Enum describing the type of an action
+ */
+export type ActionType = "Back" | "Close" | "Url" | "UpdateMediaRuntime" | "SetVariable" | "SetVariableMode" | "Conditional" | "Node";
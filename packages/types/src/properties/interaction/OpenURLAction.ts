@@ -3,4 +3,4 @@
 /**
  * An action that opens a URL.
  */
-export type OpenURLAction = { openUrlActionType: string, url: string, };
+export type OpenURLAction = { url: string, };
@@ -4,7 +4,7 @@ import type { UpdateMediaRuntimeActionMediaAction } from "./UpdateMediaRuntimeAc
 /**
  * An action that affects a video node in the Figma viewer. For example, to play, pause, or skip.
  */
-export type UpdateMediaRuntimeAction = { updateMediaRuntimeType: string, 
+export type UpdateMediaRuntimeAction = { 
 /**
  * The node ID of the media node to update. If destinationId is null, the action will update
  * the media node that contains the action.
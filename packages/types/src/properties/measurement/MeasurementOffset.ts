@@ -3,6 +3,6 @@ import type { MeasurementOffsetInner } from "./MeasurementOffsetInner";
 import type { MeasurementOffsetOuter } from "./MeasurementOffsetOuter";
 
 /**
- * Enum describing the offest of a Measurement
+ * Where the line of a Measurement is drawn, tagged with its `type`
  */
-export type MeasurementOffset = { "measurementOffsetInner": MeasurementOffsetInner } | { "measurementOffsetOuter": MeasurementOffsetOuter };
+export type MeasurementOffset = { "type": "Inner" } & MeasurementOffsetInner | { "type": "Outer" } & MeasurementOffsetOuter;
@@ -3,4 +3,8 @@
 /**
  * Measurement offset relative to the inside of the start node
  */
-export type MeasurementOffsetInner = { measurementOffsetInnerType: string, relative: number, };
+export type MeasurementOffsetInner = { 
+/**
+ * Position of the line along the measured sides, from -1 at their start to 1 at their end
+ */
+relative: number, };
@@ -1,6 +1,10 @@
 // This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
 
 /**
- * Measurement offset relative to the outside of the start nod
+ * Measurement offset relative to the outside of the start node
  */
-export type MeasurementOffsetOuter = { measurementOffsetOuterType: string, relative: number, };
+export type MeasurementOffsetOuter = { 
+/**
+ * Distance of the line from the measured sides, in pixels
+ */
+fixed: number, };
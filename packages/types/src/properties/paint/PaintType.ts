@@ -3,4 +3,4 @@
 /**
  * Enum describing the type of a paint
  */
-export type PaintType = "Solid" | "GradientLienear" | "GradientRadial" | "GradientAngular" | "GradientDiamond" | "Image" | "Emoji" | "Video";
+export type PaintType = "Solid" | "GradientLinear" | "GradientRadial" | "GradientAngular" | "GradientDiamond" | "Image" | "Emoji" | "Video";
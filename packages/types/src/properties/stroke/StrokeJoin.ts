@@ -3,4 +3,4 @@
 /**
  * A string enum describing how corners in vector paths are rendered.
  */
-export type StrokeJoin = "Mitter" | "Bevel" | "Round";
+export type StrokeJoin = "Miter" | "Bevel" | "Round";
@@ -3,6 +3,7 @@ import type { DirectionalTransition } from "./DirectionalTransition";
 import type { SimpleTransition } from "./SimpleTransition";
 
 /**
- * Enum describing the transition of a node action
+ * Enum describing the transition of a node action, written as the transition itself or `null`
+ * for none.
  */
-export type NodeActionTransition = { "SimpleTransition": SimpleTransition } | { "DirectionalTransition": DirectionalTransition } | "Null";
+export type NodeActionTransition = SimpleTransition | DirectionalTransition | null;
@@ -0,0 +1,52 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { VariableDataValue } from "../VariableDataValue";
+import type { VariableResolvedDataType } from "../VariableResolvedDataType";
+
+/**
+ * A variable defined in a document, with a value for each mode of its collection.
+ */
+export type Variable = { 
+/**
+ * The unique identifier of this variable
+ */
+id: string, 
+/**
+ * The name of this variable
+ */
+name: string, 
+/**
+ * The key of this variable
+ */
+key: string, 
+/**
+ * The id of the variable collection that contains this variable
+ */
+variableCollectionId: string, 
+/**
+ * The resolved type of the variable
+ */
+resolvedType: VariableResolvedDataType, 
+/**
+ * The values for each mode of this variable, by mode id
+ */
+valuesByMode: { [key in string]?: VariableDataValue }, 
+/**
+ * Whether this variable is remote
+ */
+remote: boolean, 
+/**
+ * The description of this variable
+ */
+description: string, 
+/**
+ * Whether this variable is hidden when publishing the current file as a library
+ */
+hiddenFromPublishing: boolean, 
+/**
+ * An array of scopes in the UI where this variable is shown
+ */
+scopes: Array<string>, 
+/**
+ * Code syntax definitions for this variable, by platform (WEB, ANDROID or iOS)
+ */
+codeSyntax: { [key in string]?: string }, };
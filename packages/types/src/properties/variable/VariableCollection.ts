@@ -0,0 +1,39 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { VariableMode } from "./VariableMode";
+
+/**
+ * A grouping of related variables that share the same modes.
+ */
+export type VariableCollection = { 
+/**
+ * The unique identifier of this variable collection
+ */
+id: string, 
+/**
+ * The name of this variable collection
+ */
+name: string, 
+/**
+ * The key of this variable collection
+ */
+key: string, 
+/**
+ * The modes of this variable collection
+ */
+modes: Array<VariableMode>, 
+/**
+ * The id of the default mode
+ */
+defaultModeId: string, 
+/**
+ * Whether this variable collection is remote
+ */
+remote: boolean, 
+/**
+ * Whether this variable collection is hidden when publishing the current file as a library
+ */
+hiddenFromPublishing: boolean, 
+/**
+ * The ids of the variables in the collection, in the order they are displayed
+ */
+variableIds: Array<string>, };
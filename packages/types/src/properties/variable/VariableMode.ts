@@ -0,0 +1,14 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * A mode of a variable collection
+ */
+export type VariableMode = { 
+/**
+ * The unique identifier of this mode
+ */
+modeId: string, 
+/**
+ * The name of this mode
+ */
+name: string, };